@@ -0,0 +1,17 @@
+-- 2026.10.18.001_add_card_history.sql
+-- 卡片修改历史表
+
+-- 卡片字段变更记录（每个被修改的字段一行）
+CREATE TABLE IF NOT EXISTS card_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    card_id TEXT NOT NULL,
+    field TEXT NOT NULL CHECK(field IN ('callsign', 'qty', 'serial', 'project_id')),
+    old_value TEXT,
+    new_value TEXT,
+    changed_by TEXT,  -- 操作人
+    changed_at TEXT NOT NULL,
+    FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+);
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_card_history_card ON card_history(card_id, changed_at);
@@ -0,0 +1,53 @@
+-- 2026.10.18.002_add_card_events.sql
+-- 卡片状态事件日志表（只追加，不修改）
+
+-- 卡片事件：录入、分发、退回、重新分发、关联运单
+CREATE TABLE IF NOT EXISTS card_events (
+    id TEXT PRIMARY KEY,
+    card_id TEXT NOT NULL,
+    event_type TEXT NOT NULL CHECK(event_type IN ('entered', 'distributed', 'returned', 'redistributed', 'waybill_attached')),
+    payload TEXT,  -- JSON 格式：分发信息 / 退卡信息 / 运单号
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+);
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_card_events_card ON card_events(card_id, created_at);
+
+-- 回填已有卡片的录入事件
+INSERT INTO card_events (id, card_id, event_type, payload, created_at)
+SELECT lower(hex(randomblob(16))), id, 'entered', NULL, created_at
+FROM cards;
+
+-- 回填已有卡片的分发事件（取自 metadata.distribution）
+INSERT INTO card_events (id, card_id, event_type, payload, created_at)
+SELECT
+    lower(hex(randomblob(16))),
+    id,
+    'distributed',
+    json_object('distribution', json_extract(metadata, '$.distribution')),
+    COALESCE(json_extract(metadata, '$.distribution.distributed_at'), updated_at)
+FROM cards
+WHERE json_valid(metadata) AND json_extract(metadata, '$.distribution') IS NOT NULL;
+
+-- 回填已有卡片的退卡事件（取自 metadata.return）
+INSERT INTO card_events (id, card_id, event_type, payload, created_at)
+SELECT
+    lower(hex(randomblob(16))),
+    id,
+    'returned',
+    json_object('return', json_extract(metadata, '$.return')),
+    COALESCE(json_extract(metadata, '$.return.returned_at'), updated_at)
+FROM cards
+WHERE json_valid(metadata) AND json_extract(metadata, '$.return') IS NOT NULL;
+
+-- 回填已有卡片的待处理运单号
+INSERT INTO card_events (id, card_id, event_type, payload, created_at)
+SELECT
+    lower(hex(randomblob(16))),
+    id,
+    'waybill_attached',
+    json_object('waybill_no', json_extract(metadata, '$.pending_waybill_no')),
+    updated_at
+FROM cards
+WHERE json_valid(metadata) AND json_extract(metadata, '$.pending_waybill_no') IS NOT NULL;
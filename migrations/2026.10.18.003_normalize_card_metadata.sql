@@ -0,0 +1,104 @@
+-- 2026.10.18.003_normalize_card_metadata.sql
+-- 将 cards.metadata JSON 拆分为关系表：分发信息、退卡信息、地址缓存
+
+-- 分发信息（每张卡片保留最近一次，历次分发见 card_events）
+CREATE TABLE IF NOT EXISTS card_distributions (
+    card_id TEXT PRIMARY KEY,
+    method TEXT NOT NULL,
+    address TEXT,
+    remarks TEXT,
+    proxy_callsign TEXT,
+    distributed_at TEXT NOT NULL,
+    FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+);
+
+-- 退卡信息（每张卡片保留最近一次）
+CREATE TABLE IF NOT EXISTS card_returns (
+    card_id TEXT PRIMARY KEY,
+    method TEXT NOT NULL,
+    remarks TEXT,
+    returned_at TEXT NOT NULL,
+    FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+);
+
+-- 地址缓存（每张卡片每个来源一条）
+CREATE TABLE IF NOT EXISTS card_addresses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    card_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    chinese_address TEXT,
+    english_address TEXT,
+    name TEXT,
+    mail_method TEXT,
+    updated_at TEXT,
+    cached_at TEXT NOT NULL,
+    UNIQUE (card_id, source),
+    FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+);
+
+-- 待处理运单号改为 cards 表的普通列
+ALTER TABLE cards ADD COLUMN pending_waybill_no TEXT;
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_card_distributions_method ON card_distributions(method);
+CREATE INDEX IF NOT EXISTS idx_card_distributions_distributed_at ON card_distributions(distributed_at);
+CREATE INDEX IF NOT EXISTS idx_card_distributions_proxy ON card_distributions(proxy_callsign);
+CREATE INDEX IF NOT EXISTS idx_card_returns_method ON card_returns(method);
+CREATE INDEX IF NOT EXISTS idx_card_returns_returned_at ON card_returns(returned_at);
+CREATE INDEX IF NOT EXISTS idx_cards_pending_waybill ON cards(pending_waybill_no);
+
+-- 回填分发信息（取自 metadata.distribution）
+INSERT INTO card_distributions (card_id, method, address, remarks, proxy_callsign, distributed_at)
+SELECT
+    id,
+    COALESCE(json_extract(metadata, '$.distribution.method'), ''),
+    json_extract(metadata, '$.distribution.address'),
+    json_extract(metadata, '$.distribution.remarks'),
+    json_extract(metadata, '$.distribution.proxy_callsign'),
+    COALESCE(json_extract(metadata, '$.distribution.distributed_at'), updated_at)
+FROM cards
+WHERE json_valid(metadata) AND json_extract(metadata, '$.distribution') IS NOT NULL;
+
+-- 回填退卡信息（取自 metadata.return）
+INSERT INTO card_returns (card_id, method, remarks, returned_at)
+SELECT
+    id,
+    COALESCE(json_extract(metadata, '$.return.method'), ''),
+    json_extract(metadata, '$.return.remarks'),
+    COALESCE(json_extract(metadata, '$.return.returned_at'), updated_at)
+FROM cards
+WHERE json_valid(metadata) AND json_extract(metadata, '$.return') IS NOT NULL;
+
+-- 回填地址缓存（兼容旧字段名 address_history）
+INSERT OR IGNORE INTO card_addresses (card_id, source, chinese_address, english_address, name, mail_method, updated_at, cached_at)
+SELECT
+    c.id,
+    COALESCE(json_extract(a.value, '$.source'), ''),
+    json_extract(a.value, '$.chinese_address'),
+    json_extract(a.value, '$.english_address'),
+    json_extract(a.value, '$.name'),
+    json_extract(a.value, '$.mail_method'),
+    json_extract(a.value, '$.updated_at'),
+    COALESCE(json_extract(a.value, '$.cached_at'), c.updated_at)
+FROM cards c, json_each(CASE WHEN json_valid(c.metadata) THEN c.metadata ELSE '{}' END, '$.address_cache') a
+WHERE a.type = 'object';
+
+INSERT OR IGNORE INTO card_addresses (card_id, source, chinese_address, english_address, name, mail_method, updated_at, cached_at)
+SELECT
+    c.id,
+    COALESCE(json_extract(a.value, '$.source'), ''),
+    json_extract(a.value, '$.chinese_address'),
+    json_extract(a.value, '$.english_address'),
+    json_extract(a.value, '$.name'),
+    json_extract(a.value, '$.mail_method'),
+    json_extract(a.value, '$.updated_at'),
+    COALESCE(json_extract(a.value, '$.cached_at'), c.updated_at)
+FROM cards c, json_each(CASE WHEN json_valid(c.metadata) THEN c.metadata ELSE '{}' END, '$.address_history') a
+WHERE a.type = 'object';
+
+-- 回填待处理运单号
+UPDATE cards SET pending_waybill_no = json_extract(metadata, '$.pending_waybill_no')
+WHERE json_valid(metadata);
+
+-- 移除已拆分的 metadata 列
+ALTER TABLE cards DROP COLUMN metadata;
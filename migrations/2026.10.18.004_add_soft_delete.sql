@@ -0,0 +1,9 @@
+-- 2026.10.18.004_add_soft_delete.sql
+-- 卡片与项目改为软删除：deleted_at 非空即视为已移入回收站
+
+ALTER TABLE cards ADD COLUMN deleted_at TEXT;
+ALTER TABLE projects ADD COLUMN deleted_at TEXT;
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_cards_deleted_at ON cards(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_projects_deleted_at ON projects(deleted_at);
@@ -0,0 +1,23 @@
+-- 2026.10.18.005_add_operators.sql
+-- 操作员登记表，以及卡片操作的操作员归属
+
+-- 操作员（多名志愿者共用一台工作站）
+CREATE TABLE IF NOT EXISTS operators (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    callsign TEXT,
+    pin_hash TEXT,  -- PBKDF2 哈希（盐与摘要 Base64 编码，以 $ 分隔），为空表示无需 PIN
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+-- 操作归属：事件、修改历史记录操作员，删除记录删除人
+-- 不设外键：删除操作员后保留历史归属
+ALTER TABLE card_events ADD COLUMN operator_id TEXT;
+ALTER TABLE card_history ADD COLUMN operator_id TEXT;
+ALTER TABLE cards ADD COLUMN deleted_by TEXT;
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_cards_creator ON cards(creator_id);
+CREATE INDEX IF NOT EXISTS idx_card_events_operator ON card_events(operator_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_card_history_operator ON card_history(operator_id, changed_at);
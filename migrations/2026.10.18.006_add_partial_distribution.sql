@@ -0,0 +1,56 @@
+-- 2026.10.18.006_add_partial_distribution.sql
+-- 支持按数量部分分发：新增"部分分发"状态与已分发数量
+
+-- 重建 cards 表以放宽 status 的 CHECK 约束（SQLite 不支持修改约束）
+-- 重建期间关闭外键，避免删除旧表时级联删除事件、历史与元数据
+-- 外键开关须在事务之外设置；重建在同一事务中完成，提交前校验外键
+PRAGMA foreign_keys = OFF;
+
+BEGIN IMMEDIATE;
+
+CREATE TABLE cards_new (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    creator_id TEXT,
+    callsign TEXT NOT NULL,
+    qty INTEGER NOT NULL CHECK(qty > 0 AND qty <= 9999),
+    serial INTEGER,  -- 序列号，前端显示时格式化为三位数如 "001"
+    status TEXT NOT NULL CHECK(status IN ('pending', 'partially_distributed', 'distributed', 'returned')) DEFAULT 'pending',
+    distributed_qty INTEGER NOT NULL DEFAULT 0 CHECK(distributed_qty >= 0),  -- 已分发数量，剩余数量为 qty - distributed_qty
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    pending_waybill_no TEXT,
+    deleted_at TEXT,
+    deleted_by TEXT,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- 回填：已分发的卡片视为全部分发
+INSERT INTO cards_new (id, project_id, creator_id, callsign, qty, serial, status, distributed_qty, created_at, updated_at, pending_waybill_no, deleted_at, deleted_by)
+SELECT
+    id, project_id, creator_id, callsign, qty, serial, status,
+    CASE WHEN status = 'distributed' THEN qty ELSE 0 END,
+    created_at, updated_at, pending_waybill_no, deleted_at, deleted_by
+FROM cards;
+
+DROP TABLE cards;
+
+ALTER TABLE cards_new RENAME TO cards;
+
+-- 重建索引
+CREATE INDEX IF NOT EXISTS idx_cards_project ON cards(project_id);
+CREATE INDEX IF NOT EXISTS idx_cards_callsign ON cards(callsign);
+CREATE INDEX IF NOT EXISTS idx_cards_status ON cards(status);
+CREATE INDEX IF NOT EXISTS idx_cards_created_at ON cards(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_cards_pending_waybill ON cards(pending_waybill_no);
+CREATE INDEX IF NOT EXISTS idx_cards_deleted_at ON cards(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_cards_creator ON cards(creator_id);
+
+-- 最近一次分发的数量（为空表示分发全部剩余数量）
+ALTER TABLE card_distributions ADD COLUMN qty INTEGER;
+
+PRAGMA foreign_key_check;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
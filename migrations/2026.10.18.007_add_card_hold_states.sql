@@ -0,0 +1,70 @@
+-- 2026.10.18.007_add_card_hold_states.sql
+-- 新增卡片状态：待付邮资、留存（留给 QSL 管理员）、已遗失，以及对应的事件类型
+
+-- 重建 cards 与 card_events 表以放宽 CHECK 约束（SQLite 不支持修改约束）
+-- 重建期间关闭外键，避免删除旧表时级联删除事件、历史与元数据
+-- 外键开关须在事务之外设置；重建在同一事务中完成，提交前校验外键
+PRAGMA foreign_keys = OFF;
+
+BEGIN IMMEDIATE;
+
+CREATE TABLE cards_new (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    creator_id TEXT,
+    callsign TEXT NOT NULL,
+    qty INTEGER NOT NULL CHECK(qty > 0 AND qty <= 9999),
+    serial INTEGER,  -- 序列号，前端显示时格式化为三位数如 "001"
+    status TEXT NOT NULL CHECK(status IN ('pending', 'partially_distributed', 'distributed', 'returned', 'awaiting_postage', 'held', 'lost')) DEFAULT 'pending',
+    distributed_qty INTEGER NOT NULL DEFAULT 0 CHECK(distributed_qty >= 0),  -- 已分发数量，剩余数量为 qty - distributed_qty
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    pending_waybill_no TEXT,
+    deleted_at TEXT,
+    deleted_by TEXT,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+INSERT INTO cards_new (id, project_id, creator_id, callsign, qty, serial, status, distributed_qty, created_at, updated_at, pending_waybill_no, deleted_at, deleted_by)
+SELECT id, project_id, creator_id, callsign, qty, serial, status, distributed_qty, created_at, updated_at, pending_waybill_no, deleted_at, deleted_by
+FROM cards;
+
+DROP TABLE cards;
+
+ALTER TABLE cards_new RENAME TO cards;
+
+CREATE TABLE card_events_new (
+    id TEXT PRIMARY KEY,
+    card_id TEXT NOT NULL,
+    event_type TEXT NOT NULL CHECK(event_type IN ('entered', 'distributed', 'returned', 'redistributed', 'waybill_attached', 'awaiting_postage', 'held', 'lost', 'released')),
+    payload TEXT,  -- JSON 格式：分发信息 / 退卡信息 / 运单号 / 备注
+    created_at TEXT NOT NULL,
+    operator_id TEXT,
+    FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+);
+
+-- 保留 rowid，同一秒内的事件仍按写入顺序排序
+INSERT INTO card_events_new (rowid, id, card_id, event_type, payload, created_at, operator_id)
+SELECT rowid, id, card_id, event_type, payload, created_at, operator_id
+FROM card_events;
+
+DROP TABLE card_events;
+
+ALTER TABLE card_events_new RENAME TO card_events;
+
+-- 重建索引
+CREATE INDEX IF NOT EXISTS idx_cards_project ON cards(project_id);
+CREATE INDEX IF NOT EXISTS idx_cards_callsign ON cards(callsign);
+CREATE INDEX IF NOT EXISTS idx_cards_status ON cards(status);
+CREATE INDEX IF NOT EXISTS idx_cards_created_at ON cards(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_cards_pending_waybill ON cards(pending_waybill_no);
+CREATE INDEX IF NOT EXISTS idx_cards_deleted_at ON cards(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_cards_creator ON cards(creator_id);
+CREATE INDEX IF NOT EXISTS idx_card_events_card ON card_events(card_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_card_events_operator ON card_events(operator_id, created_at);
+
+PRAGMA foreign_key_check;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
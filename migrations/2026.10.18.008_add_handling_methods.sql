@@ -0,0 +1,71 @@
+-- 2026.10.18.008_add_handling_methods.sql
+-- 分发方式与退卡原因登记表：显示名称、必填字段、启用状态
+
+-- 分发方式（kind = 'distribution'）与退卡原因（kind = 'return'）
+-- code 为写入分发/退卡记录的取值，登记后不可修改；停用后历史记录仍可显示
+CREATE TABLE IF NOT EXISTS card_handling_methods (
+    kind TEXT NOT NULL CHECK(kind IN ('distribution', 'return')),
+    code TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    requires_address INTEGER NOT NULL DEFAULT 0,         -- 须填写分发地址
+    requires_proxy_callsign INTEGER NOT NULL DEFAULT 0,  -- 须填写代领人呼号
+    requires_remarks INTEGER NOT NULL DEFAULT 0,         -- 须填写备注
+    is_active INTEGER NOT NULL DEFAULT 1,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (kind, code)
+);
+
+-- 内置分发方式与退卡原因（与原界面选项一致）
+INSERT OR IGNORE INTO card_handling_methods (kind, code, display_name, requires_address, requires_proxy_callsign, requires_remarks, sort_order, created_at, updated_at)
+VALUES
+    ('distribution', '快递', '快递', 1, 0, 0, 1, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')),
+    ('distribution', '挂号信', '挂号信', 1, 0, 0, 2, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')),
+    ('distribution', '平邮', '平邮', 1, 0, 0, 3, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')),
+    ('distribution', '自取', '自取', 0, 0, 0, 4, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')),
+    ('distribution', '代领', '代领', 0, 1, 0, 5, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')),
+    ('distribution', '其它', '其它', 0, 0, 0, 6, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')),
+    ('return', 'NOT FOUND', 'NOT FOUND', 0, 0, 0, 1, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')),
+    ('return', 'CALLSIGN INVALID', 'CALLSIGN INVALID', 0, 0, 0, 2, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')),
+    ('return', 'REFUSED', 'REFUSED', 0, 0, 0, 3, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')),
+    ('return', 'OTHER', 'OTHER', 0, 0, 0, 4, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'));
+
+-- 迁移既有自由文本：去除首尾空白，空值归为「其它」/「OTHER」，退卡原因统一为大写
+UPDATE card_events
+SET payload = json_set(payload, '$.distribution.method',
+    COALESCE(NULLIF(TRIM(json_extract(payload, '$.distribution.method')), ''), '其它'))
+WHERE json_valid(payload) AND json_extract(payload, '$.distribution') IS NOT NULL;
+
+UPDATE card_events
+SET payload = json_set(payload, '$.return.method',
+    COALESCE(NULLIF(UPPER(TRIM(json_extract(payload, '$.return.method'))), ''), 'OTHER'))
+WHERE json_valid(payload) AND json_extract(payload, '$.return') IS NOT NULL;
+
+UPDATE card_distributions SET method = COALESCE(NULLIF(TRIM(method), ''), '其它');
+
+UPDATE card_returns SET method = COALESCE(NULLIF(UPPER(TRIM(method)), ''), 'OTHER');
+
+-- 未登记的历史取值登记为停用项，保留显示但不再用于新的分发/退卡
+INSERT OR IGNORE INTO card_handling_methods (kind, code, display_name, is_active, sort_order, created_at, updated_at)
+SELECT 'distribution', method, method, 0, 100, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')
+FROM (
+    SELECT json_extract(payload, '$.distribution.method') AS method
+    FROM card_events
+    WHERE json_valid(payload) AND json_extract(payload, '$.distribution') IS NOT NULL
+    UNION
+    SELECT method FROM card_distributions
+);
+
+INSERT OR IGNORE INTO card_handling_methods (kind, code, display_name, is_active, sort_order, created_at, updated_at)
+SELECT 'return', method, method, 0, 100, strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours'), strftime('%Y-%m-%dT%H:%M:%S+08:00', 'now', '+8 hours')
+FROM (
+    SELECT json_extract(payload, '$.return.method') AS method
+    FROM card_events
+    WHERE json_valid(payload) AND json_extract(payload, '$.return') IS NOT NULL
+    UNION
+    SELECT method FROM card_returns
+);
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_card_handling_methods_active ON card_handling_methods(kind, is_active, sort_order);
@@ -0,0 +1,53 @@
+-- 2026.10.18.009_add_shipments.sql
+-- 合并寄件：一个顺丰订单关联多张卡片，确认订单时按运单号分发全部关联卡片，取代卡片的待处理运单号
+
+-- 寄件卡片关联（sf_orders.card_id 保留为首张卡片，兼容旧版本与订单列表展示）
+CREATE TABLE IF NOT EXISTS sf_order_cards (
+    order_id TEXT NOT NULL,  -- sf_orders.id
+    card_id TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (order_id, card_id),
+    FOREIGN KEY (order_id) REFERENCES sf_orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+);
+
+-- 回填：既有订单的单张关联卡片
+INSERT OR IGNORE INTO sf_order_cards (order_id, card_id, created_at)
+SELECT id, card_id, created_at
+FROM sf_orders
+WHERE card_id IS NOT NULL AND card_id IN (SELECT id FROM cards);
+
+-- 回填：待处理运单号关联到运单号相同的既有订单
+INSERT OR IGNORE INTO sf_order_cards (order_id, card_id, created_at)
+SELECT o.id, c.id, c.updated_at
+FROM cards c
+JOIN sf_orders o ON o.waybill_no = c.pending_waybill_no
+WHERE COALESCE(c.pending_waybill_no, '') != '';
+
+-- 回填：仍无对应订单的待处理运单号（如本地订单已删除）生成已确认的占位订单并关联卡片，
+-- 分发时可照常带出运单号
+INSERT OR IGNORE INTO sf_orders (id, order_id, waybill_no, card_id, status, sender_info, recipient_info, created_at, updated_at)
+SELECT 'legacy-' || c.id, 'LEGACY-' || c.id, c.pending_waybill_no, c.id, 'confirmed', '{}', '{}', c.updated_at, c.updated_at
+FROM cards c
+WHERE COALESCE(c.pending_waybill_no, '') != ''
+  AND NOT EXISTS (
+      SELECT 1 FROM sf_order_cards oc
+      JOIN sf_orders o ON o.id = oc.order_id
+      WHERE oc.card_id = c.id AND o.waybill_no = c.pending_waybill_no
+  );
+
+INSERT OR IGNORE INTO sf_order_cards (order_id, card_id, created_at)
+SELECT id, card_id, created_at
+FROM sf_orders
+WHERE id LIKE 'legacy-%' AND card_id IS NOT NULL;
+
+-- 分发信息记录运单号
+ALTER TABLE card_distributions ADD COLUMN waybill_no TEXT;
+
+-- 移除待处理运单号（已回填到寄件关联，历史运单号同时保留在 card_events 的 waybill_attached 事件中）
+DROP INDEX IF EXISTS idx_cards_pending_waybill;
+ALTER TABLE cards DROP COLUMN pending_waybill_no;
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_sf_order_cards_card ON sf_order_cards(card_id);
+CREATE INDEX IF NOT EXISTS idx_card_distributions_waybill ON card_distributions(waybill_no);
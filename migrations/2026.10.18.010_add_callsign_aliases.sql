@@ -0,0 +1,18 @@
+-- 2026.10.18.010_add_callsign_aliases.sql
+-- 呼号别名：记录电台换号（如 BG 升级为 BH、迁移分区），旧呼号在生效期内解析为现用呼号
+
+CREATE TABLE IF NOT EXISTS callsign_aliases (
+    id TEXT PRIMARY KEY,
+    alias TEXT NOT NULL,           -- 旧呼号（统一大写）
+    callsign TEXT NOT NULL,        -- 现用呼号（统一大写）
+    effective_from TEXT NOT NULL,  -- 生效日期（YYYY-MM-DD，含当日）
+    effective_to TEXT,             -- 失效日期（YYYY-MM-DD，含当日；为空表示长期有效，如旧呼号被重新核发则填写）
+    remarks TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    CHECK (alias != callsign)
+);
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_callsign_aliases_alias ON callsign_aliases(alias);
+CREATE INDEX IF NOT EXISTS idx_callsign_aliases_callsign ON callsign_aliases(callsign);
@@ -0,0 +1,21 @@
+-- 2026.10.18.011_add_qsl_managers.sql
+-- QSL 管理员：寄给某电台的卡片在有效期内经由其 QSL 管理员转交（X via Y），录入卡片时记录实际收卡人
+
+CREATE TABLE IF NOT EXISTS qsl_managers (
+    id TEXT PRIMARY KEY,
+    callsign TEXT NOT NULL,        -- 电台呼号（统一大写）
+    manager TEXT NOT NULL,         -- QSL 管理员呼号（统一大写）
+    effective_from TEXT,           -- 生效日期（YYYY-MM-DD，含当日；为空表示不限起始）
+    effective_to TEXT,             -- 失效日期（YYYY-MM-DD，含当日；为空表示长期有效）
+    remarks TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    CHECK (callsign != manager)
+);
+
+-- 卡片经由的 QSL 管理员（录入时按有效期自动标注）
+ALTER TABLE cards ADD COLUMN via_callsign TEXT;
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_qsl_managers_callsign ON qsl_managers(callsign);
+CREATE INDEX IF NOT EXISTS idx_cards_via_callsign ON cards(via_callsign);
@@ -0,0 +1,23 @@
+-- 2026.10.18.012_add_storage_locations.sql
+-- 存放位置：待分发卡片按柜/盒/格存放，录入时按呼号分区数字、后缀首字母或前缀自动分配格位
+
+CREATE TABLE IF NOT EXISTS storage_locations (
+    id TEXT PRIMARY KEY,
+    cabinet TEXT NOT NULL,                -- 柜
+    box_no TEXT NOT NULL,                 -- 盒（抽屉）
+    slot TEXT NOT NULL DEFAULT '',        -- 格（为空表示整盒）
+    match_prefix TEXT,                    -- 分配规则：呼号前缀，逗号分隔，如 "BH,BG"
+    match_district TEXT,                  -- 分配规则：分区数字，如 "2" 或 "1-3,5"
+    match_suffix TEXT,                    -- 分配规则：后缀首字母，如 "A-M"
+    sort_order INTEGER NOT NULL DEFAULT 0, -- 排列与规则匹配顺序（小者优先）
+    remarks TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (cabinet, box_no, slot)
+);
+
+-- 卡片存放位置（录入时按分配规则自动分配，可手动调整）
+ALTER TABLE cards ADD COLUMN storage_location_id TEXT;
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_cards_storage_location ON cards(storage_location_id);
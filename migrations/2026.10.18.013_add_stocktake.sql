@@ -0,0 +1,28 @@
+-- 2026.10.18.013_add_stocktake.sql
+-- 库存盘点：逐张扫描或录入架上卡片，与待分发清单核对，得出缺失、非待分发与无法识别的卡片
+
+-- 盘点会话
+CREATE TABLE IF NOT EXISTS stocktake_sessions (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    project_id TEXT,               -- 盘点范围（为空表示全部项目）
+    operator_id TEXT,              -- 发起盘点的操作员
+    remarks TEXT,
+    started_at TEXT NOT NULL,
+    closed_at TEXT                 -- 结束时间（为空表示进行中）
+);
+
+-- 盘点扫描记录（同一会话中每张卡片只记录一次）
+CREATE TABLE IF NOT EXISTS stocktake_scans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id TEXT NOT NULL,
+    input TEXT NOT NULL,           -- 扫描或录入的原始内容
+    card_id TEXT,                  -- 识别到的卡片（为空表示无法识别）
+    operator_id TEXT,
+    scanned_at TEXT NOT NULL,
+    FOREIGN KEY (session_id) REFERENCES stocktake_sessions(id) ON DELETE CASCADE
+);
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_stocktake_scans_session ON stocktake_scans(session_id);
+CREATE INDEX IF NOT EXISTS idx_stocktake_scans_card ON stocktake_scans(card_id);
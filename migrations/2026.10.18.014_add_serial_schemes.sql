@@ -0,0 +1,13 @@
+-- 2026.10.18.014_add_serial_schemes.sql
+-- 序列号编号方案：按项目配置前缀、年份、补零位数与起始序列号，标签与导出统一使用格式化后的序列号
+
+-- 未配置方案的项目使用默认方案（无前缀、无年份、三位补零、从 1 开始）
+-- 不设外键：导入数据时清空并重建项目表，同 ID 项目的方案得以保留（彻底删除项目时一并清理）
+CREATE TABLE IF NOT EXISTS serial_schemes (
+    project_id TEXT PRIMARY KEY,
+    prefix TEXT NOT NULL DEFAULT '',        -- 前缀（可为空）
+    year INTEGER,                           -- 年份（为空表示不含年份）
+    width INTEGER NOT NULL DEFAULT 3,       -- 数字部分补零位数
+    start_value INTEGER NOT NULL DEFAULT 1, -- 起始序列号
+    updated_at TEXT NOT NULL
+);
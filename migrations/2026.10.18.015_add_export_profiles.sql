@@ -0,0 +1,12 @@
+-- 2026.10.18.015_add_export_profiles.sql
+-- Excel 导出方案：保存导出列及顺序、工作表分组方式与是否附带汇总表，供导出时复用
+
+CREATE TABLE IF NOT EXISTS export_profiles (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    columns TEXT NOT NULL,                      -- 导出列（JSON 数组，按导出顺序）
+    group_by TEXT NOT NULL DEFAULT 'single',    -- 分组方式：single / project / status
+    include_summary INTEGER NOT NULL DEFAULT 0, -- 是否附带汇总表
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
@@ -0,0 +1,32 @@
+-- 2026.10.18.016_widen_card_history_fields.sql
+-- 修改历史新增字段：恢复卡片记为 deleted_at，保存地址记为 address（新值为地址来源）
+
+-- 重建 card_history 表以放宽 CHECK 约束（SQLite 不支持修改约束）
+-- 没有表引用 card_history，无需关闭外键；重建在同一事务中完成
+BEGIN IMMEDIATE;
+
+CREATE TABLE card_history_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    card_id TEXT NOT NULL,
+    field TEXT NOT NULL CHECK(field IN ('callsign', 'qty', 'serial', 'project_id', 'deleted_at', 'address')),
+    old_value TEXT,
+    new_value TEXT,
+    changed_by TEXT,  -- 操作人
+    changed_at TEXT NOT NULL,
+    operator_id TEXT,
+    FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+);
+
+INSERT INTO card_history_new (id, card_id, field, old_value, new_value, changed_by, changed_at, operator_id)
+SELECT id, card_id, field, old_value, new_value, changed_by, changed_at, operator_id
+FROM card_history;
+
+DROP TABLE card_history;
+
+ALTER TABLE card_history_new RENAME TO card_history;
+
+-- 重建索引
+CREATE INDEX IF NOT EXISTS idx_card_history_card ON card_history(card_id, changed_at);
+CREATE INDEX IF NOT EXISTS idx_card_history_operator ON card_history(operator_id, changed_at);
+
+COMMIT;
@@ -0,0 +1,58 @@
+// 数据库快照 Tauri 命令
+//
+// 提供前端调用的备份 API：列出 / 创建 / 恢复 / 删除快照，读写定时备份与保留设置
+
+use crate::db::{self, BackupReason, BackupSettings, BackupSnapshot};
+
+/// 列出全部快照（按创建时间从新到旧）
+#[tauri::command]
+pub async fn list_backups_cmd() -> Result<Vec<BackupSnapshot>, String> {
+    tokio::task::spawn_blocking(|| db::list_backups().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 立即创建一份手动快照
+#[tauri::command]
+pub async fn create_backup_cmd() -> Result<Option<BackupSnapshot>, String> {
+    tokio::task::spawn_blocking(|| {
+        db::create_backup(BackupReason::Manual).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 从快照恢复数据库（恢复前自动为当前数据创建快照，返回该快照）
+#[tauri::command]
+pub async fn restore_backup_cmd(file_name: String) -> Result<BackupSnapshot, String> {
+    log::info!("⏪ 从快照恢复数据库: {}", file_name);
+    tokio::task::spawn_blocking(move || db::restore_backup(&file_name).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 删除快照
+#[tauri::command]
+pub async fn delete_backup_cmd(file_name: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || db::delete_backup(&file_name).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 获取备份设置
+#[tauri::command]
+pub async fn get_backup_settings_cmd() -> Result<BackupSettings, String> {
+    tokio::task::spawn_blocking(|| db::get_backup_settings().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 保存备份设置（按新的保留策略立即清理过期快照）
+#[tauri::command]
+pub async fn save_backup_settings_cmd(settings: BackupSettings) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        db::save_backup_settings(&settings).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,68 @@
+// 呼号别名 Tauri 命令
+//
+// 提供前端调用的呼号别名 API：查询、登记、修改生效期、删除，以及录入时的别名检查
+
+use crate::db::{self, CallsignAlias};
+
+/// 查询呼号别名（`callsign` 为空时返回全部）
+#[tauri::command]
+pub async fn list_callsign_aliases_cmd(
+    callsign: Option<String>,
+) -> Result<Vec<CallsignAlias>, String> {
+    tokio::task::spawn_blocking(move || {
+        db::list_callsign_aliases(callsign.as_deref()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 检查呼号是否为当日生效的别名（录入卡片时提示现用呼号）
+#[tauri::command]
+pub async fn check_callsign_alias_cmd(callsign: String) -> Result<Option<CallsignAlias>, String> {
+    tokio::task::spawn_blocking(move || {
+        db::check_callsign_alias(&callsign).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 登记呼号别名
+#[tauri::command]
+pub async fn create_callsign_alias_cmd(
+    alias: String,
+    callsign: String,
+    effective_from: String,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<CallsignAlias, String> {
+    tokio::task::spawn_blocking(move || {
+        db::create_callsign_alias(alias, callsign, effective_from, effective_to, remarks)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 修改呼号别名的生效期与备注
+#[tauri::command]
+pub async fn update_callsign_alias_cmd(
+    id: String,
+    effective_from: String,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<CallsignAlias, String> {
+    tokio::task::spawn_blocking(move || {
+        db::update_callsign_alias(&id, effective_from, effective_to, remarks)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 删除呼号别名
+#[tauri::command]
+pub async fn delete_callsign_alias_cmd(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || db::delete_callsign_alias(&id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,32 @@
+// 卡片表格导入 Tauri 命令
+//
+// 提供前端调用的卡片导入 API：读取 .xlsx/.csv 文件预览列映射，按映射试导入或导入
+
+use crate::db::{self, CardImportMapping, CardImportPreview, CardImportReport};
+use std::path::Path;
+
+/// 读取表格文件，返回首行、数据样例与推断的列映射
+#[tauri::command]
+pub async fn preview_card_import_cmd(file_path: String) -> Result<CardImportPreview, String> {
+    tokio::task::spawn_blocking(move || {
+        db::preview_card_import_file(Path::new(&file_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 按列映射从表格文件导入卡片到项目（`dry_run` 为真时只校验、不写入）
+#[tauri::command]
+pub async fn import_cards_file_cmd(
+    project_id: String,
+    file_path: String,
+    mapping: CardImportMapping,
+    dry_run: bool,
+) -> Result<CardImportReport, String> {
+    tokio::task::spawn_blocking(move || {
+        db::import_cards_from_file(&project_id, Path::new(&file_path), &mapping, dry_run)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -2,7 +2,21 @@
 //
 // 提供前端调用的卡片管理 API
 
-use crate::db::{self, Card, CardFilter, CardStatus, PagedCards, Pagination};
+use crate::db::{
+    self, BatchProgress, BatchReport, BatchTarget, CallsignDossier, Card, CardEvent, CardFilter,
+    CardHistoryEntry, CardStatus, CardUpdate, CardWithProject, PagedCards, Pagination,
+};
+use tauri::Emitter;
+
+/// 批量操作进度事件名
+pub const CARD_BATCH_PROGRESS_EVENT: &str = "card-batch-progress";
+
+/// 推送批量操作进度到前端
+fn emit_batch_progress(app: &tauri::AppHandle, progress: &BatchProgress) {
+    if let Err(e) = app.emit(CARD_BATCH_PROGRESS_EVENT, progress) {
+        log::warn!("推送批量操作进度失败: {}", e);
+    }
+}
 
 /// 创建卡片
 #[tauri::command]
@@ -20,6 +34,8 @@ pub async fn create_card_cmd(
 }
 
 /// 查询卡片列表（分页）
+///
+/// `filter` 携带高级筛选与排序条件；`project_id`、`callsign`、`status` 若传入则覆盖其中同名字段。
 #[tauri::command]
 pub async fn list_cards_cmd(
     project_id: Option<String>,
@@ -27,13 +43,19 @@ pub async fn list_cards_cmd(
     status: Option<String>,
     page: Option<u32>,
     page_size: Option<u32>,
+    filter: Option<CardFilter>,
 ) -> Result<PagedCards, String> {
     tokio::task::spawn_blocking(move || {
-        let filter = CardFilter {
-            project_id,
-            callsign,
-            status: status.and_then(|s| CardStatus::from_str(&s)),
-        };
+        let mut filter = filter.unwrap_or_default();
+        if project_id.is_some() {
+            filter.project_id = project_id;
+        }
+        if callsign.is_some() {
+            filter.callsign = callsign;
+        }
+        if let Some(status) = status.and_then(|s| CardStatus::from_str(&s)) {
+            filter.status = Some(status);
+        }
 
         let pagination = Pagination {
             page: page.unwrap_or(1),
@@ -54,6 +76,58 @@ pub async fn get_card_cmd(id: String) -> Result<Option<Card>, String> {
         .map_err(|e| e.to_string())?
 }
 
+/// 修改卡片（呼号、数量、序列号、所属项目）
+///
+/// `clear_serial` 为 true 时清空序列号（优先于 `serial`）。
+#[tauri::command]
+pub async fn update_card_cmd(
+    id: String,
+    callsign: Option<String>,
+    qty: Option<i32>,
+    serial: Option<i32>,
+    clear_serial: Option<bool>,
+    project_id: Option<String>,
+    changed_by: Option<String>,
+) -> Result<Card, String> {
+    tokio::task::spawn_blocking(move || {
+        let serial = if clear_serial.unwrap_or(false) {
+            Some(None)
+        } else {
+            serial.map(Some)
+        };
+
+        let update = CardUpdate {
+            callsign,
+            qty,
+            serial,
+            project_id,
+        };
+        if update.is_empty() {
+            return Err("未指定需要修改的字段".to_string());
+        }
+
+        db::update_card(&id, update, changed_by).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 获取卡片修改历史
+#[tauri::command]
+pub async fn get_card_history_cmd(card_id: String) -> Result<Vec<CardHistoryEntry>, String> {
+    tokio::task::spawn_blocking(move || db::get_card_history(&card_id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 获取卡片事件日志（录入、分发、退卡、重新分发、关联运单）
+#[tauri::command]
+pub async fn get_card_events_cmd(card_id: String) -> Result<Vec<CardEvent>, String> {
+    tokio::task::spawn_blocking(move || db::list_card_events(&card_id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
 /// 分发卡片
 #[tauri::command]
 pub async fn distribute_card_cmd(
@@ -62,9 +136,11 @@ pub async fn distribute_card_cmd(
     address: Option<String>,
     remarks: Option<String>,
     proxy_callsign: Option<String>,
+    qty: Option<i32>,
 ) -> Result<Card, String> {
     tokio::task::spawn_blocking(move || {
-        db::distribute_card(&id, method, address, remarks, proxy_callsign).map_err(|e| e.to_string())
+        db::distribute_card(&id, method, address, remarks, proxy_callsign, qty)
+            .map_err(|e| e.to_string())
     })
     .await
     .map_err(|e| e.to_string())?
@@ -80,6 +156,24 @@ pub async fn get_project_callsigns_cmd(project_id: String) -> Result<Vec<String>
     .map_err(|e| e.to_string())?
 }
 
+/// 获取呼号档案（该呼号在所有项目中的卡片、统计、地址、订单与最近领取）
+#[tauri::command]
+pub async fn get_callsign_dossier_cmd(callsign: String) -> Result<CallsignDossier, String> {
+    tokio::task::spawn_blocking(move || {
+        db::get_callsign_dossier(&callsign).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 按扫描或录入的内容查找卡片（卡片编号、卡片 ID 或「呼号 序列号」）
+#[tauri::command]
+pub async fn lookup_card_cmd(input: String) -> Result<Option<CardWithProject>, String> {
+    tokio::task::spawn_blocking(move || db::lookup_card(&input).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
 /// 退回卡片
 #[tauri::command]
 pub async fn return_card_cmd(
@@ -94,7 +188,27 @@ pub async fn return_card_cmd(
     .map_err(|e| e.to_string())?
 }
 
-/// 删除卡片
+/// 变更卡片的暂存状态（待付邮资、留存、遗失，或恢复为待分发）
+#[tauri::command]
+pub async fn set_card_status_cmd(
+    id: String,
+    status: CardStatus,
+    remarks: Option<String>,
+) -> Result<Card, String> {
+    tokio::task::spawn_blocking(move || {
+        db::set_card_status(&id, status, remarks).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 获取指定状态允许流转到的状态
+#[tauri::command]
+pub fn get_card_transitions_cmd(status: CardStatus) -> Vec<CardStatus> {
+    db::allowed_card_transitions(&status).to_vec()
+}
+
+/// 删除卡片（移入回收站）
 #[tauri::command]
 pub async fn delete_card_cmd(id: String) -> Result<(), String> {
     tokio::task::spawn_blocking(move || db::delete_card(&id).map_err(|e| e.to_string()))
@@ -112,19 +226,6 @@ pub async fn get_max_serial_cmd(project_id: String) -> Result<Option<u32>, Strin
     .map_err(|e| e.to_string())?
 }
 
-/// 保存待处理运单号（不改变卡片状态）
-#[tauri::command]
-pub async fn save_pending_waybill_cmd(
-    card_id: String,
-    waybill_no: String,
-) -> Result<Card, String> {
-    tokio::task::spawn_blocking(move || {
-        db::save_pending_waybill(&card_id, waybill_no).map_err(|e| e.to_string())
-    })
-    .await
-    .map_err(|e| e.to_string())?
-}
-
 /// 保存地址到卡片
 #[tauri::command]
 pub async fn save_card_address_cmd(
@@ -151,3 +252,81 @@ pub async fn save_card_address_cmd(
     .await
     .map_err(|e| e.to_string())?
 }
+
+/// 批量分发卡片
+///
+/// `card_ids` 与 `filter` 二选一；进度通过 `card-batch-progress` 事件推送。
+#[tauri::command]
+pub async fn batch_distribute_cards_cmd(
+    app: tauri::AppHandle,
+    card_ids: Option<Vec<String>>,
+    filter: Option<CardFilter>,
+    method: String,
+    address: Option<String>,
+    remarks: Option<String>,
+    proxy_callsign: Option<String>,
+) -> Result<BatchReport, String> {
+    let target = BatchTarget::from_parts(card_ids, filter).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        db::batch_distribute_cards(target, method, address, remarks, proxy_callsign, &mut |p| {
+            emit_batch_progress(&app, p)
+        })
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 批量退卡
+#[tauri::command]
+pub async fn batch_return_cards_cmd(
+    app: tauri::AppHandle,
+    card_ids: Option<Vec<String>>,
+    filter: Option<CardFilter>,
+    method: String,
+    remarks: Option<String>,
+) -> Result<BatchReport, String> {
+    let target = BatchTarget::from_parts(card_ids, filter).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        db::batch_return_cards(target, method, remarks, &mut |p| emit_batch_progress(&app, p))
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 批量删除卡片
+#[tauri::command]
+pub async fn batch_delete_cards_cmd(
+    app: tauri::AppHandle,
+    card_ids: Option<Vec<String>>,
+    filter: Option<CardFilter>,
+) -> Result<BatchReport, String> {
+    let target = BatchTarget::from_parts(card_ids, filter).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        db::batch_delete_cards(target, &mut |p| emit_batch_progress(&app, p))
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 批量移动卡片到其他项目
+#[tauri::command]
+pub async fn batch_move_cards_cmd(
+    app: tauri::AppHandle,
+    card_ids: Option<Vec<String>>,
+    filter: Option<CardFilter>,
+    project_id: String,
+    changed_by: Option<String>,
+) -> Result<BatchReport, String> {
+    let target = BatchTarget::from_parts(card_ids, filter).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        db::batch_move_cards(target, project_id, changed_by, &mut |p| {
+            emit_batch_progress(&app, p)
+        })
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
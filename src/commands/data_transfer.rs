@@ -1,30 +1,55 @@
 // 数据传输命令
 //
-// 提供数据导出和导入的 Tauri 命令
+// 提供数据导出、覆盖导入与合并导入的 Tauri 命令
 
 use crate::db::export::{export_database, get_export_stats, ExportStats};
 use crate::db::import::{execute_import, preview_import, ImportPreview};
+use crate::db::{
+    encrypt_backup, execute_merge_import, is_encrypted_backup_file, preview_merge_import,
+    MergeOptions, MergeReport,
+};
 use std::fs;
+use std::path::Path;
 use tauri::command;
 
 /// 导出数据到文件
+///
+/// 提供口令时写出加密备份（PBKDF2 派生密钥 + AES-256-GCM），否则写出明文 JSON
 #[command]
-pub async fn export_data(file_path: String) -> Result<ExportStats, String> {
-    log::info!("📤 导出数据到: {}", file_path);
+pub async fn export_data(
+    file_path: String,
+    passphrase: Option<String>,
+) -> Result<ExportStats, String> {
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    log::info!(
+        "📤 导出数据到: {}{}",
+        file_path,
+        if passphrase.is_some() { "（加密）" } else { "" }
+    );
+
+    let stats = tokio::task::spawn_blocking(move || {
+        // 导出数据
+        let data = export_database().map_err(|e| e.to_string())?;
 
-    // 导出数据
-    let data = export_database().map_err(|e| e.to_string())?;
+        // 获取统计信息
+        let stats = get_export_stats(&data);
 
-    // 获取统计信息
-    let stats = get_export_stats(&data);
+        // 序列化为 JSON
+        let json = serde_json::to_string_pretty(&data)
+            .map_err(|e| format!("序列化数据失败: {}", e))?;
+        let json = match passphrase {
+            Some(passphrase) => encrypt_backup(&json, &passphrase).map_err(|e| e.to_string())?,
+            None => json,
+        };
 
-    // 序列化为 JSON
-    let json = serde_json::to_string_pretty(&data)
-        .map_err(|e| format!("序列化数据失败: {}", e))?;
+        // 写入文件
+        fs::write(&file_path, json)
+            .map_err(|e| format!("写入文件失败: {}", e))?;
 
-    // 写入文件
-    fs::write(&file_path, json)
-        .map_err(|e| format!("写入文件失败: {}", e))?;
+        Ok::<ExportStats, String>(stats)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
 
     log::info!(
         "✅ 数据导出成功: {} 个项目, {} 张卡片, {} 个寄件人, {} 个订单",
@@ -37,19 +62,43 @@ pub async fn export_data(file_path: String) -> Result<ExportStats, String> {
     Ok(stats)
 }
 
-/// 预览导入文件
+/// 检查导入文件是否为加密备份
+#[command]
+pub async fn is_backup_encrypted(file_path: String) -> Result<bool, String> {
+    tokio::task::spawn_blocking(move || {
+        is_encrypted_backup_file(Path::new(&file_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 预览导入文件（加密备份需提供口令）
 #[command]
-pub async fn preview_import_data(file_path: String) -> Result<ImportPreview, String> {
+pub async fn preview_import_data(
+    file_path: String,
+    passphrase: Option<String>,
+) -> Result<ImportPreview, String> {
     log::info!("📂 预览导入文件: {}", file_path);
-    preview_import(&file_path).map_err(|e| e.to_string())
+    tokio::task::spawn_blocking(move || {
+        preview_import(&file_path, passphrase.as_deref()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 /// 执行数据导入
 #[command]
-pub async fn import_data(file_path: String) -> Result<ExportStats, String> {
+pub async fn import_data(
+    file_path: String,
+    passphrase: Option<String>,
+) -> Result<ExportStats, String> {
     log::info!("📥 导入数据从: {}", file_path);
 
-    let stats = execute_import(&file_path).map_err(|e| e.to_string())?;
+    let stats = tokio::task::spawn_blocking(move || {
+        execute_import(&file_path, passphrase.as_deref()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
 
     log::info!(
         "✅ 数据导入成功: {} 个项目, {} 张卡片, {} 个寄件人, {} 个订单",
@@ -61,3 +110,33 @@ pub async fn import_data(file_path: String) -> Result<ExportStats, String> {
 
     Ok(stats)
 }
+
+/// 预览合并导入（按选项合并后回滚，返回各表统计与冲突明细）
+#[command]
+pub async fn preview_merge_import_data(
+    file_path: String,
+    options: MergeOptions,
+    passphrase: Option<String>,
+) -> Result<MergeReport, String> {
+    log::info!("📂 预览合并导入: {}", file_path);
+    tokio::task::spawn_blocking(move || {
+        preview_merge_import(&file_path, &options, passphrase.as_deref()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 执行合并导入（按 UUID 合并，不清空本地数据）
+#[command]
+pub async fn merge_import_data(
+    file_path: String,
+    options: MergeOptions,
+    passphrase: Option<String>,
+) -> Result<MergeReport, String> {
+    log::info!("📥 合并导入数据从: {}", file_path);
+    tokio::task::spawn_blocking(move || {
+        execute_merge_import(&file_path, &options, passphrase.as_deref()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -1,10 +1,15 @@
 // 卡片导出 Tauri 命令
 //
-// 提供将卡片列表导出为 Excel 文件的功能
+// 提供将卡片列表按导出方案（列及顺序、工作表分组、汇总表）导出为 Excel 文件的功能，
+// 以及导出方案的保存与管理
 
-use crate::db::{self, CardStatus, CardWithProject, Project};
+use crate::db::{
+    self, parse_datetime, CardStatus, CardWithProject, ExportColumn, ExportGroupBy,
+    ExportProfile, ExportProfileInput, HandlingMethod, HandlingMethodKind,
+};
 use chrono::Local;
-use rust_xlsxwriter::{Workbook, Format};
+use rust_xlsxwriter::{Workbook, Format, Worksheet};
+use std::collections::{HashMap, HashSet};
 use std::io::Cursor;
 
 /// 数量显示模式
@@ -45,12 +50,27 @@ fn format_qty(qty: i32, mode: QtyDisplayMode) -> String {
 fn format_status(status: &CardStatus) -> &'static str {
     match status {
         CardStatus::Pending => "待分发",
+        CardStatus::PartiallyDistributed => "部分分发",
         CardStatus::Distributed => "已分发",
         CardStatus::Returned => "已退卡",
+        CardStatus::AwaitingPostage => "待付邮资",
+        CardStatus::Held => "留存",
+        CardStatus::Lost => "已遗失",
     }
 }
 
-/// 格式化序号为三位数
+/// 格式化状态列：部分分发在精确数量模式下附带分发进度
+fn format_status_cell(card: &CardWithProject, qty_mode: QtyDisplayMode) -> String {
+    let status = format_status(&card.status);
+    match (&card.status, qty_mode) {
+        (CardStatus::PartiallyDistributed, QtyDisplayMode::Exact) => {
+            format!("{} ({}/{})", status, card.distributed_qty, card.qty)
+        }
+        _ => status.to_string(),
+    }
+}
+
+/// 格式化序号为三位数（卡片缺少按编号方案格式化的序列号时使用）
 fn format_serial(serial: Option<i32>) -> String {
     match serial {
         Some(s) => format!("{:03}", s),
@@ -117,85 +137,307 @@ fn extract_qrz_cache_columns(card: &CardWithProject) -> (String, String, String)
     (qrz_cn_chinese, qrz_cn_english, qrz_com_english)
 }
 
-/// 生成 Excel 文件内容
-fn generate_excel(cards: &[CardWithProject], qty_mode: QtyDisplayMode) -> Result<Vec<u8>, String> {
-    let mut workbook = Workbook::new();
-    let worksheet = workbook.add_worksheet();
+/// 分组与汇总表中状态的排列顺序
+const STATUS_ORDER: [CardStatus; 7] = [
+    CardStatus::Pending,
+    CardStatus::PartiallyDistributed,
+    CardStatus::Distributed,
+    CardStatus::Returned,
+    CardStatus::AwaitingPostage,
+    CardStatus::Held,
+    CardStatus::Lost,
+];
+
+/// 汇总表名称
+const SUMMARY_SHEET_NAME: &str = "汇总";
+
+/// 不分组时的工作表名称
+const CARDS_SHEET_NAME: &str = "卡片";
+
+/// Excel 工作表名称最大长度
+const MAX_SHEET_NAME_LEN: usize = 31;
+
+/// 分发方式 / 退卡原因取值到显示名称的映射（未登记的取值原样输出）
+#[derive(Default)]
+struct MethodNames {
+    distribution: HashMap<String, String>,
+    returns: HashMap<String, String>,
+}
+
+impl MethodNames {
+    fn new(methods: Vec<HandlingMethod>) -> Self {
+        let mut names = Self::default();
+        for method in methods {
+            let map = match method.kind {
+                HandlingMethodKind::Distribution => &mut names.distribution,
+                HandlingMethodKind::Return => &mut names.returns,
+            };
+            map.insert(method.code, method.display_name);
+        }
+        names
+    }
+
+    fn lookup(map: &HashMap<String, String>, code: &str) -> String {
+        map.get(code).cloned().unwrap_or_else(|| code.to_string())
+    }
+}
+
+/// 格式化时间列为「年-月-日 时:分」（无法解析时原样输出）
+fn format_time(value: &str) -> String {
+    parse_datetime(value)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// 单元格内容
+fn format_cell(
+    card: &CardWithProject,
+    column: ExportColumn,
+    qty_mode: QtyDisplayMode,
+    names: &MethodNames,
+) -> String {
+    let distribution = card.metadata.as_ref().and_then(|m| m.distribution.as_ref());
+    let return_info = card.metadata.as_ref().and_then(|m| m.return_info.as_ref());
+
+    match column {
+        // 序号（按项目编号方案格式化）
+        ExportColumn::Serial => card
+            .serial_display
+            .clone()
+            .unwrap_or_else(|| format_serial(card.serial)),
+        // 呼号（经由 QSL 管理员时为「X via Y」）
+        ExportColumn::Callsign => card.callsign_via(),
+        ExportColumn::Project => card.project_name.clone(),
+        ExportColumn::Qty => format_qty(card.qty, qty_mode),
+        ExportColumn::Status => format_status_cell(card, qty_mode),
+        ExportColumn::StorageLocation => card.storage_location.clone().unwrap_or_default(),
+        ExportColumn::DistributionMethod => distribution
+            .map(|d| MethodNames::lookup(&names.distribution, &d.method))
+            .unwrap_or_default(),
+        ExportColumn::ProxyCallsign => distribution
+            .and_then(|d| d.proxy_callsign.clone())
+            .unwrap_or_default(),
+        ExportColumn::DistributionAddress => distribution
+            .and_then(|d| d.address.clone())
+            .unwrap_or_default(),
+        ExportColumn::WaybillNo => distribution
+            .and_then(|d| d.waybill_no.clone())
+            .unwrap_or_default(),
+        ExportColumn::DistributedAt => distribution
+            .map(|d| format_time(&d.distributed_at))
+            .unwrap_or_default(),
+        ExportColumn::ReturnReason => return_info
+            .map(|r| MethodNames::lookup(&names.returns, &r.method))
+            .unwrap_or_default(),
+        ExportColumn::ReturnedAt => return_info
+            .map(|r| format_time(&r.returned_at))
+            .unwrap_or_default(),
+        // QRZ 缓存地址列（仅使用缓存，无缓存留空）
+        ExportColumn::QrzCnChinese => extract_qrz_cache_columns(card).0,
+        ExportColumn::QrzCnEnglish => extract_qrz_cache_columns(card).1,
+        ExportColumn::QrzComEnglish => extract_qrz_cache_columns(card).2,
+        ExportColumn::CreatedAt => format_time(&card.created_at),
+    }
+}
+
+/// 列宽
+fn column_width(column: ExportColumn) -> f64 {
+    match column {
+        ExportColumn::Serial | ExportColumn::Qty | ExportColumn::Status => 10.0,
+        ExportColumn::Callsign => 22.0,
+        ExportColumn::Project => 20.0,
+        ExportColumn::StorageLocation
+        | ExportColumn::DistributionMethod
+        | ExportColumn::ProxyCallsign => 14.0,
+        ExportColumn::WaybillNo
+        | ExportColumn::DistributedAt
+        | ExportColumn::ReturnReason
+        | ExportColumn::ReturnedAt
+        | ExportColumn::CreatedAt => 18.0,
+        ExportColumn::DistributionAddress
+        | ExportColumn::QrzCnChinese
+        | ExportColumn::QrzCnEnglish
+        | ExportColumn::QrzComEnglish => 36.0,
+    }
+}
+
+/// 按分组方式拆分卡片（项目按卡片中首次出现的顺序，状态按 [`STATUS_ORDER`]，不含空组）
+fn group_cards(
+    cards: &[CardWithProject],
+    group_by: ExportGroupBy,
+) -> Vec<(String, Vec<&CardWithProject>)> {
+    match group_by {
+        ExportGroupBy::Single => vec![(CARDS_SHEET_NAME.to_string(), cards.iter().collect())],
+        ExportGroupBy::Project => {
+            let mut groups: Vec<(String, Vec<&CardWithProject>)> = Vec::new();
+            let mut index: HashMap<&str, usize> = HashMap::new();
+            for card in cards {
+                let i = *index.entry(&card.project_id).or_insert_with(|| {
+                    groups.push((card.project_name.clone(), Vec::new()));
+                    groups.len() - 1
+                });
+                groups[i].1.push(card);
+            }
+            groups
+        }
+        ExportGroupBy::Status => STATUS_ORDER
+            .iter()
+            .map(|status| {
+                let group: Vec<&CardWithProject> =
+                    cards.iter().filter(|c| &c.status == status).collect();
+                (format_status(status).to_string(), group)
+            })
+            .filter(|(_, group)| !group.is_empty())
+            .collect(),
+    }
+}
+
+/// 生成合法且不重复的工作表名称（去除非法字符、截断至 31 个字符，重名时追加序号）
+fn unique_sheet_name(name: &str, used: &mut HashSet<String>) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('\'');
+    let base = if cleaned.is_empty() { "未命名" } else { cleaned };
+
+    let mut n = 1;
+    loop {
+        let suffix = if n == 1 { String::new() } else { format!("({})", n) };
+        let keep = MAX_SHEET_NAME_LEN - suffix.chars().count();
+        let candidate = format!("{}{}", base.chars().take(keep).collect::<String>(), suffix);
+        // Excel 工作表名称不区分大小写
+        if used.insert(candidate.to_lowercase()) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
 
-    // 设置表头格式
+/// 写入卡片工作表
+fn write_cards_sheet(
+    worksheet: &mut Worksheet,
+    cards: &[&CardWithProject],
+    columns: &[ExportColumn],
+    qty_mode: QtyDisplayMode,
+    names: &MethodNames,
+) -> Result<(), String> {
     let header_format = Format::new().set_bold();
 
-    // 写入表头
-    worksheet
-        .write_string_with_format(0, 0, "序号", &header_format)
-        .map_err(|e| format!("写入表头失败: {}", e))?;
-    worksheet
-        .write_string_with_format(0, 1, "呼号", &header_format)
-        .map_err(|e| format!("写入表头失败: {}", e))?;
-    worksheet
-        .write_string_with_format(0, 2, "数量", &header_format)
-        .map_err(|e| format!("写入表头失败: {}", e))?;
-    worksheet
-        .write_string_with_format(0, 3, "状态", &header_format)
-        .map_err(|e| format!("写入表头失败: {}", e))?;
-    worksheet
-        .write_string_with_format(0, 4, "QRZ.cn(中文)", &header_format)
-        .map_err(|e| format!("写入表头失败: {}", e))?;
-    worksheet
-        .write_string_with_format(0, 5, "QRZ.cn(English)", &header_format)
-        .map_err(|e| format!("写入表头失败: {}", e))?;
-    worksheet
-        .write_string_with_format(0, 6, "QRZ.com", &header_format)
-        .map_err(|e| format!("写入表头失败: {}", e))?;
-
-    // 写入数据行
+    for (col, column) in columns.iter().enumerate() {
+        let col = col as u16;
+        worksheet
+            .write_string_with_format(0, col, column.header(), &header_format)
+            .map_err(|e| format!("写入表头失败: {}", e))?;
+        worksheet.set_column_width(col, column_width(*column)).ok();
+    }
+
     for (i, card) in cards.iter().enumerate() {
         let row = (i + 1) as u32;
+        for (col, column) in columns.iter().enumerate() {
+            worksheet
+                .write_string(row, col as u16, format_cell(card, *column, qty_mode, names))
+                .map_err(|e| format!("写入数据失败: {}", e))?;
+        }
+    }
 
-        // 序号
-        let serial_str = format_serial(card.serial);
-        worksheet
-            .write_string(row, 0, &serial_str)
-            .map_err(|e| format!("写入数据失败: {}", e))?;
+    Ok(())
+}
 
-        // 呼号
-        worksheet
-            .write_string(row, 1, &card.callsign)
-            .map_err(|e| format!("写入数据失败: {}", e))?;
+/// 写入汇总表：每个分组一行（卡片数、卡片总数量与各状态卡片数），末行合计
+fn write_summary_sheet(
+    worksheet: &mut Worksheet,
+    groups: &[(String, Vec<&CardWithProject>)],
+    group_by: ExportGroupBy,
+) -> Result<(), String> {
+    let header_format = Format::new().set_bold();
+    let group_header = match group_by {
+        ExportGroupBy::Single => "范围",
+        ExportGroupBy::Project => "项目",
+        ExportGroupBy::Status => "状态",
+    };
 
-        // 数量
-        let qty_str = format_qty(card.qty, qty_mode);
+    let mut headers = vec![group_header, "卡片数", "卡片总数量"];
+    headers.extend(STATUS_ORDER.iter().map(format_status));
+    for (col, header) in headers.iter().enumerate() {
         worksheet
-            .write_string(row, 2, &qty_str)
-            .map_err(|e| format!("写入数据失败: {}", e))?;
+            .write_string_with_format(0, col as u16, *header, &header_format)
+            .map_err(|e| format!("写入表头失败: {}", e))?;
+    }
+    worksheet.set_column_width(0, 24).ok();
 
-        // 状态
-        let status_str = format_status(&card.status);
-        worksheet
-            .write_string(row, 3, status_str)
-            .map_err(|e| format!("写入数据失败: {}", e))?;
+    let all: Vec<&CardWithProject> = groups.iter().flat_map(|(_, g)| g.iter().copied()).collect();
+    let total_label = "合计".to_string();
+    let rows = groups
+        .iter()
+        .map(|(name, group)| (name, group, None))
+        .chain(std::iter::once((&total_label, &all, Some(&header_format))));
 
-        // QRZ 缓存地址列（仅使用缓存，无缓存留空）
-        let (qrz_cn_chinese, qrz_cn_english, qrz_com_english) = extract_qrz_cache_columns(card);
-        worksheet
-            .write_string(row, 4, &qrz_cn_chinese)
-            .map_err(|e| format!("写入数据失败: {}", e))?;
-        worksheet
-            .write_string(row, 5, &qrz_cn_english)
-            .map_err(|e| format!("写入数据失败: {}", e))?;
+    for (i, (name, group, format)) in rows.enumerate() {
+        let row = (i + 1) as u32;
+        let mut values = vec![
+            group.len() as f64,
+            group.iter().map(|c| f64::from(c.qty)).sum(),
+        ];
+        values.extend(
+            STATUS_ORDER
+                .iter()
+                .map(|status| group.iter().filter(|c| &c.status == status).count() as f64),
+        );
+
+        let label = if group_by == ExportGroupBy::Single && format.is_none() {
+            "全部卡片"
+        } else {
+            name.as_str()
+        };
+        let result = match format {
+            Some(format) => worksheet.write_string_with_format(row, 0, label, format),
+            None => worksheet.write_string(row, 0, label),
+        };
+        result.map_err(|e| format!("写入数据失败: {}", e))?;
+        for (col, value) in values.into_iter().enumerate() {
+            let col = (col + 1) as u16;
+            let result = match format {
+                Some(format) => worksheet.write_number_with_format(row, col, value, format),
+                None => worksheet.write_number(row, col, value),
+            };
+            result.map_err(|e| format!("写入数据失败: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 生成 Excel 文件内容
+///
+/// 按导出方案的列与顺序写入，按分组方式拆分为多个工作表，需要时在最前面添加汇总表。
+fn generate_excel(
+    cards: &[CardWithProject],
+    qty_mode: QtyDisplayMode,
+    profile: &ExportProfileInput,
+    names: &MethodNames,
+) -> Result<Vec<u8>, String> {
+    let mut workbook = Workbook::new();
+    let groups = group_cards(cards, profile.group_by);
+    let mut used_names = HashSet::new();
+
+    if profile.include_summary {
+        let name = unique_sheet_name(SUMMARY_SHEET_NAME, &mut used_names);
+        let worksheet = workbook.add_worksheet();
         worksheet
-            .write_string(row, 6, &qrz_com_english)
-            .map_err(|e| format!("写入数据失败: {}", e))?;
+            .set_name(name)
+            .map_err(|e| format!("设置工作表名称失败: {}", e))?;
+        write_summary_sheet(worksheet, &groups, profile.group_by)?;
     }
 
-    // 设置列宽
-    worksheet.set_column_width(0, 10).ok(); // 序号
-    worksheet.set_column_width(1, 15).ok(); // 呼号
-    worksheet.set_column_width(2, 10).ok(); // 数量
-    worksheet.set_column_width(3, 10).ok(); // 状态
-    worksheet.set_column_width(4, 36).ok(); // QRZ.cn(中文)
-    worksheet.set_column_width(5, 36).ok(); // QRZ.cn(English)
-    worksheet.set_column_width(6, 36).ok(); // QRZ.com
+    for (name, group) in &groups {
+        let name = unique_sheet_name(name, &mut used_names);
+        let worksheet = workbook.add_worksheet();
+        worksheet
+            .set_name(name)
+            .map_err(|e| format!("设置工作表名称失败: {}", e))?;
+        write_cards_sheet(worksheet, group, &profile.columns, qty_mode, names)?;
+    }
 
     // 保存到内存
     let mut buffer = Cursor::new(Vec::new());
@@ -221,33 +463,52 @@ pub struct ExportResult {
 
 /// 导出卡片到 Excel
 ///
-/// 返回导出结果，包含是否成功、文件路径或错误信息
+/// 返回导出结果，包含是否成功、文件路径或错误信息。
+/// `project_id` 为空时导出全部项目的卡片；`filter` 可选，用于按高级筛选条件与排序导出
+/// （项目固定为 `project_id`）；`profile` 为导出方案（列及顺序、分组与汇总表），为空时按默认列导出。
 #[tauri::command]
 pub async fn export_cards_to_excel(
     app: tauri::AppHandle,
-    project_id: String,
+    project_id: Option<String>,
     qty_display_mode: String,
+    filter: Option<db::CardFilter>,
+    profile: Option<ExportProfileInput>,
 ) -> Result<ExportResult, String> {
     use tauri_plugin_dialog::DialogExt;
 
+    let profile = profile.unwrap_or_default();
+    if profile.columns.is_empty() {
+        return Err("至少选择一列导出".to_string());
+    }
+
     // 在后台线程中查询数据
-    let project_id_clone = project_id.clone();
-    let (project, cards) = tokio::task::spawn_blocking(move || {
-        // 获取项目信息
-        let project = db::get_project(&project_id_clone)
-            .map_err(|e| format!("获取项目失败: {}", e))?
-            .ok_or_else(|| format!("项目不存在: {}", project_id_clone))?;
+    let (scope_name, cards, methods) = tokio::task::spawn_blocking(move || {
+        // 获取项目信息（未指定项目时导出全部项目）
+        let scope_name = match &project_id {
+            Some(project_id) => {
+                db::get_project(project_id)
+                    .map_err(|e| format!("获取项目失败: {}", e))?
+                    .ok_or_else(|| format!("项目不存在: {}", project_id))?
+                    .name
+            }
+            None => "全部项目".to_string(),
+        };
 
         // 获取所有卡片（不分页，避免分页上限截断）
         let filter = db::CardFilter {
-            project_id: Some(project_id_clone),
-            callsign: None,
-            status: None,
+            project_id,
+            ..filter.unwrap_or_default()
         };
         let cards = db::list_all_cards(filter)
             .map_err(|e| format!("获取卡片列表失败: {}", e))?;
 
-        Ok::<(Project, Vec<CardWithProject>), String>((project, cards))
+        // 分发方式与退卡原因的显示名称（含已停用的取值）
+        let methods = db::list_handling_methods(None, true)
+            .map_err(|e| format!("获取处理方式失败: {}", e))?;
+
+        Ok::<(String, Vec<CardWithProject>, Vec<HandlingMethod>), String>((
+            scope_name, cards, methods,
+        ))
     })
     .await
     .map_err(|e| format!("任务执行失败: {}", e))??;
@@ -264,10 +525,10 @@ pub async fn export_cards_to_excel(
 
     // 生成 Excel 内容
     let qty_mode = QtyDisplayMode::from_str(&qty_display_mode);
-    let excel_data = generate_excel(&cards, qty_mode)?;
+    let excel_data = generate_excel(&cards, qty_mode, &profile, &MethodNames::new(methods))?;
 
     // 生成默认文件名
-    let default_filename = generate_filename(&project.name);
+    let default_filename = generate_filename(&scope_name);
 
     // 使用 channel 等待对话框结果
     let (tx, rx) = tokio::sync::oneshot::channel();
@@ -312,10 +573,41 @@ pub async fn export_cards_to_excel(
     })
 }
 
+/// 列出已保存的导出方案
+#[tauri::command]
+pub async fn list_export_profiles_cmd() -> Result<Vec<ExportProfile>, String> {
+    tokio::task::spawn_blocking(|| db::list_export_profiles().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 保存导出方案（`id` 为空时新建）
+#[tauri::command]
+pub async fn save_export_profile_cmd(
+    id: Option<String>,
+    input: ExportProfileInput,
+) -> Result<ExportProfile, String> {
+    tokio::task::spawn_blocking(move || {
+        db::save_export_profile(id.as_deref(), input).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 删除导出方案
+#[tauri::command]
+pub async fn delete_export_profile_cmd(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        db::delete_export_profile(&id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::{AddressEntry, CardMetadata};
+    use crate::db::{AddressEntry, CardMetadata, DistributionInfo};
 
     #[test]
     fn test_format_serial() {
@@ -349,8 +641,22 @@ mod tests {
     #[test]
     fn test_format_status() {
         assert_eq!(format_status(&CardStatus::Pending), "待分发");
+        assert_eq!(format_status(&CardStatus::PartiallyDistributed), "部分分发");
         assert_eq!(format_status(&CardStatus::Distributed), "已分发");
         assert_eq!(format_status(&CardStatus::Returned), "已退卡");
+        assert_eq!(format_status(&CardStatus::AwaitingPostage), "待付邮资");
+        assert_eq!(format_status(&CardStatus::Held), "留存");
+        assert_eq!(format_status(&CardStatus::Lost), "已遗失");
+    }
+
+    #[test]
+    fn test_format_status_cell_partial_progress() {
+        let mut card = sample_card(None);
+        card.qty = 5;
+        card.distributed_qty = 2;
+        card.status = CardStatus::PartiallyDistributed;
+        assert_eq!(format_status_cell(&card, QtyDisplayMode::Exact), "部分分发 (2/5)");
+        assert_eq!(format_status_cell(&card, QtyDisplayMode::Approximate), "部分分发");
     }
 
     #[test]
@@ -376,9 +682,14 @@ mod tests {
             project_id: "project-1".to_string(),
             project_name: "Project".to_string(),
             callsign: "BH2ABC".to_string(),
+            via_callsign: None,
             qty: 1,
             serial: Some(1),
+            serial_display: Some("001".to_string()),
             status: CardStatus::Pending,
+            distributed_qty: 0,
+            storage_location_id: None,
+            storage_location: None,
             metadata,
             created_at: "2026-02-11T00:00:00+08:00".to_string(),
             updated_at: "2026-02-11T00:00:00+08:00".to_string(),
@@ -448,4 +759,69 @@ mod tests {
         assert_eq!(cn_en, "Shanghai, China");
         assert!(com_en.is_empty());
     }
+
+    #[test]
+    fn test_unique_sheet_name() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_sheet_name("汇总", &mut used), "汇总");
+        assert_eq!(unique_sheet_name("汇总", &mut used), "汇总(2)");
+        assert_eq!(unique_sheet_name("A/B:C?", &mut used), "A_B_C_");
+        assert_eq!(unique_sheet_name("  ", &mut used), "未命名");
+
+        let long = "X".repeat(40);
+        let first = unique_sheet_name(&long, &mut used);
+        let second = unique_sheet_name(&long.to_lowercase(), &mut used);
+        assert_eq!(first.chars().count(), MAX_SHEET_NAME_LEN);
+        assert_eq!(second, format!("{}(2)", "x".repeat(MAX_SHEET_NAME_LEN - 3)));
+    }
+
+    #[test]
+    fn test_group_cards_and_format_cell() {
+        let mut distributed = sample_card(Some(CardMetadata {
+            distribution: Some(DistributionInfo {
+                method: "express".to_string(),
+                address: None,
+                remarks: None,
+                proxy_callsign: None,
+                qty: None,
+                waybill_no: Some("SF1234567890".to_string()),
+                distributed_at: "2026-02-12T09:30:00+08:00".to_string(),
+            }),
+            ..Default::default()
+        }));
+        distributed.status = CardStatus::Distributed;
+        let mut other_project = sample_card(None);
+        other_project.project_id = "project-2".to_string();
+        other_project.project_name = "Other".to_string();
+        let cards = vec![distributed, other_project, sample_card(None)];
+
+        let by_project = group_cards(&cards, ExportGroupBy::Project);
+        assert_eq!(by_project.len(), 2);
+        assert_eq!(by_project[0].0, "Project");
+        assert_eq!(by_project[0].1.len(), 2);
+
+        let by_status = group_cards(&cards, ExportGroupBy::Status);
+        let names: Vec<&str> = by_status.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["待分发", "已分发"]);
+
+        let names = MethodNames {
+            distribution: HashMap::from([("express".to_string(), "快递".to_string())]),
+            returns: HashMap::new(),
+        };
+        let card = &cards[0];
+        let cell = |column| format_cell(card, column, QtyDisplayMode::Exact, &names);
+        assert_eq!(cell(ExportColumn::DistributionMethod), "快递");
+        assert_eq!(cell(ExportColumn::WaybillNo), "SF1234567890");
+        assert_eq!(cell(ExportColumn::DistributedAt), "2026-02-12 09:30");
+        assert!(cell(ExportColumn::ReturnReason).is_empty());
+
+        let profile = ExportProfileInput {
+            group_by: ExportGroupBy::Status,
+            include_summary: true,
+            ..Default::default()
+        };
+        assert!(!generate_excel(&cards, QtyDisplayMode::Exact, &profile, &names)
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -2,6 +2,7 @@
 //
 // 清除所有用户数据并重置应用
 
+use crate::db::{create_backup, BackupReason};
 use crate::security::clear_all_credentials;
 use rusqlite::Connection;
 use std::path::{Path, PathBuf};
@@ -48,6 +49,14 @@ fn clear_and_delete_database(db_path: &Path) -> Result<(), String> {
     std::fs::remove_file(db_path)
         .map_err(|e| format!("无法删除数据库文件: {}", e))?;
 
+    // WAL 模式的附属文件（连接全部关闭后通常已不存在）
+    for suffix in ["-wal", "-shm"] {
+        let path = PathBuf::from(format!("{}{}", db_path.display(), suffix));
+        if path.exists() {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+
     Ok(())
 }
 
@@ -91,6 +100,7 @@ fn get_config_dir() -> Result<PathBuf, String> {
 ///
 /// 保留：
 /// - 呼号模板 (templates/callsign.toml)
+/// - 数据库快照 (backups/，删除前会先创建一份快照)
 #[tauri::command]
 pub async fn factory_reset() -> Result<(), String> {
     log::info!("开始执行恢复出厂设置...");
@@ -99,14 +109,16 @@ pub async fn factory_reset() -> Result<(), String> {
     let mut errors = Vec::new();
 
     // 1. 清空并删除数据库文件
-    #[cfg(debug_assertions)]
-    let db_path = PathBuf::from("data").join("cards.db");
-    #[cfg(not(debug_assertions))]
-    let db_path = config_dir.join("cards.db");
+    let db_path = crate::db::get_db_path().map_err(|e| e.to_string())?;
 
     if db_path.exists() {
-        // 先清空数据库内容，然后关闭连接再删除文件
+        // 删除前先备份（快照目录不随出厂设置删除，备份失败则中止）
+        create_backup(BackupReason::PreFactoryReset)
+            .map_err(|e| format!("恢复出厂设置前备份失败: {}", e))?;
+
+        // 先关闭连接池中的连接并清空数据库内容，然后关闭连接再删除文件
         // 这样可以避免 Windows 上的文件锁问题
+        crate::db::sqlite::close_all_connections();
         if let Err(e) = clear_and_delete_database(&db_path) {
             log::error!("清空数据库失败: {}", e);
             errors.push(format!("清空数据库失败: {}", e));
@@ -0,0 +1,67 @@
+// 分发方式与退卡原因 Tauri 命令
+//
+// 提供前端调用的登记表 API：查询、登记、修改（显示名称、必填字段、启用状态）
+
+use crate::db::{self, HandlingMethod, HandlingMethodKind, HandlingMethodUpdate};
+
+/// 查询分发方式或退卡原因
+///
+/// `kind` 为空时返回全部类别；默认仅返回启用项。
+#[tauri::command]
+pub async fn list_handling_methods_cmd(
+    kind: Option<HandlingMethodKind>,
+    include_inactive: Option<bool>,
+) -> Result<Vec<HandlingMethod>, String> {
+    tokio::task::spawn_blocking(move || {
+        db::list_handling_methods(kind, include_inactive.unwrap_or(false))
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 登记分发方式或退卡原因
+#[tauri::command]
+pub async fn create_handling_method_cmd(
+    kind: HandlingMethodKind,
+    code: String,
+    display_name: Option<String>,
+) -> Result<HandlingMethod, String> {
+    tokio::task::spawn_blocking(move || {
+        db::create_handling_method(kind, code, display_name).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 修改登记项（仅修改传入的字段）
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_handling_method_cmd(
+    kind: HandlingMethodKind,
+    code: String,
+    display_name: Option<String>,
+    requires_address: Option<bool>,
+    requires_proxy_callsign: Option<bool>,
+    requires_remarks: Option<bool>,
+    is_active: Option<bool>,
+    sort_order: Option<i32>,
+) -> Result<HandlingMethod, String> {
+    tokio::task::spawn_blocking(move || {
+        let update = HandlingMethodUpdate {
+            display_name,
+            requires_address,
+            requires_proxy_callsign,
+            requires_remarks,
+            is_active,
+            sort_order,
+        };
+        if update.is_empty() {
+            return Err("未指定需要修改的字段".to_string());
+        }
+
+        db::update_handling_method(kind, &code, update).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -3,11 +3,16 @@
 // 定义所有暴露给前端的 API
 
 pub mod app_settings;
+pub mod backups;
+pub mod callsign_aliases;
+pub mod card_import;
 pub mod cards;
 pub mod data_transfer;
 pub mod export;
 pub mod factory_reset;
+pub mod handling_methods;
 pub mod logger;
+pub mod operators;
 pub mod platform;
 pub mod printer;
 pub mod profile;
@@ -15,7 +20,12 @@ pub mod projects;
 pub mod qrz_cn;
 pub mod qrz_com;
 pub mod qrz_herbertgao;
+pub mod qsl_managers;
+pub mod recycle_bin;
 pub mod security;
+pub mod serial_schemes;
 pub mod sf_express;
+pub mod stocktake;
+pub mod storage_locations;
 pub mod sync;
 pub mod tspl_config;
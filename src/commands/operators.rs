@@ -0,0 +1,101 @@
+// 操作员 Tauri 命令
+//
+// 提供前端调用的操作员 API：管理、登录/注销、工作量统计
+
+use crate::db::{self, Operator, OperatorStats};
+
+/// 创建操作员
+#[tauri::command]
+pub async fn create_operator_cmd(
+    name: String,
+    callsign: Option<String>,
+    pin: Option<String>,
+) -> Result<Operator, String> {
+    tokio::task::spawn_blocking(move || {
+        db::create_operator(name, callsign, pin).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 列出所有操作员
+#[tauri::command]
+pub async fn list_operators_cmd() -> Result<Vec<Operator>, String> {
+    tokio::task::spawn_blocking(|| db::list_operators().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 修改操作员姓名与呼号
+#[tauri::command]
+pub async fn update_operator_cmd(
+    id: String,
+    name: String,
+    callsign: Option<String>,
+    pin: Option<String>,
+) -> Result<Operator, String> {
+    tokio::task::spawn_blocking(move || {
+        db::update_operator(&id, name, callsign, pin).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 设置或清除操作员 PIN
+#[tauri::command]
+pub async fn set_operator_pin_cmd(
+    id: String,
+    current_pin: Option<String>,
+    new_pin: Option<String>,
+) -> Result<Operator, String> {
+    tokio::task::spawn_blocking(move || {
+        db::set_operator_pin(&id, current_pin, new_pin).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 删除操作员
+#[tauri::command]
+pub async fn delete_operator_cmd(id: String, pin: Option<String>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || db::delete_operator(&id, pin).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 登录为当前操作员
+#[tauri::command]
+pub async fn login_operator_cmd(id: String, pin: Option<String>) -> Result<Operator, String> {
+    tokio::task::spawn_blocking(move || db::login_operator(&id, pin).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 注销当前操作员
+#[tauri::command]
+pub async fn logout_operator_cmd() -> Result<(), String> {
+    tokio::task::spawn_blocking(|| db::logout_operator().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 获取当前登录的操作员
+#[tauri::command]
+pub async fn get_current_operator_cmd() -> Result<Option<Operator>, String> {
+    tokio::task::spawn_blocking(|| db::get_current_operator().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 按操作员统计工作量
+#[tauri::command]
+pub async fn get_operator_stats_cmd(
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<OperatorStats>, String> {
+    tokio::task::spawn_blocking(move || {
+        db::get_operator_stats(from, to).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -56,6 +56,51 @@ fn inject_label_title(config: &mut TemplateConfig, data: &mut HashMap<String, St
     }
 }
 
+/// 标签条码内容配置键：`callsign`（默认）为呼号，`card_code` 为卡片编号
+const LABEL_BARCODE_CONTENT_KEY: &str = "label_barcode_content";
+
+/// 按卡片补充运行时数据，并按 app_settings 中的 label_barcode_content 配置切换 barcode 元素的内容
+///
+/// 运行时数据含 `card_id` 时补充 `card_code`（卡片编号，模板中可直接引用 `{card_code}`），
+/// 并以按项目编号方案格式化的序列号覆盖 `sn`，使标签与列表、导出一致；
+/// 配置为 `card_code` 且运行时数据含卡片编号时，将 barcode 元素的 format 改为 `{card_code}`，
+/// 扫码即可识别到单张卡片。
+fn inject_card_fields(config: &mut TemplateConfig, data: &mut HashMap<String, String>) {
+    if let Some(card_id) = data.get("card_id").cloned() {
+        if !data.contains_key("card_code") {
+            data.insert("card_code".to_string(), crate::db::card_code(&card_id));
+        }
+        match crate::db::format_card_serial(&card_id) {
+            Ok(Some(sn)) => {
+                data.insert("sn".to_string(), sn);
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("读取卡片序列号失败: {}，使用传入的序列号", e),
+        }
+    }
+
+    match crate::db::app_settings::get_setting(LABEL_BARCODE_CONTENT_KEY) {
+        Ok(Some(content)) if content == "card_code" => {
+            if !data.contains_key("card_code") {
+                log::debug!("运行时数据中无卡片编号，条码使用模板配置");
+                return;
+            }
+            for element in &mut config.elements {
+                if element.id == "barcode" && element.element_type == "barcode" {
+                    element.source = "computed".to_string();
+                    element.format = Some("{card_code}".to_string());
+                    log::info!("已将 barcode 元素切换为卡片编号");
+                    break;
+                }
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            log::warn!("读取 label_barcode_content 配置失败: {}，使用模板配置", e);
+        }
+    }
+}
+
 /// 加载模板配置
 ///
 /// 优先级：
@@ -120,6 +165,61 @@ fn get_address_template_path() -> PathBuf {
     }
 }
 
+/// 获取存放位置标签模板文件路径
+fn get_storage_template_path() -> PathBuf {
+    // 开发模式：config/templates/storage_slot.toml
+    #[cfg(debug_assertions)]
+    {
+        PathBuf::from("config/templates/storage_slot.toml")
+    }
+
+    // 生产模式：使用系统配置目录
+    #[cfg(not(debug_assertions))]
+    {
+        let config_dir = dirs::config_dir()
+            .unwrap_or_else(|| dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")));
+        config_dir.join("qsl-cardhub").join("templates").join("storage_slot.toml")
+    }
+}
+
+/// 构建存放位置标签的打印请求
+///
+/// 数据键：`code`（位置编号）、`cabinet`、`box_no`、`slot`、`rule`（分配规则说明）。
+/// 整盒位置的 `slot` 为空字符串，模板中宜引用 `code`。
+async fn build_storage_label_request(location_id: String) -> Result<PrintRequest, String> {
+    let location = tokio::task::spawn_blocking(move || {
+        crate::db::get_storage_location(&location_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("存放位置不存在: {}", location_id))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let rule = [
+        ("前缀", &location.match_prefix),
+        ("分区", &location.match_district),
+        ("后缀", &location.match_suffix),
+    ]
+    .iter()
+    .filter_map(|(name, value)| value.as_ref().map(|v| format!("{} {}", name, v)))
+    .collect::<Vec<_>>()
+    .join(" / ");
+    // 模板元素不支持空文本，未设置规则时打印说明文字
+    let rule = if rule.is_empty() { "手动存放".to_string() } else { rule };
+
+    let mut data = HashMap::new();
+    data.insert("code".to_string(), location.code);
+    data.insert("cabinet".to_string(), location.cabinet);
+    data.insert("box_no".to_string(), location.box_no);
+    data.insert("slot".to_string(), location.slot);
+    data.insert("rule".to_string(), rule);
+
+    Ok(PrintRequest {
+        template_path: Some(get_storage_template_path().to_string_lossy().to_string()),
+        data,
+    })
+}
+
 /// 加载地址模板配置
 fn load_address_template_config() -> Result<TemplateConfig, String> {
     let address_template_path = get_address_template_path();
@@ -281,9 +381,10 @@ pub async fn preview_qsl(
     // 1. 加载模板配置
     let mut config = load_template_config(request.template_path.as_ref())?;
 
-    // 1.5 注入数据库中的 label_title 配置
+    // 1.5 注入数据库中的 label_title 与条码内容配置
     let mut data = request.data;
     inject_label_title(&mut config, &mut data);
+    inject_card_fields(&mut config, &mut data);
 
     // 2. 模板解析
     log::debug!("解析模板，数据: {:?}", data);
@@ -452,9 +553,10 @@ pub async fn print_qsl(
     // 1. 加载模板配置
     let mut config = load_template_config(request.template_path.as_ref())?;
 
-    // 1.5 注入数据库中的 label_title 配置
+    // 1.5 注入数据库中的 label_title 与条码内容配置
     let mut data = request.data;
     inject_label_title(&mut config, &mut data);
+    inject_card_fields(&mut config, &mut data);
 
     // 2. 模板解析
     let resolved_elements = TemplateEngine::resolve(&config, &data)
@@ -720,6 +822,36 @@ pub async fn print_address(
     Ok(())
 }
 
+/// 生成存放位置标签预览（PNG）
+///
+/// 使用存放位置标签模板（storage_slot.toml），可在模板中引用位置编号与分配规则。
+#[tauri::command]
+pub async fn preview_storage_label(
+    location_id: String,
+    state: State<'_, PrinterState>,
+) -> Result<PreviewResponse, String> {
+    log::info!("开始生成存放位置标签预览: {}", location_id);
+    let request = build_storage_label_request(location_id).await?;
+    preview_qsl(request, state).await
+}
+
+/// 打印存放位置标签
+///
+/// # 参数
+/// - `printer_name`: 打印机名称
+/// - `location_id`: 存放位置 ID
+#[tauri::command]
+pub async fn print_storage_label(
+    printer_name: String,
+    location_id: String,
+    state: State<'_, PrinterState>,
+    profile_state: State<'_, ProfileState>,
+) -> Result<(), String> {
+    log::info!("开始打印存放位置标签: 打印机={}, 位置={}", printer_name, location_id);
+    let request = build_storage_label_request(location_id).await?;
+    print_qsl(printer_name, request, state, profile_state).await
+}
+
 /// 生成 TSPL 指令（用于调试）
 ///
 /// # 参数
@@ -737,9 +869,10 @@ pub async fn generate_tspl(
     // 1. 加载模板配置（每次都重新从文件读取）
     let mut config = load_template_config(request.template_path.as_ref())?;
 
-    // 1.5 注入数据库中的 label_title 配置
+    // 1.5 注入数据库中的 label_title 与条码内容配置
     let mut data = request.data;
     inject_label_title(&mut config, &mut data);
+    inject_card_fields(&mut config, &mut data);
 
     // 2. 模板解析 → 布局 → 渲染
     let resolved_elements = TemplateEngine::resolve(&config, &data)
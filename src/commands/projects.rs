@@ -36,7 +36,7 @@ pub async fn update_project_cmd(id: String, name: String) -> Result<Project, Str
         .map_err(|e| e.to_string())?
 }
 
-/// 删除项目
+/// 删除项目（连同其卡片移入回收站）
 #[tauri::command]
 pub async fn delete_project_cmd(id: String) -> Result<(), String> {
     tokio::task::spawn_blocking(move || db::delete_project(&id).map_err(|e| e.to_string()))
@@ -0,0 +1,71 @@
+// QSL 管理员 Tauri 命令
+//
+// 提供前端调用的 QSL 管理员 API：查询、登记、修改、删除，以及从 CSV 文件导入
+
+use crate::db::{self, QslManager, QslManagerImportReport};
+
+/// 查询 QSL 管理员登记（`callsign` 为空时返回全部）
+#[tauri::command]
+pub async fn list_qsl_managers_cmd(callsign: Option<String>) -> Result<Vec<QslManager>, String> {
+    tokio::task::spawn_blocking(move || {
+        db::list_qsl_managers(callsign.as_deref()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 登记 QSL 管理员
+#[tauri::command]
+pub async fn create_qsl_manager_cmd(
+    callsign: String,
+    manager: String,
+    effective_from: Option<String>,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<QslManager, String> {
+    tokio::task::spawn_blocking(move || {
+        db::create_qsl_manager(callsign, manager, effective_from, effective_to, remarks)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 修改 QSL 管理员登记
+#[tauri::command]
+pub async fn update_qsl_manager_cmd(
+    id: String,
+    manager: String,
+    effective_from: Option<String>,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<QslManager, String> {
+    tokio::task::spawn_blocking(move || {
+        db::update_qsl_manager(&id, manager, effective_from, effective_to, remarks)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 删除 QSL 管理员登记
+#[tauri::command]
+pub async fn delete_qsl_manager_cmd(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || db::delete_qsl_manager(&id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 从 CSV 文件导入 QSL 管理员（每行 `呼号,管理员[,生效日期[,失效日期[,备注]]]`）
+#[tauri::command]
+pub async fn import_qsl_managers_csv_cmd(
+    file_path: String,
+) -> Result<QslManagerImportReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let content =
+            std::fs::read_to_string(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+        db::import_qsl_managers_csv(&content).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,53 @@
+// 回收站 Tauri 命令
+//
+// 提供前端调用的回收站 API：查看、恢复、彻底删除
+
+use crate::db::{self, PurgeReport, RecycleBin};
+
+/// 查询回收站内容
+#[tauri::command]
+pub async fn list_recycle_bin_cmd() -> Result<RecycleBin, String> {
+    tokio::task::spawn_blocking(|| db::list_recycle_bin().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 恢复卡片
+#[tauri::command]
+pub async fn restore_card_cmd(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || db::restore_card(&id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 恢复项目
+#[tauri::command]
+pub async fn restore_project_cmd(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || db::restore_project(&id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 彻底删除卡片
+#[tauri::command]
+pub async fn purge_card_cmd(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || db::purge_card(&id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 彻底删除项目（连同其全部卡片）
+#[tauri::command]
+pub async fn purge_project_cmd(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || db::purge_project(&id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 清空回收站
+#[tauri::command]
+pub async fn empty_recycle_bin_cmd() -> Result<PurgeReport, String> {
+    tokio::task::spawn_blocking(|| db::empty_recycle_bin().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
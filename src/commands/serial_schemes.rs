@@ -0,0 +1,54 @@
+// 序列号编号方案 Tauri 命令
+//
+// 提供前端调用的编号方案 API：查询与设置方案、检查断号与重复、按录入顺序重新编号
+
+use crate::db::{self, SerialAudit, SerialScheme, SerialSchemeInput};
+
+/// 查询项目的编号方案（未配置时返回默认方案）
+#[tauri::command]
+pub async fn get_serial_scheme_cmd(project_id: String) -> Result<SerialScheme, String> {
+    tokio::task::spawn_blocking(move || {
+        db::get_serial_scheme(&project_id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 设置项目的编号方案
+#[tauri::command]
+pub async fn set_serial_scheme_cmd(
+    project_id: String,
+    scheme: SerialSchemeInput,
+) -> Result<SerialScheme, String> {
+    tokio::task::spawn_blocking(move || {
+        db::set_serial_scheme(&project_id, scheme).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 检查项目的序列号（断号与重复）
+#[tauri::command]
+pub async fn audit_project_serials_cmd(
+    project_id: String,
+    skip_four: bool,
+) -> Result<SerialAudit, String> {
+    tokio::task::spawn_blocking(move || {
+        db::audit_project_serials(&project_id, skip_four).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 按录入顺序重新编号项目内的全部卡片（返回序列号有变化的卡片数）
+#[tauri::command]
+pub async fn renumber_project_serials_cmd(
+    project_id: String,
+    skip_four: bool,
+) -> Result<u32, String> {
+    tokio::task::spawn_blocking(move || {
+        db::renumber_project_serials(&project_id, skip_four).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
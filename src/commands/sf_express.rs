@@ -533,8 +533,11 @@ pub struct CreateOrderParams {
     pub recipient: RecipientInfo,
     /// 托寄物名称（可选，默认"QSL卡片"）
     pub cargo_name: Option<String>,
-    /// 关联的卡片 ID（可选）
+    /// 关联的卡片 ID（可选，旧版单卡寄件）
     pub card_id: Option<String>,
+    /// 合并寄件的卡片 ID 列表（可选，同一呼号的多张卡片，优先于 card_id）
+    #[serde(default)]
+    pub card_ids: Option<Vec<String>>,
     /// 付款方式：1=寄方付, 2=收方付（可选，默认寄方付）
     pub pay_method: Option<i32>,
 }
@@ -614,7 +617,14 @@ fn get_sf_client() -> Result<SFExpressClient, String> {
 #[tauri::command]
 pub fn sf_create_order(params: CreateOrderParams) -> Result<CreateOrderResponse, String> {
     let cargo_name = params.cargo_name.unwrap_or_else(|| "QSL卡片".to_string());
-    log::info!("创建顺丰订单: cargo={}, card_id={:?}", cargo_name, params.card_id);
+    let card_ids = params
+        .card_ids
+        .clone()
+        .unwrap_or_else(|| params.card_id.iter().cloned().collect());
+    log::info!("创建顺丰订单: cargo={}, card_ids={:?}", cargo_name, card_ids);
+
+    // 先校验合并寄件的卡片，避免顺丰已下单而本地无法保存
+    db::check_shipment_cards(&card_ids).map_err(|e| e.to_string())?;
 
     // 加载寄件人信息
     let sender = db::get_sender(&params.sender_id)
@@ -706,7 +716,7 @@ pub fn sf_create_order(params: CreateOrderParams) -> Result<CreateOrderResponse,
     // 保存到本地数据库
     let local_order = db::create_order(
         order_id.clone(),
-        params.card_id,
+        card_ids,
         params.pay_method,
         Some(cargo_name.clone()),
         sender,
@@ -739,6 +749,8 @@ pub struct ConfirmOrderResponse {
     pub res_status: Option<i32>,
     /// 更新后的本地订单
     pub local_order: SFOrder,
+    /// 关联卡片的分发结果（无关联卡片或未取得运单号时为空）
+    pub distribution: Option<db::BatchReport>,
 }
 
 /// 确认顺丰订单
@@ -748,8 +760,8 @@ pub fn sf_confirm_order(order_id: String) -> Result<ConfirmOrderResponse, String
 
     let client = get_sf_client()?;
 
-    // 获取本地订单
-    let local_order = db::get_order_by_order_id(&order_id)
+    // 本地订单须存在（在调用顺丰接口前检查）
+    db::get_order_by_order_id(&order_id)
         .map_err(|e| format!("获取订单失败: {}", e))?
         .ok_or_else(|| format!("订单不存在: {}", order_id))?;
 
@@ -773,16 +785,9 @@ pub fn sf_confirm_order(order_id: String) -> Result<ConfirmOrderResponse, String
 
     let waybill_no = waybill_no_list.first().cloned();
 
-    // 更新本地订单状态
-    let updated_order = db::update_order_status(&order_id, OrderStatus::Confirmed, waybill_no.clone())
-        .map_err(|e| format!("更新订单状态失败: {}", e))?;
-
-    // 如果有关联卡片，回填运单号到卡片备注
-    if let (Some(card_id), Some(waybill)) = (&updated_order.card_id, &waybill_no) {
-        log::info!("回填运单号到卡片: card_id={}, waybill={}", card_id, waybill);
-        // 这里可以调用卡片更新接口回填运单号
-        // TODO: 实现卡片备注更新
-    }
+    // 以运单号分发合并寄件的全部关联卡片并标记订单已确认（同一事务，失败时可重新确认）
+    let (updated_order, distribution) = db::confirm_shipment(&order_id, waybill_no)
+        .map_err(|e| format!("确认本地订单失败: {}", e))?;
 
     log::info!("订单确认成功: order_id={}, waybill_nos={:?}", order_id, waybill_no_list);
 
@@ -791,9 +796,16 @@ pub fn sf_confirm_order(order_id: String) -> Result<ConfirmOrderResponse, String
         waybill_no_list,
         res_status: response.res_status,
         local_order: updated_order,
+        distribution,
     })
 }
 
+/// 查询某呼号可合并寄件的卡片（跨项目、未加入待确认订单）
+#[tauri::command]
+pub fn sf_list_shipment_candidates(callsign: String) -> Result<Vec<db::CardWithProject>, String> {
+    db::list_shipment_candidates(&callsign).map_err(|e| e.to_string())
+}
+
 /// 取消顺丰订单
 #[tauri::command]
 pub fn sf_cancel_order(order_id: String) -> Result<SFOrder, String> {
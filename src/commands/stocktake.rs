@@ -0,0 +1,91 @@
+// 库存盘点 Tauri 命令
+//
+// 提供前端调用的库存盘点 API：开始、扫描、撤销扫描、核对报告、结束、删除、缺失卡片批量标记遗失
+
+use crate::db::{self, BatchReport, StocktakeReport, StocktakeScanResult, StocktakeSession};
+
+/// 开始盘点（`project_id` 为空时盘点全部项目）
+#[tauri::command]
+pub async fn start_stocktake_cmd(
+    name: Option<String>,
+    project_id: Option<String>,
+    remarks: Option<String>,
+) -> Result<StocktakeSession, String> {
+    tokio::task::spawn_blocking(move || {
+        db::start_stocktake(name, project_id, remarks).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 查询全部盘点
+#[tauri::command]
+pub async fn list_stocktake_sessions_cmd() -> Result<Vec<StocktakeSession>, String> {
+    tokio::task::spawn_blocking(|| db::list_stocktake_sessions().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 记录一次扫描或录入（条码、卡片 ID 或「呼号 序列号」）
+#[tauri::command]
+pub async fn record_stocktake_scan_cmd(
+    session_id: String,
+    input: String,
+) -> Result<StocktakeScanResult, String> {
+    tokio::task::spawn_blocking(move || {
+        db::record_stocktake_scan(&session_id, &input).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 撤销一条扫描记录
+#[tauri::command]
+pub async fn delete_stocktake_scan_cmd(scan_id: i64) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        db::delete_stocktake_scan(scan_id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 生成盘点核对报告
+#[tauri::command]
+pub async fn get_stocktake_report_cmd(session_id: String) -> Result<StocktakeReport, String> {
+    tokio::task::spawn_blocking(move || {
+        db::get_stocktake_report(&session_id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 结束盘点
+#[tauri::command]
+pub async fn close_stocktake_cmd(session_id: String) -> Result<StocktakeSession, String> {
+    tokio::task::spawn_blocking(move || db::close_stocktake(&session_id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 删除盘点
+#[tauri::command]
+pub async fn delete_stocktake_cmd(session_id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        db::delete_stocktake(&session_id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 将盘点缺失的卡片批量标记为遗失
+#[tauri::command]
+pub async fn mark_stocktake_missing_lost_cmd(
+    session_id: String,
+    card_ids: Vec<String>,
+) -> Result<BatchReport, String> {
+    tokio::task::spawn_blocking(move || {
+        db::mark_stocktake_missing_lost(&session_id, card_ids).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,69 @@
+// 存放位置 Tauri 命令
+//
+// 提供前端调用的存放位置 API：查询、新建、修改、删除、调整卡片位置、按规则补充分配
+
+use crate::db::{self, StorageLocation, StorageLocationInput};
+
+/// 查询全部存放位置
+#[tauri::command]
+pub async fn list_storage_locations_cmd() -> Result<Vec<StorageLocation>, String> {
+    tokio::task::spawn_blocking(|| db::list_storage_locations().map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 新建存放位置
+#[tauri::command]
+pub async fn create_storage_location_cmd(
+    location: StorageLocationInput,
+) -> Result<StorageLocation, String> {
+    tokio::task::spawn_blocking(move || {
+        db::create_storage_location(location).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 修改存放位置
+#[tauri::command]
+pub async fn update_storage_location_cmd(
+    id: String,
+    location: StorageLocationInput,
+) -> Result<StorageLocation, String> {
+    tokio::task::spawn_blocking(move || {
+        db::update_storage_location(&id, location).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 删除存放位置
+#[tauri::command]
+pub async fn delete_storage_location_cmd(id: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || db::delete_storage_location(&id).map_err(|e| e.to_string()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 修改卡片的存放位置（`location_id` 为空时清除）
+#[tauri::command]
+pub async fn set_card_storage_location_cmd(
+    card_id: String,
+    location_id: Option<String>,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        db::set_card_storage_location(&card_id, location_id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 为尚未分配位置的卡片按规则补充分配（返回分配的卡片数）
+#[tauri::command]
+pub async fn assign_storage_locations_cmd(project_id: Option<String>) -> Result<u32, String> {
+    tokio::task::spawn_blocking(move || {
+        db::assign_storage_locations(project_id.as_deref()).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
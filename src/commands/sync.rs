@@ -2,6 +2,7 @@
 //
 // 提供云端同步的 Tauri 命令
 
+use crate::db::{create_backup, BackupReason};
 use crate::db::export::ExportStats;
 use crate::db::import::{import_from_export_data, AppSettingsClearMode};
 use crate::db::models::{format_datetime, now_china};
@@ -313,9 +314,16 @@ pub async fn restore_from_cloud() -> Result<RestoreResult, String> {
     // 用快照构造等价 ExportData 调共用导入内核（无条件清空全部 5 张业务表，含 app_settings）
     let export_data = sync_data_to_export_data(pulled);
 
-    let mut conn = crate::db::sqlite::get_connection().map_err(|e| e.to_string())?;
-    import_from_export_data(&mut conn, &export_data, AppSettingsClearMode::Unconditional)
-        .map_err(|e| format!("从云端恢复失败: {}", e))?;
+    tokio::task::spawn_blocking(move || {
+        // 覆盖本地数据前先备份（备份失败则不恢复）
+        create_backup(BackupReason::PreCloudRestore)
+            .map_err(|e| format!("恢复前备份失败: {}", e))?;
+        let mut conn = crate::db::sqlite::get_connection().map_err(|e| e.to_string())?;
+        import_from_export_data(&mut conn, &export_data, AppSettingsClearMode::Unconditional)
+            .map_err(|e| format!("从云端恢复失败: {}", e))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
 
     // 对齐本地基线为快照版本并落盘（null→None→下次按首次/无条件处理）
     config.base_version = server_version;
@@ -348,6 +356,12 @@ fn sync_data_to_export_data(pulled: crate::sync::client::PullResponse) -> crate:
             sf_senders: pulled.data.sf_senders,
             sf_orders: pulled.data.sf_orders,
             app_settings: Some(pulled.data.app_settings),
+            // 旧云端快照不含事件日志，导入时按卡片状态与元数据推导
+            card_events: pulled.data.card_events,
+            // 旧云端快照不含修改历史
+            card_history: pulled.data.card_history,
+            // 旧云端快照不含编号方案
+            serial_schemes: pulled.data.serial_schemes,
         },
     }
 }
@@ -3,13 +3,18 @@
 // 提供 app_settings 表的 CRUD 操作
 
 use crate::db::models::AppSetting;
-use crate::db::sqlite::get_connection;
+use crate::db::sqlite::{database, Database};
 use crate::error::AppError;
 use rusqlite::OptionalExtension;
 
 /// 获取单个配置项
 pub fn get_setting(key: &str) -> Result<Option<String>, AppError> {
-    let conn = get_connection()?;
+    get_setting_in(database()?, key)
+}
+
+/// 同 [`get_setting`]，在指定的数据库上下文中执行
+pub fn get_setting_in(db: &Database, key: &str) -> Result<Option<String>, AppError> {
+    let conn = db.connection()?;
     let mut stmt = conn
         .prepare("SELECT value FROM app_settings WHERE key = ?1")
         .map_err(|e| AppError::Other(format!("准备查询失败: {}", e)))?;
@@ -24,7 +29,12 @@ pub fn get_setting(key: &str) -> Result<Option<String>, AppError> {
 
 /// 设置配置项（存在则更新，不存在则插入）
 pub fn set_setting(key: &str, value: &str) -> Result<(), AppError> {
-    let conn = get_connection()?;
+    set_setting_in(database()?, key, value)
+}
+
+/// 同 [`set_setting`]，在指定的数据库上下文中执行
+pub fn set_setting_in(db: &Database, key: &str, value: &str) -> Result<(), AppError> {
+    let conn = db.connection()?;
     conn.execute(
         "INSERT INTO app_settings (key, value) VALUES (?1, ?2)
          ON CONFLICT(key) DO UPDATE SET value = excluded.value",
@@ -37,7 +47,12 @@ pub fn set_setting(key: &str, value: &str) -> Result<(), AppError> {
 
 /// 获取所有配置项
 pub fn get_all_settings() -> Result<Vec<AppSetting>, AppError> {
-    let conn = get_connection()?;
+    get_all_settings_in(database()?)
+}
+
+/// 同 [`get_all_settings`]，在指定的数据库上下文中执行
+pub fn get_all_settings_in(db: &Database) -> Result<Vec<AppSetting>, AppError> {
+    let conn = db.connection()?;
     let mut stmt = conn
         .prepare("SELECT key, value FROM app_settings ORDER BY key")
         .map_err(|e| AppError::Other(format!("准备查询失败: {}", e)))?;
@@ -0,0 +1,666 @@
+// 自动备份模块
+//
+// 使用 SQLite 在线备份 API 为数据库创建快照：按计划定时备份，并在迁移、导入、
+// 从云端恢复、恢复出厂设置前自动备份。定时快照按日 / 按周保留，支持列出与恢复
+
+use crate::db::models::{format_datetime, now_china};
+use crate::db::sqlite::{database, format_version, get_db_version, run_migrations, Database};
+use crate::error::AppError;
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, TimeZone};
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// 备份目录名（位于数据库文件所在目录下，恢复出厂设置不会删除）
+pub const BACKUP_DIR_NAME: &str = "backups";
+
+/// 备份设置文件名（位于备份目录下，仅对本机生效，不随导出与云端同步）
+const BACKUP_SETTINGS_FILE: &str = "backup.toml";
+
+/// 快照文件名：`cards-<东八区时间>-<原因>-<随机后缀>.db`
+///
+/// 随机后缀避免同一毫秒内创建的快照重名（旧版本的快照没有后缀）。
+const SNAPSHOT_PREFIX: &str = "cards-";
+const SNAPSHOT_EXTENSION: &str = "db";
+const SNAPSHOT_TIME_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+const SNAPSHOT_SUFFIX_LEN: usize = 8;
+
+/// 在线备份每步复制的页数与步间暂停（避免长时间占用数据库锁）
+const BACKUP_PAGES_PER_STEP: i32 = 256;
+const BACKUP_STEP_PAUSE: Duration = Duration::from_millis(10);
+
+/// 定时备份的检查间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(10 * 60);
+
+/// 从快照恢复期间持有，定时备份等待恢复完成后再执行
+static RESTORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 快照原因
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum BackupReason {
+    /// 定时备份（按日 / 按周保留）
+    Scheduled,
+    /// 手动备份（不自动清理）
+    Manual,
+    /// 数据库迁移前
+    PreMigration,
+    /// 导入数据前
+    PreImport,
+    /// 从云端恢复前
+    PreCloudRestore,
+    /// 恢复出厂设置前
+    PreFactoryReset,
+    /// 从快照恢复前
+    PreRestore,
+}
+
+impl BackupReason {
+    const ALL: [BackupReason; 7] = [
+        BackupReason::Scheduled,
+        BackupReason::Manual,
+        BackupReason::PreMigration,
+        BackupReason::PreImport,
+        BackupReason::PreCloudRestore,
+        BackupReason::PreFactoryReset,
+        BackupReason::PreRestore,
+    ];
+
+    /// 文件名中的原因标识
+    fn as_str(&self) -> &'static str {
+        match self {
+            BackupReason::Scheduled => "scheduled",
+            BackupReason::Manual => "manual",
+            BackupReason::PreMigration => "pre_migration",
+            BackupReason::PreImport => "pre_import",
+            BackupReason::PreCloudRestore => "pre_cloud_restore",
+            BackupReason::PreFactoryReset => "pre_factory_reset",
+            BackupReason::PreRestore => "pre_restore",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.as_str() == s)
+    }
+}
+
+/// 备份设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(default)]
+pub struct BackupSettings {
+    /// 是否启用定时备份
+    pub enabled: bool,
+    /// 定时备份间隔（小时，1-168）
+    pub interval_hours: u32,
+    /// 按日保留的定时快照天数（每天保留最新一份）
+    pub keep_daily: u32,
+    /// 按周保留的定时快照周数（每周保留最新一份）
+    pub keep_weekly: u32,
+    /// 操作前快照保留份数
+    pub keep_pre_operation: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+            keep_daily: 7,
+            keep_weekly: 4,
+            keep_pre_operation: 10,
+        }
+    }
+}
+
+impl BackupSettings {
+    fn validate(&self) -> Result<(), AppError> {
+        if !(1..=168).contains(&self.interval_hours) {
+            return Err(AppError::InvalidParameter(
+                "备份间隔须在 1-168 小时之间".to_string(),
+            ));
+        }
+        if self.keep_daily == 0 && self.keep_weekly == 0 {
+            return Err(AppError::InvalidParameter(
+                "按日与按周保留份数不能同时为 0".to_string(),
+            ));
+        }
+        if self.keep_pre_operation == 0 {
+            return Err(AppError::InvalidParameter(
+                "操作前快照至少保留 1 份".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 数据库快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct BackupSnapshot {
+    /// 文件名（恢复 / 删除时作为标识）
+    pub file_name: String,
+    /// 快照原因
+    pub reason: BackupReason,
+    /// 创建时间
+    pub created_at: String,
+    /// 文件大小（字节）
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub size_bytes: u64,
+    /// 快照的数据库版本号（无法读取时为 0）
+    pub db_version: i32,
+    /// 快照的可读版本号
+    pub db_version_display: String,
+}
+
+/// 从备份目录中解析出的快照文件
+struct SnapshotFile {
+    file_name: String,
+    reason: BackupReason,
+    created: DateTime<FixedOffset>,
+}
+
+impl SnapshotFile {
+    /// 解析快照文件名，非快照文件返回 None
+    fn parse(file_name: &str) -> Option<Self> {
+        let stem = file_name
+            .strip_prefix(SNAPSHOT_PREFIX)?
+            .strip_suffix(SNAPSHOT_EXTENSION)?
+            .strip_suffix('.')?;
+        // 时间部分固定为 `YYYYMMDD-HHMMSSmmm`，原因后的随机后缀可选
+        let (time, rest) = stem.split_at_checked(18)?;
+        let rest = rest.strip_prefix('-')?;
+        let reason = rest.split_once('-').map_or(rest, |(reason, _)| reason);
+        let reason = BackupReason::parse(reason)?;
+        let naive = NaiveDateTime::parse_from_str(time, SNAPSHOT_TIME_FORMAT).ok()?;
+        let created = now_china()
+            .offset()
+            .from_local_datetime(&naive)
+            .single()?;
+
+        Some(Self {
+            file_name: file_name.to_string(),
+            reason,
+            created,
+        })
+    }
+}
+
+/// 数据库文件对应的备份目录
+pub(crate) fn backup_dir_for(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(BACKUP_DIR_NAME)
+}
+
+/// 数据库上下文的备份目录（内存数据库没有备份目录）
+fn backup_dir_in(db: &Database) -> Option<PathBuf> {
+    db.file_path().map(backup_dir_for)
+}
+
+/// 使用在线备份 API 将 `conn` 的主库复制为快照文件
+///
+/// 先写入临时文件，完成后再改名，避免中断时留下不完整的快照。
+pub(crate) fn snapshot_connection(
+    conn: &Connection,
+    backup_dir: &Path,
+    reason: BackupReason,
+) -> Result<BackupSnapshot, AppError> {
+    fs::create_dir_all(backup_dir).map_err(|e| {
+        AppError::DirectoryCreationFailed(format!("无法创建备份目录: {}", e))
+    })?;
+
+    let created = now_china();
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    let file_name = format!(
+        "{}{}-{}-{}.{}",
+        SNAPSHOT_PREFIX,
+        created.format(SNAPSHOT_TIME_FORMAT),
+        reason.as_str(),
+        &suffix[..SNAPSHOT_SUFFIX_LEN],
+        SNAPSHOT_EXTENSION
+    );
+    let path = backup_dir.join(&file_name);
+    let tmp_path = backup_dir.join(format!("{}.tmp", file_name));
+
+    let result = (|| {
+        let mut dst = Connection::open(&tmp_path)?;
+        Backup::new(conn, &mut dst)?.run_to_completion(
+            BACKUP_PAGES_PER_STEP,
+            BACKUP_STEP_PAUSE,
+            None,
+        )
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(AppError::Other(format!("创建数据库快照失败: {}", e)));
+    }
+    fs::rename(&tmp_path, &path)
+        .map_err(|e| AppError::Other(format!("保存数据库快照失败: {}", e)))?;
+
+    log::info!("💾 已创建数据库快照: {}", path.display());
+    Ok(describe_snapshot(
+        backup_dir,
+        &SnapshotFile {
+            file_name,
+            reason,
+            created,
+        },
+    ))
+}
+
+/// 读取快照的文件大小与数据库版本
+fn describe_snapshot(backup_dir: &Path, file: &SnapshotFile) -> BackupSnapshot {
+    let path = backup_dir.join(&file.file_name);
+    let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    let db_version = Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .ok()
+        .and_then(|conn| get_db_version(&conn).ok())
+        .unwrap_or(0);
+
+    BackupSnapshot {
+        file_name: file.file_name.clone(),
+        reason: file.reason,
+        created_at: format_datetime(&file.created),
+        size_bytes,
+        db_version,
+        db_version_display: format_version(db_version),
+    }
+}
+
+/// 列出备份目录中的快照文件（按创建时间从新到旧）
+fn snapshot_files(backup_dir: &Path) -> Result<Vec<SnapshotFile>, AppError> {
+    if !backup_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(backup_dir)
+        .map_err(|e| AppError::Other(format!("无法读取备份目录: {}", e)))?;
+    let mut files: Vec<SnapshotFile> = entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter_map(|name| SnapshotFile::parse(&name))
+        .collect();
+    files.sort_by_key(|file| std::cmp::Reverse(file.created));
+
+    Ok(files)
+}
+
+/// 按保留策略挑出应删除的快照（`files` 按创建时间从新到旧）
+///
+/// - 定时快照：最近 `keep_daily` 个有快照的日期各保留最新一份，
+///   最近 `keep_weekly` 个有快照的 ISO 周各保留最新一份，二者取并集
+/// - 操作前快照：保留最新 `keep_pre_operation` 份
+/// - 手动快照：不自动清理
+fn expired_snapshots<'a>(
+    files: &'a [SnapshotFile],
+    settings: &BackupSettings,
+) -> Vec<&'a SnapshotFile> {
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    let mut pre_operation = 0;
+
+    files
+        .iter()
+        .filter(|file| match file.reason {
+            BackupReason::Manual => false,
+            BackupReason::Scheduled => {
+                let date = file.created.date_naive();
+                let week = date.iso_week();
+                // 从新到旧遍历，首次出现的日期 / 周即该日 / 周最新的一份
+                let keep_day = days.len() < settings.keep_daily as usize && days.insert(date);
+                let keep_week = weeks.len() < settings.keep_weekly as usize
+                    && weeks.insert((week.year(), week.week()));
+                !(keep_day || keep_week)
+            }
+            _ => {
+                pre_operation += 1;
+                pre_operation > settings.keep_pre_operation
+            }
+        })
+        .collect()
+}
+
+/// 按保留策略清理备份目录，返回删除的快照数
+fn prune_backup_dir(backup_dir: &Path, settings: &BackupSettings) -> Result<usize, AppError> {
+    let files = snapshot_files(backup_dir)?;
+    let expired = expired_snapshots(&files, settings);
+    for file in &expired {
+        fs::remove_file(backup_dir.join(&file.file_name)).map_err(|e| {
+            AppError::Other(format!("删除过期快照 {} 失败: {}", file.file_name, e))
+        })?;
+        log::info!("🗑️ 已清理过期快照: {}", file.file_name);
+    }
+    Ok(expired.len())
+}
+
+/// 读取备份设置（设置文件不存在时使用默认值）
+fn load_backup_settings(backup_dir: &Path) -> Result<BackupSettings, AppError> {
+    let path = backup_dir.join(BACKUP_SETTINGS_FILE);
+    if !path.exists() {
+        return Ok(BackupSettings::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AppError::Other(format!("读取备份设置失败: {}", e)))?;
+    toml::from_str(&content).map_err(|e| AppError::Other(format!("解析备份设置失败: {}", e)))
+}
+
+/// 需要文件数据库的操作取备份目录（内存数据库报错）
+fn require_backup_dir(db: &Database) -> Result<PathBuf, AppError> {
+    backup_dir_in(db).ok_or_else(|| AppError::Other("内存数据库不支持备份".to_string()))
+}
+
+/// 在指定快照文件名中拒绝路径分隔符等非快照名称
+fn find_snapshot(backup_dir: &Path, file_name: &str) -> Result<SnapshotFile, AppError> {
+    let file = SnapshotFile::parse(file_name)
+        .ok_or_else(|| AppError::InvalidParameter(format!("无效的快照文件名: {}", file_name)))?;
+    if !backup_dir.join(file_name).is_file() {
+        return Err(AppError::InvalidParameter(format!("快照不存在: {}", file_name)));
+    }
+    Ok(file)
+}
+
+/// 创建数据库快照并按保留策略清理
+///
+/// 内存数据库不备份，返回 None。
+pub fn create_backup(reason: BackupReason) -> Result<Option<BackupSnapshot>, AppError> {
+    create_backup_in(database()?, reason)
+}
+
+/// 同 [`create_backup`]，在指定的数据库上下文中执行
+pub fn create_backup_in(
+    db: &Database,
+    reason: BackupReason,
+) -> Result<Option<BackupSnapshot>, AppError> {
+    let conn = db.connection()?;
+    create_backup_conn(db, &conn, reason)
+}
+
+/// 同 [`create_backup_in`]，使用调用方已取出的连接（crate-private，避免同时占用两个连接）
+pub(crate) fn create_backup_conn(
+    db: &Database,
+    conn: &Connection,
+    reason: BackupReason,
+) -> Result<Option<BackupSnapshot>, AppError> {
+    let Some(backup_dir) = backup_dir_in(db) else {
+        return Ok(None);
+    };
+
+    let snapshot = snapshot_connection(conn, &backup_dir, reason)?;
+    let settings = load_backup_settings(&backup_dir)?;
+    if let Err(e) = prune_backup_dir(&backup_dir, &settings) {
+        log::warn!("清理过期快照失败: {}", e);
+    }
+
+    Ok(Some(snapshot))
+}
+
+/// 列出全部快照（按创建时间从新到旧）
+pub fn list_backups() -> Result<Vec<BackupSnapshot>, AppError> {
+    list_backups_in(database()?)
+}
+
+/// 同 [`list_backups`]，在指定的数据库上下文中执行
+pub fn list_backups_in(db: &Database) -> Result<Vec<BackupSnapshot>, AppError> {
+    let Some(backup_dir) = backup_dir_in(db) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(snapshot_files(&backup_dir)?
+        .iter()
+        .map(|file| describe_snapshot(&backup_dir, file))
+        .collect())
+}
+
+/// 从快照恢复数据库
+///
+/// 先校验快照完整性与版本，再为当前数据库创建「恢复前」快照，
+/// 然后以在线备份 API 将快照写回主库并补齐迁移。返回恢复前快照。
+///
+/// 恢复期间暂停定时备份；恢复前后作废连接池中的全部连接，
+/// 之后的读写均使用新打开的连接，不复用恢复前的连接。
+pub fn restore_backup(file_name: &str) -> Result<BackupSnapshot, AppError> {
+    restore_backup_in(database()?, file_name)
+}
+
+/// 同 [`restore_backup`]，在指定的数据库上下文中执行
+pub fn restore_backup_in(db: &Database, file_name: &str) -> Result<BackupSnapshot, AppError> {
+    let _restoring = RESTORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let backup_dir = require_backup_dir(db)?;
+    let file = find_snapshot(&backup_dir, file_name)?;
+    let path = backup_dir.join(&file.file_name);
+
+    // 校验快照：完整性检查通过，且版本不高于本地
+    let src = Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| AppError::Other(format!("无法打开快照: {}", e)))?;
+    let integrity: String = src
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| AppError::Other(format!("快照完整性检查失败: {}", e)))?;
+    if integrity != "ok" {
+        return Err(AppError::DataFormatError(format!(
+            "快照已损坏: {}",
+            integrity
+        )));
+    }
+    let snapshot_version = get_db_version(&src)?;
+    let local_version = get_db_version(&*db.connection()?)?;
+    if snapshot_version > local_version {
+        return Err(AppError::InvalidParameter(format!(
+            "快照的数据库版本（{}）高于本地版本（{}），请升级应用后再恢复",
+            format_version(snapshot_version),
+            format_version(local_version)
+        )));
+    }
+
+    let pre_restore = create_backup_in(db, BackupReason::PreRestore)?
+        .ok_or_else(|| AppError::Other("内存数据库不支持备份".to_string()))?;
+
+    db.reset_connections();
+    let mut conn = db.connection()?;
+    Backup::new(&src, &mut conn)
+        .and_then(|backup| {
+            backup.run_to_completion(BACKUP_PAGES_PER_STEP, BACKUP_STEP_PAUSE, None)
+        })
+        .map_err(|e| AppError::Other(format!("从快照恢复失败: {}", e)))?;
+
+    // 旧版本快照补齐迁移
+    run_migrations(&conn)?;
+    drop(conn);
+    db.reset_connections();
+
+    // 快照中的操作员可能与当前会话不一致，恢复后需重新登录
+    db.set_current_operator_id(None);
+
+    log::info!("✅ 已从快照恢复数据库: {}", file.file_name);
+    Ok(pre_restore)
+}
+
+/// 删除快照
+pub fn delete_backup(file_name: &str) -> Result<(), AppError> {
+    delete_backup_in(database()?, file_name)
+}
+
+/// 同 [`delete_backup`]，在指定的数据库上下文中执行
+pub fn delete_backup_in(db: &Database, file_name: &str) -> Result<(), AppError> {
+    let backup_dir = require_backup_dir(db)?;
+    let file = find_snapshot(&backup_dir, file_name)?;
+    fs::remove_file(backup_dir.join(&file.file_name))
+        .map_err(|e| AppError::Other(format!("删除快照失败: {}", e)))
+}
+
+/// 获取备份设置
+pub fn get_backup_settings() -> Result<BackupSettings, AppError> {
+    get_backup_settings_in(database()?)
+}
+
+/// 同 [`get_backup_settings`]，在指定的数据库上下文中执行
+pub fn get_backup_settings_in(db: &Database) -> Result<BackupSettings, AppError> {
+    match backup_dir_in(db) {
+        Some(backup_dir) => load_backup_settings(&backup_dir),
+        None => Ok(BackupSettings::default()),
+    }
+}
+
+/// 保存备份设置并按新的保留策略清理
+pub fn save_backup_settings(settings: &BackupSettings) -> Result<(), AppError> {
+    save_backup_settings_in(database()?, settings)
+}
+
+/// 同 [`save_backup_settings`]，在指定的数据库上下文中执行
+pub fn save_backup_settings_in(db: &Database, settings: &BackupSettings) -> Result<(), AppError> {
+    settings.validate()?;
+    let backup_dir = require_backup_dir(db)?;
+    fs::create_dir_all(&backup_dir).map_err(|e| {
+        AppError::DirectoryCreationFailed(format!("无法创建备份目录: {}", e))
+    })?;
+
+    let content = toml::to_string_pretty(settings)
+        .map_err(|e| AppError::Other(format!("序列化备份设置失败: {}", e)))?;
+    fs::write(backup_dir.join(BACKUP_SETTINGS_FILE), content)
+        .map_err(|e| AppError::Other(format!("保存备份设置失败: {}", e)))?;
+
+    prune_backup_dir(&backup_dir, settings)?;
+    Ok(())
+}
+
+/// 执行一次定时备份检查：已启用且距最近一次定时快照超过间隔时创建快照
+pub fn run_scheduled_backup_in(db: &Database) -> Result<Option<BackupSnapshot>, AppError> {
+    let Some(backup_dir) = backup_dir_in(db) else {
+        return Ok(None);
+    };
+    let settings = load_backup_settings(&backup_dir)?;
+    if !settings.enabled {
+        return Ok(None);
+    }
+
+    let latest = snapshot_files(&backup_dir)?
+        .into_iter()
+        .find(|file| file.reason == BackupReason::Scheduled);
+    let interval = chrono::Duration::hours(i64::from(settings.interval_hours));
+    if latest.is_some_and(|file| now_china() - file.created < interval) {
+        return Ok(None);
+    }
+
+    create_backup_in(db, BackupReason::Scheduled)
+}
+
+/// 启动定时备份线程（启动时立即检查一次，之后每 10 分钟检查一次）
+pub fn start_backup_scheduler() {
+    let spawned = std::thread::Builder::new()
+        .name("backup-scheduler".to_string())
+        .spawn(|| loop {
+            let restoring = RESTORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            match database().and_then(run_scheduled_backup_in) {
+                Ok(Some(snapshot)) => log::info!("✅ 定时备份完成: {}", snapshot.file_name),
+                Ok(None) => {}
+                Err(e) => log::error!("定时备份失败: {}", e),
+            }
+            drop(restoring);
+            std::thread::sleep(SCHEDULER_TICK);
+        });
+    if let Err(e) = spawned {
+        log::error!("无法启动定时备份线程: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::projects::{create_project_in, list_projects_in};
+
+    fn snapshot_at(reason: BackupReason, time: &str) -> SnapshotFile {
+        let created = DateTime::parse_from_rfc3339(time).unwrap();
+        SnapshotFile {
+            file_name: format!(
+                "{}{}-{}.{}",
+                SNAPSHOT_PREFIX,
+                created.format(SNAPSHOT_TIME_FORMAT),
+                reason.as_str(),
+                SNAPSHOT_EXTENSION
+            ),
+            reason,
+            created,
+        }
+    }
+
+    /// 定时快照按日 / 按周保留各自最新一份，操作前快照按份数保留，手动快照不清理
+    #[test]
+    fn test_expired_snapshots_retention() {
+        let files = vec![
+            snapshot_at(BackupReason::Scheduled, "2026-10-18T20:00:00+08:00"),
+            snapshot_at(BackupReason::PreImport, "2026-10-18T12:00:00+08:00"),
+            snapshot_at(BackupReason::Scheduled, "2026-10-18T08:00:00+08:00"),
+            snapshot_at(BackupReason::Scheduled, "2026-10-17T08:00:00+08:00"),
+            snapshot_at(BackupReason::PreFactoryReset, "2026-10-16T08:00:00+08:00"),
+            snapshot_at(BackupReason::Scheduled, "2026-10-10T08:00:00+08:00"),
+            snapshot_at(BackupReason::Scheduled, "2026-10-09T08:00:00+08:00"),
+            snapshot_at(BackupReason::Manual, "2026-01-01T08:00:00+08:00"),
+        ];
+        let settings = BackupSettings {
+            keep_daily: 2,
+            keep_weekly: 2,
+            keep_pre_operation: 1,
+            ..BackupSettings::default()
+        };
+
+        let expired: Vec<String> = expired_snapshots(&files, &settings)
+            .iter()
+            .map(|f| f.file_name.clone())
+            .collect();
+        assert_eq!(
+            expired,
+            vec![
+                files[2].file_name.clone(),
+                files[4].file_name.clone(),
+                files[6].file_name.clone(),
+            ]
+        );
+        assert!(SnapshotFile::parse(&files[0].file_name).is_some());
+        assert!(SnapshotFile::parse("../cards.db").is_none());
+        let suffixed =
+            SnapshotFile::parse("cards-20261018-200000123-pre_restore-1a2b3c4d.db").unwrap();
+        assert_eq!(suffixed.reason, BackupReason::PreRestore);
+    }
+
+    /// 快照可列出，恢复后数据回到快照时的状态，并留下恢复前快照
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = Database::open(dir.path().join("cards.db")).unwrap();
+
+        create_project_in(&db, "快照前".to_string()).unwrap();
+        let snapshot = create_backup_in(&db, BackupReason::Manual).unwrap().unwrap();
+        // 同一毫秒内创建的快照也不重名
+        let again = create_backup_in(&db, BackupReason::Manual).unwrap().unwrap();
+        assert_ne!(again.file_name, snapshot.file_name);
+        create_project_in(&db, "快照后".to_string()).unwrap();
+
+        let listed = list_backups_in(&db).unwrap();
+        assert_eq!(listed.len(), 2);
+        assert!(listed.iter().any(|s| s.file_name == snapshot.file_name));
+        assert!(listed[0].db_version > 0);
+
+        let pre_restore = restore_backup_in(&db, &snapshot.file_name).unwrap();
+        assert_eq!(pre_restore.reason, BackupReason::PreRestore);
+        let names: Vec<String> = list_projects_in(&db)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["快照前".to_string()]);
+        assert_eq!(list_backups_in(&db).unwrap().len(), 3);
+        assert!(restore_backup_in(&db, "../cards.db").is_err());
+    }
+}
@@ -0,0 +1,409 @@
+// 卡片批量操作模块
+//
+// 批量分发、退卡、删除、移动到其他项目。
+// 整批在同一事务内执行，每张卡片使用独立 SAVEPOINT：单卡失败只回滚该卡，
+// 其余卡片随事务一并提交，并返回逐卡结果报告
+
+use crate::db::cards::{
+    delete_card_conn, distribute_card_conn, get_card_conn, list_all_cards_conn, return_card_conn,
+    update_card_conn,
+};
+use crate::db::models::{
+    BatchItemResult, BatchOperation, BatchProgress, BatchReport, BatchTarget, CardUpdate,
+};
+use crate::db::operators::get_current_operator_in;
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use rusqlite::{Connection, Transaction};
+
+/// 解析批量操作目标为卡片 ID 列表（去重，保持顺序）
+fn resolve_target(conn: &Connection, target: BatchTarget) -> Result<Vec<String>, AppError> {
+    let ids = match target {
+        BatchTarget::Ids(ids) => ids,
+        BatchTarget::Filter(filter) => {
+            if filter.is_empty() {
+                return Err(AppError::InvalidParameter(
+                    "批量操作的筛选条件不能为空".to_string(),
+                ));
+            }
+            list_all_cards_conn(conn, *filter)?
+                .into_iter()
+                .map(|c| c.id)
+                .collect()
+        }
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    Ok(ids.into_iter().filter(|id| seen.insert(id.clone())).collect())
+}
+
+/// 批量执行内核
+///
+/// `op` 对单张卡片执行操作；每张卡片处理完后回调 `on_progress`。
+pub(crate) fn run_batch<F>(
+    conn: &mut Connection,
+    operation: BatchOperation,
+    target: BatchTarget,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+    op: F,
+) -> Result<BatchReport, AppError>
+where
+    F: FnMut(&Connection, &str) -> Result<(), AppError>,
+{
+    let mut tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+
+    let report = run_batch_tx(&mut tx, operation, target, on_progress, op)?;
+
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!(
+        "✅ 批量{}完成: 成功 {}，失败 {}",
+        operation.display_name(),
+        report.succeeded,
+        report.failed
+    );
+    Ok(report)
+}
+
+/// 同 [`run_batch`]，在调用方的事务内执行（不提交），用于与其他写入组成同一事务
+pub(crate) fn run_batch_tx<F>(
+    tx: &mut Transaction,
+    operation: BatchOperation,
+    target: BatchTarget,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+    mut op: F,
+) -> Result<BatchReport, AppError>
+where
+    F: FnMut(&Connection, &str) -> Result<(), AppError>,
+{
+    let ids = resolve_target(tx, target)?;
+    let total = ids.len() as u32;
+    let mut items = Vec::with_capacity(ids.len());
+
+    for (index, id) in ids.iter().enumerate() {
+        let sp = tx
+            .savepoint()
+            .map_err(|e| AppError::Other(format!("创建保存点失败: {}", e)))?;
+
+        let callsign = get_card_conn(&sp, id)?.map(|c| c.callsign);
+        let result = op(&sp, id);
+
+        let item = match result {
+            Ok(()) => {
+                sp.commit()
+                    .map_err(|e| AppError::Other(format!("提交保存点失败: {}", e)))?;
+                BatchItemResult {
+                    card_id: id.clone(),
+                    callsign,
+                    success: true,
+                    error: None,
+                }
+            }
+            Err(e) => {
+                // 保存点未提交，drop 时自动回滚该卡的改动
+                drop(sp);
+                log::warn!("批量{}失败: {} - {}", operation.display_name(), id, e);
+                BatchItemResult {
+                    card_id: id.clone(),
+                    callsign,
+                    success: false,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+
+        on_progress(&BatchProgress {
+            operation,
+            current: index as u32 + 1,
+            total,
+            card_id: id.clone(),
+            success: item.success,
+        });
+        items.push(item);
+    }
+
+    let succeeded = items.iter().filter(|i| i.success).count() as u32;
+    Ok(BatchReport {
+        operation,
+        total,
+        succeeded,
+        failed: total - succeeded,
+        items,
+    })
+}
+
+/// 批量分发卡片
+pub fn batch_distribute_cards(
+    target: BatchTarget,
+    method: String,
+    address: Option<String>,
+    remarks: Option<String>,
+    proxy_callsign: Option<String>,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+) -> Result<BatchReport, AppError> {
+    batch_distribute_cards_in(
+        database()?,
+        target,
+        method,
+        address,
+        remarks,
+        proxy_callsign,
+        on_progress,
+    )
+}
+
+/// 同 [`batch_distribute_cards`]，在指定的数据库上下文中执行
+pub fn batch_distribute_cards_in(
+    db: &Database,
+    target: BatchTarget,
+    method: String,
+    address: Option<String>,
+    remarks: Option<String>,
+    proxy_callsign: Option<String>,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+) -> Result<BatchReport, AppError> {
+    let operator_id = db.current_operator_id();
+    let mut conn = db.connection()?;
+    run_batch(&mut conn, BatchOperation::Distribute, target, on_progress, |conn, id| {
+        distribute_card_conn(
+            conn,
+            id,
+            method.clone(),
+            address.clone(),
+            remarks.clone(),
+            proxy_callsign.clone(),
+            None,
+            None,
+            operator_id.as_deref(),
+        )
+        .map(|_| ())
+    })
+}
+
+/// 批量退卡
+pub fn batch_return_cards(
+    target: BatchTarget,
+    method: String,
+    remarks: Option<String>,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+) -> Result<BatchReport, AppError> {
+    batch_return_cards_in(database()?, target, method, remarks, on_progress)
+}
+
+/// 同 [`batch_return_cards`]，在指定的数据库上下文中执行
+pub fn batch_return_cards_in(
+    db: &Database,
+    target: BatchTarget,
+    method: String,
+    remarks: Option<String>,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+) -> Result<BatchReport, AppError> {
+    let operator_id = db.current_operator_id();
+    let mut conn = db.connection()?;
+    run_batch(&mut conn, BatchOperation::Return, target, on_progress, |conn, id| {
+        return_card_conn(conn, id, method.clone(), remarks.clone(), operator_id.as_deref())
+            .map(|_| ())
+    })
+}
+
+/// 批量删除卡片（移入回收站）
+pub fn batch_delete_cards(
+    target: BatchTarget,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+) -> Result<BatchReport, AppError> {
+    batch_delete_cards_in(database()?, target, on_progress)
+}
+
+/// 同 [`batch_delete_cards`]，在指定的数据库上下文中执行
+pub fn batch_delete_cards_in(
+    db: &Database,
+    target: BatchTarget,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+) -> Result<BatchReport, AppError> {
+    let operator_id = db.current_operator_id();
+    let mut conn = db.connection()?;
+    run_batch(&mut conn, BatchOperation::Delete, target, on_progress, |conn, id| {
+        delete_card_conn(conn, id, operator_id.as_deref())
+    })
+}
+
+/// 批量移动卡片到其他项目（逐卡记录修改历史）
+pub fn batch_move_cards(
+    target: BatchTarget,
+    project_id: String,
+    changed_by: Option<String>,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+) -> Result<BatchReport, AppError> {
+    batch_move_cards_in(database()?, target, project_id, changed_by, on_progress)
+}
+
+/// 同 [`batch_move_cards`]，在指定的数据库上下文中执行
+pub fn batch_move_cards_in(
+    db: &Database,
+    target: BatchTarget,
+    project_id: String,
+    changed_by: Option<String>,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+) -> Result<BatchReport, AppError> {
+    let operator = get_current_operator_in(db)?;
+    let changed_by = changed_by.or_else(|| operator.as_ref().map(|o| o.name.clone()));
+    let operator_id = operator.map(|o| o.id);
+
+    let mut conn = db.connection()?;
+    batch_move_cards_conn(
+        &mut conn,
+        target,
+        project_id,
+        changed_by,
+        operator_id.as_deref(),
+        on_progress,
+    )
+}
+
+/// 批量移动主体（crate-private，接收连接以便测试）。
+fn batch_move_cards_conn(
+    conn: &mut Connection,
+    target: BatchTarget,
+    project_id: String,
+    changed_by: Option<String>,
+    operator_id: Option<&str>,
+    on_progress: &mut dyn FnMut(&BatchProgress),
+) -> Result<BatchReport, AppError> {
+    // 目标项目不存在时整批失败，不逐卡报错
+    let project_exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1 AND deleted_at IS NULL)",
+            [&project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Other(format!("查询项目失败: {}", e)))?;
+
+    if !project_exists {
+        return Err(AppError::ProfileNotFound(format!(
+            "项目不存在: {}",
+            project_id
+        )));
+    }
+
+    run_batch(conn, BatchOperation::Move, target, on_progress, |conn, id| {
+        let update = CardUpdate {
+            project_id: Some(project_id.clone()),
+            ..Default::default()
+        };
+        update_card_conn(conn, id, update, changed_by.clone(), operator_id).map(|_| ())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{CardFilter, CardStatus};
+
+    fn setup_db() -> Connection {
+        let conn = crate::db::sqlite::open_test_connection();
+        for (id, name) in [("p1", "项目一"), ("p2", "项目二")] {
+            conn.execute(
+                "INSERT INTO projects (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+                rusqlite::params![id, name, "2026-01-01T00:00:00+08:00"],
+            )
+            .unwrap();
+        }
+        for (id, project_id, callsign) in [
+            ("c1", "p1", "BH2RO"),
+            ("c2", "p1", "BG2ABC"),
+            ("c3", "p2", "BH2RO"),
+        ] {
+            conn.execute(
+                r#"
+                INSERT INTO cards (id, project_id, callsign, qty, status, created_at, updated_at)
+                VALUES (?1, ?2, ?3, 1, 'pending', ?4, ?4)
+                "#,
+                rusqlite::params![id, project_id, callsign, "2026-01-01T00:00:00+08:00"],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    /// 按筛选条件批量分发：全部成功并推送逐卡进度
+    #[test]
+    fn test_batch_distribute_by_filter_reports_progress() {
+        let mut conn = setup_db();
+        let filter = CardFilter {
+            project_id: Some("p1".to_string()),
+            ..Default::default()
+        };
+
+        let mut progress = Vec::new();
+        let report = run_batch(
+            &mut conn,
+            BatchOperation::Distribute,
+            BatchTarget::Filter(Box::new(filter)),
+            &mut |p| progress.push((p.current, p.total)),
+            |conn, id| {
+                distribute_card_conn(conn, id, "自取".to_string(), None, None, None, None, None, None)
+                    .map(|_| ())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(report.total, 2);
+        assert_eq!(report.succeeded, 2);
+        assert_eq!(progress, vec![(1, 2), (2, 2)]);
+
+        let distributed: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM cards WHERE status = ?1",
+                [CardStatus::Distributed.as_str()],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(distributed, 2);
+    }
+
+    /// 单卡失败只回滚该卡：移动时呼号冲突的卡片失败，其余卡片照常提交
+    #[test]
+    fn test_batch_move_partial_failure() {
+        let mut conn = setup_db();
+        let target = BatchTarget::Ids(vec![
+            "c1".to_string(),
+            "c2".to_string(),
+            "missing".to_string(),
+        ]);
+
+        let report =
+            batch_move_cards_conn(&mut conn, target, "p2".to_string(), None, None, &mut |_| {})
+                .unwrap();
+
+        assert_eq!(report.total, 3);
+        assert_eq!(report.succeeded, 1);
+        assert!(!report.items[0].success, "c1 与 p2 中的 BH2RO 冲突");
+        assert!(report.items[1].success);
+        assert!(!report.items[2].success);
+        assert!(report.items[2].callsign.is_none());
+
+        let project_of = |id: &str| -> String {
+            conn.query_row("SELECT project_id FROM cards WHERE id = ?1", [id], |r| r.get(0))
+                .unwrap()
+        };
+        assert_eq!(project_of("c1"), "p1");
+        assert_eq!(project_of("c2"), "p2");
+    }
+
+    /// 空筛选条件视为误操作，整批拒绝
+    #[test]
+    fn test_batch_rejects_empty_filter() {
+        let mut conn = setup_db();
+        let result = run_batch(
+            &mut conn,
+            BatchOperation::Delete,
+            BatchTarget::Filter(Box::default()),
+            &mut |_| {},
+            |conn, id| delete_card_conn(conn, id, None),
+        );
+        assert!(matches!(result, Err(AppError::InvalidParameter(_))));
+    }
+}
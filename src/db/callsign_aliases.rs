@@ -0,0 +1,544 @@
+// 呼号别名模块
+//
+// 记录电台换号（如 BG 升级为 BH、迁移分区）：旧呼号在生效期内解析为现用呼号，
+// 呼号档案、卡片搜索与项目呼号集合据此把旧呼号名下的卡片归入现用呼号
+
+use crate::db::cards::validate_callsign;
+use crate::db::models::{format_datetime, now_china, CallsignAlias};
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+
+/// 当日日期（中国时区）的 SQL 表达式
+pub(crate) const TODAY_SQL: &str = "date('now', '+8 hours')";
+
+/// 查询列（与 `map_alias_row` 的列序一致）
+const ALIAS_COLUMNS: &str =
+    "id, alias, callsign, effective_from, effective_to, remarks, created_at, updated_at";
+
+/// 将查询行映射为 `CallsignAlias`（列序见 `ALIAS_COLUMNS`）
+fn map_alias_row(row: &rusqlite::Row) -> rusqlite::Result<CallsignAlias> {
+    Ok(CallsignAlias {
+        id: row.get(0)?,
+        alias: row.get(1)?,
+        callsign: row.get(2)?,
+        effective_from: row.get(3)?,
+        effective_to: row.get(4)?,
+        remarks: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+/// 生成「解析后呼号」的 SQL 表达式（crate-private，供各查询拼接）
+///
+/// `callsign_expr` 为呼号列或参数，`date_expr` 为取值 `YYYY-MM-DD…` 的日期/时间表达式
+/// （仅比较前 10 位）。该日期落在某条别名的生效期内时取其现用呼号，否则取呼号本身；
+/// 结果统一大写。同一旧呼号的生效期互不重叠，至多命中一条。
+pub(crate) fn resolved_callsign_sql(callsign_expr: &str, date_expr: &str) -> String {
+    format!(
+        "COALESCE((SELECT ca.callsign FROM callsign_aliases ca \
+         WHERE ca.alias = UPPER({c}) AND ca.effective_from <= substr({d}, 1, 10) \
+         AND (ca.effective_to IS NULL OR ca.effective_to >= substr({d}, 1, 10)) LIMIT 1), UPPER({c}))",
+        c = callsign_expr,
+        d = date_expr,
+    )
+}
+
+/// 查询旧呼号在当日生效的别名
+fn get_active_alias_conn(
+    conn: &Connection,
+    callsign: &str,
+) -> Result<Option<CallsignAlias>, AppError> {
+    let result = conn.query_row(
+        &format!(
+            r#"
+            SELECT {} FROM callsign_aliases
+            WHERE alias = ?1 AND effective_from <= {today}
+              AND (effective_to IS NULL OR effective_to >= {today})
+            LIMIT 1
+            "#,
+            ALIAS_COLUMNS,
+            today = TODAY_SQL,
+        ),
+        [callsign.to_uppercase()],
+        map_alias_row,
+    );
+
+    match result {
+        Ok(alias) => Ok(Some(alias)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询呼号别名失败: {}", e))),
+    }
+}
+
+/// 将呼号解析为当日的现用呼号（crate-private；非别名时返回大写的呼号本身）
+pub(crate) fn resolve_callsign_conn(conn: &Connection, callsign: &str) -> Result<String, AppError> {
+    Ok(match get_active_alias_conn(conn, callsign)? {
+        Some(alias) => alias.callsign,
+        None => callsign.to_uppercase(),
+    })
+}
+
+/// 按指定日期解析呼号（crate-private；`date` 为 `YYYY-MM-DD…` 格式，如卡片的录入时间）
+pub(crate) fn resolve_callsign_on_conn(
+    conn: &Connection,
+    callsign: &str,
+    date: &str,
+) -> Result<String, AppError> {
+    conn.query_row(
+        &format!("SELECT {}", resolved_callsign_sql("?1", "?2")),
+        [callsign, date],
+        |row| row.get(0),
+    )
+    .map_err(|e| AppError::Other(format!("解析呼号别名失败: {}", e)))
+}
+
+/// 查询指向某现用呼号的别名（按生效日期排序）
+pub(crate) fn list_aliases_of_conn(
+    conn: &Connection,
+    callsign: &str,
+) -> Result<Vec<CallsignAlias>, AppError> {
+    let mut stmt = conn
+        .prepare_cached(&format!(
+            "SELECT {} FROM callsign_aliases WHERE callsign = ?1 ORDER BY effective_from ASC, alias ASC",
+            ALIAS_COLUMNS
+        ))
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let aliases = stmt
+        .query_map([callsign.to_uppercase()], map_alias_row)
+        .map_err(|e| AppError::Other(format!("查询呼号别名失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取呼号别名失败: {}", e)))?;
+
+    Ok(aliases)
+}
+
+fn get_alias_conn(conn: &Connection, id: &str) -> Result<Option<CallsignAlias>, AppError> {
+    let result = conn.query_row(
+        &format!(
+            "SELECT {} FROM callsign_aliases WHERE id = ?1",
+            ALIAS_COLUMNS
+        ),
+        [id],
+        map_alias_row,
+    );
+
+    match result {
+        Ok(alias) => Ok(Some(alias)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询呼号别名失败: {}", e))),
+    }
+}
+
+/// 校验并规范化日期（YYYY-MM-DD）
+fn normalize_date(value: &str, field: &str) -> Result<String, AppError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .map_err(|_| AppError::InvalidParameter(format!("{}格式无效，应为 YYYY-MM-DD", field)))
+}
+
+/// 校验生效期，并检查同一旧呼号的生效期不重叠
+fn check_alias_period_conn(
+    conn: &Connection,
+    id: Option<&str>,
+    alias: &str,
+    effective_from: &str,
+    effective_to: Option<&str>,
+) -> Result<(), AppError> {
+    if effective_to.is_some_and(|to| to < effective_from) {
+        return Err(AppError::InvalidParameter(
+            "失效日期不能早于生效日期".to_string(),
+        ));
+    }
+
+    let overlapping: Option<String> = conn
+        .query_row(
+            r#"
+            SELECT callsign FROM callsign_aliases
+            WHERE alias = ?1 AND (?2 IS NULL OR id != ?2)
+              AND effective_from <= COALESCE(?4, '9999-12-31')
+              AND COALESCE(effective_to, '9999-12-31') >= ?3
+            LIMIT 1
+            "#,
+            rusqlite::params![alias, id, effective_from, effective_to],
+            |row| row.get(0),
+        )
+        .map(Some)
+        .or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+            e => Err(AppError::Other(format!("查询呼号别名失败: {}", e))),
+        })?;
+
+    match overlapping {
+        Some(callsign) => Err(AppError::InvalidParameter(format!(
+            "{} 在该时段内已登记为 {} 的别名",
+            alias, callsign
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// 空白备注视为未填写
+fn normalize_remarks(remarks: Option<String>) -> Option<String> {
+    remarks
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+}
+
+/// 检查呼号是否为当日生效的别名（录入卡片时提示）
+pub fn check_callsign_alias(callsign: &str) -> Result<Option<CallsignAlias>, AppError> {
+    check_callsign_alias_in(database()?, callsign)
+}
+
+/// 同 [`check_callsign_alias`]，在指定的数据库上下文中执行
+pub fn check_callsign_alias_in(
+    db: &Database,
+    callsign: &str,
+) -> Result<Option<CallsignAlias>, AppError> {
+    let conn = db.connection()?;
+    get_active_alias_conn(&conn, callsign.trim())
+}
+
+/// 查询呼号别名列表（按旧呼号、生效日期排序）
+///
+/// `callsign` 不为空时仅返回旧呼号或现用呼号与之相同的别名。
+pub fn list_callsign_aliases(callsign: Option<&str>) -> Result<Vec<CallsignAlias>, AppError> {
+    list_callsign_aliases_in(database()?, callsign)
+}
+
+/// 同 [`list_callsign_aliases`]，在指定的数据库上下文中执行
+pub fn list_callsign_aliases_in(
+    db: &Database,
+    callsign: Option<&str>,
+) -> Result<Vec<CallsignAlias>, AppError> {
+    let conn = db.connection()?;
+    let callsign = callsign.map(|c| c.trim().to_uppercase());
+
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+            SELECT {} FROM callsign_aliases
+            WHERE ?1 IS NULL OR alias = ?1 OR callsign = ?1
+            ORDER BY alias ASC, effective_from ASC
+            "#,
+            ALIAS_COLUMNS
+        ))
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let aliases = stmt
+        .query_map([callsign], map_alias_row)
+        .map_err(|e| AppError::Other(format!("查询呼号别名失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取呼号别名失败: {}", e)))?;
+
+    Ok(aliases)
+}
+
+/// 登记呼号别名
+///
+/// 现用呼号本身若为当日生效的别名，改为登记到其现用呼号，保证别名只解析一层；
+/// 原先指向旧呼号的别名同时改指向新的现用呼号（如 BA→BG 后又 BG→BH，BA 改指向 BH）。
+pub fn create_callsign_alias(
+    alias: String,
+    callsign: String,
+    effective_from: String,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<CallsignAlias, AppError> {
+    create_callsign_alias_in(
+        database()?,
+        alias,
+        callsign,
+        effective_from,
+        effective_to,
+        remarks,
+    )
+}
+
+/// 同 [`create_callsign_alias`]，在指定的数据库上下文中执行
+pub fn create_callsign_alias_in(
+    db: &Database,
+    alias: String,
+    callsign: String,
+    effective_from: String,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<CallsignAlias, AppError> {
+    let alias = alias.trim().to_uppercase();
+    let callsign = callsign.trim().to_uppercase();
+    validate_callsign(&alias)?;
+    validate_callsign(&callsign)?;
+    let effective_from = normalize_date(&effective_from, "生效日期")?;
+    let effective_to = effective_to
+        .filter(|d| !d.trim().is_empty())
+        .map(|d| normalize_date(&d, "失效日期"))
+        .transpose()?;
+
+    let mut conn = db.connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+
+    let callsign = resolve_callsign_conn(&tx, &callsign)?;
+    if callsign == alias {
+        return Err(AppError::InvalidParameter(
+            "旧呼号与现用呼号不能相同".to_string(),
+        ));
+    }
+    check_alias_period_conn(&tx, None, &alias, &effective_from, effective_to.as_deref())?;
+
+    let now = format_datetime(&now_china());
+    let entry = CallsignAlias {
+        id: uuid::Uuid::new_v4().to_string(),
+        alias,
+        callsign,
+        effective_from,
+        effective_to,
+        remarks: normalize_remarks(remarks),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+
+    tx.execute(
+        r#"
+        INSERT INTO callsign_aliases (id, alias, callsign, effective_from, effective_to, remarks, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        "#,
+        rusqlite::params![
+            &entry.id,
+            &entry.alias,
+            &entry.callsign,
+            &entry.effective_from,
+            &entry.effective_to,
+            &entry.remarks,
+            &entry.created_at,
+            &entry.updated_at,
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("登记呼号别名失败: {}", e)))?;
+
+    tx.execute(
+        "UPDATE callsign_aliases SET callsign = ?1, updated_at = ?2 WHERE callsign = ?3 AND alias != ?1",
+        rusqlite::params![&entry.callsign, &entry.updated_at, &entry.alias],
+    )
+    .map_err(|e| AppError::Other(format!("更新呼号别名失败: {}", e)))?;
+
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!("✅ 登记呼号别名成功: {} → {}", entry.alias, entry.callsign);
+    Ok(entry)
+}
+
+/// 修改呼号别名的生效期与备注（旧呼号、现用呼号不可修改，需要时删除后重新登记）
+pub fn update_callsign_alias(
+    id: &str,
+    effective_from: String,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<CallsignAlias, AppError> {
+    update_callsign_alias_in(database()?, id, effective_from, effective_to, remarks)
+}
+
+/// 同 [`update_callsign_alias`]，在指定的数据库上下文中执行
+pub fn update_callsign_alias_in(
+    db: &Database,
+    id: &str,
+    effective_from: String,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<CallsignAlias, AppError> {
+    let effective_from = normalize_date(&effective_from, "生效日期")?;
+    let effective_to = effective_to
+        .filter(|d| !d.trim().is_empty())
+        .map(|d| normalize_date(&d, "失效日期"))
+        .transpose()?;
+
+    let conn = db.connection()?;
+    let current = get_alias_conn(&conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("呼号别名不存在: {}", id)))?;
+    check_alias_period_conn(
+        &conn,
+        Some(id),
+        &current.alias,
+        &effective_from,
+        effective_to.as_deref(),
+    )?;
+
+    let now = format_datetime(&now_china());
+    conn.execute(
+        r#"
+        UPDATE callsign_aliases
+        SET effective_from = ?1, effective_to = ?2, remarks = ?3, updated_at = ?4
+        WHERE id = ?5
+        "#,
+        rusqlite::params![
+            effective_from,
+            effective_to,
+            normalize_remarks(remarks),
+            now,
+            id
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("更新呼号别名失败: {}", e)))?;
+
+    log::info!(
+        "✅ 更新呼号别名成功: {} → {}",
+        current.alias,
+        current.callsign
+    );
+    get_alias_conn(&conn, id)?.ok_or_else(|| AppError::Other("更新后无法获取呼号别名".to_string()))
+}
+
+/// 删除呼号别名
+pub fn delete_callsign_alias(id: &str) -> Result<(), AppError> {
+    delete_callsign_alias_in(database()?, id)
+}
+
+/// 同 [`delete_callsign_alias`]，在指定的数据库上下文中执行
+pub fn delete_callsign_alias_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let conn = db.connection()?;
+
+    let affected = conn
+        .execute("DELETE FROM callsign_aliases WHERE id = ?1", [id])
+        .map_err(|e| AppError::Other(format!("删除呼号别名失败: {}", e)))?;
+
+    if affected == 0 {
+        return Err(AppError::ProfileNotFound(format!("呼号别名不存在: {}", id)));
+    }
+
+    log::info!("✅ 删除呼号别名成功: {}", id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::callsign_dossier::get_callsign_dossier_in;
+    use crate::db::cards::{create_card_in, get_project_callsigns_in, list_cards_in};
+    use crate::db::models::{CardFilter, Pagination};
+    use crate::db::projects::create_project_in;
+
+    /// 旧呼号名下的卡片归入现用呼号：档案、搜索与项目呼号集合均按别名解析
+    #[test]
+    fn test_alias_resolves_in_dossier_search_and_project_callsigns() {
+        let db = Database::open_in_memory().unwrap();
+        let first = create_project_in(&db, "项目一".to_string()).unwrap();
+        let second = create_project_in(&db, "项目二".to_string()).unwrap();
+        create_card_in(&db, first.id.clone(), "BG2ABC".to_string(), 1, Some(1)).unwrap();
+        create_card_in(&db, second.id.clone(), "BH2XYZ".to_string(), 2, Some(1)).unwrap();
+
+        assert!(check_callsign_alias_in(&db, "bg2abc").unwrap().is_none());
+        let alias = create_callsign_alias_in(
+            &db,
+            " bg2abc ".to_string(),
+            "BH2XYZ".to_string(),
+            "2020-01-01".to_string(),
+            None,
+            Some("  ".to_string()),
+        )
+        .unwrap();
+        assert_eq!(alias.alias, "BG2ABC");
+        assert!(alias.remarks.is_none());
+        assert_eq!(
+            check_callsign_alias_in(&db, "bg2abc")
+                .unwrap()
+                .unwrap()
+                .callsign,
+            "BH2XYZ"
+        );
+
+        let dossier = get_callsign_dossier_in(&db, "BG2ABC").unwrap();
+        assert_eq!(dossier.callsign, "BH2XYZ");
+        assert_eq!(dossier.total_cards, 2);
+        assert_eq!(dossier.aliases.len(), 1);
+
+        let filter = CardFilter {
+            callsign: Some("BH2XYZ".to_string()),
+            ..Default::default()
+        };
+        let found = list_cards_in(&db, filter, Pagination::default()).unwrap();
+        assert_eq!(found.total, 2);
+
+        assert_eq!(
+            get_project_callsigns_in(&db, &first.id).unwrap(),
+            vec!["BH2XYZ"]
+        );
+
+        // 同一旧呼号的生效期不可重叠；失效后不再解析
+        assert!(create_callsign_alias_in(
+            &db,
+            "BG2ABC".to_string(),
+            "BH2OTHER".to_string(),
+            "2024-06-01".to_string(),
+            None,
+            None,
+        )
+        .is_err());
+        update_callsign_alias_in(
+            &db,
+            &alias.id,
+            "2020-01-01".to_string(),
+            Some("2020-12-31".to_string()),
+            None,
+        )
+        .unwrap();
+        assert!(check_callsign_alias_in(&db, "BG2ABC").unwrap().is_none());
+        assert_eq!(
+            get_callsign_dossier_in(&db, "BH2XYZ").unwrap().total_cards,
+            1
+        );
+    }
+
+    /// 别名只解析一层：现用呼号再次换号时，既有别名改指向最新呼号
+    #[test]
+    fn test_alias_chain_is_flattened() {
+        let db = Database::open_in_memory().unwrap();
+        create_callsign_alias_in(
+            &db,
+            "BA2AA".to_string(),
+            "BG2AA".to_string(),
+            "2010-01-01".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        create_callsign_alias_in(
+            &db,
+            "BG2AA".to_string(),
+            "BH2AA".to_string(),
+            "2020-01-01".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+
+        let aliases = list_callsign_aliases_in(&db, Some("bh2aa")).unwrap();
+        assert_eq!(aliases.len(), 2);
+        assert!(aliases.iter().all(|a| a.callsign == "BH2AA"));
+
+        // 指向已换号的旧呼号时登记到其现用呼号；形成环路时拒绝
+        let later = create_callsign_alias_in(
+            &db,
+            "BD2AA".to_string(),
+            "BG2AA".to_string(),
+            "2021-01-01".to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(later.callsign, "BH2AA");
+        assert!(create_callsign_alias_in(
+            &db,
+            "BH2AA".to_string(),
+            "BG2AA".to_string(),
+            "2025-01-01".to_string(),
+            None,
+            None,
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,244 @@
+// 呼号档案模块
+//
+// 汇总某电台在所有项目中的卡片、状态统计、地址缓存、顺丰订单与最近一次当面领取，
+// 供来访时一次性交付全部卡片
+
+use crate::db::callsign_aliases::{
+    list_aliases_of_conn, resolve_callsign_conn, resolved_callsign_sql,
+};
+use crate::db::cards::{list_callsign_cards_conn, validate_callsign};
+use crate::db::models::{AddressEntry, CallsignDossier, CallsignPickup, CardStatus};
+use crate::db::sf_express::list_orders_by_callsign_conn;
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use rusqlite::Connection;
+use std::collections::HashSet;
+
+/// 获取呼号档案
+pub fn get_callsign_dossier(callsign: &str) -> Result<CallsignDossier, AppError> {
+    get_callsign_dossier_in(database()?, callsign)
+}
+
+/// 同 [`get_callsign_dossier`]，在指定的数据库上下文中执行
+pub fn get_callsign_dossier_in(db: &Database, callsign: &str) -> Result<CallsignDossier, AppError> {
+    let conn = db.connection()?;
+    get_callsign_dossier_conn(&conn, callsign)
+}
+
+/// 档案查询主体（crate-private，接收连接以便测试）。
+pub(crate) fn get_callsign_dossier_conn(
+    conn: &Connection,
+    callsign: &str,
+) -> Result<CallsignDossier, AppError> {
+    let callsign = callsign.trim().to_uppercase();
+    validate_callsign(&callsign)?;
+    // 查询旧呼号时转为其现用呼号，旧呼号名下的卡片一并归入
+    let callsign = resolve_callsign_conn(conn, &callsign)?;
+
+    let cards = list_callsign_cards_conn(conn, &callsign)?;
+
+    let mut dossier = CallsignDossier {
+        callsign: callsign.clone(),
+        aliases: list_aliases_of_conn(conn, &callsign)?,
+        cards: Vec::new(),
+        project_count: 0,
+        total_cards: cards.len() as i64,
+        pending_cards: 0,
+        partially_distributed_cards: 0,
+        distributed_cards: 0,
+        returned_cards: 0,
+        awaiting_postage_cards: 0,
+        held_cards: 0,
+        lost_cards: 0,
+        deliverable_qty: 0,
+        addresses: merge_addresses(
+            cards
+                .iter()
+                .filter_map(|c| c.metadata.as_ref()?.address_cache.as_deref())
+                .flatten(),
+        ),
+        sf_orders: list_orders_by_callsign_conn(conn, &callsign)?,
+        last_pickup: get_last_pickup_conn(conn, &callsign)?,
+    };
+
+    let mut projects = HashSet::new();
+    for card in &cards {
+        projects.insert(card.project_id.as_str());
+        let counter = match card.status {
+            CardStatus::Pending => &mut dossier.pending_cards,
+            CardStatus::PartiallyDistributed => &mut dossier.partially_distributed_cards,
+            CardStatus::Distributed => &mut dossier.distributed_cards,
+            CardStatus::Returned => &mut dossier.returned_cards,
+            CardStatus::AwaitingPostage => &mut dossier.awaiting_postage_cards,
+            CardStatus::Held => &mut dossier.held_cards,
+            CardStatus::Lost => &mut dossier.lost_cards,
+        };
+        *counter += 1;
+
+        if matches!(
+            card.status,
+            CardStatus::Pending
+                | CardStatus::PartiallyDistributed
+                | CardStatus::AwaitingPostage
+                | CardStatus::Held
+        ) {
+            dossier.deliverable_qty += (card.qty - card.distributed_qty).max(0);
+        }
+    }
+    dossier.project_count = projects.len() as i64;
+    dossier.cards = cards;
+
+    Ok(dossier)
+}
+
+/// 合并各卡片的地址缓存：内容相同的地址只保留最近缓存的一条，按缓存时间倒序
+fn merge_addresses<'a>(entries: impl Iterator<Item = &'a AddressEntry>) -> Vec<AddressEntry> {
+    let mut entries: Vec<&AddressEntry> = entries.collect();
+    entries.sort_by(|a, b| b.cached_at.cmp(&a.cached_at));
+
+    let mut seen = HashSet::new();
+    entries
+        .into_iter()
+        .filter(|e| {
+            seen.insert((
+                e.source.clone(),
+                e.chinese_address.clone(),
+                e.english_address.clone(),
+                e.name.clone(),
+                e.mail_method.clone(),
+            ))
+        })
+        .cloned()
+        .collect()
+}
+
+/// 查询最近一次当面领取
+///
+/// 取该呼号卡片的分发事件、以及该呼号作为代领人的分发事件中（均按别名解析旧呼号），
+/// 分发方式无需地址（自取、代领等，见 `card_handling_methods.requires_address`）的最近一条。
+fn get_last_pickup_conn(
+    conn: &Connection,
+    callsign: &str,
+) -> Result<Option<CallsignPickup>, AppError> {
+    let result = conn.query_row(
+        &format!(
+            r#"
+        SELECT
+            e.card_id,
+            c.callsign,
+            COALESCE(p.name, ''),
+            json_extract(e.payload, '$.distribution.method'),
+            json_extract(e.payload, '$.distribution.proxy_callsign'),
+            json_extract(e.payload, '$.distribution.qty'),
+            {card_callsign} != ?1,
+            COALESCE(json_extract(e.payload, '$.distribution.distributed_at'), e.created_at)
+        FROM card_events e
+        JOIN cards c ON c.id = e.card_id
+        LEFT JOIN projects p ON p.id = c.project_id
+        LEFT JOIN card_handling_methods m
+            ON m.kind = 'distribution' AND m.code = json_extract(e.payload, '$.distribution.method')
+        WHERE e.event_type IN ('distributed', 'redistributed')
+          AND c.deleted_at IS NULL
+          AND COALESCE(m.requires_address, 0) = 0
+          AND ({card_callsign} = ?1 OR {proxy_callsign} = ?1)
+        ORDER BY e.created_at DESC, e.rowid DESC
+        LIMIT 1
+        "#,
+            card_callsign = resolved_callsign_sql("c.callsign", "c.created_at"),
+            proxy_callsign = resolved_callsign_sql(
+                "json_extract(e.payload, '$.distribution.proxy_callsign')",
+                "e.created_at"
+            ),
+        ),
+        [callsign],
+        |row| {
+            Ok(CallsignPickup {
+                card_id: row.get(0)?,
+                callsign: row.get(1)?,
+                project_name: row.get(2)?,
+                method: row.get(3)?,
+                proxy_callsign: row.get(4)?,
+                qty: row.get(5)?,
+                as_proxy: row.get(6)?,
+                picked_up_at: row.get(7)?,
+            })
+        },
+    );
+
+    match result {
+        Ok(pickup) => Ok(Some(pickup)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询领取记录失败: {}", e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::cards::{
+        create_card_in, distribute_card_in, return_card_in, save_card_address_in,
+    };
+    use crate::db::projects::create_project_in;
+
+    /// 档案汇总跨项目卡片、去重地址，最近领取包含代他人领取
+    #[test]
+    fn test_dossier_aggregates_cards_across_projects() {
+        let db = Database::open_in_memory().unwrap();
+        let first = create_project_in(&db, "项目一".to_string()).unwrap();
+        let second = create_project_in(&db, "项目二".to_string()).unwrap();
+        let third = create_project_in(&db, "项目三".to_string()).unwrap();
+
+        let a = create_card_in(&db, first.id.clone(), "BH2RO".to_string(), 3, Some(1)).unwrap();
+        let b = create_card_in(&db, second.id.clone(), "bh2ro".to_string(), 2, Some(1)).unwrap();
+        let c = create_card_in(&db, third.id.clone(), "BH2RO".to_string(), 1, Some(1)).unwrap();
+        let other = create_card_in(&db, second.id.clone(), "BG2ABC".to_string(), 1, Some(2)).unwrap();
+
+        for card in [&a, &b] {
+            save_card_address_in(
+                &db,
+                &card.id,
+                "qrz.cn".to_string(),
+                Some("北京市海淀区".to_string()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        }
+        distribute_card_in(&db, &a.id, "自取".to_string(), None, None, None, Some(1)).unwrap();
+        return_card_in(&db, &c.id, "NOT FOUND".to_string(), None).unwrap();
+
+        let dossier = get_callsign_dossier_in(&db, " bh2ro ").unwrap();
+        assert_eq!(dossier.callsign, "BH2RO");
+        assert_eq!(dossier.total_cards, 3);
+        assert_eq!(dossier.project_count, 3);
+        assert_eq!(dossier.pending_cards, 1);
+        assert_eq!(dossier.partially_distributed_cards, 1);
+        assert_eq!(dossier.returned_cards, 1);
+        assert_eq!(dossier.deliverable_qty, 4);
+        assert_eq!(dossier.addresses.len(), 1);
+        assert!(dossier.sf_orders.is_empty());
+        let pickup = dossier.last_pickup.unwrap();
+        assert_eq!(pickup.card_id, a.id);
+        assert!(!pickup.as_proxy);
+
+        // BH2RO 代 BG2ABC 领取后，最近领取为代领记录
+        distribute_card_in(
+            &db,
+            &other.id,
+            "代领".to_string(),
+            None,
+            None,
+            Some("BH2RO".to_string()),
+            None,
+        )
+        .unwrap();
+        let pickup = get_callsign_dossier_in(&db, "BH2RO").unwrap().last_pickup.unwrap();
+        assert_eq!(pickup.card_id, other.id);
+        assert_eq!(pickup.callsign, "BG2ABC");
+        assert!(pickup.as_proxy);
+
+        assert!(get_callsign_dossier_in(&db, "").is_err());
+    }
+}
@@ -0,0 +1,486 @@
+// 卡片事件日志模块
+//
+// 卡片的每次状态流转都以事件形式追加写入 card_events 表，
+// cards 表中的 status、已分发数量以及 card_distributions/card_returns 是事件日志的投影
+
+use crate::db::card_metadata::{save_card_distribution_conn, save_card_return_conn};
+use crate::db::cards::CARD_VISIBLE_CONDITION;
+use crate::db::models::{
+    format_datetime, now_china, Card, CardEvent, CardEventType, CardMetadata, CardStatus,
+    DistributionInfo, ReturnInfo,
+};
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// 事件附加数据（以 JSON 存于 card_events.payload）
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct EventPayload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distribution: Option<DistributionInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "return")]
+    return_info: Option<ReturnInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    waybill_no: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remarks: Option<String>,
+}
+
+/// 写入一条事件（仅插入，不刷新投影）
+///
+/// 用于导入等需要原样写入事件的场景；业务操作应使用 `append_card_event_conn`。
+pub(crate) fn insert_card_event_conn(conn: &Connection, event: &CardEvent) -> Result<(), AppError> {
+    let payload = EventPayload {
+        distribution: event.distribution.clone(),
+        return_info: event.return_info.clone(),
+        waybill_no: event.waybill_no.clone(),
+        remarks: event.remarks.clone(),
+    };
+    let payload_json = if payload.distribution.is_none()
+        && payload.return_info.is_none()
+        && payload.waybill_no.is_none()
+        && payload.remarks.is_none()
+    {
+        None
+    } else {
+        Some(
+            serde_json::to_string(&payload)
+                .map_err(|e| AppError::Other(format!("序列化事件数据失败: {}", e)))?,
+        )
+    };
+
+    conn.execute(
+        r#"
+        INSERT INTO card_events (id, card_id, event_type, payload, created_at, operator_id)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+        rusqlite::params![
+            &event.id,
+            &event.card_id,
+            event.event_type.as_str(),
+            payload_json,
+            &event.created_at,
+            &event.operator_id,
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("写入卡片事件失败: {}", e)))?;
+
+    Ok(())
+}
+
+/// 追加一条事件并刷新卡片投影
+///
+/// 调用方负责事务：事件写入与投影更新应处于同一事务内。
+pub(crate) fn append_card_event_conn(conn: &Connection, event: &CardEvent) -> Result<(), AppError> {
+    insert_card_event_conn(conn, event)?;
+    refresh_card_projection_conn(conn, &event.card_id)
+}
+
+/// 将查询行映射为 `CardEvent`（列序：id, card_id, event_type, payload, created_at, operator_id）
+fn map_event_row(row: &rusqlite::Row) -> rusqlite::Result<CardEvent> {
+    let event_type_str: String = row.get(2)?;
+    let payload_str: Option<String> = row.get(3)?;
+    let payload: EventPayload = payload_str
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+
+    Ok(CardEvent {
+        id: row.get(0)?,
+        card_id: row.get(1)?,
+        event_type: CardEventType::from_str(&event_type_str).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(
+                2,
+                rusqlite::types::Type::Text,
+                format!("未知事件类型: {}", event_type_str).into(),
+            )
+        })?,
+        distribution: payload.distribution,
+        return_info: payload.return_info,
+        waybill_no: payload.waybill_no,
+        remarks: payload.remarks,
+        operator_id: row.get(5)?,
+        created_at: row.get(4)?,
+    })
+}
+
+/// 查询卡片的事件日志（按时间正序）
+pub fn list_card_events(card_id: &str) -> Result<Vec<CardEvent>, AppError> {
+    list_card_events_in(database()?, card_id)
+}
+
+/// 同 [`list_card_events`]，在指定的数据库上下文中执行
+pub fn list_card_events_in(db: &Database, card_id: &str) -> Result<Vec<CardEvent>, AppError> {
+    let conn = db.connection()?;
+    list_card_events_conn(&conn, card_id)
+}
+
+/// 事件日志查询主体（crate-private，接收连接以便在事务内复用）。
+///
+/// 同一秒内的事件按写入顺序（rowid）排序。
+pub(crate) fn list_card_events_conn(
+    conn: &Connection,
+    card_id: &str,
+) -> Result<Vec<CardEvent>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, card_id, event_type, payload, created_at, operator_id
+            FROM card_events
+            WHERE card_id = ?1
+            ORDER BY created_at ASC, rowid ASC
+            "#,
+        )
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let events = stmt
+        .query_map([card_id], map_event_row)
+        .map_err(|e| AppError::Other(format!("查询卡片事件失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取卡片事件失败: {}", e)))?;
+
+    Ok(events)
+}
+
+/// 查询全部事件（用于导出，按卡片与时间排序，不含回收站中的卡片）
+pub(crate) fn list_all_card_events_conn(conn: &Connection) -> Result<Vec<CardEvent>, AppError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+            SELECT e.id, e.card_id, e.event_type, e.payload, e.created_at, e.operator_id
+            FROM card_events e
+            JOIN cards c ON c.id = e.card_id
+            WHERE {}
+            ORDER BY e.card_id ASC, e.created_at ASC, e.rowid ASC
+            "#,
+            CARD_VISIBLE_CONDITION
+        ))
+        .map_err(|e| AppError::Other(format!("准备事件查询失败: {}", e)))?;
+
+    let events = stmt
+        .query_map([], map_event_row)
+        .map_err(|e| AppError::Other(format!("查询卡片事件失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取卡片事件失败: {}", e)))?;
+
+    Ok(events)
+}
+
+/// 卡片是否已有分发记录（决定新的分发是首次分发还是重新分发）
+pub(crate) fn has_distribution_event_conn(conn: &Connection, card_id: &str) -> Result<bool, AppError> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM card_events WHERE card_id = ?1 AND event_type IN ('distributed', 'redistributed'))",
+        [card_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| AppError::Other(format!("查询卡片事件失败: {}", e)))
+}
+
+/// 由事件日志投影出卡片状态、已分发数量与元数据
+///
+/// - 状态取最后一个改变状态的事件（关联寄件事件不参与投影）
+/// - 分发事件累加分发数量（未指定数量即分发全部剩余数量），
+///   未达到卡片数量 `qty` 时为部分分发；退卡后已分发数量归零
+/// - 待付邮资、留存、遗失不影响已分发数量；恢复后按已分发数量回到待分发或部分分发
+/// - 分发信息取最近一次分发/重新分发，退卡信息取最近一次退卡
+/// - 地址缓存不属于事件日志，沿用 `base` 中的值
+pub fn project_card_events(
+    events: &[CardEvent],
+    qty: i32,
+    base: Option<CardMetadata>,
+) -> (CardStatus, i32, Option<CardMetadata>) {
+    let mut status = CardStatus::Pending;
+    let mut distributed_qty = 0;
+    let mut metadata = CardMetadata {
+        address_cache: base.and_then(|m| m.address_cache),
+        ..Default::default()
+    };
+
+    for event in events {
+        match event.event_type {
+            CardEventType::Entered => {
+                status = CardStatus::Pending;
+                distributed_qty = 0;
+            }
+            CardEventType::Distributed | CardEventType::Redistributed => {
+                distributed_qty = match event.distribution.as_ref().and_then(|d| d.qty) {
+                    Some(n) => (distributed_qty + n).min(qty),
+                    None => qty,
+                };
+                status = if distributed_qty < qty {
+                    CardStatus::PartiallyDistributed
+                } else {
+                    CardStatus::Distributed
+                };
+                if event.distribution.is_some() {
+                    metadata.distribution = event.distribution.clone();
+                }
+            }
+            CardEventType::Returned => {
+                status = CardStatus::Returned;
+                distributed_qty = 0;
+                if event.return_info.is_some() {
+                    metadata.return_info = event.return_info.clone();
+                }
+            }
+            CardEventType::WaybillAttached => {}
+            CardEventType::AwaitingPostage => status = CardStatus::AwaitingPostage,
+            CardEventType::Held => status = CardStatus::Held,
+            CardEventType::Lost => status = CardStatus::Lost,
+            CardEventType::Released => {
+                status = if distributed_qty == 0 {
+                    CardStatus::Pending
+                } else if distributed_qty < qty {
+                    CardStatus::PartiallyDistributed
+                } else {
+                    CardStatus::Distributed
+                };
+            }
+        }
+    }
+
+    let is_empty = metadata.distribution.is_none()
+        && metadata.return_info.is_none()
+        && metadata.address_cache.is_none();
+
+    (
+        status,
+        distributed_qty,
+        if is_empty { None } else { Some(metadata) },
+    )
+}
+
+/// 按事件日志重算并写回卡片的状态、已分发数量、分发信息与退卡信息
+pub(crate) fn refresh_card_projection_conn(conn: &Connection, card_id: &str) -> Result<(), AppError> {
+    let qty: i32 = match conn.query_row("SELECT qty FROM cards WHERE id = ?1", [card_id], |row| {
+        row.get(0)
+    }) {
+        Ok(qty) => qty,
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+            return Err(AppError::ProfileNotFound(format!("卡片不存在: {}", card_id)));
+        }
+        Err(e) => return Err(AppError::Other(format!("查询卡片失败: {}", e))),
+    };
+
+    let events = list_card_events_conn(conn, card_id)?;
+    let (status, distributed_qty, metadata) = project_card_events(&events, qty, None);
+    let metadata = metadata.unwrap_or_default();
+    let updated_at = format_datetime(&now_china());
+
+    conn.execute(
+        "UPDATE cards SET status = ?1, distributed_qty = ?2, updated_at = ?3 WHERE id = ?4",
+        rusqlite::params![status.as_str(), distributed_qty, updated_at, card_id],
+    )
+    .map_err(|e| AppError::Other(format!("更新卡片失败: {}", e)))?;
+
+    save_card_distribution_conn(conn, card_id, metadata.distribution.as_ref())?;
+    save_card_return_conn(conn, card_id, metadata.return_info.as_ref())?;
+
+    Ok(())
+}
+
+/// 根据卡片现有元数据推导事件日志
+///
+/// 用于导入不含事件日志的旧格式数据（v1.0/v1.1、云端快照），规则与迁移回填一致。
+pub fn events_from_card(card: &Card) -> Vec<CardEvent> {
+    let mut events = Vec::new();
+
+    let mut entered = CardEvent::new(&card.id, CardEventType::Entered);
+    entered.created_at = card.created_at.clone();
+    events.push(entered);
+
+    if let Some(ref metadata) = card.metadata {
+        if let Some(ref distribution) = metadata.distribution {
+            let mut event = CardEvent::new(&card.id, CardEventType::Distributed);
+            event.created_at = distribution.distributed_at.clone();
+            // 部分分发的卡片以一次分发记录全部已分发数量
+            let qty = if card.distributed_qty > 0 && card.distributed_qty < card.qty {
+                Some(card.distributed_qty)
+            } else {
+                distribution.qty
+            };
+            event.distribution = Some(DistributionInfo {
+                qty,
+                ..distribution.clone()
+            });
+            events.push(event);
+        }
+        if let Some(ref return_info) = metadata.return_info {
+            let mut event = CardEvent::new(&card.id, CardEventType::Returned);
+            event.created_at = return_info.returned_at.clone();
+            event.return_info = Some(return_info.clone());
+            events.push(event);
+        }
+    }
+
+    // 待付邮资、留存、遗失不在元数据中，按卡片状态补一条状态事件
+    let status_event = match card.status {
+        CardStatus::AwaitingPostage => Some(CardEventType::AwaitingPostage),
+        CardStatus::Held => Some(CardEventType::Held),
+        CardStatus::Lost => Some(CardEventType::Lost),
+        _ => None,
+    };
+    if let Some(event_type) = status_event {
+        let mut event = CardEvent::new(&card.id, event_type);
+        event.created_at = card.updated_at.clone();
+        events.push(event);
+    }
+
+    events.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distribution(method: &str, at: &str) -> DistributionInfo {
+        DistributionInfo {
+            method: method.to_string(),
+            address: None,
+            remarks: None,
+            proxy_callsign: None,
+            qty: None,
+            waybill_no: None,
+            distributed_at: at.to_string(),
+        }
+    }
+
+    fn event(event_type: CardEventType, at: &str) -> CardEvent {
+        let mut event = CardEvent::new("c1", event_type);
+        event.created_at = at.to_string();
+        event
+    }
+
+    /// 重新分发后投影取最新分发信息，状态回到已分发，退卡信息保留
+    #[test]
+    fn test_project_redistribution_keeps_latest() {
+        let mut first = event(CardEventType::Distributed, "2026-01-02T00:00:00+08:00");
+        first.distribution = Some(distribution("自取", "2026-01-02T00:00:00+08:00"));
+        let mut returned = event(CardEventType::Returned, "2026-01-03T00:00:00+08:00");
+        returned.return_info = Some(ReturnInfo {
+            method: "NOT FOUND".to_string(),
+            remarks: None,
+            returned_at: "2026-01-03T00:00:00+08:00".to_string(),
+        });
+        let mut second = event(CardEventType::Redistributed, "2026-01-04T00:00:00+08:00");
+        second.distribution = Some(distribution("快递", "2026-01-04T00:00:00+08:00"));
+
+        let events = vec![
+            event(CardEventType::Entered, "2026-01-01T00:00:00+08:00"),
+            first,
+            returned,
+            second,
+        ];
+        let (status, distributed_qty, metadata) = project_card_events(&events, 2, None);
+        let metadata = metadata.unwrap();
+
+        assert_eq!(status, CardStatus::Distributed);
+        assert_eq!(distributed_qty, 2);
+        assert_eq!(metadata.distribution.unwrap().method, "快递");
+        assert_eq!(metadata.return_info.unwrap().method, "NOT FOUND");
+    }
+
+    /// 按数量分发：累加至卡片数量前为部分分发，退卡后已分发数量归零
+    #[test]
+    fn test_project_partial_distribution() {
+        let mut first = event(CardEventType::Distributed, "2026-01-02T00:00:00+08:00");
+        first.distribution = Some(DistributionInfo {
+            qty: Some(2),
+            ..distribution("自取", "2026-01-02T00:00:00+08:00")
+        });
+        let mut events = vec![event(CardEventType::Entered, "2026-01-01T00:00:00+08:00"), first];
+
+        let (status, distributed_qty, _) = project_card_events(&events, 5, None);
+        assert_eq!(status, CardStatus::PartiallyDistributed);
+        assert_eq!(distributed_qty, 2);
+
+        let mut second = event(CardEventType::Distributed, "2026-01-03T00:00:00+08:00");
+        second.distribution = Some(DistributionInfo {
+            qty: Some(3),
+            ..distribution("快递", "2026-01-03T00:00:00+08:00")
+        });
+        events.push(second);
+        let (status, distributed_qty, metadata) = project_card_events(&events, 5, None);
+        assert_eq!(status, CardStatus::Distributed);
+        assert_eq!(distributed_qty, 5);
+        assert_eq!(metadata.unwrap().distribution.unwrap().qty, Some(3));
+
+        events.push(event(CardEventType::Returned, "2026-01-04T00:00:00+08:00"));
+        let (status, distributed_qty, _) = project_card_events(&events, 5, None);
+        assert_eq!(status, CardStatus::Returned);
+        assert_eq!(distributed_qty, 0);
+    }
+
+    /// 关联寄件事件不参与投影；分发信息保留运单号
+    #[test]
+    fn test_project_ignores_legacy_waybill_event() {
+        let mut waybill = event(CardEventType::WaybillAttached, "2026-01-02T00:00:00+08:00");
+        waybill.waybill_no = Some("SF123".to_string());
+
+        let mut events = vec![event(CardEventType::Entered, "2026-01-01T00:00:00+08:00"), waybill];
+        let (status, _, metadata) = project_card_events(&events, 1, None);
+        assert_eq!(status, CardStatus::Pending);
+        assert!(metadata.is_none());
+
+        let mut distributed = event(CardEventType::Distributed, "2026-01-03T00:00:00+08:00");
+        distributed.distribution = Some(DistributionInfo {
+            waybill_no: Some("SF123".to_string()),
+            ..distribution("快递", "2026-01-03T00:00:00+08:00")
+        });
+        events.push(distributed);
+        let (status, _, metadata) = project_card_events(&events, 1, None);
+        assert_eq!(status, CardStatus::Distributed);
+        assert_eq!(
+            metadata.unwrap().distribution.unwrap().waybill_no.as_deref(),
+            Some("SF123")
+        );
+    }
+
+    /// 旧格式导入：待付邮资、留存、遗失按卡片状态补状态事件，投影后状态不变
+    #[test]
+    fn test_events_from_card_keeps_hold_state() {
+        let mut card = Card::new("p1".to_string(), "BH2RO".to_string(), 2, None);
+        card.status = CardStatus::Held;
+
+        let events = events_from_card(&card);
+        let types: Vec<CardEventType> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(types, vec![CardEventType::Entered, CardEventType::Held]);
+
+        let (status, _, _) = project_card_events(&events, card.qty, None);
+        assert_eq!(status, CardStatus::Held);
+    }
+
+    /// 迁移回填：已有卡片按 metadata 生成事件，投影结果与原 metadata 一致
+    #[test]
+    fn test_events_from_card_matches_metadata() {
+        let card = Card {
+            id: "c1".to_string(),
+            project_id: "p1".to_string(),
+            creator_id: None,
+            callsign: "BH2RO".to_string(),
+            via_callsign: None,
+            qty: 1,
+            serial: None,
+            status: CardStatus::Distributed,
+            distributed_qty: 1,
+            storage_location_id: None,
+            metadata: Some(CardMetadata {
+                distribution: Some(distribution("挂号信", "2026-01-02T00:00:00+08:00")),
+                ..Default::default()
+            }),
+            created_at: "2026-01-01T00:00:00+08:00".to_string(),
+            updated_at: "2026-01-02T00:00:00+08:00".to_string(),
+        };
+
+        let events = events_from_card(&card);
+        let types: Vec<CardEventType> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(types, vec![CardEventType::Entered, CardEventType::Distributed]);
+
+        let (status, distributed_qty, metadata) = project_card_events(&events, card.qty, None);
+        assert_eq!(status, card.status);
+        assert_eq!(distributed_qty, card.distributed_qty);
+        assert_eq!(metadata.unwrap().distribution.unwrap().method, "挂号信");
+    }
+}
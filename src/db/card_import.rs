@@ -0,0 +1,393 @@
+// 卡片表格导入模块
+//
+// 从 .xlsx 或 .csv 文件按列映射批量录入卡片：逐行校验呼号、数量与序列号、检查重复呼号，
+// 在同一事务内写入，支持只校验不写入的试导入
+
+use crate::db::cards::{insert_new_card_conn, validate_callsign, validate_qty};
+use crate::db::models::{
+    Card, CardImportField, CardImportMapping, CardImportPreview, CardImportReport,
+    CardImportRowError,
+};
+use crate::db::serial_schemes::check_serial_conn;
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use crate::utils::spreadsheet::read_sheet_rows;
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 预览中展示的数据行数
+const PREVIEW_ROWS: usize = 10;
+
+/// 按表头推断列映射时各字段的关键字（小写）
+const CALLSIGN_KEYWORDS: &[&str] = &["呼号", "callsign", "call"];
+const QTY_KEYWORDS: &[&str] = &["数量", "张数", "qty", "quantity", "count"];
+const SERIAL_KEYWORDS: &[&str] = &["序列号", "序号", "编号", "serial", "sn"];
+const REMARKS_KEYWORDS: &[&str] = &["备注", "remark", "note", "comment"];
+
+/// 是否为空行（所有单元格均为空白）
+fn is_blank(row: &[String]) -> bool {
+    row.iter().all(|cell| cell.trim().is_empty())
+}
+
+/// 读取单元格（列不存在时为空串）
+fn cell(row: &[String], column: Option<u32>) -> &str {
+    column
+        .and_then(|c| row.get(c as usize))
+        .map(|s| s.trim())
+        .unwrap_or_default()
+}
+
+/// 第一个非空行是否为表头：呼号必含数字，各单元格均不含数字时视为表头
+fn looks_like_header(rows: &[Vec<String>]) -> bool {
+    rows.iter().find(|row| !is_blank(row)).is_some_and(|row| {
+        !row.iter()
+            .any(|cell| cell.chars().any(|c| c.is_ascii_digit()))
+    })
+}
+
+/// 按表头关键字推断列映射（无表头时依次为呼号、数量、序列号、备注）
+fn suggest_mapping(headers: &[String], has_header: bool) -> CardImportMapping {
+    if !has_header {
+        let column = |i: u32| (headers.len() > i as usize).then_some(i);
+        return CardImportMapping {
+            has_header,
+            callsign: 0,
+            qty: column(1),
+            serial: column(2),
+            remarks: column(3),
+        };
+    }
+
+    let find = |keywords: &[&str]| {
+        headers
+            .iter()
+            .position(|h| {
+                let h = h.trim().to_lowercase();
+                keywords.iter().any(|k| h.contains(k))
+            })
+            .map(|i| i as u32)
+    };
+    CardImportMapping {
+        has_header,
+        callsign: find(CALLSIGN_KEYWORDS).unwrap_or(0),
+        qty: find(QTY_KEYWORDS),
+        serial: find(SERIAL_KEYWORDS),
+        remarks: find(REMARKS_KEYWORDS),
+    }
+}
+
+/// 读取表格文件并生成列映射预览
+pub fn preview_card_import_file(path: &Path) -> Result<CardImportPreview, AppError> {
+    Ok(preview_card_import_rows(&read_sheet_rows(path)?))
+}
+
+/// 由表格行生成列映射预览
+fn preview_card_import_rows(rows: &[Vec<String>]) -> CardImportPreview {
+    let has_header = looks_like_header(rows);
+    let mut data = rows.iter().skip_while(|row| is_blank(row));
+    let headers = if has_header {
+        data.next().cloned().unwrap_or_default()
+    } else {
+        rows.iter()
+            .find(|row| !is_blank(row))
+            .cloned()
+            .unwrap_or_default()
+    };
+    let data: Vec<&Vec<String>> = data.filter(|row| !is_blank(row)).collect();
+
+    CardImportPreview {
+        mapping: suggest_mapping(&headers, has_header),
+        column_count: rows.iter().map(|row| row.len()).max().unwrap_or(0) as u32,
+        total_rows: data.len() as u32,
+        rows: data.into_iter().take(PREVIEW_ROWS).cloned().collect(),
+        headers,
+    }
+}
+
+/// 解析序列号单元格：取末尾的数字部分（兼容 `BY-2026-0012` 等格式化形式），空单元格为 `None`
+fn parse_serial(value: &str) -> Result<Option<i32>, AppError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .rsplit(|c: char| !c.is_ascii_digit())
+        .next()
+        .unwrap_or_default()
+        .parse::<i32>()
+        .map(Some)
+        .map_err(|_| AppError::InvalidParameter(format!("序列号无效：{}", value)))
+}
+
+/// 解析数量单元格，空单元格为 1
+fn parse_qty(value: &str) -> Result<i32, AppError> {
+    if value.is_empty() {
+        return Ok(1);
+    }
+    let qty = value
+        .parse::<i32>()
+        .map_err(|_| AppError::InvalidParameter(format!("数量无效：{}", value)))?;
+    validate_qty(qty)?;
+    Ok(qty)
+}
+
+/// 校验一行的呼号、数量与序列号（序列号须符合项目的编号方案），失败时返回出错字段与原因
+fn parse_row(
+    conn: &Connection,
+    project_id: &str,
+    callsign: &str,
+    row: &[String],
+    mapping: &CardImportMapping,
+) -> Result<(i32, Option<i32>), (CardImportField, AppError)> {
+    validate_callsign(callsign).map_err(|e| (CardImportField::Callsign, e))?;
+    let qty = parse_qty(cell(row, mapping.qty)).map_err(|e| (CardImportField::Qty, e))?;
+    let serial =
+        parse_serial(cell(row, mapping.serial)).map_err(|e| (CardImportField::Serial, e))?;
+    if let Some(serial) = serial {
+        check_serial_conn(conn, project_id, serial).map_err(|e| (CardImportField::Serial, e))?;
+    }
+    Ok((qty, serial))
+}
+
+/// 从表格文件导入卡片到指定项目
+///
+/// 按列映射逐行读取：空行忽略；呼号或数量无效、序列号无法识别或超出编号方案范围的行，
+/// 以及呼号与项目中已有卡片或文件中前面的行重复（大小写不敏感）的行记入错误、不导入，
+/// 其余行在同一事务内写入。`dry_run` 为真时执行同样的校验后回滚，不写入任何数据。
+pub fn import_cards_from_file(
+    project_id: &str,
+    path: &Path,
+    mapping: &CardImportMapping,
+    dry_run: bool,
+) -> Result<CardImportReport, AppError> {
+    let rows = read_sheet_rows(path)?;
+    import_card_rows_in(database()?, project_id, &rows, mapping, dry_run)
+}
+
+/// 同 [`import_cards_from_file`]，以已读取的表格行在指定的数据库上下文中执行
+pub fn import_card_rows_in(
+    db: &Database,
+    project_id: &str,
+    rows: &[Vec<String>],
+    mapping: &CardImportMapping,
+    dry_run: bool,
+) -> Result<CardImportReport, AppError> {
+    // 各字段须映射到不同的列
+    let mut columns = vec![mapping.callsign];
+    columns.extend(
+        [mapping.qty, mapping.serial, mapping.remarks]
+            .into_iter()
+            .flatten(),
+    );
+    let mapped = columns.len();
+    columns.sort_unstable();
+    columns.dedup();
+    if columns.len() != mapped {
+        return Err(AppError::InvalidParameter(
+            "同一列不能同时映射到多个字段".to_string(),
+        ));
+    }
+
+    let mut conn = db.connection()?;
+    let project_exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1 AND deleted_at IS NULL)",
+            [project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Other(format!("查询项目失败: {}", e)))?;
+    if !project_exists {
+        return Err(AppError::ProfileNotFound(format!(
+            "项目不存在: {}",
+            project_id
+        )));
+    }
+
+    let mut report = CardImportReport {
+        dry_run,
+        imported: 0,
+        duplicates: 0,
+        errors: Vec::new(),
+    };
+    let creator_id = db.current_operator_id();
+    // 文件中已出现的呼号及其所在行号
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    let mut exists_stmt = tx
+        .prepare(
+            "SELECT EXISTS(SELECT 1 FROM cards WHERE project_id = ?1 AND callsign = ?2 COLLATE NOCASE AND deleted_at IS NULL)",
+        )
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let header_index = if mapping.has_header {
+        rows.iter().position(|row| !is_blank(row))
+    } else {
+        None
+    };
+    for (index, row) in rows.iter().enumerate() {
+        let row_no = index + 1;
+        if Some(index) == header_index || is_blank(row) {
+            continue;
+        }
+
+        let row_error = |field: CardImportField, message: String| CardImportRowError {
+            row: row_no as u32,
+            field,
+            message,
+        };
+
+        let callsign = cell(row, Some(mapping.callsign)).to_uppercase();
+        let (qty, serial) = match parse_row(&tx, project_id, &callsign, row, mapping) {
+            Ok(parsed) => parsed,
+            Err((field, e)) => {
+                report.errors.push(row_error(field, e.to_string()));
+                continue;
+            }
+        };
+
+        if let Some(first) = seen.get(&callsign) {
+            report.duplicates += 1;
+            report.errors.push(row_error(
+                CardImportField::Callsign,
+                format!("呼号 {} 与第 {} 行重复", callsign, first),
+            ));
+            continue;
+        }
+        seen.insert(callsign.clone(), row_no);
+
+        let exists: bool = exists_stmt
+            .query_row([project_id, callsign.as_str()], |row| row.get(0))
+            .map_err(|e| AppError::Other(format!("查询呼号失败: {}", e)))?;
+        if exists {
+            report.duplicates += 1;
+            report.errors.push(row_error(
+                CardImportField::Callsign,
+                format!("呼号 {} 已在此项目中录入", callsign),
+            ));
+            continue;
+        }
+
+        let remarks = Some(cell(row, mapping.remarks))
+            .filter(|r| !r.is_empty())
+            .map(str::to_string);
+        let mut card = Card::new(project_id.to_string(), callsign, qty, serial);
+        card.creator_id = creator_id.clone();
+        insert_new_card_conn(&tx, card, remarks)?;
+        report.imported += 1;
+    }
+    drop(exists_stmt);
+
+    // 试导入不提交，事务在离开作用域时回滚
+    if !dry_run {
+        tx.commit()
+            .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+    }
+
+    log::info!(
+        "✅ {}卡片: 项目 {}，{} {} 张，重复 {} 行，错误 {} 行",
+        if dry_run { "试导入" } else { "导入" },
+        project_id,
+        if dry_run { "可导入" } else { "新增" },
+        report.imported,
+        report.duplicates,
+        report.errors.len() as u32 - report.duplicates
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::card_events::list_card_events_in;
+    use crate::db::cards::create_card_in;
+    use crate::db::projects::create_project_in;
+
+    fn rows(lines: &[&[&str]]) -> Vec<Vec<String>> {
+        lines
+            .iter()
+            .map(|line| line.iter().map(|s| s.to_string()).collect())
+            .collect()
+    }
+
+    /// 表头推断映射；试导入不写入；无效值、重复呼号与超出编号方案的序列号按行号与字段报告，其余行导入
+    #[test]
+    fn test_import_card_rows() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        create_card_in(&db, project.id.clone(), "BA1AA".to_string(), 1, None).unwrap();
+
+        let sheet = rows(&[
+            &["备注", "Callsign", "数量", "序号"],
+            &["首张", "bh2ro", "2", "BY-2026-0007"],
+            &["", "", "", ""],
+            &["", "X", "1", ""],
+            &["", "BG1ABC", "0", ""],
+            &["", "BH2RO", "", ""],
+            &["", "ba1aa", "", ""],
+            &["", "BD7XYZ", "", ""],
+            &["", "BD8ABC", "", "1000"],
+        ]);
+        let preview = preview_card_import_rows(&sheet);
+        assert!(preview.mapping.has_header);
+        assert_eq!(preview.mapping.callsign, 1);
+        assert_eq!(preview.mapping.qty, Some(2));
+        assert_eq!(preview.mapping.serial, Some(3));
+        assert_eq!(preview.mapping.remarks, Some(0));
+        assert_eq!(preview.total_rows, 7);
+
+        let dry = import_card_rows_in(&db, &project.id, &sheet, &preview.mapping, true).unwrap();
+        assert!(dry.dry_run);
+        assert_eq!(dry.imported, 2);
+        assert_eq!(dry.duplicates, 2);
+        let errors: Vec<(u32, CardImportField)> =
+            dry.errors.iter().map(|e| (e.row, e.field)).collect();
+        assert_eq!(
+            errors,
+            vec![
+                (4, CardImportField::Callsign),
+                (5, CardImportField::Qty),
+                (6, CardImportField::Callsign),
+                (7, CardImportField::Callsign),
+                (9, CardImportField::Serial),
+            ]
+        );
+        assert_eq!(dry.errors[2].message, "呼号 BH2RO 与第 2 行重复");
+        assert_eq!(dry.errors[3].message, "呼号 BA1AA 已在此项目中录入");
+        let count = |db: &Database| -> i64 {
+            db.connection()
+                .unwrap()
+                .query_row("SELECT COUNT(*) FROM cards", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count(&db), 1);
+
+        let report =
+            import_card_rows_in(&db, &project.id, &sheet, &preview.mapping, false).unwrap();
+        assert_eq!(report.imported, 2);
+        assert_eq!(count(&db), 3);
+        let (id, qty, serial): (String, i32, Option<i32>) = db
+            .connection()
+            .unwrap()
+            .query_row(
+                "SELECT id, qty, serial FROM cards WHERE callsign = 'BH2RO'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((qty, serial), (2, Some(7)));
+        let events = list_card_events_in(&db, &id).unwrap();
+        assert_eq!(events[0].remarks.as_deref(), Some("首张"));
+
+        let conflicting = CardImportMapping {
+            has_header: true,
+            callsign: 1,
+            qty: Some(1),
+            serial: None,
+            remarks: None,
+        };
+        assert!(import_card_rows_in(&db, &project.id, &sheet, &conflicting, true).is_err());
+    }
+}
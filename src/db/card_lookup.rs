@@ -0,0 +1,198 @@
+// 卡片识别模块
+//
+// 将扫描枪读入或手工录入的内容识别为具体卡片，供柜台扫码分发与库存盘点共用
+
+use crate::db::cards::{
+    get_card_conn, list_cards_matching_conn, CARD_STOCKED_CONDITION, CARD_VISIBLE_CONDITION,
+};
+use crate::db::models::CardWithProject;
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use rusqlite::Connection;
+
+/// 卡片编号长度（卡片 ID 前 8 位十六进制字符）
+pub const CARD_CODE_LEN: usize = 8;
+
+/// 卡片编号：卡片 ID 前 8 位的大写形式，印在标签条码中用于扫码识别单张卡片
+pub fn card_code(card_id: &str) -> String {
+    card_id
+        .chars()
+        .take(CARD_CODE_LEN)
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// 是否形如卡片编号（8 位十六进制字符）
+fn is_card_code(input: &str) -> bool {
+    input.len() == CARD_CODE_LEN && input.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// 按卡片编号查找卡片 ID（多张卡片编号相同时报错）
+fn find_by_card_code_conn(conn: &Connection, code: &str) -> Result<Option<String>, AppError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT c.id FROM cards c WHERE {} AND substr(c.id, 1, {}) = ?1",
+            CARD_VISIBLE_CONDITION, CARD_CODE_LEN
+        ))
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+    let ids = stmt
+        .query_map([code.to_lowercase()], |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::Other(format!("查询卡片失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取卡片失败: {}", e)))?;
+
+    match ids.as_slice() {
+        [] => Ok(None),
+        [id] => Ok(Some(id.clone())),
+        _ => Err(AppError::InvalidParameter(format!(
+            "卡片编号 {} 对应 {} 张卡片，请输入「呼号 序列号」以区分",
+            code.to_uppercase(),
+            ids.len()
+        ))),
+    }
+}
+
+/// 识别扫描或录入的内容，返回卡片 ID（无法识别时返回 `None`）
+///
+/// 支持卡片 ID、卡片编号（标签条码）、`呼号 序列号`（空格、`#` 或逗号分隔，序列号可带编号方案的前缀与年份）
+/// 与单独的呼号。
+/// 指定 `project_id` 时优先匹配该项目的卡片，无匹配时在全部项目中查找；
+/// 多张卡片同时匹配时优先存放中的卡片，仍无法区分则报错，须补充序列号。
+pub(crate) fn resolve_card_input_conn(
+    conn: &Connection,
+    project_id: Option<&str>,
+    input: &str,
+) -> Result<Option<String>, AppError> {
+    let tokens: Vec<&str> = input
+        .split(|c: char| c.is_whitespace() || c == '#' || c == ',')
+        .filter(|t| !t.is_empty())
+        .collect();
+    let (callsign, serial) = match tokens.as_slice() {
+        [single] if uuid::Uuid::parse_str(single).is_ok() => {
+            return Ok(get_card_conn(conn, single)?.map(|c| c.id));
+        }
+        [single] => {
+            if is_card_code(single)
+                && let Some(id) = find_by_card_code_conn(conn, single)?
+            {
+                return Ok(Some(id));
+            }
+            (*single, None)
+        }
+        // 序列号可为按编号方案格式化的形式（如 `BY-2026-0012`），取末尾的数字部分
+        [callsign, serial] => match serial
+            .rsplit(|c: char| !c.is_ascii_digit())
+            .next()
+            .unwrap_or_default()
+            .parse::<i32>()
+        {
+            Ok(serial) => (*callsign, Some(serial)),
+            Err(_) => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    let sql = format!(
+        r#"
+        SELECT c.id, {stocked} FROM cards c
+        WHERE {visible} AND c.callsign = ?1 COLLATE NOCASE AND (?2 IS NULL OR c.serial = ?2)
+          AND (?3 IS NULL OR c.project_id = ?3)
+        "#,
+        stocked = CARD_STOCKED_CONDITION,
+        visible = CARD_VISIBLE_CONDITION,
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    for scope in [project_id, None] {
+        let matches: Vec<(String, bool)> = stmt
+            .query_map(rusqlite::params![callsign, serial, scope], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .map_err(|e| AppError::Other(format!("查询卡片失败: {}", e)))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Other(format!("读取卡片失败: {}", e)))?;
+
+        let stocked: Vec<&String> = matches
+            .iter()
+            .filter(|(_, s)| *s)
+            .map(|(id, _)| id)
+            .collect();
+        match (matches.as_slice(), stocked.as_slice()) {
+            ([], _) => continue,
+            ([(id, _)], _) => return Ok(Some(id.clone())),
+            (_, [id]) => return Ok(Some(id.to_string())),
+            _ => {
+                return Err(AppError::InvalidParameter(format!(
+                    "{} 对应 {} 张卡片，请输入「呼号 序列号」以区分",
+                    input,
+                    matches.len()
+                )));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// 按扫描或录入的内容查找卡片（无法识别时返回 `None`）
+pub fn lookup_card(input: &str) -> Result<Option<CardWithProject>, AppError> {
+    lookup_card_in(database()?, input)
+}
+
+/// 同 [`lookup_card`]，在指定的数据库上下文中执行
+pub fn lookup_card_in(db: &Database, input: &str) -> Result<Option<CardWithProject>, AppError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(AppError::InvalidParameter("扫描内容不能为空".to_string()));
+    }
+
+    let conn = db.connection()?;
+    match resolve_card_input_conn(&conn, None, input)? {
+        Some(id) => Ok(list_cards_matching_conn(&conn, "c.id = ?1", &[&id])?
+            .into_iter()
+            .next()),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::cards::{create_card_in, distribute_card_in};
+    use crate::db::projects::create_project_in;
+
+    /// 卡片编号、呼号 + 序列号与单独呼号均可识别，已分发的同呼号卡片不影响识别
+    #[test]
+    fn test_lookup_card() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let card =
+            create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, Some(5)).unwrap();
+        let earlier = create_project_in(&db, "往期".to_string()).unwrap();
+        let old = create_card_in(&db, earlier.id.clone(), "BH2RO".to_string(), 1, Some(1)).unwrap();
+        distribute_card_in(&db, &old.id, "自取".to_string(), None, None, None, None).unwrap();
+
+        let code = card_code(&card.id);
+        assert_eq!(code.len(), CARD_CODE_LEN);
+        let found = lookup_card_in(&db, &code).unwrap().unwrap();
+        assert_eq!(found.id, card.id);
+        assert_eq!(found.project_name, "项目");
+        assert_eq!(
+            lookup_card_in(&db, &code.to_lowercase())
+                .unwrap()
+                .unwrap()
+                .id,
+            card.id
+        );
+
+        assert_eq!(lookup_card_in(&db, "bh2ro #1").unwrap().unwrap().id, old.id);
+        assert_eq!(
+            lookup_card_in(&db, "BH2RO BY-2026-0005").unwrap().unwrap().id,
+            card.id
+        );
+        assert_eq!(lookup_card_in(&db, "BH2RO").unwrap().unwrap().id, card.id);
+        assert!(lookup_card_in(&db, "BA1AA").unwrap().is_none());
+        assert!(lookup_card_in(&db, " ").is_err());
+    }
+}
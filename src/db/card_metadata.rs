@@ -0,0 +1,341 @@
+// 卡片元数据存储模块
+//
+// 分发信息、退卡信息、地址缓存分别存于 card_distributions、card_returns、card_addresses 表，
+// 对外仍组装为 `CardMetadata`
+
+use crate::db::models::{AddressEntry, CardMetadata, DistributionInfo, ReturnInfo};
+use crate::error::AppError;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// 按卡片 ID 批量查询时每条语句的 ID 数量上限（低于 SQLite 参数个数限制）
+const ID_CHUNK_SIZE: usize = 500;
+
+/// 查询单张卡片的元数据，无任何元数据时返回 None
+pub(crate) fn load_card_metadata_conn(
+    conn: &Connection,
+    card_id: &str,
+) -> Result<Option<CardMetadata>, AppError> {
+    let mut map = load_card_metadata_map_conn(conn, Some(&[card_id.to_string()]))?;
+    Ok(map.remove(card_id))
+}
+
+/// 批量查询卡片元数据，按卡片 ID 索引
+///
+/// `card_ids` 为 None 时查询全部卡片（导出使用）。无任何元数据的卡片不出现在结果中。
+pub(crate) fn load_card_metadata_map_conn(
+    conn: &Connection,
+    card_ids: Option<&[String]>,
+) -> Result<HashMap<String, CardMetadata>, AppError> {
+    let mut map = HashMap::new();
+    match card_ids {
+        Some(ids) => {
+            for chunk in ids.chunks(ID_CHUNK_SIZE) {
+                load_metadata_into(conn, &mut map, Some(chunk))?;
+            }
+        }
+        None => load_metadata_into(conn, &mut map, None)?,
+    }
+    Ok(map)
+}
+
+/// 从三处存储读取元数据并合并进 `map`
+///
+/// 按 ID 读取时 IN 列表的占位符个数随 ID 数变化，语句不放入预编译语句缓存。
+fn load_metadata_into(
+    conn: &Connection,
+    map: &mut HashMap<String, CardMetadata>,
+    ids: Option<&[String]>,
+) -> Result<(), AppError> {
+    let scope = |column: &str| match ids {
+        Some(ids) => format!("WHERE {} IN ({})", column, vec!["?"; ids.len()].join(", ")),
+        None => String::new(),
+    };
+    let params = || rusqlite::params_from_iter(ids.unwrap_or_default());
+
+    let sql = format!(
+        "SELECT card_id, method, address, remarks, proxy_callsign, qty, waybill_no, distributed_at FROM card_distributions {}",
+        scope("card_id")
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::Other(format!("准备分发信息查询失败: {}", e)))?;
+    let rows = stmt
+        .query_map(params(), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                DistributionInfo {
+                    method: row.get(1)?,
+                    address: row.get(2)?,
+                    remarks: row.get(3)?,
+                    proxy_callsign: row.get(4)?,
+                    qty: row.get(5)?,
+                    waybill_no: row.get(6)?,
+                    distributed_at: row.get(7)?,
+                },
+            ))
+        })
+        .map_err(|e| AppError::Other(format!("查询分发信息失败: {}", e)))?;
+    for row in rows {
+        let (card_id, distribution) =
+            row.map_err(|e| AppError::Other(format!("读取分发信息失败: {}", e)))?;
+        map.entry(card_id).or_default().distribution = Some(distribution);
+    }
+
+    let sql = format!(
+        "SELECT card_id, method, remarks, returned_at FROM card_returns {}",
+        scope("card_id")
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::Other(format!("准备退卡信息查询失败: {}", e)))?;
+    let rows = stmt
+        .query_map(params(), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                ReturnInfo {
+                    method: row.get(1)?,
+                    remarks: row.get(2)?,
+                    returned_at: row.get(3)?,
+                },
+            ))
+        })
+        .map_err(|e| AppError::Other(format!("查询退卡信息失败: {}", e)))?;
+    for row in rows {
+        let (card_id, return_info) =
+            row.map_err(|e| AppError::Other(format!("读取退卡信息失败: {}", e)))?;
+        map.entry(card_id).or_default().return_info = Some(return_info);
+    }
+
+    let sql = format!(
+        r#"
+        SELECT card_id, source, chinese_address, english_address, name, mail_method, updated_at, cached_at
+        FROM card_addresses {}
+        ORDER BY id
+        "#,
+        scope("card_id")
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| AppError::Other(format!("准备地址缓存查询失败: {}", e)))?;
+    let rows = stmt
+        .query_map(params(), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                AddressEntry {
+                    source: row.get(1)?,
+                    chinese_address: row.get(2)?,
+                    english_address: row.get(3)?,
+                    name: row.get(4)?,
+                    mail_method: row.get(5)?,
+                    updated_at: row.get(6)?,
+                    cached_at: row.get(7)?,
+                },
+            ))
+        })
+        .map_err(|e| AppError::Other(format!("查询地址缓存失败: {}", e)))?;
+    for row in rows {
+        let (card_id, entry) =
+            row.map_err(|e| AppError::Other(format!("读取地址缓存失败: {}", e)))?;
+        map.entry(card_id)
+            .or_default()
+            .address_cache
+            .get_or_insert_with(Vec::new)
+            .push(entry);
+    }
+
+    Ok(())
+}
+
+/// 覆盖写入卡片的分发信息（None 表示删除）
+pub(crate) fn save_card_distribution_conn(
+    conn: &Connection,
+    card_id: &str,
+    distribution: Option<&DistributionInfo>,
+) -> Result<(), AppError> {
+    conn.execute("DELETE FROM card_distributions WHERE card_id = ?1", [card_id])
+        .map_err(|e| AppError::Other(format!("清除分发信息失败: {}", e)))?;
+
+    if let Some(d) = distribution {
+        conn.execute(
+            r#"
+            INSERT INTO card_distributions (card_id, method, address, remarks, proxy_callsign, qty, waybill_no, distributed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            rusqlite::params![
+                card_id,
+                &d.method,
+                &d.address,
+                &d.remarks,
+                &d.proxy_callsign,
+                &d.qty,
+                &d.waybill_no,
+                &d.distributed_at,
+            ],
+        )
+        .map_err(|e| AppError::Other(format!("写入分发信息失败: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// 覆盖写入卡片的退卡信息（None 表示删除）
+pub(crate) fn save_card_return_conn(
+    conn: &Connection,
+    card_id: &str,
+    return_info: Option<&ReturnInfo>,
+) -> Result<(), AppError> {
+    conn.execute("DELETE FROM card_returns WHERE card_id = ?1", [card_id])
+        .map_err(|e| AppError::Other(format!("清除退卡信息失败: {}", e)))?;
+
+    if let Some(r) = return_info {
+        conn.execute(
+            "INSERT INTO card_returns (card_id, method, remarks, returned_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![card_id, &r.method, &r.remarks, &r.returned_at],
+        )
+        .map_err(|e| AppError::Other(format!("写入退卡信息失败: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// 覆盖写入卡片的地址缓存（按给定顺序，同一来源只保留第一条）
+pub(crate) fn save_card_addresses_conn(
+    conn: &Connection,
+    card_id: &str,
+    entries: &[AddressEntry],
+) -> Result<(), AppError> {
+    conn.execute("DELETE FROM card_addresses WHERE card_id = ?1", [card_id])
+        .map_err(|e| AppError::Other(format!("清除地址缓存失败: {}", e)))?;
+
+    for entry in entries {
+        conn.execute(
+            r#"
+            INSERT OR IGNORE INTO card_addresses
+                (card_id, source, chinese_address, english_address, name, mail_method, updated_at, cached_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            rusqlite::params![
+                card_id,
+                &entry.source,
+                &entry.chinese_address,
+                &entry.english_address,
+                &entry.name,
+                &entry.mail_method,
+                &entry.updated_at,
+                &entry.cached_at,
+            ],
+        )
+        .map_err(|e| AppError::Other(format!("写入地址缓存失败: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// 覆盖写入卡片的分发、退卡与地址缓存
+pub(crate) fn save_card_metadata_conn(
+    conn: &Connection,
+    card_id: &str,
+    metadata: Option<&CardMetadata>,
+) -> Result<(), AppError> {
+    save_card_distribution_conn(conn, card_id, metadata.and_then(|m| m.distribution.as_ref()))?;
+    save_card_return_conn(conn, card_id, metadata.and_then(|m| m.return_info.as_ref()))?;
+    save_card_addresses_conn(
+        conn,
+        card_id,
+        metadata
+            .and_then(|m| m.address_cache.as_deref())
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = crate::db::sqlite::open_test_connection();
+        conn.execute(
+            "INSERT INTO projects (id, name, created_at, updated_at) VALUES ('p1', '项目一', ?1, ?1)",
+            ["2026-01-01T00:00:00+08:00"],
+        )
+        .unwrap();
+        for id in ["c1", "c2"] {
+            conn.execute(
+                r#"
+                INSERT INTO cards (id, project_id, callsign, qty, status, created_at, updated_at)
+                VALUES (?1, 'p1', ?1, 1, 'pending', ?2, ?2)
+                "#,
+                rusqlite::params![id, "2026-01-01T00:00:00+08:00"],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn address(source: &str) -> AddressEntry {
+        AddressEntry {
+            source: source.to_string(),
+            chinese_address: None,
+            english_address: Some(format!("{} address", source)),
+            name: None,
+            mail_method: None,
+            updated_at: None,
+            cached_at: "2026-01-01T00:00:00+08:00".to_string(),
+        }
+    }
+
+    /// 写入后读回的元数据与原值一致，地址缓存保持顺序
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let conn = setup_db();
+        let metadata = CardMetadata {
+            distribution: Some(DistributionInfo {
+                method: "代领".to_string(),
+                address: None,
+                remarks: Some("备注".to_string()),
+                proxy_callsign: Some("BG2XYZ".to_string()),
+                qty: Some(2),
+                waybill_no: Some("SF1".to_string()),
+                distributed_at: "2026-02-01T10:00:00+08:00".to_string(),
+            }),
+            address_cache: Some(vec![address("qrz.com"), address("qrz.cn")]),
+            ..Default::default()
+        };
+        save_card_metadata_conn(&conn, "c1", Some(&metadata)).unwrap();
+
+        let loaded = load_card_metadata_conn(&conn, "c1").unwrap().unwrap();
+        let distribution = loaded.distribution.unwrap();
+        assert_eq!(distribution.method, "代领");
+        assert_eq!(distribution.proxy_callsign.as_deref(), Some("BG2XYZ"));
+        assert_eq!(distribution.qty, Some(2));
+        assert_eq!(distribution.waybill_no.as_deref(), Some("SF1"));
+        let sources: Vec<String> = loaded
+            .address_cache
+            .unwrap()
+            .into_iter()
+            .map(|a| a.source)
+            .collect();
+        assert_eq!(sources, vec!["qrz.com", "qrz.cn"]);
+
+        let all = load_card_metadata_map_conn(&conn, None).unwrap();
+        assert_eq!(all.len(), 1);
+        assert!(!all.contains_key("c2"));
+
+        save_card_metadata_conn(&conn, "c1", None).unwrap();
+        assert!(load_card_metadata_conn(&conn, "c1").unwrap().is_none());
+    }
+
+    /// 删除卡片时级联删除其元数据
+    #[test]
+    fn test_metadata_cascades_on_delete() {
+        let conn = setup_db();
+        save_card_addresses_conn(&conn, "c1", &[address("qrz.cn")]).unwrap();
+        conn.execute("DELETE FROM cards WHERE id = 'c1'", []).unwrap();
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM card_addresses", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -2,16 +2,31 @@
 //
 // 提供卡片的 CRUD 操作
 
+use crate::db::callsign_aliases::resolved_callsign_sql;
+use crate::db::card_events::{
+    append_card_event_conn, has_distribution_event_conn, insert_card_event_conn,
+    refresh_card_projection_conn,
+};
+use crate::db::card_metadata::{
+    load_card_metadata_conn, load_card_metadata_map_conn, save_card_addresses_conn,
+};
+use crate::db::handling_methods::{check_handling_method_conn, check_handling_method_with_conn};
 use crate::db::models::{
-    format_datetime, now_china, Card, CardFilter, CardStatus, CardWithProject, DistributionInfo,
-    PagedCards, Pagination, ReturnInfo,
+    format_datetime, now_china, Card, CardEvent, CardEventType, CardFilter, CardHistoryEntry,
+    CardSortField, CardStatus, CardUpdate, CardWithProject, DistributionInfo, HandlingMethodKind,
+    PagedCards, Pagination, ReturnInfo, SerialScheme, SortOrder,
 };
-use crate::db::sqlite::get_connection;
+use crate::db::operators::get_current_operator_in;
+use crate::db::qsl_managers::effective_manager_conn;
+use crate::db::serial_schemes::check_serial_conn;
+use crate::db::sqlite::{database, Database};
+use crate::db::storage_locations::assign_storage_location_conn;
 use crate::error::AppError;
 use regex::Regex;
+use rusqlite::Connection;
 
 /// 呼号验证正则（3-10 字符，仅字母、数字、斜杠）
-fn validate_callsign(callsign: &str) -> Result<(), AppError> {
+pub(crate) fn validate_callsign(callsign: &str) -> Result<(), AppError> {
     let re = Regex::new(r"^[A-Za-z0-9/]{3,10}$").unwrap();
     if !re.is_match(callsign) {
         return Err(AppError::InvalidParameter(
@@ -22,7 +37,7 @@ fn validate_callsign(callsign: &str) -> Result<(), AppError> {
 }
 
 /// 数量验证（1-9999）
-fn validate_qty(qty: i32) -> Result<(), AppError> {
+pub(crate) fn validate_qty(qty: i32) -> Result<(), AppError> {
     if qty < 1 || qty > 9999 {
         return Err(AppError::InvalidParameter(
             "数量无效：必须在 1-9999 之间".to_string(),
@@ -31,19 +46,92 @@ fn validate_qty(qty: i32) -> Result<(), AppError> {
     Ok(())
 }
 
+/// 卡片状态流转规则：返回从 `from` 出发允许到达的状态
+///
+/// - 待分发、部分分发：可分发、退卡，或转为待付邮资、留存、遗失
+/// - 已分发：可修改分发信息（重新分发）或退卡
+/// - 已退卡：可再次分发、修改退卡信息或标记遗失
+/// - 待付邮资、留存：可分发、退卡、互相转换、标记遗失或恢复为待分发
+/// - 已遗失：仅可恢复为待分发（找回后）
+///
+/// 恢复为待分发时若已有部分数量分发，实际状态为部分分发。
+pub fn allowed_card_transitions(from: &CardStatus) -> &'static [CardStatus] {
+    use CardStatus::*;
+    match from {
+        Pending | PartiallyDistributed => &[
+            PartiallyDistributed,
+            Distributed,
+            Returned,
+            AwaitingPostage,
+            Held,
+            Lost,
+        ],
+        Distributed => &[Distributed, Returned],
+        Returned => &[PartiallyDistributed, Distributed, Returned, Lost],
+        AwaitingPostage => &[
+            Pending,
+            PartiallyDistributed,
+            Distributed,
+            Returned,
+            Held,
+            Lost,
+        ],
+        Held => &[
+            Pending,
+            PartiallyDistributed,
+            Distributed,
+            Returned,
+            AwaitingPostage,
+            Lost,
+        ],
+        Lost => &[Pending],
+    }
+}
+
+/// 校验状态流转是否允许
+pub(crate) fn check_card_transition(from: &CardStatus, to: &CardStatus) -> Result<(), AppError> {
+    if allowed_card_transitions(from).contains(to) {
+        return Ok(());
+    }
+    Err(AppError::InvalidParameter(format!(
+        "卡片状态不允许从「{}」变更为「{}」",
+        from.display_name(),
+        to.display_name()
+    )))
+}
+
+/// 未删除卡片的判定条件（卡片本身及所属项目均不在回收站中），卡片表别名须为 `c`
+pub(crate) const CARD_VISIBLE_CONDITION: &str =
+    "c.deleted_at IS NULL AND c.project_id IN (SELECT id FROM projects WHERE deleted_at IS NULL)";
+
+/// 存放中的卡片状态（尚有卡片在架上：待分发、部分分发、待付邮资、留存），卡片表别名须为 `c`
+pub(crate) const CARD_STOCKED_CONDITION: &str =
+    "c.status IN ('pending', 'partially_distributed', 'awaiting_postage', 'held')";
+
 /// 创建卡片
 pub fn create_card(project_id: String, callsign: String, qty: i32, serial: Option<i32>) -> Result<Card, AppError> {
+    create_card_in(database()?, project_id, callsign, qty, serial)
+}
+
+/// 同 [`create_card`]，在指定的数据库上下文中执行
+pub fn create_card_in(
+    db: &Database,
+    project_id: String,
+    callsign: String,
+    qty: i32,
+    serial: Option<i32>,
+) -> Result<Card, AppError> {
     // 验证参数
     let callsign = callsign.trim().to_uppercase();
     validate_callsign(&callsign)?;
     validate_qty(qty)?;
 
-    let conn = get_connection()?;
+    let mut conn = db.connection()?;
 
     // 检查项目是否存在
     let project_exists: bool = conn
         .query_row(
-            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1)",
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1 AND deleted_at IS NULL)",
             [&project_id],
             |row| row.get(0),
         )
@@ -56,10 +144,10 @@ pub fn create_card(project_id: String, callsign: String, qty: i32, serial: Optio
         )));
     }
 
-    // 检查同项目下呼号是否已存在（大小写不敏感）
+    // 检查同项目下呼号是否已存在（大小写不敏感，回收站中的卡片不计）
     let callsign_exists: bool = conn
         .query_row(
-            "SELECT EXISTS(SELECT 1 FROM cards WHERE project_id = ?1 AND callsign = ?2 COLLATE NOCASE)",
+            "SELECT EXISTS(SELECT 1 FROM cards WHERE project_id = ?1 AND callsign = ?2 COLLATE NOCASE AND deleted_at IS NULL)",
             [&project_id, &callsign],
             |row| row.get(0),
         )
@@ -71,42 +159,78 @@ pub fn create_card(project_id: String, callsign: String, qty: i32, serial: Optio
         ));
     }
 
-    // 创建卡片
-    let card = Card::new(project_id, callsign, qty, serial);
+    if let Some(serial) = serial {
+        check_serial_conn(&conn, &project_id, serial)?;
+    }
+
+    // 创建卡片（归属到当前操作员）
+    let mut card = Card::new(project_id, callsign, qty, serial);
+    card.creator_id = db.current_operator_id();
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    let card = insert_new_card_conn(&tx, card, None)?;
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!(
+        "✅ 创建卡片成功: {} x {} ({})",
+        card.callsign,
+        card.qty,
+        card.id
+    );
+    Ok(card)
+}
+
+/// 写入新卡片并记录录入事件（`remarks` 记入录入事件的备注）
+///
+/// 按 QSL 管理员登记标注经由的管理员，按分配规则分配存放位置。
+/// 调用方负责参数校验、重复呼号检查与事务。
+pub(crate) fn insert_new_card_conn(
+    conn: &Connection,
+    mut card: Card,
+    remarks: Option<String>,
+) -> Result<Card, AppError> {
+    card.via_callsign = effective_manager_conn(conn, &card.callsign, &card.created_at)?;
+    card.storage_location_id = assign_storage_location_conn(conn, &card.callsign)?;
 
     conn.execute(
         r#"
-        INSERT INTO cards (id, project_id, creator_id, callsign, qty, serial, status, metadata, created_at, updated_at)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+        INSERT INTO cards (id, project_id, creator_id, callsign, via_callsign, qty, serial, status, storage_location_id, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
         "#,
         rusqlite::params![
             &card.id,
             &card.project_id,
             &card.creator_id,
             &card.callsign,
+            &card.via_callsign,
             &card.qty,
             &card.serial,
             card.status.as_str(),
-            Option::<String>::None,
+            &card.storage_location_id,
             &card.created_at,
             &card.updated_at,
         ],
     )
     .map_err(|e| AppError::Other(format!("创建卡片失败: {}", e)))?;
 
-    log::info!(
-        "✅ 创建卡片成功: {} x {} ({})",
-        card.callsign,
-        card.qty,
-        card.id
-    );
+    // 记录录入事件
+    let mut entered = CardEvent::new(&card.id, CardEventType::Entered);
+    entered.created_at = card.created_at.clone();
+    entered.operator_id = card.creator_id.clone();
+    entered.remarks = remarks;
+    insert_card_event_conn(conn, &entered)?;
+
     Ok(card)
 }
 
-/// 共享的卡片查询 SELECT 主体（SELECT 列 + FROM cards LEFT JOIN projects）。
+/// 共享的卡片查询 SELECT 主体（SELECT 列 + FROM cards LEFT JOIN projects、storage_locations、serial_schemes）。
 ///
-/// 列序必须与 `map_card_row` 的读取顺序（0..=9）严格一致。
+/// 列序必须与 `map_card_row` 的读取顺序（0..=15）严格一致。
 /// 结尾保留换行，保证与后续拼接的 `WHERE …`/`ORDER BY …`/`LIMIT/OFFSET` 之间留有空白。
+/// 位置编号（第 12 列）与 `storage_locations::location_code` 的格式一致。
 const CARD_SELECT_BODY: &str = r#"
         SELECT
             c.id,
@@ -116,19 +240,34 @@ const CARD_SELECT_BODY: &str = r#"
             c.qty,
             c.serial,
             c.status,
-            c.metadata,
             c.created_at,
-            c.updated_at
+            c.updated_at,
+            c.distributed_qty,
+            c.via_callsign,
+            c.storage_location_id,
+            sl.cabinet || '-' || sl.box_no || CASE WHEN sl.slot = '' THEN '' ELSE '-' || sl.slot END as storage_location,
+            ss.prefix,
+            ss.year,
+            ss.width
         FROM cards c
         LEFT JOIN projects p ON c.project_id = p.id
+        LEFT JOIN storage_locations sl ON sl.id = c.storage_location_id
+        LEFT JOIN serial_schemes ss ON ss.project_id = c.project_id
+"#;
+
+/// 筛选与排序所需的元数据表连接（分发信息 `d`、退卡信息 `r`，均为一对一）。
+///
+/// 数据查询拼接在 `CARD_SELECT_BODY` 之后，计数查询拼接在 `FROM cards c` 之后。
+const CARD_FILTER_JOINS: &str = r#"        LEFT JOIN card_distributions d ON d.card_id = c.id
+        LEFT JOIN card_returns r ON r.card_id = c.id
 "#;
 
 /// 构建 WHERE 子句与对应参数（crate-private）。
 ///
-/// 返回的 WHERE 串不含前导/尾随空白：无条件时为空字符串，否则为 `WHERE …`。
+/// 返回的 WHERE 串不含前导/尾随空白，形如 `WHERE …`，始终排除回收站中的卡片。
 /// 片段间空白由调用方共享主体（`CARD_SELECT_BODY`）的尾部换行保证。
 fn build_card_where(filter: &CardFilter) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
-    let mut conditions = Vec::new();
+    let mut conditions = vec![CARD_VISIBLE_CONDITION.to_string()];
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
     if let Some(ref project_id) = filter.project_id {
@@ -136,50 +275,178 @@ fn build_card_where(filter: &CardFilter) -> (String, Vec<Box<dyn rusqlite::ToSql
         params.push(Box::new(project_id.clone()));
     }
 
+    // 呼号关键词同时匹配别名解析后的现用呼号（按录入时间解析）
     if let Some(ref callsign) = filter.callsign {
-        conditions.push(format!("c.callsign LIKE ?{}", params.len() + 1));
+        let n = params.len() + 1;
+        conditions.push(format!(
+            "(c.callsign LIKE ?{n} OR {resolved} LIKE ?{n})",
+            resolved = resolved_callsign_sql("c.callsign", "c.created_at"),
+        ));
         params.push(Box::new(format!("%{}%", callsign.to_uppercase())));
     }
 
+    if let Some(ref via) = filter.via_callsign {
+        conditions.push(format!("c.via_callsign = ?{}", params.len() + 1));
+        params.push(Box::new(via.trim().to_uppercase()));
+    }
+
+    if let Some(ref location_id) = filter.storage_location_id {
+        conditions.push(format!("c.storage_location_id = ?{}", params.len() + 1));
+        params.push(Box::new(location_id.clone()));
+    }
+
     if let Some(ref status) = filter.status {
         conditions.push(format!("c.status = ?{}", params.len() + 1));
         params.push(Box::new(status.as_str().to_string()));
     }
 
-    let where_clause = if conditions.is_empty() {
-        String::new()
-    } else {
-        format!("WHERE {}", conditions.join(" AND "))
-    };
+    // 时间区间：起点直接比较，终点按前缀比较，使仅传日期时包含当天
+    for (column, from, to) in [
+        ("c.created_at", &filter.created_from, &filter.created_to),
+        ("d.distributed_at", &filter.distributed_from, &filter.distributed_to),
+        ("r.returned_at", &filter.returned_from, &filter.returned_to),
+    ] {
+        if let Some(from) = from {
+            conditions.push(format!("{} >= ?{}", column, params.len() + 1));
+            params.push(Box::new(from.clone()));
+        }
+        if let Some(to) = to {
+            let n = params.len() + 1;
+            conditions.push(format!("substr({}, 1, length(?{n})) <= ?{n}", column));
+            params.push(Box::new(to.clone()));
+        }
+    }
+
+    if let Some(ref method) = filter.distribution_method {
+        conditions.push(format!("d.method = ?{}", params.len() + 1));
+        params.push(Box::new(method.clone()));
+    }
+
+    if let Some(ref method) = filter.return_method {
+        conditions.push(format!("r.method = ?{}", params.len() + 1));
+        params.push(Box::new(method.clone()));
+    }
+
+    for (column, min, max) in [
+        ("c.serial", filter.serial_min, filter.serial_max),
+        ("c.qty", filter.qty_min, filter.qty_max),
+    ] {
+        if let Some(min) = min {
+            conditions.push(format!("{} >= ?{}", column, params.len() + 1));
+            params.push(Box::new(min));
+        }
+        if let Some(max) = max {
+            conditions.push(format!("{} <= ?{}", column, params.len() + 1));
+            params.push(Box::new(max));
+        }
+    }
+
+    // 存在性条件（不占用参数）
+    let proxy_present = "COALESCE(d.proxy_callsign, '') != ''";
+    let address_present = "EXISTS (SELECT 1 FROM card_addresses a WHERE a.card_id = c.id)";
+    let shipment_open = "EXISTS (SELECT 1 FROM sf_order_cards oc JOIN sf_orders o ON o.id = oc.order_id WHERE oc.card_id = c.id AND o.status = 'pending')";
+    for (flag, present) in [
+        (filter.has_proxy, proxy_present),
+        (filter.has_address, address_present),
+        (filter.has_open_shipment, shipment_open),
+    ] {
+        match flag {
+            Some(true) => conditions.push(present.to_string()),
+            Some(false) => conditions.push(format!("NOT ({})", present)),
+            None => {}
+        }
+    }
+
+    let where_clause = format!("WHERE {}", conditions.join(" AND "));
 
     (where_clause, params)
 }
 
+/// 构建 ORDER BY 子句（crate-private），默认 `c.created_at DESC`。
+///
+/// 非录入时间排序时以录入时间降序作为次序键，保证结果稳定。
+fn build_card_order(filter: &CardFilter) -> String {
+    let column = match filter.sort_by.unwrap_or(CardSortField::CreatedAt) {
+        CardSortField::CreatedAt => "c.created_at",
+        CardSortField::UpdatedAt => "c.updated_at",
+        CardSortField::Callsign => "c.callsign",
+        CardSortField::Serial => "c.serial",
+        CardSortField::Qty => "c.qty",
+        CardSortField::DistributedAt => "d.distributed_at",
+        CardSortField::ReturnedAt => "r.returned_at",
+    };
+    let direction = match filter.sort_order.unwrap_or(SortOrder::Desc) {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+
+    if column == "c.created_at" {
+        format!("ORDER BY c.created_at {}", direction)
+    } else {
+        format!("ORDER BY {} {}, c.created_at DESC", column, direction)
+    }
+}
+
 /// 将查询行映射为 `CardWithProject`（crate-private）。
 ///
-/// 列序须与 `CARD_SELECT_BODY` 的 SELECT 列顺序（0..=9）一致，零行为变更。
+/// 列序须与 `CARD_SELECT_BODY` 的 SELECT 列顺序（0..=15）一致，零行为变更。
 /// 第 2 列 `project_name` 读为 `String`（孤儿卡片 NULL 的既有行为不在本次改动范围）。
+/// `metadata` 暂置为 None，由 `attach_card_metadata` 批量填充。
 fn map_card_row(row: &rusqlite::Row) -> rusqlite::Result<CardWithProject> {
     let status_str: String = row.get(6)?;
-    let metadata_str: Option<String> = row.get(7)?;
+    let serial: Option<i32> = row.get(5)?;
+    // 第 13..=15 列为编号方案（未配置时为 NULL，使用默认方案）
+    let mut scheme = SerialScheme::default_for("");
+    if let Some(width) = row.get::<_, Option<u32>>(15)? {
+        scheme.prefix = row.get(13)?;
+        scheme.year = row.get(14)?;
+        scheme.width = width;
+    }
 
     Ok(CardWithProject {
         id: row.get(0)?,
         project_id: row.get(1)?,
         project_name: row.get(2)?,
         callsign: row.get(3)?,
+        via_callsign: row.get(10)?,
         qty: row.get(4)?,
-        serial: row.get(5)?,
+        serial,
+        serial_display: serial.map(|s| scheme.format(s)),
         status: CardStatus::from_str(&status_str).unwrap_or(CardStatus::Pending),
-        metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
-        created_at: row.get(8)?,
-        updated_at: row.get(9)?,
+        distributed_qty: row.get(9)?,
+        storage_location_id: row.get(11)?,
+        storage_location: row.get(12)?,
+        metadata: None,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
     })
 }
 
+/// 为查询结果批量填充元数据（分发、退卡、地址缓存、待处理运单号）
+fn attach_card_metadata(
+    conn: &Connection,
+    cards: &mut [CardWithProject],
+) -> Result<(), AppError> {
+    let ids: Vec<String> = cards.iter().map(|c| c.id.clone()).collect();
+    let mut map = load_card_metadata_map_conn(conn, Some(&ids))?;
+    for card in cards.iter_mut() {
+        card.metadata = map.remove(&card.id);
+    }
+    Ok(())
+}
+
 /// 查询卡片列表（分页）
 pub fn list_cards(filter: CardFilter, pagination: Pagination) -> Result<PagedCards, AppError> {
-    let conn = get_connection()?;
+    list_cards_in(database()?, filter, pagination)
+}
+
+/// 同 [`list_cards`]，在指定的数据库上下文中执行
+pub fn list_cards_in(
+    db: &Database,
+    filter: CardFilter,
+    pagination: Pagination,
+) -> Result<PagedCards, AppError> {
+    let conn = db.connection()?;
     list_cards_conn(&conn, filter, pagination)
 }
 
@@ -193,7 +460,10 @@ fn list_cards_conn(
     let (where_clause, mut params) = build_card_where(&filter);
 
     // 查询总数（不消费 LIMIT/OFFSET 参数）
-    let count_sql = format!("SELECT COUNT(*) FROM cards c {}", where_clause);
+    let count_sql = format!(
+        "SELECT COUNT(*) FROM cards c\n{}{}",
+        CARD_FILTER_JOINS, where_clause
+    );
 
     let total: u64 = {
         let mut stmt = conn
@@ -215,9 +485,11 @@ fn list_cards_conn(
 
     // 查询数据：占位符编号随条件数（params.len()）动态计算
     let data_sql = format!(
-        "{body}{where_clause}\n        ORDER BY c.created_at DESC\n        LIMIT ?{limit} OFFSET ?{offset}\n",
+        "{body}{joins}{where_clause}\n        {order_clause}\n        LIMIT ?{limit} OFFSET ?{offset}\n",
         body = CARD_SELECT_BODY,
+        joins = CARD_FILTER_JOINS,
         where_clause = where_clause,
+        order_clause = build_card_order(&filter),
         limit = params.len() + 1,
         offset = params.len() + 2,
     );
@@ -232,11 +504,12 @@ fn list_cards_conn(
     // params_ref 在 push 完 LIMIT/OFFSET 之后、params 不再修改时构造
     let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
-    let items = stmt
+    let mut items = stmt
         .query_map(params_ref.as_slice(), map_card_row)
         .map_err(|e| AppError::Other(format!("查询卡片列表失败: {}", e)))?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| AppError::Other(format!("读取卡片数据失败: {}", e)))?;
+    attach_card_metadata(conn, &mut items)?;
 
     Ok(PagedCards {
         items,
@@ -251,21 +524,31 @@ fn list_cards_conn(
 ///
 /// 与分页的 `list_cards` 解耦，用于导出等需要全量数据的场景，不施加 `page_size` 上限。
 pub fn list_all_cards(filter: CardFilter) -> Result<Vec<CardWithProject>, AppError> {
-    let conn = get_connection()?;
+    list_all_cards_in(database()?, filter)
+}
+
+/// 同 [`list_all_cards`]，在指定的数据库上下文中执行
+pub fn list_all_cards_in(
+    db: &Database,
+    filter: CardFilter,
+) -> Result<Vec<CardWithProject>, AppError> {
+    let conn = db.connection()?;
     list_all_cards_conn(&conn, filter)
 }
 
 /// 全量查询主体（crate-private，接收连接以便测试），无 LIMIT/OFFSET。
-fn list_all_cards_conn(
+pub(crate) fn list_all_cards_conn(
     conn: &rusqlite::Connection,
     filter: CardFilter,
 ) -> Result<Vec<CardWithProject>, AppError> {
     let (where_clause, params) = build_card_where(&filter);
 
     let data_sql = format!(
-        "{body}{where_clause}\n        ORDER BY c.created_at DESC\n",
+        "{body}{joins}{where_clause}\n        {order_clause}\n",
         body = CARD_SELECT_BODY,
+        joins = CARD_FILTER_JOINS,
         where_clause = where_clause,
+        order_clause = build_card_order(&filter),
     );
 
     let mut stmt = conn
@@ -274,160 +557,724 @@ fn list_all_cards_conn(
 
     let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
-    let cards = stmt
+    let mut cards = stmt
         .query_map(params_ref.as_slice(), map_card_row)
         .map_err(|e| AppError::Other(format!("查询卡片列表失败: {}", e)))?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| AppError::Other(format!("读取卡片数据失败: {}", e)))?;
+    attach_card_metadata(conn, &mut cards)?;
+
+    Ok(cards)
+}
+
+/// 查询某呼号在所有项目中的卡片（crate-private，供呼号档案使用）。
+///
+/// 呼号按大写精确匹配（旧呼号名下的卡片按录入时间解析别名后一并匹配），
+/// 按项目名称、序列号排序，不含回收站中的卡片。
+pub(crate) fn list_callsign_cards_conn(
+    conn: &Connection,
+    callsign: &str,
+) -> Result<Vec<CardWithProject>, AppError> {
+    list_cards_matching_conn(
+        conn,
+        &format!("{} = ?1", resolved_callsign_sql("c.callsign", "c.created_at")),
+        &[&callsign.to_uppercase()],
+    )
+}
+
+/// 按附加条件查询卡片（crate-private，供呼号档案、盘点等模块使用）。
+///
+/// `condition` 与可见性条件以 AND 连接（卡片表别名 `c`、项目表别名 `p`），
+/// 按项目名称、序列号排序，不含回收站中的卡片。
+pub(crate) fn list_cards_matching_conn(
+    conn: &Connection,
+    condition: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> Result<Vec<CardWithProject>, AppError> {
+    let data_sql = format!(
+        "{body}WHERE {visible} AND ({condition})\n        ORDER BY p.name ASC, c.serial ASC, c.created_at ASC\n",
+        body = CARD_SELECT_BODY,
+        visible = CARD_VISIBLE_CONDITION,
+        condition = condition,
+    );
+
+    let mut stmt = conn
+        .prepare(&data_sql)
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let mut cards = stmt
+        .query_map(params, map_card_row)
+        .map_err(|e| AppError::Other(format!("查询卡片列表失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取卡片数据失败: {}", e)))?;
+    attach_card_metadata(conn, &mut cards)?;
 
     Ok(cards)
 }
 
 /// 获取单个卡片
 pub fn get_card(id: &str) -> Result<Option<Card>, AppError> {
-    let conn = get_connection()?;
+    get_card_in(database()?, id)
+}
 
-    let result = conn.query_row(
+/// 同 [`get_card`]，在指定的数据库上下文中执行
+pub fn get_card_in(db: &Database, id: &str) -> Result<Option<Card>, AppError> {
+    let conn = db.connection()?;
+    get_card_conn(&conn, id)
+}
+
+/// 单卡查询主体（crate-private，接收连接以便在事务内复用）。
+///
+/// 回收站中的卡片视为不存在。
+pub(crate) fn get_card_conn(conn: &Connection, id: &str) -> Result<Option<Card>, AppError> {
+    let sql = format!(
         r#"
-        SELECT id, project_id, creator_id, callsign, qty, serial, status, metadata, created_at, updated_at
-        FROM cards WHERE id = ?1
-        "#,
-        [id],
-        |row| {
-            let status_str: String = row.get(6)?;
-            let metadata_str: Option<String> = row.get(7)?;
-
-            Ok(Card {
-                id: row.get(0)?,
-                project_id: row.get(1)?,
-                creator_id: row.get(2)?,
-                callsign: row.get(3)?,
-                qty: row.get(4)?,
-                serial: row.get(5)?,
-                status: CardStatus::from_str(&status_str).unwrap_or(CardStatus::Pending),
-                metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
-            })
-        },
+            SELECT c.id, c.project_id, c.creator_id, c.callsign, c.qty, c.serial, c.status, c.created_at, c.updated_at, c.distributed_qty, c.via_callsign, c.storage_location_id
+            FROM cards c WHERE c.id = ?1 AND {}
+            "#,
+        CARD_VISIBLE_CONDITION
     );
+    let mut stmt = conn
+        .prepare_cached(&sql)
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+    let result = stmt.query_row([id], |row| {
+        let status_str: String = row.get(6)?;
+
+        Ok(Card {
+            id: row.get(0)?,
+            project_id: row.get(1)?,
+            creator_id: row.get(2)?,
+            callsign: row.get(3)?,
+            via_callsign: row.get(10)?,
+            qty: row.get(4)?,
+            serial: row.get(5)?,
+            status: CardStatus::from_str(&status_str).unwrap_or(CardStatus::Pending),
+            distributed_qty: row.get(9)?,
+            storage_location_id: row.get(11)?,
+            metadata: None,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+        })
+    });
 
     match result {
-        Ok(card) => Ok(Some(card)),
+        Ok(mut card) => {
+            card.metadata = load_card_metadata_conn(conn, id)?;
+            Ok(Some(card))
+        }
         Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
         Err(e) => Err(AppError::Other(format!("查询卡片失败: {}", e))),
     }
 }
 
+/// 修改卡片
+///
+/// 可修改呼号、数量、序列号和所属项目。每个实际发生变化的字段都会在
+/// `card_history` 中写入一条记录（旧值/新值/操作人/时间），与卡片更新处于同一事务。
+/// 未发生任何变化时直接返回原卡片，不写历史。
+/// 未指定操作人时使用当前操作员的姓名。
+pub fn update_card(
+    id: &str,
+    update: CardUpdate,
+    changed_by: Option<String>,
+) -> Result<Card, AppError> {
+    update_card_in(database()?, id, update, changed_by)
+}
+
+/// 同 [`update_card`]，在指定的数据库上下文中执行
+pub fn update_card_in(
+    db: &Database,
+    id: &str,
+    update: CardUpdate,
+    changed_by: Option<String>,
+) -> Result<Card, AppError> {
+    let operator = get_current_operator_in(db)?;
+    let changed_by = changed_by.or_else(|| operator.as_ref().map(|o| o.name.clone()));
+    let operator_id = operator.map(|o| o.id);
+
+    let mut conn = db.connection()?;
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    let card = update_card_conn(&tx, id, update, changed_by, operator_id.as_deref())?;
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    Ok(card)
+}
+
+/// 修改卡片主体（crate-private，由调用方管理事务）。
+pub(crate) fn update_card_conn(
+    conn: &Connection,
+    id: &str,
+    update: CardUpdate,
+    changed_by: Option<String>,
+    operator_id: Option<&str>,
+) -> Result<Card, AppError> {
+    let card = get_card_conn(conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("卡片不存在: {}", id)))?;
+
+    // (字段名, 旧值, 新值)
+    let mut changes: Vec<(&str, Option<String>, Option<String>)> = Vec::new();
+
+    let callsign = match update.callsign {
+        Some(callsign) => {
+            let callsign = callsign.trim().to_uppercase();
+            validate_callsign(&callsign)?;
+            callsign
+        }
+        None => card.callsign.clone(),
+    };
+    if callsign != card.callsign {
+        changes.push(("callsign", Some(card.callsign.clone()), Some(callsign.clone())));
+    }
+
+    let qty = match update.qty {
+        Some(qty) => {
+            validate_qty(qty)?;
+            if qty < card.distributed_qty {
+                return Err(AppError::InvalidParameter(format!(
+                    "数量不能少于已分发数量 {}",
+                    card.distributed_qty
+                )));
+            }
+            qty
+        }
+        None => card.qty,
+    };
+    if qty != card.qty {
+        changes.push(("qty", Some(card.qty.to_string()), Some(qty.to_string())));
+    }
+
+    let serial = update.serial.unwrap_or(card.serial);
+    if serial != card.serial {
+        // 仅校验修改后的序列号（转移项目时保留原序列号，不按目标项目的方案校验）
+        if let Some(serial) = serial {
+            let target_project = update.project_id.as_deref().unwrap_or(&card.project_id);
+            check_serial_conn(conn, target_project, serial)?;
+        }
+        changes.push((
+            "serial",
+            card.serial.map(|s| s.to_string()),
+            serial.map(|s| s.to_string()),
+        ));
+    }
+
+    let project_id = update.project_id.unwrap_or_else(|| card.project_id.clone());
+    if project_id != card.project_id {
+        let project_exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1 AND deleted_at IS NULL)",
+                [&project_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Other(format!("查询项目失败: {}", e)))?;
+
+        if !project_exists {
+            return Err(AppError::ProfileNotFound(format!(
+                "项目不存在: {}",
+                project_id
+            )));
+        }
+
+        changes.push((
+            "project_id",
+            Some(card.project_id.clone()),
+            Some(project_id.clone()),
+        ));
+    }
+
+    if changes.is_empty() {
+        return Ok(card);
+    }
+
+    // 呼号或项目变化时，检查目标项目下呼号是否重复（大小写不敏感，排除自身与回收站中的卡片）
+    if callsign != card.callsign || project_id != card.project_id {
+        let callsign_exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM cards WHERE project_id = ?1 AND callsign = ?2 COLLATE NOCASE AND id != ?3 AND deleted_at IS NULL)",
+                [&project_id, &callsign, id],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Other(format!("查询呼号失败: {}", e)))?;
+
+        if callsign_exists {
+            return Err(AppError::InvalidParameter(
+                "该呼号已在此项目中录入".to_string(),
+            ));
+        }
+    }
+
+    // 转移项目时，目标项目中已有卡片使用同一序列号则拒绝（须先修改序列号）
+    if project_id != card.project_id
+        && let Some(serial) = serial
+    {
+        let serial_exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM cards WHERE project_id = ?1 AND serial = ?2 AND id != ?3 AND deleted_at IS NULL)",
+                rusqlite::params![project_id, serial, id],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Other(format!("查询序列号失败: {}", e)))?;
+
+        if serial_exists {
+            return Err(AppError::InvalidParameter(format!(
+                "目标项目中已有卡片使用序列号 {}，请先修改序列号",
+                serial
+            )));
+        }
+    }
+
+    let changed_by = changed_by
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let now = format_datetime(&now_china());
+
+    // 呼号变化时按录入时间重新标注经由的 QSL 管理员，并按新呼号重新分配存放位置（无匹配规则时保持原位置）
+    let (via_callsign, storage_location_id) = if callsign != card.callsign {
+        (
+            effective_manager_conn(conn, &callsign, &card.created_at)?,
+            assign_storage_location_conn(conn, &callsign)?.or(card.storage_location_id.clone()),
+        )
+    } else {
+        (card.via_callsign.clone(), card.storage_location_id.clone())
+    };
+
+    conn.execute(
+        "UPDATE cards SET callsign = ?1, via_callsign = ?2, qty = ?3, serial = ?4, project_id = ?5, storage_location_id = ?6, updated_at = ?7 WHERE id = ?8",
+        rusqlite::params![callsign, via_callsign, qty, serial, project_id, storage_location_id, now, id],
+    )
+    .map_err(|e| AppError::Other(format!("更新卡片失败: {}", e)))?;
+
+    for (field, old_value, new_value) in &changes {
+        conn.execute(
+            r#"
+            INSERT INTO card_history (card_id, field, old_value, new_value, changed_by, changed_at, operator_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            rusqlite::params![id, field, old_value, new_value, changed_by, now, operator_id],
+        )
+        .map_err(|e| AppError::Other(format!("写入修改历史失败: {}", e)))?;
+    }
+
+    // 数量变化可能使部分分发与已分发互相转换
+    if qty != card.qty {
+        refresh_card_projection_conn(conn, id)?;
+    }
+
+    log::info!("✅ 修改卡片成功: {} ({} 个字段)", id, changes.len());
+    get_card_conn(conn, id)?.ok_or_else(|| AppError::Other("更新后无法获取卡片".to_string()))
+}
+
+/// 查询卡片的修改历史（按时间正序）
+pub fn get_card_history(card_id: &str) -> Result<Vec<CardHistoryEntry>, AppError> {
+    get_card_history_in(database()?, card_id)
+}
+
+/// 同 [`get_card_history`]，在指定的数据库上下文中执行
+pub fn get_card_history_in(db: &Database, card_id: &str) -> Result<Vec<CardHistoryEntry>, AppError> {
+    let conn = db.connection()?;
+    get_card_history_conn(&conn, card_id)
+}
+
+/// 修改历史查询主体（crate-private，接收连接以便测试）。
+fn get_card_history_conn(
+    conn: &Connection,
+    card_id: &str,
+) -> Result<Vec<CardHistoryEntry>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, card_id, field, old_value, new_value, changed_by, changed_at, operator_id
+            FROM card_history
+            WHERE card_id = ?1
+            ORDER BY changed_at ASC, id ASC
+            "#,
+        )
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let entries = stmt
+        .query_map([card_id], map_history_row)
+        .map_err(|e| AppError::Other(format!("查询修改历史失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取修改历史失败: {}", e)))?;
+
+    Ok(entries)
+}
+
+/// 查询全部卡片的修改历史（crate-private，供导出与云端同步使用）。
+///
+/// 不含回收站中的卡片，与导出的卡片范围一致。
+pub(crate) fn list_all_card_history_conn(
+    conn: &Connection,
+) -> Result<Vec<CardHistoryEntry>, AppError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+            SELECT h.id, h.card_id, h.field, h.old_value, h.new_value, h.changed_by, h.changed_at, h.operator_id
+            FROM card_history h
+            JOIN cards c ON c.id = h.card_id
+            WHERE {}
+            ORDER BY h.card_id ASC, h.changed_at ASC, h.id ASC
+            "#,
+            CARD_VISIBLE_CONDITION
+        ))
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let entries = stmt
+        .query_map([], map_history_row)
+        .map_err(|e| AppError::Other(format!("查询修改历史失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取修改历史失败: {}", e)))?;
+
+    Ok(entries)
+}
+
+/// 写入一条导入的修改历史（crate-private，由调用方管理事务）。
+///
+/// 记录 ID 由本地重新分配，避免与其他电台的历史记录冲突。
+pub(crate) fn insert_card_history_conn(
+    conn: &Connection,
+    entry: &CardHistoryEntry,
+) -> Result<(), AppError> {
+    conn.execute(
+        r#"
+        INSERT INTO card_history (card_id, field, old_value, new_value, changed_by, changed_at, operator_id)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        "#,
+        rusqlite::params![
+            &entry.card_id,
+            &entry.field,
+            &entry.old_value,
+            &entry.new_value,
+            &entry.changed_by,
+            &entry.changed_at,
+            &entry.operator_id,
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("写入修改历史失败: {}", e)))?;
+    Ok(())
+}
+
+/// 修改历史行映射（列顺序：id, card_id, field, old_value, new_value, changed_by, changed_at, operator_id）
+fn map_history_row(row: &rusqlite::Row) -> rusqlite::Result<CardHistoryEntry> {
+    Ok(CardHistoryEntry {
+        id: row.get(0)?,
+        card_id: row.get(1)?,
+        field: row.get(2)?,
+        old_value: row.get(3)?,
+        new_value: row.get(4)?,
+        changed_by: row.get(5)?,
+        operator_id: row.get(7)?,
+        changed_at: row.get(6)?,
+    })
+}
+
 /// 分发卡片
 ///
-/// 允许对任意状态的卡片执行分发操作：
-/// - 待分发(pending) → 已分发(distributed)：首次分发
-/// - 已分发(distributed) → 已分发(distributed)：修改分发信息
-/// - 已退回(returned) → 已分发(distributed)：重新分发
+/// 已遗失的卡片不可分发（须先恢复），其余状态均可分发，每次分发都追加一条事件：
+/// - 尚无分发记录、或部分分发后继续分发：记为分发(distributed)
+/// - 已有分发记录（修改分发信息、退回后再次分发）：记为重新分发(redistributed)
 ///
-/// 分发信息和退回信息独立存储在 metadata 中，互不覆盖。
+/// `qty` 为本次分发数量，为空时分发全部剩余数量；分发数量不足卡片数量时为部分分发。
+/// `method` 须为已启用的登记分发方式，并填写其要求的地址、代领人呼号或备注。
+/// 卡片的 status 与 metadata 由事件日志投影得出，历史分发信息保留在事件中。
 pub fn distribute_card(
     id: &str,
     method: String,
     address: Option<String>,
     remarks: Option<String>,
     proxy_callsign: Option<String>,
+    qty: Option<i32>,
 ) -> Result<Card, AppError> {
-    let conn = get_connection()?;
-
-    // 获取卡片
-    let card = get_card(id)?.ok_or_else(|| AppError::ProfileNotFound(format!("卡片不存在: {}", id)))?;
+    distribute_card_in(database()?, id, method, address, remarks, proxy_callsign, qty)
+}
 
-    // 构建元数据（保留已有的退回信息）
-    let distribution = DistributionInfo {
+/// 同 [`distribute_card`]，在指定的数据库上下文中执行
+#[allow(clippy::too_many_arguments)]
+pub fn distribute_card_in(
+    db: &Database,
+    id: &str,
+    method: String,
+    address: Option<String>,
+    remarks: Option<String>,
+    proxy_callsign: Option<String>,
+    qty: Option<i32>,
+) -> Result<Card, AppError> {
+    let mut conn = db.connection()?;
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    let operator_id = db.current_operator_id();
+    let card = distribute_card_conn(
+        &tx,
+        id,
         method,
         address,
         remarks,
         proxy_callsign,
-        distributed_at: format_datetime(&now_china()),
-    };
+        qty,
+        None,
+        operator_id.as_deref(),
+    )?;
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
 
-    let mut metadata = card.metadata.unwrap_or_default();
-    metadata.distribution = Some(distribution);
+    log::info!("✅ 分发卡片成功: {}", id);
+    Ok(card)
+}
 
-    let metadata_json = serde_json::to_string(&metadata)
-        .map_err(|e| AppError::Other(format!("序列化元数据失败: {}", e)))?;
+/// 分发主体（crate-private，由调用方管理事务）。
+///
+/// `waybill_no` 为随顺丰寄件确认分发时的运单号，记入分发信息。此时卡片已随订单寄出，
+/// 分发方式只须已登记，不要求仍为启用状态（停用仅限制新的手工分发）。
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn distribute_card_conn(
+    conn: &Connection,
+    id: &str,
+    method: String,
+    address: Option<String>,
+    remarks: Option<String>,
+    proxy_callsign: Option<String>,
+    qty: Option<i32>,
+    waybill_no: Option<String>,
+    operator_id: Option<&str>,
+) -> Result<Card, AppError> {
+    let card = get_card_conn(conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("卡片不存在: {}", id)))?;
+    check_card_transition(&card.status, &CardStatus::Distributed)?;
+    let method = method.trim().to_string();
+    check_handling_method_with_conn(
+        conn,
+        HandlingMethodKind::Distribution,
+        &method,
+        address.as_deref(),
+        proxy_callsign.as_deref(),
+        remarks.as_deref(),
+        waybill_no.is_none(),
+    )?;
+
+    // 已全部分发的卡片只能整体重新分发（修改分发信息）
+    if let Some(qty) = qty {
+        let remaining = card.remaining_qty();
+        if remaining == 0 {
+            return Err(AppError::InvalidParameter(
+                "卡片已全部分发，无剩余数量".to_string(),
+            ));
+        }
+        if qty < 1 || qty > remaining {
+            return Err(AppError::InvalidParameter(format!(
+                "分发数量无效：必须在 1-{} 之间",
+                remaining
+            )));
+        }
+    }
 
-    let updated_at = format_datetime(&now_china());
+    // 部分分发后继续分发（含中途转为待付邮资、留存）不算重新分发
+    let partially_distributed = card.distributed_qty > 0 && card.remaining_qty() > 0;
+    let event_type = if !partially_distributed && has_distribution_event_conn(conn, id)? {
+        CardEventType::Redistributed
+    } else {
+        CardEventType::Distributed
+    };
 
-    // 更新卡片
-    conn.execute(
-        "UPDATE cards SET status = ?1, metadata = ?2, updated_at = ?3 WHERE id = ?4",
-        rusqlite::params![CardStatus::Distributed.as_str(), metadata_json, updated_at, id],
-    )
-    .map_err(|e| AppError::Other(format!("更新卡片失败: {}", e)))?;
+    let mut event = CardEvent::new(id, event_type);
+    event.operator_id = operator_id.map(str::to_string);
+    event.distribution = Some(DistributionInfo {
+        method,
+        address,
+        remarks,
+        proxy_callsign,
+        qty,
+        waybill_no,
+        distributed_at: event.created_at.clone(),
+    });
+    append_card_event_conn(conn, &event)?;
 
-    log::info!("✅ 分发卡片成功: {}", id);
-    get_card(id)?.ok_or_else(|| AppError::Other("更新后无法获取卡片".to_string()))
+    get_card_conn(conn, id)?.ok_or_else(|| AppError::Other("更新后无法获取卡片".to_string()))
 }
 
 /// 退卡
 ///
-/// 允许对任意状态的卡片执行退回操作：
+/// 已遗失的卡片不可退回（须先恢复），其余状态均可退回，每次退回都追加一条退卡事件：
 /// - 待分发(pending) → 已退回(returned)：直接退回
 /// - 已分发(distributed) → 已退回(returned)：分发后退回
 /// - 已退回(returned) → 已退回(returned)：修改退回信息
 ///
-/// 分发信息和退回信息独立存储在 metadata 中，互不覆盖。
+/// `method` 须为已启用的登记退卡原因（要求备注的须填写备注）。
+/// 卡片的 status 与 metadata 由事件日志投影得出，分发信息不受影响。
 pub fn return_card(id: &str, method: String, remarks: Option<String>) -> Result<Card, AppError> {
-    let conn = get_connection()?;
+    return_card_in(database()?, id, method, remarks)
+}
 
-    // 获取卡片
-    let card = get_card(id)?.ok_or_else(|| AppError::ProfileNotFound(format!("卡片不存在: {}", id)))?;
+/// 同 [`return_card`]，在指定的数据库上下文中执行
+pub fn return_card_in(
+    db: &Database,
+    id: &str,
+    method: String,
+    remarks: Option<String>,
+) -> Result<Card, AppError> {
+    let mut conn = db.connection()?;
 
-    // 构建元数据（保留已有的分发信息）
-    let return_info = ReturnInfo {
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    let operator_id = db.current_operator_id();
+    let card = return_card_conn(&tx, id, method, remarks, operator_id.as_deref())?;
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!("✅ 退卡成功: {}", id);
+    Ok(card)
+}
+
+/// 退卡主体（crate-private，由调用方管理事务）。
+pub(crate) fn return_card_conn(
+    conn: &Connection,
+    id: &str,
+    method: String,
+    remarks: Option<String>,
+    operator_id: Option<&str>,
+) -> Result<Card, AppError> {
+    let card = get_card_conn(conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("卡片不存在: {}", id)))?;
+    check_card_transition(&card.status, &CardStatus::Returned)?;
+    let method = method.trim().to_string();
+    check_handling_method_conn(
+        conn,
+        HandlingMethodKind::Return,
+        &method,
+        None,
+        None,
+        remarks.as_deref(),
+    )?;
+
+    let mut event = CardEvent::new(id, CardEventType::Returned);
+    event.operator_id = operator_id.map(str::to_string);
+    event.return_info = Some(ReturnInfo {
         method,
         remarks,
-        returned_at: format_datetime(&now_china()),
-    };
+        returned_at: event.created_at.clone(),
+    });
+    append_card_event_conn(conn, &event)?;
 
-    let mut metadata = card.metadata.unwrap_or_default();
-    metadata.return_info = Some(return_info);
+    get_card_conn(conn, id)?.ok_or_else(|| AppError::Other("更新后无法获取卡片".to_string()))
+}
 
-    let metadata_json = serde_json::to_string(&metadata)
-        .map_err(|e| AppError::Other(format!("序列化元数据失败: {}", e)))?;
+/// 变更卡片的暂存状态
+///
+/// `status` 可为待付邮资、留存、遗失，或待分发（解除上述状态）；
+/// 分发与退卡请使用 [`distribute_card`] 与 [`return_card`]。流转规则见 [`allowed_card_transitions`]。
+pub fn set_card_status(id: &str, status: CardStatus, remarks: Option<String>) -> Result<Card, AppError> {
+    set_card_status_in(database()?, id, status, remarks)
+}
 
-    let updated_at = format_datetime(&now_china());
+/// 同 [`set_card_status`]，在指定的数据库上下文中执行
+pub fn set_card_status_in(
+    db: &Database,
+    id: &str,
+    status: CardStatus,
+    remarks: Option<String>,
+) -> Result<Card, AppError> {
+    let mut conn = db.connection()?;
 
-    // 更新卡片
-    conn.execute(
-        "UPDATE cards SET status = ?1, metadata = ?2, updated_at = ?3 WHERE id = ?4",
-        rusqlite::params![CardStatus::Returned.as_str(), metadata_json, updated_at, id],
-    )
-    .map_err(|e| AppError::Other(format!("更新卡片失败: {}", e)))?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    let operator_id = db.current_operator_id();
+    let card = set_card_status_conn(&tx, id, status, remarks, operator_id.as_deref())?;
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
 
-    log::info!("✅ 退卡成功: {}", id);
-    get_card(id)?.ok_or_else(|| AppError::Other("更新后无法获取卡片".to_string()))
+    log::info!("✅ 卡片状态变更为{}: {}", card.status.display_name(), id);
+    Ok(card)
+}
+
+/// 状态变更主体（crate-private，由调用方管理事务）。
+pub(crate) fn set_card_status_conn(
+    conn: &Connection,
+    id: &str,
+    status: CardStatus,
+    remarks: Option<String>,
+    operator_id: Option<&str>,
+) -> Result<Card, AppError> {
+    let event_type = match status {
+        CardStatus::AwaitingPostage => CardEventType::AwaitingPostage,
+        CardStatus::Held => CardEventType::Held,
+        CardStatus::Lost => CardEventType::Lost,
+        CardStatus::Pending => CardEventType::Released,
+        _ => {
+            return Err(AppError::InvalidParameter(format!(
+                "请通过分发或退卡操作变更为「{}」",
+                status.display_name()
+            )));
+        }
+    };
+
+    let card = get_card_conn(conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("卡片不存在: {}", id)))?;
+    check_card_transition(&card.status, &status)?;
+
+    let mut event = CardEvent::new(id, event_type);
+    event.operator_id = operator_id.map(str::to_string);
+    event.remarks = remarks
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    append_card_event_conn(conn, &event)?;
+
+    get_card_conn(conn, id)?.ok_or_else(|| AppError::Other("更新后无法获取卡片".to_string()))
 }
 
-/// 删除卡片
+/// 删除卡片（移入回收站，可恢复或彻底删除）
 pub fn delete_card(id: &str) -> Result<(), AppError> {
-    let conn = get_connection()?;
+    delete_card_in(database()?, id)
+}
 
-    // 检查卡片是否存在
-    let card = get_card(id)?;
+/// 同 [`delete_card`]，在指定的数据库上下文中执行
+pub fn delete_card_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let conn = db.connection()?;
+    delete_card_conn(&conn, id, db.current_operator_id().as_deref())?;
+
+    log::info!("✅ 卡片已移入回收站: {}", id);
+    Ok(())
+}
+
+/// 软删除主体（crate-private，由调用方管理事务）。
+pub(crate) fn delete_card_conn(
+    conn: &Connection,
+    id: &str,
+    operator_id: Option<&str>,
+) -> Result<(), AppError> {
+    // 检查卡片是否存在（已在回收站中的卡片视为不存在）
+    let card = get_card_conn(conn, id)?;
     if card.is_none() {
         return Err(AppError::ProfileNotFound(format!("卡片不存在: {}", id)));
     }
 
-    conn.execute("DELETE FROM cards WHERE id = ?1", [id])
-        .map_err(|e| AppError::Other(format!("删除卡片失败: {}", e)))?;
+    let now = format_datetime(&now_china());
+    conn.execute(
+        "UPDATE cards SET deleted_at = ?1, deleted_by = ?2, updated_at = ?1 WHERE id = ?3",
+        rusqlite::params![now, operator_id, id],
+    )
+    .map_err(|e| AppError::Other(format!("删除卡片失败: {}", e)))?;
 
-    log::info!("✅ 删除卡片成功: {}", id);
     Ok(())
 }
 
 /// 获取项目的最大序列号
 /// 返回该项目中最大的数字序列号，如果没有记录则返回 None
 pub fn get_max_serial_by_project(project_id: &str) -> Result<Option<u32>, AppError> {
-    let conn = get_connection()?;
+    get_max_serial_by_project_in(database()?, project_id)
+}
+
+/// 同 [`get_max_serial_by_project`]，在指定的数据库上下文中执行
+pub fn get_max_serial_by_project_in(
+    db: &Database,
+    project_id: &str,
+) -> Result<Option<u32>, AppError> {
+    let conn = db.connection()?;
 
     // 查询该项目下所有卡片的最大序列号
     let result: Result<Option<i32>, _> = conn.query_row(
@@ -448,48 +1295,24 @@ pub fn get_max_serial_by_project(project_id: &str) -> Result<Option<u32>, AppErr
     }
 }
 
-/// 保存待处理运单号（不改变卡片状态）
-///
-/// 用于顺丰下单后暂存运单号，等待用户点击"确认分发"后再正式分发
-pub fn save_pending_waybill(card_id: &str, waybill_no: String) -> Result<Card, AppError> {
-    let conn = get_connection()?;
-
-    // 获取卡片
-    let card = get_card(card_id)?
-        .ok_or_else(|| AppError::ProfileNotFound(format!("卡片不存在: {}", card_id)))?;
-
-    // 更新 metadata 中的 pending_waybill_no
-    let mut metadata = card.metadata.unwrap_or_default();
-    metadata.pending_waybill_no = Some(waybill_no.clone());
-
-    let metadata_json = serde_json::to_string(&metadata)
-        .map_err(|e| AppError::Other(format!("序列化元数据失败: {}", e)))?;
-
-    let updated_at = format_datetime(&now_china());
-
-    // 更新卡片（不改变状态）
-    conn.execute(
-        "UPDATE cards SET metadata = ?1, updated_at = ?2 WHERE id = ?3",
-        rusqlite::params![metadata_json, updated_at, card_id],
-    )
-    .map_err(|e| AppError::Other(format!("更新卡片失败: {}", e)))?;
-
-    log::info!("✅ 保存待处理运单号成功: {} -> {}", card_id, waybill_no);
-    get_card(card_id)?.ok_or_else(|| AppError::Other("更新后无法获取卡片".to_string()))
+/// 获取项目下的所有呼号（去重，统一大写；旧呼号解析为现用呼号）
+pub fn get_project_callsigns(project_id: &str) -> Result<Vec<String>, AppError> {
+    get_project_callsigns_in(database()?, project_id)
 }
 
-/// 获取项目下的所有呼号（去重，统一大写）
-pub fn get_project_callsigns(project_id: &str) -> Result<Vec<String>, AppError> {
-    let conn = get_connection()?;
+/// 同 [`get_project_callsigns`]，在指定的数据库上下文中执行
+pub fn get_project_callsigns_in(db: &Database, project_id: &str) -> Result<Vec<String>, AppError> {
+    let conn = db.connection()?;
 
     let mut stmt = conn
-        .prepare(
+        .prepare(&format!(
             r#"
-            SELECT DISTINCT UPPER(callsign) FROM cards
-            WHERE project_id = ?1
-            ORDER BY UPPER(callsign) ASC
+            SELECT DISTINCT {} AS resolved FROM cards c
+            WHERE c.project_id = ?1 AND c.deleted_at IS NULL
+            ORDER BY resolved ASC
             "#,
-        )
+            resolved_callsign_sql("c.callsign", "c.created_at")
+        ))
         .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
 
     let callsigns = stmt
@@ -511,15 +1334,44 @@ pub fn save_card_address(
     mail_method: Option<String>,
     updated_at: Option<String>,
 ) -> Result<Card, AppError> {
-    let conn = get_connection()?;
+    save_card_address_in(
+        database()?,
+        card_id,
+        source,
+        chinese_address,
+        english_address,
+        name,
+        mail_method,
+        updated_at,
+    )
+}
+
+/// 同 [`save_card_address`]，在指定的数据库上下文中执行
+#[allow(clippy::too_many_arguments)]
+pub fn save_card_address_in(
+    db: &Database,
+    card_id: &str,
+    source: String,
+    chinese_address: Option<String>,
+    english_address: Option<String>,
+    name: Option<String>,
+    mail_method: Option<String>,
+    updated_at: Option<String>,
+) -> Result<Card, AppError> {
+    let operator = get_current_operator_in(db)?;
+    let mut conn = db.connection()?;
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
 
     // 获取卡片
-    let mut card = get_card(card_id)?
+    let mut card = get_card_conn(&tx, card_id)?
         .ok_or_else(|| AppError::ProfileNotFound(format!("卡片不存在: {}", card_id)))?;
 
     // 使用 Card 的方法添加或更新地址
     card.add_or_update_address(
-        source,
+        source.clone(),
         chinese_address,
         english_address,
         name,
@@ -527,27 +1379,45 @@ pub fn save_card_address(
         updated_at,
     );
 
-    // 序列化 metadata
-    let metadata_json = if let Some(ref metadata) = card.metadata {
-        Some(serde_json::to_string(metadata)
-            .map_err(|e| AppError::Other(format!("序列化元数据失败: {}", e)))?)
-    } else {
-        None
-    };
+    let entries = card
+        .metadata
+        .as_ref()
+        .and_then(|m| m.address_cache.as_deref())
+        .unwrap_or_default();
+    save_card_addresses_conn(&tx, card_id, entries)?;
 
     let card_updated_at = format_datetime(&now_china());
 
     // 更新数据库
-    conn.execute(
-        "UPDATE cards SET metadata = ?1, updated_at = ?2 WHERE id = ?3",
-        rusqlite::params![metadata_json, card_updated_at, card_id],
+    tx.execute(
+        "UPDATE cards SET updated_at = ?1 WHERE id = ?2",
+        rusqlite::params![card_updated_at, card_id],
     )
     .map_err(|e| AppError::Other(format!("更新卡片失败: {}", e)))?;
 
+    // 地址变更以来源记入修改历史，记录当前操作员
+    insert_card_history_conn(
+        &tx,
+        &CardHistoryEntry {
+            id: 0,
+            card_id: card_id.to_string(),
+            field: "address".to_string(),
+            old_value: None,
+            new_value: Some(source),
+            changed_by: operator.as_ref().map(|o| o.name.clone()),
+            operator_id: operator.map(|o| o.id),
+            changed_at: card_updated_at,
+        },
+    )?;
+
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
     log::info!("✅ 保存地址到卡片成功: {}", card_id);
 
     // 返回更新后的卡片
-    get_card(card_id)?.ok_or_else(|| AppError::Other("更新后无法获取卡片".to_string()))
+    get_card_conn(&conn, card_id)?
+        .ok_or_else(|| AppError::Other("更新后无法获取卡片".to_string()))
 }
 
 #[cfg(test)]
@@ -558,7 +1428,15 @@ mod tests {
     /// 创建内存测试库：建表语句取自迁移以与生产 schema 保真。
     ///
     /// - `projects` 取 `migrations/2026.1.24.001_init.sql`
-    /// - `cards` 取 `migrations/2026.1.24.002_add_cards.sql`（含两条 CHECK 与 FK）
+    /// - `cards` 取 `migrations/2026.1.24.002_add_cards.sql`（含两条 CHECK 与 FK），
+    ///   按 `2026.10.18.003_normalize_card_metadata.sql` 移除 `metadata`
+    /// - 元数据三表取 `migrations/2026.10.18.003_normalize_card_metadata.sql`
+    /// - `projects`/`cards` 的 `deleted_at` 取 `migrations/2026.10.18.004_add_soft_delete.sql`
+    /// - `cards` 的 `distributed_qty` 取 `migrations/2026.10.18.006_add_partial_distribution.sql`，
+    ///   status 约束取 `migrations/2026.10.18.007_add_card_hold_states.sql`
+    /// - `card_distributions` 的 `waybill_no` 取 `migrations/2026.10.18.009_add_shipments.sql`
+    /// - `storage_locations`（仅卡片查询连接的列）与 `cards` 的 `storage_location_id`
+    ///   取 `migrations/2026.10.18.012_add_storage_locations.sql`
     ///
     /// 不开 `PRAGMA foreign_keys`：测试断言不依赖 FK 强制，仅靠先插 projects 命中 LEFT JOIN。
     fn setup_test_db() -> Connection {
@@ -569,21 +1447,78 @@ mod tests {
                 id TEXT PRIMARY KEY,
                 name TEXT NOT NULL UNIQUE,
                 created_at TEXT NOT NULL,
-                updated_at TEXT NOT NULL
+                updated_at TEXT NOT NULL,
+                deleted_at TEXT
             );
             CREATE TABLE cards (
                 id TEXT PRIMARY KEY,
                 project_id TEXT NOT NULL,
                 creator_id TEXT,
                 callsign TEXT NOT NULL,
+                via_callsign TEXT,
                 qty INTEGER NOT NULL CHECK(qty > 0 AND qty <= 9999),
                 serial INTEGER,
-                status TEXT NOT NULL CHECK(status IN ('pending', 'distributed', 'returned')) DEFAULT 'pending',
-                metadata TEXT,
+                status TEXT NOT NULL CHECK(status IN ('pending', 'partially_distributed', 'distributed', 'returned', 'awaiting_postage', 'held', 'lost')) DEFAULT 'pending',
+                distributed_qty INTEGER NOT NULL DEFAULT 0,
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL,
+                deleted_at TEXT,
+                storage_location_id TEXT,
                 FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
             );
+            CREATE TABLE card_distributions (
+                card_id TEXT PRIMARY KEY,
+                method TEXT NOT NULL,
+                address TEXT,
+                remarks TEXT,
+                proxy_callsign TEXT,
+                qty INTEGER,
+                distributed_at TEXT NOT NULL,
+                waybill_no TEXT,
+                FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+            );
+            CREATE TABLE card_returns (
+                card_id TEXT PRIMARY KEY,
+                method TEXT NOT NULL,
+                remarks TEXT,
+                returned_at TEXT NOT NULL,
+                FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+            );
+            CREATE TABLE card_addresses (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                card_id TEXT NOT NULL,
+                source TEXT NOT NULL,
+                chinese_address TEXT,
+                english_address TEXT,
+                name TEXT,
+                mail_method TEXT,
+                updated_at TEXT,
+                cached_at TEXT NOT NULL,
+                UNIQUE (card_id, source),
+                FOREIGN KEY (card_id) REFERENCES cards(id) ON DELETE CASCADE
+            );
+            CREATE TABLE callsign_aliases (
+                id TEXT PRIMARY KEY,
+                alias TEXT NOT NULL,
+                callsign TEXT NOT NULL,
+                effective_from TEXT NOT NULL,
+                effective_to TEXT,
+                remarks TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            CREATE TABLE storage_locations (
+                id TEXT PRIMARY KEY,
+                cabinet TEXT NOT NULL,
+                box_no TEXT NOT NULL,
+                slot TEXT NOT NULL DEFAULT ''
+            );
+            CREATE TABLE serial_schemes (
+                project_id TEXT PRIMARY KEY,
+                prefix TEXT NOT NULL DEFAULT '',
+                year INTEGER,
+                width INTEGER NOT NULL DEFAULT 3
+            );
             "#,
         )
         .unwrap();
@@ -603,15 +1538,15 @@ mod tests {
     ///
     /// - `created_at` 固定宽度递增（serial 越大越新），确保 DESC 排序可判定
     /// - `qty` 固定为 1，`serial` 递增 1..=count，使 qty != serial，能捕获列错位
-    /// - `status='pending'`、`metadata` 为合法 JSON
+    /// - `status='pending'`，不含任何元数据
     fn insert_cards(conn: &Connection, project_id: &str, count: i32) {
         for serial in 1..=count {
             let created_at = format!("2026-01-01 00:00:{:04}", serial);
             conn.execute(
                 r#"
                 INSERT INTO cards
-                    (id, project_id, creator_id, callsign, qty, serial, status, metadata, created_at, updated_at)
-                VALUES (?1, ?2, NULL, ?3, ?4, ?5, 'pending', ?6, ?7, ?7)
+                    (id, project_id, creator_id, callsign, qty, serial, status, created_at, updated_at)
+                VALUES (?1, ?2, NULL, ?3, ?4, ?5, 'pending', ?6, ?6)
                 "#,
                 rusqlite::params![
                     format!("{}-card-{:04}", project_id, serial),
@@ -619,7 +1554,6 @@ mod tests {
                     format!("BH2T{:04}", serial),
                     1, // qty 固定为 1，与 serial 取不同值
                     serial,
-                    "{}", // 合法 JSON 元数据
                     created_at,
                 ],
             )
@@ -656,12 +1590,8 @@ mod tests {
         assert_eq!(top.qty, 1);
         assert_eq!(top.status, CardStatus::Pending);
         assert_eq!(top.callsign, "BH2T0150");
-        // metadata 为合法空 JSON 对象，解析为 Some(默认 CardMetadata)
-        assert!(top.metadata.is_some());
-        let meta = top.metadata.as_ref().unwrap();
-        assert!(meta.distribution.is_none());
-        assert!(meta.return_info.is_none());
-        assert!(meta.address_cache.is_none());
+        // 元数据三表均无记录，metadata 为 None
+        assert!(top.metadata.is_none());
     }
 
     /// 3.3 分页上限：150 条传 page_size=100000，断言被钳到 100、total=150。
@@ -721,6 +1651,7 @@ mod tests {
             project_id: Some("p1".to_string()),
             callsign: Some("BH2T".to_string()),
             status: Some(CardStatus::Pending),
+            ..Default::default()
         };
         let paged_n3 = list_cards_conn(
             &conn,
@@ -752,4 +1683,363 @@ mod tests {
         assert_eq!(paged_n0.items.len(), 100);
         assert_eq!(paged_n0.total, 150);
     }
+
+    /// 基于真实迁移建库，插入一个项目与一张卡片，返回连接
+    fn setup_migrated_db() -> Connection {
+        let conn = crate::db::sqlite::open_test_connection();
+        insert_project(&conn, "p1", "项目一");
+        insert_project(&conn, "p2", "项目二");
+        insert_cards(&conn, "p1", 2);
+        conn
+    }
+
+    /// 修改多个字段：卡片更新，且每个变化字段各写一条历史
+    #[test]
+    fn test_update_card_writes_history_per_field() {
+        let conn = setup_migrated_db();
+
+        let update = CardUpdate {
+            callsign: Some(" bg2xyz ".to_string()),
+            qty: Some(3),
+            serial: Some(None),
+            project_id: Some("p2".to_string()),
+        };
+        let card =
+            update_card_conn(&conn, "p1-card-0001", update, Some("BH2RO".to_string()), None)
+                .unwrap();
+
+        assert_eq!(card.callsign, "BG2XYZ");
+        assert_eq!(card.qty, 3);
+        assert_eq!(card.serial, None);
+        assert_eq!(card.project_id, "p2");
+
+        let history = get_card_history_conn(&conn, "p1-card-0001").unwrap();
+        let fields: Vec<&str> = history.iter().map(|h| h.field.as_str()).collect();
+        assert_eq!(fields, vec!["callsign", "qty", "serial", "project_id"]);
+        assert_eq!(history[0].old_value.as_deref(), Some("BH2T0001"));
+        assert_eq!(history[0].new_value.as_deref(), Some("BG2XYZ"));
+        assert_eq!(history[2].old_value.as_deref(), Some("1"));
+        assert_eq!(history[2].new_value, None);
+        assert!(history.iter().all(|h| h.changed_by.as_deref() == Some("BH2RO")));
+    }
+
+    /// 值未变化时不写历史
+    #[test]
+    fn test_update_card_noop_writes_no_history() {
+        let conn = setup_migrated_db();
+
+        let update = CardUpdate {
+            callsign: Some("bh2t0001".to_string()),
+            qty: Some(1),
+            ..Default::default()
+        };
+        update_card_conn(&conn, "p1-card-0001", update, None, None).unwrap();
+
+        assert!(get_card_history_conn(&conn, "p1-card-0001").unwrap().is_empty());
+    }
+
+    /// 目标项目已存在同呼号时拒绝修改，且不留下历史
+    #[test]
+    fn test_update_card_rejects_duplicate_callsign() {
+        let conn = setup_migrated_db();
+
+        let update = CardUpdate {
+            callsign: Some("BH2T0002".to_string()),
+            ..Default::default()
+        };
+        let result = update_card_conn(&conn, "p1-card-0001", update, None, None);
+        assert!(matches!(result, Err(AppError::InvalidParameter(_))));
+        assert!(get_card_history_conn(&conn, "p1-card-0001").unwrap().is_empty());
+    }
+
+    /// 转移项目时目标项目已有同一序列号则拒绝；同时改用空闲序列号可转移
+    #[test]
+    fn test_update_card_rejects_serial_clash_on_project_move() {
+        let conn = setup_migrated_db();
+        insert_cards(&conn, "p2", 1);
+
+        let update = CardUpdate {
+            callsign: Some("BG2XYZ".to_string()),
+            project_id: Some("p2".to_string()),
+            ..Default::default()
+        };
+        let result = update_card_conn(&conn, "p1-card-0001", update, None, None);
+        assert!(matches!(result, Err(AppError::InvalidParameter(_))));
+        assert!(get_card_history_conn(&conn, "p1-card-0001").unwrap().is_empty());
+
+        let update = CardUpdate {
+            callsign: Some("BG2XYZ".to_string()),
+            serial: Some(Some(5)),
+            project_id: Some("p2".to_string()),
+            ..Default::default()
+        };
+        let card = update_card_conn(&conn, "p1-card-0001", update, None, None).unwrap();
+        assert_eq!(card.project_id, "p2");
+        assert_eq!(card.serial, Some(5));
+    }
+
+    /// 删除卡片时级联删除其历史
+    #[test]
+    fn test_card_history_cascades_on_delete() {
+        let conn = setup_migrated_db();
+
+        let update = CardUpdate {
+            qty: Some(9),
+            ..Default::default()
+        };
+        update_card_conn(&conn, "p1-card-0001", update, None, None).unwrap();
+        conn.execute("DELETE FROM cards WHERE id = 'p1-card-0001'", []).unwrap();
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM card_history", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    /// 按数量分发：剩余数量内可多次分发，分完后为已分发；数量不可改为少于已分发数量
+    #[test]
+    fn test_partial_distribution_tracks_remaining_qty() {
+        let conn = setup_migrated_db();
+        conn.execute("UPDATE cards SET qty = 5 WHERE id = 'p1-card-0001'", [])
+            .unwrap();
+        let distribute = |qty: Option<i32>| {
+            distribute_card_conn(&conn, "p1-card-0001", "自取".to_string(), None, None, None, qty, None, None)
+        };
+
+        let card = distribute(Some(2)).unwrap();
+        assert_eq!(card.status, CardStatus::PartiallyDistributed);
+        assert_eq!(card.remaining_qty(), 3);
+        assert!(distribute(Some(4)).is_err());
+
+        let update = CardUpdate {
+            qty: Some(1),
+            ..Default::default()
+        };
+        assert!(update_card_conn(&conn, "p1-card-0001", update, None, None).is_err());
+
+        let card = distribute(Some(3)).unwrap();
+        assert_eq!(card.status, CardStatus::Distributed);
+        assert_eq!(card.distributed_qty, 5);
+        assert!(distribute(Some(1)).is_err());
+
+        // 增加数量后重新变为部分分发，且不计为重新分发
+        let update = CardUpdate {
+            qty: Some(6),
+            ..Default::default()
+        };
+        let card = update_card_conn(&conn, "p1-card-0001", update, None, None).unwrap();
+        assert_eq!(card.status, CardStatus::PartiallyDistributed);
+        let card = distribute(None).unwrap();
+        assert_eq!(card.status, CardStatus::Distributed);
+        assert_eq!(card.distributed_qty, 6);
+
+        let events = crate::db::card_events::list_card_events_conn(&conn, "p1-card-0001").unwrap();
+        assert!(events.iter().all(|e| e.event_type == CardEventType::Distributed));
+    }
+
+    /// 状态流转：待付邮资、留存、遗失按规则校验，恢复后回到部分分发并可继续分发
+    #[test]
+    fn test_hold_states_follow_transition_rules() {
+        let conn = setup_migrated_db();
+        conn.execute("UPDATE cards SET qty = 3 WHERE id = 'p1-card-0001'", [])
+            .unwrap();
+        let id = "p1-card-0001";
+
+        distribute_card_conn(&conn, id, "自取".to_string(), None, None, None, Some(1), None, None)
+            .unwrap();
+        let card = set_card_status_conn(
+            &conn,
+            id,
+            CardStatus::AwaitingPostage,
+            Some(" 邮资未付 ".to_string()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(card.status, CardStatus::AwaitingPostage);
+        assert_eq!(card.distributed_qty, 1);
+
+        let card = set_card_status_conn(&conn, id, CardStatus::Lost, None, None).unwrap();
+        assert_eq!(card.status, CardStatus::Lost);
+        // 遗失的卡片不可分发、退卡或转为留存
+        assert!(distribute_card_conn(&conn, id, "自取".to_string(), None, None, None, None, None, None).is_err());
+        assert!(return_card_conn(&conn, id, "NOT FOUND".to_string(), None, None).is_err());
+        assert!(set_card_status_conn(&conn, id, CardStatus::Held, None, None).is_err());
+        // 分发、退卡不可通过状态变更完成
+        assert!(set_card_status_conn(&conn, id, CardStatus::Distributed, None, None).is_err());
+
+        let card = set_card_status_conn(&conn, id, CardStatus::Pending, None, None).unwrap();
+        assert_eq!(card.status, CardStatus::PartiallyDistributed);
+
+        let card = distribute_card_conn(&conn, id, "自取".to_string(), None, None, None, None, None, None)
+            .unwrap();
+        assert_eq!(card.status, CardStatus::Distributed);
+        assert!(set_card_status_conn(&conn, id, CardStatus::Held, None, None).is_err());
+
+        let events = crate::db::card_events::list_card_events_conn(&conn, id).unwrap();
+        let types: Vec<CardEventType> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                CardEventType::Distributed,
+                CardEventType::AwaitingPostage,
+                CardEventType::Lost,
+                CardEventType::Released,
+                CardEventType::Distributed
+            ]
+        );
+        assert_eq!(events[1].remarks.as_deref(), Some("邮资未付"));
+    }
+
+    /// 再次分发记为重新分发，事件日志保留首次分发信息，metadata 投影为最新一次
+    #[test]
+    fn test_redistribute_appends_event_and_keeps_history() {
+        let conn = setup_migrated_db();
+
+        distribute_card_conn(&conn, "p1-card-0001", "自取".to_string(), None, None, None, None, None, None)
+            .unwrap();
+        return_card_conn(&conn, "p1-card-0001", "NOT FOUND".to_string(), None, None).unwrap();
+        let card = distribute_card_conn(
+            &conn,
+            "p1-card-0001",
+            "快递".to_string(),
+            Some("北京市海淀区".to_string()),
+            Some("SF123".to_string()),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(card.status, CardStatus::Distributed);
+        let metadata = card.metadata.unwrap();
+        assert_eq!(metadata.distribution.unwrap().method, "快递");
+        assert_eq!(metadata.return_info.unwrap().method, "NOT FOUND");
+
+        let events = crate::db::card_events::list_card_events_conn(&conn, "p1-card-0001").unwrap();
+        let types: Vec<CardEventType> = events.iter().map(|e| e.event_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                CardEventType::Distributed,
+                CardEventType::Returned,
+                CardEventType::Redistributed
+            ]
+        );
+        assert_eq!(events[0].distribution.as_ref().unwrap().method, "自取");
+    }
+
+    /// 高级筛选：序列号/数量区间、分发方式、代领人存在性、录入日期按前缀包含当天
+    #[test]
+    fn test_advanced_filter_conditions() {
+        let conn = setup_test_db();
+        insert_project(&conn, "p1", "项目一");
+        insert_cards(&conn, "p1", 10);
+        conn.execute("UPDATE cards SET qty = 5 WHERE serial IN (3, 4)", [])
+            .unwrap();
+        conn.execute(
+            r#"
+            INSERT INTO card_distributions (card_id, method, proxy_callsign, distributed_at)
+            SELECT id, '代领', 'BG2XYZ', '2026-02-01T10:00:00+08:00' FROM cards WHERE serial IN (3, 4)
+            "#,
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            r#"
+            INSERT INTO card_distributions (card_id, method, distributed_at)
+            SELECT id, '自取', '2026-02-01T10:00:00+08:00' FROM cards WHERE serial = 5
+            "#,
+            [],
+        )
+        .unwrap();
+
+        let serials = |filter: CardFilter| -> Vec<i32> {
+            let mut v: Vec<i32> = list_all_cards_conn(&conn, filter)
+                .unwrap()
+                .iter()
+                .filter_map(|c| c.serial)
+                .collect();
+            v.sort();
+            v
+        };
+
+        let by_range = CardFilter {
+            serial_min: Some(2),
+            serial_max: Some(4),
+            qty_min: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(serials(by_range), vec![3, 4]);
+
+        let by_method = CardFilter {
+            distribution_method: Some("代领".to_string()),
+            has_proxy: Some(true),
+            distributed_from: Some("2026-02-01".to_string()),
+            distributed_to: Some("2026-02-01".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(serials(by_method), vec![3, 4]);
+
+        // 已分发但无代领人、以及未分发的卡片均视为无代领人
+        let without_proxy = CardFilter {
+            has_proxy: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(serials(without_proxy).len(), 8);
+
+        let by_created = CardFilter {
+            created_to: Some("2026-01-01".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(serials(by_created).len(), 10);
+
+        conn.execute(
+            r#"
+            INSERT INTO card_addresses (card_id, source, english_address, cached_at)
+            VALUES ('p1-card-0001', 'qrz.com', 'SOMEWHERE', '2026-01-01T00:00:00+08:00')
+            "#,
+            [],
+        )
+        .unwrap();
+        let with_address = CardFilter {
+            has_address: Some(true),
+            ..Default::default()
+        };
+        let cards = list_all_cards_conn(&conn, with_address).unwrap();
+        assert_eq!(cards.len(), 1);
+        let cache = cards[0].metadata.as_ref().unwrap().address_cache.as_ref().unwrap();
+        assert_eq!(cache[0].english_address.as_deref(), Some("SOMEWHERE"));
+    }
+
+    /// 排序：按序列号升序，分页与全量查询一致
+    #[test]
+    fn test_sort_by_serial_asc() {
+        let conn = setup_test_db();
+        insert_project(&conn, "p1", "项目一");
+        insert_cards(&conn, "p1", 5);
+
+        let filter = CardFilter {
+            sort_by: Some(CardSortField::Serial),
+            sort_order: Some(SortOrder::Asc),
+            ..Default::default()
+        };
+        let all = list_all_cards_conn(&conn, filter.clone()).unwrap();
+        assert_eq!(
+            all.iter().map(|c| c.serial.unwrap()).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+
+        let paged = list_cards_conn(
+            &conn,
+            filter,
+            Pagination {
+                page: 1,
+                page_size: 2,
+            },
+        )
+        .unwrap();
+        assert_eq!(paged.items[0].serial, Some(1));
+        assert_eq!(paged.total, 5);
+    }
 }
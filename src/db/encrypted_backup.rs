@@ -0,0 +1,207 @@
+// 加密备份模块
+//
+// 以用户口令加密导出文件（PBKDF2-HMAC-SHA256 派生密钥 + AES-256-GCM），
+// 导入时按文件内容识别明文与加密格式，解密前校验口令与文件完整性
+
+use crate::error::AppError;
+use crate::security::encryption::{open, seal, SealedData, PBKDF2_ITERATIONS};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 加密备份文件的格式标识
+pub const ENCRYPTED_BACKUP_FORMAT: &str = "qsl-cardhub-encrypted-backup";
+
+/// 加密备份格式版本
+const ENCRYPTED_BACKUP_VERSION: u32 = 1;
+
+/// 备份口令最小长度
+pub const MIN_BACKUP_PASSPHRASE_LEN: usize = 8;
+
+/// 加密备份文件结构（JSON）
+///
+/// 头部字段（格式、版本、算法、迭代次数）作为 AES-GCM 的附加认证数据，
+/// 任何字段或密文被改动都会导致解密认证失败。
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedBackup {
+    /// 格式标识，固定为 [`ENCRYPTED_BACKUP_FORMAT`]
+    format: String,
+    /// 格式版本
+    version: u32,
+    /// 密钥派生算法
+    kdf: String,
+    /// 密钥派生迭代次数
+    iterations: u32,
+    /// 加密算法
+    cipher: String,
+    /// 盐值（Base64）
+    salt: String,
+    /// nonce（Base64）
+    nonce: String,
+    /// 密文（Base64，含认证标签，明文为导出 JSON）
+    ciphertext: String,
+}
+
+impl EncryptedBackup {
+    /// 附加认证数据
+    fn aad(&self) -> Vec<u8> {
+        format!(
+            "{}|{}|{}|{}|{}",
+            self.format, self.version, self.kdf, self.iterations, self.cipher
+        )
+        .into_bytes()
+    }
+}
+
+/// 内容是否为加密备份（按格式标识识别，明文导出文件返回 `false`）
+pub fn is_encrypted_backup(content: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(content)
+        .ok()
+        .and_then(|v| v.get("format")?.as_str().map(|f| f == ENCRYPTED_BACKUP_FORMAT))
+        .unwrap_or(false)
+}
+
+/// 以口令加密导出 JSON，返回加密备份文件内容
+pub fn encrypt_backup(json: &str, passphrase: &str) -> Result<String, AppError> {
+    if passphrase.chars().count() < MIN_BACKUP_PASSPHRASE_LEN {
+        return Err(AppError::InvalidParameter(format!(
+            "备份口令至少 {} 个字符",
+            MIN_BACKUP_PASSPHRASE_LEN
+        )));
+    }
+
+    let mut backup = EncryptedBackup {
+        format: ENCRYPTED_BACKUP_FORMAT.to_string(),
+        version: ENCRYPTED_BACKUP_VERSION,
+        kdf: "pbkdf2-sha256".to_string(),
+        iterations: PBKDF2_ITERATIONS,
+        cipher: "aes-256-gcm".to_string(),
+        salt: String::new(),
+        nonce: String::new(),
+        ciphertext: String::new(),
+    };
+    let sealed = seal(passphrase.as_bytes(), json.as_bytes(), &backup.aad())
+        .map_err(|e| AppError::Other(format!("加密备份失败: {}", e)))?;
+    backup.salt = STANDARD.encode(&sealed.salt);
+    backup.nonce = STANDARD.encode(&sealed.nonce);
+    backup.ciphertext = STANDARD.encode(&sealed.ciphertext);
+
+    serde_json::to_string_pretty(&backup)
+        .map_err(|e| AppError::Other(format!("序列化加密备份失败: {}", e)))
+}
+
+/// 以口令解密加密备份，返回导出 JSON
+///
+/// AES-GCM 认证失败（口令错误或文件被改动）时返回错误，不返回任何部分数据。
+pub fn decrypt_backup(content: &str, passphrase: &str) -> Result<String, AppError> {
+    let backup: EncryptedBackup = serde_json::from_str(content)
+        .map_err(|e| AppError::DataFormatError(format!("加密备份文件格式错误: {}", e)))?;
+    if backup.format != ENCRYPTED_BACKUP_FORMAT || backup.version > ENCRYPTED_BACKUP_VERSION {
+        return Err(AppError::DataFormatError(format!(
+            "不支持的加密备份格式版本: {}",
+            backup.version
+        )));
+    }
+    if backup.kdf != "pbkdf2-sha256" || backup.cipher != "aes-256-gcm" {
+        return Err(AppError::DataFormatError(format!(
+            "不支持的加密算法: {} / {}",
+            backup.kdf, backup.cipher
+        )));
+    }
+    // 迭代次数在认证之前使用，只接受当前版本的固定值，避免构造的超大迭代次数拖死导入
+    if backup.iterations != PBKDF2_ITERATIONS {
+        return Err(AppError::DataFormatError(format!(
+            "不支持的密钥派生迭代次数: {}",
+            backup.iterations
+        )));
+    }
+
+    let decode = |field: &str| {
+        STANDARD
+            .decode(field)
+            .map_err(|_| AppError::DataFormatError("加密备份文件已损坏".to_string()))
+    };
+    let sealed = SealedData {
+        salt: decode(&backup.salt)?,
+        nonce: decode(&backup.nonce)?,
+        ciphertext: decode(&backup.ciphertext)?,
+    };
+    let plaintext = open(passphrase.as_bytes(), &sealed, &backup.aad(), backup.iterations)
+        .map_err(|_| AppError::InvalidParameter("口令错误或备份文件已损坏".to_string()))?;
+
+    String::from_utf8(plaintext)
+        .map_err(|_| AppError::DataFormatError("加密备份内容不是有效的 UTF-8".to_string()))
+}
+
+/// 读取导出文件内容：明文直接返回，加密备份以口令解密（未提供口令时报错）
+pub(crate) fn read_backup_file(path: &Path, passphrase: Option<&str>) -> Result<String, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::Other(format!("无法读取文件: {}", e)))?;
+    decode_backup_content(content, passphrase)
+}
+
+/// 同 [`read_backup_file`]，处理已读取的文件内容
+pub(crate) fn decode_backup_content(
+    content: String,
+    passphrase: Option<&str>,
+) -> Result<String, AppError> {
+    if !is_encrypted_backup(&content) {
+        return Ok(content);
+    }
+
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => decrypt_backup(&content, passphrase),
+        None => Err(AppError::InvalidParameter(
+            "该备份文件已加密，请输入备份口令".to_string(),
+        )),
+    }
+}
+
+/// 文件是否为加密备份（供前端决定是否提示输入口令）
+pub fn is_encrypted_backup_file(path: &Path) -> Result<bool, AppError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::Other(format!("无法读取文件: {}", e)))?;
+    Ok(is_encrypted_backup(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 加密后可按格式识别并以正确口令还原；口令错误、密文或头部被改动均无法解密
+    #[test]
+    fn test_encrypted_backup_roundtrip() {
+        let json = r#"{"version":"1.5","tables":{"cards":[{"callsign":"BH2RO"}]}}"#;
+        assert!(encrypt_backup(json, "short").is_err());
+
+        let content = encrypt_backup(json, "correct horse").unwrap();
+        assert!(is_encrypted_backup(&content));
+        assert!(!is_encrypted_backup(json));
+        assert!(!content.contains("BH2RO"));
+        assert_eq!(decrypt_backup(&content, "correct horse").unwrap(), json);
+        assert!(decrypt_backup(&content, "wrong horse").is_err());
+
+        let mut tampered: serde_json::Value = serde_json::from_str(&content).unwrap();
+        tampered["iterations"] = serde_json::json!(1);
+        assert!(decrypt_backup(&tampered.to_string(), "correct horse").is_err());
+
+        let mut tampered: EncryptedBackup = serde_json::from_str(&content).unwrap();
+        let mut bytes = STANDARD.decode(&tampered.ciphertext).unwrap();
+        bytes[0] ^= 0xff;
+        tampered.ciphertext = STANDARD.encode(bytes);
+        let tampered = serde_json::to_string(&tampered).unwrap();
+        assert!(decrypt_backup(&tampered, "correct horse").is_err());
+    }
+
+    /// 头部迭代次数被改为非固定值时在派生密钥前拒绝
+    #[test]
+    fn test_rejects_unexpected_iterations() {
+        let content = encrypt_backup("{}", "correct horse").unwrap();
+        let mut tampered: serde_json::Value = serde_json::from_str(&content).unwrap();
+        tampered["iterations"] = serde_json::json!(u32::MAX);
+        assert!(matches!(
+            decrypt_backup(&tampered.to_string(), "correct horse"),
+            Err(AppError::DataFormatError(_))
+        ));
+    }
+}
@@ -2,8 +2,11 @@
 //
 // 将本地数据库导出为 JSON 格式文件
 
-use crate::db::models::{AppSetting, Card, Project};
-use crate::db::sqlite::{format_version, get_connection, get_db_version};
+use crate::db::card_metadata::load_card_metadata_map_conn;
+use crate::db::cards::{list_all_card_history_conn, CARD_VISIBLE_CONDITION};
+use crate::db::models::{AppSetting, Card, CardEvent, CardHistoryEntry, Project, SerialScheme};
+use crate::db::serial_schemes::list_serial_schemes_conn;
+use crate::db::sqlite::{database, format_version, get_db_version, Database};
 use crate::error::AppError;
 use crate::sf_express::{RecipientInfo, SFOrder, SenderInfo};
 use serde::{Deserialize, Serialize};
@@ -16,7 +19,13 @@ use ts_rs::TS;
 /// 版本历史:
 /// - 1.0: 初始版本
 /// - 1.1: SFOrder.sender_info/recipient_info 从 JSON 字符串改为嵌套对象
-pub const EXPORT_FORMAT_VERSION: &str = "1.1";
+/// - 1.2: 新增 card_events 卡片事件日志
+/// - 1.3: SFOrder 新增 card_ids 合并寄件关联卡片；CardMetadata 移除 pending_waybill_no
+/// - 1.4: Card 新增 via_callsign 经由的 QSL 管理员
+/// - 1.5: Card 新增 storage_location_id 存放位置（导入时仅保留本地已登记的位置）
+/// - 1.6: 新增 card_history 卡片修改历史
+/// - 1.7: 新增 serial_schemes 项目编号方案
+pub const EXPORT_FORMAT_VERSION: &str = "1.7";
 
 /// 导出数据结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +61,15 @@ pub struct ExportTables {
     /// 全局配置项列表（可选，向后兼容）
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub app_settings: Option<Vec<AppSetting>>,
+    /// 卡片事件日志（可选，向后兼容；缺省时导入按卡片元数据推导）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card_events: Option<Vec<CardEvent>>,
+    /// 卡片修改历史（可选，向后兼容；缺省时导入后无修改历史）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card_history: Option<Vec<CardHistoryEntry>>,
+    /// 项目编号方案（可选，向后兼容；缺省时导入保留本地同 ID 项目的方案）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_schemes: Option<Vec<SerialScheme>>,
 }
 
 /// 导出统计
@@ -71,7 +89,12 @@ pub struct ExportStats {
 
 /// 导出所有数据
 pub fn export_database() -> Result<ExportData, AppError> {
-    let conn = get_connection()?;
+    export_database_in(database()?)
+}
+
+/// 同 [`export_database`]，在指定的数据库上下文中执行
+pub fn export_database_in(db: &Database) -> Result<ExportData, AppError> {
+    let conn = db.connection()?;
 
     // 获取数据库版本
     let db_version = get_db_version(&conn)?;
@@ -89,6 +112,15 @@ pub fn export_database() -> Result<ExportData, AppError> {
     // 导出所有卡片
     let cards = export_cards(&conn)?;
 
+    // 导出卡片事件日志
+    let card_events = crate::db::card_events::list_all_card_events_conn(&conn)?;
+
+    // 导出卡片修改历史
+    let card_history = list_all_card_history_conn(&conn)?;
+
+    // 导出项目编号方案
+    let serial_schemes = list_serial_schemes_conn(&conn)?;
+
     // 导出所有寄件人
     let sf_senders = export_senders(&conn)?;
 
@@ -96,7 +128,7 @@ pub fn export_database() -> Result<ExportData, AppError> {
     let sf_orders = export_orders(&conn)?;
 
     // 导出全局配置
-    let app_settings = Some(crate::db::app_settings::get_all_settings()?);
+    let app_settings = Some(crate::db::app_settings::get_all_settings_in(db)?);
 
     // 读取同步配置中的 client_id
     let client_id = crate::sync::config::load_sync_config()
@@ -105,9 +137,12 @@ pub fn export_database() -> Result<ExportData, AppError> {
         .map(|c| c.client_id);
 
     log::info!(
-        "📦 导出数据完成: {} 个项目, {} 张卡片, {} 个寄件人, {} 个订单, {} 个配置项",
+        "📦 导出数据完成: {} 个项目, {} 个编号方案, {} 张卡片, {} 条卡片事件, {} 条修改历史, {} 个寄件人, {} 个订单, {} 个配置项",
         projects.len(),
+        serial_schemes.len(),
         cards.len(),
+        card_events.len(),
+        card_history.len(),
         sf_senders.len(),
         sf_orders.len(),
         app_settings.as_ref().map_or(0, |s| s.len())
@@ -126,14 +161,19 @@ pub fn export_database() -> Result<ExportData, AppError> {
             sf_senders,
             sf_orders,
             app_settings,
+            card_events: Some(card_events),
+            card_history: Some(card_history),
+            serial_schemes: Some(serial_schemes),
         },
     })
 }
 
-/// 导出项目列表
-fn export_projects(conn: &rusqlite::Connection) -> Result<Vec<Project>, AppError> {
+/// 导出项目列表（不含回收站中的项目）
+pub(crate) fn export_projects(conn: &rusqlite::Connection) -> Result<Vec<Project>, AppError> {
     let mut stmt = conn
-        .prepare("SELECT id, name, created_at, updated_at FROM projects ORDER BY created_at")
+        .prepare(
+            "SELECT id, name, created_at, updated_at FROM projects WHERE deleted_at IS NULL ORDER BY created_at",
+        )
         .map_err(|e| AppError::Other(format!("准备项目查询失败: {}", e)))?;
 
     let projects = stmt
@@ -152,44 +192,53 @@ fn export_projects(conn: &rusqlite::Connection) -> Result<Vec<Project>, AppError
     Ok(projects)
 }
 
-/// 导出卡片列表
-fn export_cards(conn: &rusqlite::Connection) -> Result<Vec<Card>, AppError> {
+/// 导出卡片列表（不含回收站中的卡片）
+pub(crate) fn export_cards(conn: &rusqlite::Connection) -> Result<Vec<Card>, AppError> {
     use crate::db::models::CardStatus;
 
     let mut stmt = conn
-        .prepare(
-            "SELECT id, project_id, creator_id, callsign, qty, serial, status, metadata, created_at, updated_at
-             FROM cards ORDER BY created_at"
-        )
+        .prepare(&format!(
+            "SELECT c.id, c.project_id, c.creator_id, c.callsign, c.qty, c.serial, c.status, c.created_at, c.updated_at, c.distributed_qty, c.via_callsign, c.storage_location_id
+             FROM cards c WHERE {} ORDER BY c.created_at",
+            CARD_VISIBLE_CONDITION
+        ))
         .map_err(|e| AppError::Other(format!("准备卡片查询失败: {}", e)))?;
 
-    let cards = stmt
+    let mut cards = stmt
         .query_map([], |row| {
             let status_str: String = row.get(6)?;
-            let metadata_str: Option<String> = row.get(7)?;
 
             Ok(Card {
                 id: row.get(0)?,
                 project_id: row.get(1)?,
                 creator_id: row.get(2)?,
                 callsign: row.get(3)?,
+                via_callsign: row.get(10)?,
                 qty: row.get(4)?,
                 serial: row.get(5)?,
                 status: CardStatus::from_str(&status_str).unwrap_or(CardStatus::Pending),
-                metadata: metadata_str.and_then(|s| serde_json::from_str(&s).ok()),
-                created_at: row.get(8)?,
-                updated_at: row.get(9)?,
+                distributed_qty: row.get(9)?,
+                storage_location_id: row.get(11)?,
+                metadata: None,
+                created_at: row.get(7)?,
+                updated_at: row.get(8)?,
             })
         })
         .map_err(|e| AppError::Other(format!("查询卡片失败: {}", e)))?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| AppError::Other(format!("读取卡片数据失败: {}", e)))?;
 
+    // 元数据按表整体读取后回填，导出格式保持不变
+    let mut metadata = load_card_metadata_map_conn(conn, None)?;
+    for card in &mut cards {
+        card.metadata = metadata.remove(&card.id);
+    }
+
     Ok(cards)
 }
 
 /// 导出寄件人列表
-fn export_senders(conn: &rusqlite::Connection) -> Result<Vec<SenderInfo>, AppError> {
+pub(crate) fn export_senders(conn: &rusqlite::Connection) -> Result<Vec<SenderInfo>, AppError> {
     let mut stmt = conn
         .prepare(
             "SELECT id, name, phone, mobile, province, city, district, address, is_default, created_at, updated_at
@@ -221,18 +270,30 @@ fn export_senders(conn: &rusqlite::Connection) -> Result<Vec<SenderInfo>, AppErr
 }
 
 /// 导出订单列表
-fn export_orders(conn: &rusqlite::Connection) -> Result<Vec<SFOrder>, AppError> {
+///
+/// 关联卡片在回收站中时不导出关联关系，保证导入时外键有效。
+pub(crate) fn export_orders(conn: &rusqlite::Connection) -> Result<Vec<SFOrder>, AppError> {
     let mut stmt = conn
-        .prepare(
-            "SELECT id, order_id, waybill_no, card_id, status, pay_method, cargo_name, sender_info, recipient_info, created_at, updated_at
-             FROM sf_orders ORDER BY created_at"
-        )
+        .prepare(&format!(
+            r#"
+            SELECT o.id, o.order_id, o.waybill_no, c.id, o.status, o.pay_method, o.cargo_name, o.sender_info, o.recipient_info, o.created_at, o.updated_at,
+                   (SELECT json_group_array(card_id) FROM (
+                        SELECT oc.card_id FROM sf_order_cards oc
+                        JOIN cards c ON c.id = oc.card_id AND {visible}
+                        WHERE oc.order_id = o.id ORDER BY oc.rowid))
+            FROM sf_orders o
+            LEFT JOIN cards c ON c.id = o.card_id AND {visible}
+            ORDER BY o.created_at
+            "#,
+            visible = CARD_VISIBLE_CONDITION
+        ))
         .map_err(|e| AppError::Other(format!("准备订单查询失败: {}", e)))?;
 
     let orders = stmt
         .query_map([], |row| {
             let sender_info_json: String = row.get(7)?;
             let recipient_info_json: String = row.get(8)?;
+            let card_ids_json: String = row.get(11)?;
 
             let sender_info: SenderInfo = serde_json::from_str(&sender_info_json).map_err(|e| {
                 rusqlite::Error::FromSqlConversionFailure(
@@ -249,12 +310,20 @@ fn export_orders(conn: &rusqlite::Connection) -> Result<Vec<SFOrder>, AppError>
                         Box::new(e),
                     )
                 })?;
+            let card_ids: Vec<String> = serde_json::from_str(&card_ids_json).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    11,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?;
 
             Ok(SFOrder {
                 id: row.get(0)?,
                 order_id: row.get(1)?,
                 waybill_no: row.get(2)?,
                 card_id: row.get(3)?,
+                card_ids,
                 status: row.get(4)?,
                 pay_method: row.get(5)?,
                 cargo_name: row.get(6)?,
@@ -287,6 +356,6 @@ mod tests {
 
     #[test]
     fn test_export_format_version() {
-        assert_eq!(EXPORT_FORMAT_VERSION, "1.1");
+        assert_eq!(EXPORT_FORMAT_VERSION, "1.7");
     }
 }
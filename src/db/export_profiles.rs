@@ -0,0 +1,243 @@
+// Excel 导出方案模块
+//
+// 保存导出列及顺序、工作表分组方式与汇总表设置，供卡片导出时复用
+
+use crate::db::models::{
+    format_datetime, now_china, ExportGroupBy, ExportProfile, ExportProfileInput,
+};
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashSet;
+
+/// 方案名称最大长度
+const MAX_PROFILE_NAME_LEN: usize = 30;
+
+/// 校验并规范化导出方案设置
+fn normalize_export_profile(mut input: ExportProfileInput) -> Result<ExportProfileInput, AppError> {
+    input.name = input.name.trim().to_string();
+    if input.name.is_empty() {
+        return Err(AppError::InvalidParameter("方案名称不能为空".to_string()));
+    }
+    if input.name.chars().count() > MAX_PROFILE_NAME_LEN {
+        return Err(AppError::InvalidParameter(format!(
+            "方案名称不能超过 {} 个字符",
+            MAX_PROFILE_NAME_LEN
+        )));
+    }
+    if input.columns.is_empty() {
+        return Err(AppError::InvalidParameter("至少选择一列导出".to_string()));
+    }
+    let mut seen = HashSet::new();
+    if let Some(column) = input.columns.iter().find(|c| !seen.insert(**c)) {
+        return Err(AppError::InvalidParameter(format!(
+            "导出列重复: {}",
+            column.header()
+        )));
+    }
+    Ok(input)
+}
+
+/// 读取导出方案行（无法识别的列忽略，以兼容较新版本保存的方案）
+fn row_to_profile(row: &rusqlite::Row) -> rusqlite::Result<ExportProfile> {
+    let columns: String = row.get(2)?;
+    let columns: Vec<serde_json::Value> = serde_json::from_str(&columns).unwrap_or_default();
+    let group_by: String = row.get(3)?;
+
+    Ok(ExportProfile {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        columns: columns
+            .into_iter()
+            .filter_map(|v| serde_json::from_value(v).ok())
+            .collect(),
+        group_by: ExportGroupBy::parse(&group_by).unwrap_or_default(),
+        include_summary: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+const SELECT_PROFILE: &str =
+    "SELECT id, name, columns, group_by, include_summary, created_at, updated_at
+     FROM export_profiles";
+
+fn get_export_profile_conn(conn: &Connection, id: &str) -> Result<Option<ExportProfile>, AppError> {
+    conn.query_row(
+        &format!("{} WHERE id = ?1", SELECT_PROFILE),
+        [id],
+        row_to_profile,
+    )
+    .optional()
+    .map_err(|e| AppError::Other(format!("查询导出方案失败: {}", e)))
+}
+
+/// 列出全部导出方案（按名称排序）
+pub fn list_export_profiles() -> Result<Vec<ExportProfile>, AppError> {
+    list_export_profiles_in(database()?)
+}
+
+/// 同 [`list_export_profiles`]，在指定的数据库上下文中执行
+pub fn list_export_profiles_in(db: &Database) -> Result<Vec<ExportProfile>, AppError> {
+    let conn = db.connection()?;
+    let mut stmt = conn
+        .prepare(&format!("{} ORDER BY name", SELECT_PROFILE))
+        .map_err(|e| AppError::Other(format!("准备查询失败: {}", e)))?;
+    let profiles = stmt
+        .query_map([], row_to_profile)
+        .map_err(|e| AppError::Other(format!("查询导出方案失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取导出方案失败: {}", e)))?;
+    Ok(profiles)
+}
+
+/// 保存导出方案：`id` 为空时新建，否则更新该方案
+pub fn save_export_profile(
+    id: Option<&str>,
+    input: ExportProfileInput,
+) -> Result<ExportProfile, AppError> {
+    save_export_profile_in(database()?, id, input)
+}
+
+/// 同 [`save_export_profile`]，在指定的数据库上下文中执行
+pub fn save_export_profile_in(
+    db: &Database,
+    id: Option<&str>,
+    input: ExportProfileInput,
+) -> Result<ExportProfile, AppError> {
+    let input = normalize_export_profile(input)?;
+    let conn = db.connection()?;
+
+    let duplicate: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM export_profiles WHERE name = ?1 AND id != ?2)",
+            rusqlite::params![input.name, id.unwrap_or("")],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Other(format!("查询导出方案失败: {}", e)))?;
+    if duplicate {
+        return Err(AppError::InvalidParameter(format!(
+            "导出方案「{}」已存在",
+            input.name
+        )));
+    }
+
+    let columns = serde_json::to_string(&input.columns)
+        .map_err(|e| AppError::Other(format!("序列化导出列失败: {}", e)))?;
+    let now = format_datetime(&now_china());
+    let id = match id {
+        Some(id) => {
+            let updated = conn
+                .execute(
+                    "UPDATE export_profiles
+                     SET name = ?2, columns = ?3, group_by = ?4, include_summary = ?5, updated_at = ?6
+                     WHERE id = ?1",
+                    rusqlite::params![
+                        id,
+                        input.name,
+                        columns,
+                        input.group_by.as_str(),
+                        input.include_summary,
+                        now
+                    ],
+                )
+                .map_err(|e| AppError::Other(format!("更新导出方案失败: {}", e)))?;
+            if updated == 0 {
+                return Err(AppError::ProfileNotFound(format!("导出方案不存在: {}", id)));
+            }
+            id.to_string()
+        }
+        None => {
+            let id = uuid::Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO export_profiles
+                 (id, name, columns, group_by, include_summary, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)",
+                rusqlite::params![
+                    id,
+                    input.name,
+                    columns,
+                    input.group_by.as_str(),
+                    input.include_summary,
+                    now
+                ],
+            )
+            .map_err(|e| AppError::Other(format!("创建导出方案失败: {}", e)))?;
+            id
+        }
+    };
+
+    get_export_profile_conn(&conn, &id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("导出方案不存在: {}", id)))
+}
+
+/// 删除导出方案
+pub fn delete_export_profile(id: &str) -> Result<(), AppError> {
+    delete_export_profile_in(database()?, id)
+}
+
+/// 同 [`delete_export_profile`]，在指定的数据库上下文中执行
+pub fn delete_export_profile_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let conn = db.connection()?;
+    let deleted = conn
+        .execute("DELETE FROM export_profiles WHERE id = ?1", [id])
+        .map_err(|e| AppError::Other(format!("删除导出方案失败: {}", e)))?;
+    if deleted == 0 {
+        return Err(AppError::ProfileNotFound(format!("导出方案不存在: {}", id)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::ExportColumn;
+
+    /// 新建、更新、重名与重复列校验、删除
+    #[test]
+    fn test_export_profile_crud() {
+        let db = Database::open_in_memory().unwrap();
+        let input = ExportProfileInput {
+            name: " 分发明细 ".to_string(),
+            columns: vec![
+                ExportColumn::Callsign,
+                ExportColumn::DistributionMethod,
+                ExportColumn::WaybillNo,
+            ],
+            group_by: ExportGroupBy::Status,
+            include_summary: true,
+        };
+
+        let created = save_export_profile_in(&db, None, input.clone()).unwrap();
+        assert_eq!(created.name, "分发明细");
+        assert_eq!(created.columns, input.columns);
+        assert_eq!(created.group_by, ExportGroupBy::Status);
+        assert!(created.include_summary);
+        assert!(save_export_profile_in(&db, None, input.clone()).is_err());
+
+        let updated = save_export_profile_in(
+            &db,
+            Some(&created.id),
+            ExportProfileInput {
+                columns: vec![ExportColumn::Serial, ExportColumn::Callsign],
+                group_by: ExportGroupBy::Project,
+                ..input.clone()
+            },
+        )
+        .unwrap();
+        assert_eq!(updated.id, created.id);
+        assert_eq!(updated.group_by, ExportGroupBy::Project);
+        assert_eq!(list_export_profiles_in(&db).unwrap()[0].columns.len(), 2);
+
+        let duplicated = ExportProfileInput {
+            name: "重复列".to_string(),
+            columns: vec![ExportColumn::Callsign, ExportColumn::Callsign],
+            ..input
+        };
+        assert!(save_export_profile_in(&db, None, duplicated).is_err());
+
+        delete_export_profile_in(&db, &created.id).unwrap();
+        assert!(list_export_profiles_in(&db).unwrap().is_empty());
+        assert!(delete_export_profile_in(&db, &created.id).is_err());
+    }
+}
@@ -0,0 +1,372 @@
+// 分发方式与退卡原因登记模块
+//
+// 维护可配置的分发方式、退卡原因（显示名称、必填字段、启用状态），
+// 分发与退卡时按登记项校验处理方式及其必填字段
+
+use crate::db::models::{
+    format_datetime, now_china, HandlingMethod, HandlingMethodKind, HandlingMethodUpdate,
+};
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use rusqlite::Connection;
+
+/// 查询列（与 `map_handling_method_row` 的列序一致）
+const HANDLING_METHOD_COLUMNS: &str = "kind, code, display_name, requires_address, requires_proxy_callsign, requires_remarks, is_active, sort_order, created_at, updated_at";
+
+/// 将查询行映射为 `HandlingMethod`（列序见 `HANDLING_METHOD_COLUMNS`）
+fn map_handling_method_row(row: &rusqlite::Row) -> rusqlite::Result<HandlingMethod> {
+    let kind = match row.get_ref(0)?.as_str()? {
+        "return" => HandlingMethodKind::Return,
+        _ => HandlingMethodKind::Distribution,
+    };
+    Ok(HandlingMethod {
+        kind,
+        code: row.get(1)?,
+        display_name: row.get(2)?,
+        requires_address: row.get(3)?,
+        requires_proxy_callsign: row.get(4)?,
+        requires_remarks: row.get(5)?,
+        is_active: row.get(6)?,
+        sort_order: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+/// 查询单个登记项
+pub(crate) fn get_handling_method_conn(
+    conn: &Connection,
+    kind: HandlingMethodKind,
+    code: &str,
+) -> Result<Option<HandlingMethod>, AppError> {
+    let result = conn.query_row(
+        &format!(
+            "SELECT {} FROM card_handling_methods WHERE kind = ?1 AND code = ?2",
+            HANDLING_METHOD_COLUMNS
+        ),
+        rusqlite::params![kind.as_str(), code],
+        map_handling_method_row,
+    );
+
+    match result {
+        Ok(method) => Ok(Some(method)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询{}失败: {}", kind.display_name(), e))),
+    }
+}
+
+/// 是否为空（未填写或仅含空白）
+fn is_blank(value: Option<&str>) -> bool {
+    value.is_none_or(|v| v.trim().is_empty())
+}
+
+/// 按登记项校验处理方式（crate-private，供分发、退卡调用）
+///
+/// 处理方式须已登记且启用，登记项要求的地址、代领人呼号、备注须已填写。
+pub(crate) fn check_handling_method_conn(
+    conn: &Connection,
+    kind: HandlingMethodKind,
+    code: &str,
+    address: Option<&str>,
+    proxy_callsign: Option<&str>,
+    remarks: Option<&str>,
+) -> Result<HandlingMethod, AppError> {
+    check_handling_method_with_conn(conn, kind, code, address, proxy_callsign, remarks, true)
+}
+
+/// 同 [`check_handling_method_conn`]，`require_active` 为 false 时允许已停用的登记项
+/// （用于记录已在别处完成的处理，如顺丰订单确认后的分发）
+pub(crate) fn check_handling_method_with_conn(
+    conn: &Connection,
+    kind: HandlingMethodKind,
+    code: &str,
+    address: Option<&str>,
+    proxy_callsign: Option<&str>,
+    remarks: Option<&str>,
+    require_active: bool,
+) -> Result<HandlingMethod, AppError> {
+    let method = get_handling_method_conn(conn, kind, code)?.ok_or_else(|| {
+        AppError::InvalidParameter(format!("未登记的{}: {}", kind.display_name(), code))
+    })?;
+
+    if require_active && !method.is_active {
+        return Err(AppError::InvalidParameter(format!(
+            "{}「{}」已停用",
+            kind.display_name(),
+            method.display_name
+        )));
+    }
+    if method.requires_address && is_blank(address) {
+        return Err(AppError::InvalidParameter(format!(
+            "{}「{}」须填写分发地址",
+            kind.display_name(),
+            method.display_name
+        )));
+    }
+    if method.requires_proxy_callsign && is_blank(proxy_callsign) {
+        return Err(AppError::InvalidParameter(format!(
+            "{}「{}」须填写代领人呼号",
+            kind.display_name(),
+            method.display_name
+        )));
+    }
+    if method.requires_remarks && is_blank(remarks) {
+        return Err(AppError::InvalidParameter(format!(
+            "{}「{}」须填写备注",
+            kind.display_name(),
+            method.display_name
+        )));
+    }
+
+    Ok(method)
+}
+
+/// 查询登记项列表（按排序、登记时间排序）
+///
+/// `kind` 为空时返回全部类别；`include_inactive` 为 false 时仅返回启用项。
+pub fn list_handling_methods(
+    kind: Option<HandlingMethodKind>,
+    include_inactive: bool,
+) -> Result<Vec<HandlingMethod>, AppError> {
+    list_handling_methods_in(database()?, kind, include_inactive)
+}
+
+/// 同 [`list_handling_methods`]，在指定的数据库上下文中执行
+pub fn list_handling_methods_in(
+    db: &Database,
+    kind: Option<HandlingMethodKind>,
+    include_inactive: bool,
+) -> Result<Vec<HandlingMethod>, AppError> {
+    let conn = db.connection()?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+            SELECT {} FROM card_handling_methods
+            WHERE (?1 IS NULL OR kind = ?1) AND (?2 OR is_active = 1)
+            ORDER BY kind ASC, sort_order ASC, created_at ASC
+            "#,
+            HANDLING_METHOD_COLUMNS
+        ))
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let methods = stmt
+        .query_map(
+            rusqlite::params![kind.map(|k| k.as_str()), include_inactive],
+            map_handling_method_row,
+        )
+        .map_err(|e| AppError::Other(format!("查询登记项列表失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取登记项数据失败: {}", e)))?;
+
+    Ok(methods)
+}
+
+/// 登记分发方式或退卡原因
+///
+/// `code` 为写入分发/退卡记录的取值，登记后不可修改；`display_name` 为空时与 `code` 相同。
+/// 新登记项默认启用、不要求任何字段，排在同类别末尾，可通过 [`update_handling_method`] 调整。
+pub fn create_handling_method(
+    kind: HandlingMethodKind,
+    code: String,
+    display_name: Option<String>,
+) -> Result<HandlingMethod, AppError> {
+    create_handling_method_in(database()?, kind, code, display_name)
+}
+
+/// 同 [`create_handling_method`]，在指定的数据库上下文中执行
+pub fn create_handling_method_in(
+    db: &Database,
+    kind: HandlingMethodKind,
+    code: String,
+    display_name: Option<String>,
+) -> Result<HandlingMethod, AppError> {
+    let code = code.trim().to_string();
+    if code.is_empty() {
+        return Err(AppError::InvalidParameter(format!(
+            "{}取值不能为空",
+            kind.display_name()
+        )));
+    }
+    let display_name = display_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| code.clone());
+
+    let conn = db.connection()?;
+    if get_handling_method_conn(&conn, kind, &code)?.is_some() {
+        return Err(AppError::InvalidParameter(format!(
+            "{}已存在: {}",
+            kind.display_name(),
+            code
+        )));
+    }
+
+    let now = format_datetime(&now_china());
+    conn.execute(
+        r#"
+        INSERT INTO card_handling_methods (kind, code, display_name, sort_order, created_at, updated_at)
+        VALUES (
+            ?1, ?2, ?3,
+            (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM card_handling_methods WHERE kind = ?1),
+            ?4, ?4
+        )
+        "#,
+        rusqlite::params![kind.as_str(), code, display_name, now],
+    )
+    .map_err(|e| AppError::Other(format!("登记{}失败: {}", kind.display_name(), e)))?;
+
+    log::info!("✅ 登记{}成功: {}", kind.display_name(), code);
+    get_handling_method_conn(&conn, kind, &code)?
+        .ok_or_else(|| AppError::Other("登记后无法获取登记项".to_string()))
+}
+
+/// 修改登记项（显示名称、必填字段、启用状态、排序）
+///
+/// 登记项不可删除：历史分发/退卡记录仍引用其取值，不再使用时请停用。
+pub fn update_handling_method(
+    kind: HandlingMethodKind,
+    code: &str,
+    update: HandlingMethodUpdate,
+) -> Result<HandlingMethod, AppError> {
+    update_handling_method_in(database()?, kind, code, update)
+}
+
+/// 同 [`update_handling_method`]，在指定的数据库上下文中执行
+pub fn update_handling_method_in(
+    db: &Database,
+    kind: HandlingMethodKind,
+    code: &str,
+    update: HandlingMethodUpdate,
+) -> Result<HandlingMethod, AppError> {
+    let conn = db.connection()?;
+    let current = get_handling_method_conn(&conn, kind, code)?.ok_or_else(|| {
+        AppError::ProfileNotFound(format!("{}不存在: {}", kind.display_name(), code))
+    })?;
+
+    let display_name = match update.display_name {
+        Some(name) => {
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return Err(AppError::InvalidParameter("显示名称不能为空".to_string()));
+            }
+            name
+        }
+        None => current.display_name,
+    };
+
+    let now = format_datetime(&now_china());
+    conn.execute(
+        r#"
+        UPDATE card_handling_methods
+        SET display_name = ?1, requires_address = ?2, requires_proxy_callsign = ?3,
+            requires_remarks = ?4, is_active = ?5, sort_order = ?6, updated_at = ?7
+        WHERE kind = ?8 AND code = ?9
+        "#,
+        rusqlite::params![
+            display_name,
+            update.requires_address.unwrap_or(current.requires_address),
+            update
+                .requires_proxy_callsign
+                .unwrap_or(current.requires_proxy_callsign),
+            update.requires_remarks.unwrap_or(current.requires_remarks),
+            update.is_active.unwrap_or(current.is_active),
+            update.sort_order.unwrap_or(current.sort_order),
+            now,
+            kind.as_str(),
+            code,
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("更新{}失败: {}", kind.display_name(), e)))?;
+
+    log::info!("✅ 更新{}成功: {}", kind.display_name(), code);
+    get_handling_method_conn(&conn, kind, code)?
+        .ok_or_else(|| AppError::Other("更新后无法获取登记项".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::cards::{create_card_in, distribute_card_in, return_card_in};
+    use crate::db::projects::create_project_in;
+
+    /// 内置登记项：邮寄须填地址、代领须填代领人，停用后不可用于新的分发
+    #[test]
+    fn test_distribute_validates_against_registry() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let card = create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 3, None).unwrap();
+
+        let methods =
+            list_handling_methods_in(&db, Some(HandlingMethodKind::Distribution), false).unwrap();
+        assert_eq!(methods.first().map(|m| m.code.as_str()), Some("快递"));
+        assert!(methods.iter().all(|m| m.kind == HandlingMethodKind::Distribution));
+
+        let distribute = |method: &str, address: Option<&str>, proxy: Option<&str>| {
+            distribute_card_in(
+                &db,
+                &card.id,
+                method.to_string(),
+                address.map(str::to_string),
+                None,
+                proxy.map(str::to_string),
+                Some(1),
+            )
+        };
+        assert!(distribute("直接分发", None, None).is_err());
+        assert!(distribute("快递", None, None).is_err());
+        assert!(distribute("快递", Some("  "), None).is_err());
+        assert!(distribute("代领", None, None).is_err());
+        distribute("快递", Some("北京市海淀区"), None).unwrap();
+        distribute("代领", None, Some("BG2XYZ")).unwrap();
+
+        let update = HandlingMethodUpdate {
+            is_active: Some(false),
+            ..Default::default()
+        };
+        let disabled =
+            update_handling_method_in(&db, HandlingMethodKind::Distribution, "自取", update).unwrap();
+        assert!(!disabled.is_active);
+        assert!(distribute("自取", None, None).is_err());
+        assert!(
+            !list_handling_methods_in(&db, Some(HandlingMethodKind::Distribution), false)
+                .unwrap()
+                .iter()
+                .any(|m| m.code == "自取")
+        );
+    }
+
+    /// 新登记的退卡原因可要求备注；同类别取值不可重复
+    #[test]
+    fn test_custom_return_reason_requires_remarks() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let card = create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, None).unwrap();
+
+        let reason = create_handling_method_in(
+            &db,
+            HandlingMethodKind::Return,
+            " MOVED ".to_string(),
+            Some("已搬迁".to_string()),
+        )
+        .unwrap();
+        assert_eq!(reason.code, "MOVED");
+        assert_eq!(reason.sort_order, 5);
+        assert!(
+            create_handling_method_in(&db, HandlingMethodKind::Return, "MOVED".to_string(), None)
+                .is_err()
+        );
+
+        let update = HandlingMethodUpdate {
+            requires_remarks: Some(true),
+            ..Default::default()
+        };
+        update_handling_method_in(&db, HandlingMethodKind::Return, "MOVED", update).unwrap();
+
+        assert!(return_card_in(&db, &card.id, "MOVED".to_string(), None).is_err());
+        let returned =
+            return_card_in(&db, &card.id, "MOVED".to_string(), Some("新地址未知".to_string()))
+                .unwrap();
+        assert_eq!(returned.metadata.unwrap().return_info.unwrap().method, "MOVED");
+    }
+}
@@ -2,17 +2,23 @@
 //
 // 从 JSON 格式文件导入数据到本地数据库
 
+use crate::db::backups::{create_backup_conn, BackupReason};
+use crate::db::card_events::{events_from_card, insert_card_event_conn};
+use crate::db::card_metadata::save_card_metadata_conn;
+use crate::db::cards::insert_card_history_conn;
+use crate::db::encrypted_backup::{decode_backup_content, is_encrypted_backup, read_backup_file};
 use crate::db::export::{ExportData, ExportStats, ExportTables, EXPORT_FORMAT_VERSION};
-use crate::db::models::{Card, Project};
-use crate::db::sqlite::{format_version, get_connection, get_db_version};
+use crate::db::models::{Card, CardStatus, Project};
+use crate::db::serial_schemes::save_serial_scheme_conn;
+use crate::db::shipments::{link_order_cards_conn, link_pending_waybill_conn};
+use crate::db::sqlite::{database, format_version, get_db_version, Database};
 use crate::error::AppError;
 use crate::sf_express::{RecipientInfo, SFOrder, SenderInfo};
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::path::Path;
 
 /// 支持的导出格式版本
-const SUPPORTED_VERSIONS: &[&str] = &["1.0", "1.1"];
+const SUPPORTED_VERSIONS: &[&str] = &["1.0", "1.1", "1.2", "1.3", "1.4", "1.5", "1.6", "1.7"];
 
 // ==================== v1.0 兼容类型 ====================
 
@@ -71,6 +77,7 @@ impl ExportDataV1_0 {
                 id: order.id,
                 order_id: order.order_id,
                 waybill_no: order.waybill_no,
+                card_ids: order.card_id.iter().cloned().collect(),
                 card_id: order.card_id,
                 status: order.status,
                 pay_method: order.pay_method,
@@ -95,13 +102,16 @@ impl ExportDataV1_0 {
                 sf_senders: self.tables.sf_senders,
                 sf_orders,
                 app_settings: None,
+                card_events: None,
+                card_history: None,
+                serial_schemes: None,
             },
         })
     }
 }
 
 /// 解析导出数据，支持多版本
-fn parse_export_data(content: &str) -> Result<ExportData, AppError> {
+pub(crate) fn parse_export_data(content: &str) -> Result<ExportData, AppError> {
     // 先解析版本号
     let version_check: serde_json::Value = serde_json::from_str(content)
         .map_err(|e| AppError::Other(format!("文件格式错误: {}", e)))?;
@@ -127,7 +137,7 @@ fn parse_export_data(content: &str) -> Result<ExportData, AppError> {
                 .map_err(|e| AppError::Other(format!("解析 v1.0 格式失败: {}", e)))?;
             data_v1.into_current()
         }
-        "1.1" | _ => {
+        "1.1" | "1.2" | "1.3" | "1.4" | "1.5" | "1.6" | "1.7" | _ => {
             serde_json::from_str(content)
                 .map_err(|e| AppError::Other(format!("解析文件失败: {}", e)))
         }
@@ -157,24 +167,38 @@ pub struct ImportPreview {
     pub local_db_version: i32,
     /// 本地可读版本号
     pub local_db_version_display: String,
+    /// 是否为加密备份
+    pub encrypted: bool,
 }
 
 /// 预览导入文件
 ///
-/// 解析导入文件并检查版本兼容性，返回预览信息
-pub fn preview_import<P: AsRef<Path>>(file_path: P) -> Result<ImportPreview, AppError> {
-    let file_path = file_path.as_ref();
+/// 解析导入文件并检查版本兼容性，返回预览信息。
+/// 加密备份须提供口令，口令错误或文件被改动时返回错误。
+pub fn preview_import<P: AsRef<Path>>(
+    file_path: P,
+    passphrase: Option<&str>,
+) -> Result<ImportPreview, AppError> {
+    preview_import_in(database()?, file_path, passphrase)
+}
 
-    // 读取文件内容
-    let content = fs::read_to_string(file_path).map_err(|e| {
-        AppError::Other(format!("无法读取文件: {}", e))
-    })?;
+/// 同 [`preview_import`]，在指定的数据库上下文中执行
+pub fn preview_import_in<P: AsRef<Path>>(
+    db: &Database,
+    file_path: P,
+    passphrase: Option<&str>,
+) -> Result<ImportPreview, AppError> {
+    // 读取文件内容（加密备份以口令解密并校验完整性）
+    let content = std::fs::read_to_string(file_path.as_ref())
+        .map_err(|e| AppError::Other(format!("无法读取文件: {}", e)))?;
+    let encrypted = is_encrypted_backup(&content);
+    let content = decode_backup_content(content, passphrase)?;
 
     // 解析 JSON（支持多版本）
     let data = parse_export_data(&content)?;
 
     // 获取本地数据库版本
-    let conn = get_connection()?;
+    let conn = db.connection()?;
     let local_db_version = get_db_version(&conn)?;
     let local_db_version_display = format_version(local_db_version);
 
@@ -210,9 +234,25 @@ pub fn preview_import<P: AsRef<Path>>(file_path: P) -> Result<ImportPreview, App
         error_message,
         local_db_version,
         local_db_version_display,
+        encrypted,
     })
 }
 
+/// 检查导入文件的数据库版本不高于本地版本
+pub(crate) fn check_import_db_version(
+    conn: &rusqlite::Connection,
+    data: &ExportData,
+) -> Result<(), AppError> {
+    let local_db_version = get_db_version(conn)?;
+    if data.db_version > local_db_version {
+        return Err(AppError::Other(format!(
+            "导入文件的数据库版本（{}）高于本地版本（{}），请升级应用后再导入",
+            data.db_version_display, format_version(local_db_version)
+        )));
+    }
+    Ok(())
+}
+
 /// `app_settings` 表的清空策略
 ///
 /// 文件导入与「从云端恢复」对 `app_settings` 的清空语义不同。
@@ -243,10 +283,20 @@ pub fn import_from_export_data(
     })?;
 
     // 清空现有数据（按外键依赖顺序）
+    tx.execute("DELETE FROM sf_order_cards", [])
+        .map_err(|e| AppError::Other(format!("清空寄件关联表失败: {}", e)))?;
     tx.execute("DELETE FROM sf_orders", [])
         .map_err(|e| AppError::Other(format!("清空订单表失败: {}", e)))?;
     tx.execute("DELETE FROM sf_senders", [])
         .map_err(|e| AppError::Other(format!("清空寄件人表失败: {}", e)))?;
+    tx.execute("DELETE FROM card_events", [])
+        .map_err(|e| AppError::Other(format!("清空卡片事件表失败: {}", e)))?;
+    tx.execute("DELETE FROM card_history", [])
+        .map_err(|e| AppError::Other(format!("清空修改历史表失败: {}", e)))?;
+    for table in ["card_distributions", "card_returns", "card_addresses"] {
+        tx.execute(&format!("DELETE FROM {}", table), [])
+            .map_err(|e| AppError::Other(format!("清空卡片元数据表失败 ({}): {}", table, e)))?;
+    }
     tx.execute("DELETE FROM cards", [])
         .map_err(|e| AppError::Other(format!("清空卡片表失败: {}", e)))?;
     tx.execute("DELETE FROM projects", [])
@@ -269,33 +319,83 @@ pub fn import_from_export_data(
     }
     log::info!("📦 导入 {} 个项目", data.tables.projects.len());
 
-    // 导入卡片
+    // 导入项目编号方案（旧格式不含编号方案时保留本地同 ID 项目的方案）
+    if let Some(ref schemes) = data.tables.serial_schemes {
+        tx.execute("DELETE FROM serial_schemes", [])
+            .map_err(|e| AppError::Other(format!("清空编号方案表失败: {}", e)))?;
+        for scheme in schemes {
+            save_serial_scheme_conn(&tx, scheme).map_err(|e| {
+                AppError::Other(format!("导入编号方案失败 ({}): {}", scheme.project_id, e))
+            })?;
+        }
+        log::info!("📦 导入 {} 个编号方案", schemes.len());
+    }
+
+    // 导入卡片（元数据拆分写入 card_distributions/card_returns/card_addresses）
     for card in &data.tables.cards {
-        let metadata_json = card
-            .metadata
-            .as_ref()
-            .map(|m| serde_json::to_string(m).unwrap_or_default());
+        // 旧格式不含已分发数量，已分发的卡片视为全部分发
+        let distributed_qty = match card.status {
+            CardStatus::Distributed if card.distributed_qty == 0 => card.qty,
+            _ => card.distributed_qty,
+        };
 
         tx.execute(
-            "INSERT INTO cards (id, project_id, creator_id, callsign, qty, serial, status, metadata, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO cards (id, project_id, creator_id, callsign, via_callsign, qty, serial, status, distributed_qty, created_at, updated_at, storage_location_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, (SELECT id FROM storage_locations WHERE id = ?12))",
             rusqlite::params![
                 &card.id,
                 &card.project_id,
                 &card.creator_id,
                 &card.callsign,
+                &card.via_callsign,
                 card.qty,
                 card.serial,
                 card.status.as_str(),
-                metadata_json,
+                distributed_qty,
                 &card.created_at,
                 &card.updated_at,
+                &card.storage_location_id,
             ],
         )
         .map_err(|e| AppError::Other(format!("导入卡片失败 ({}): {}", card.id, e)))?;
+
+        save_card_metadata_conn(&tx, &card.id, card.metadata.as_ref())
+            .map_err(|e| AppError::Other(format!("导入卡片元数据失败 ({}): {}", card.id, e)))?;
     }
     log::info!("📦 导入 {} 张卡片", data.tables.cards.len());
 
+    // 导入卡片事件日志（旧格式不含事件日志时按卡片元数据推导）
+    let event_count = match data.tables.card_events {
+        Some(ref events) => {
+            for event in events {
+                insert_card_event_conn(&tx, event).map_err(|e| {
+                    AppError::Other(format!("导入卡片事件失败 ({}): {}", event.id, e))
+                })?;
+            }
+            events.len()
+        }
+        None => {
+            let mut count = 0;
+            for card in &data.tables.cards {
+                for event in events_from_card(card) {
+                    insert_card_event_conn(&tx, &event)?;
+                    count += 1;
+                }
+            }
+            count
+        }
+    };
+    log::info!("📦 导入 {} 条卡片事件", event_count);
+
+    // 导入卡片修改历史（旧格式不含修改历史）
+    let history = data.tables.card_history.as_deref().unwrap_or_default();
+    for entry in history {
+        insert_card_history_conn(&tx, entry).map_err(|e| {
+            AppError::Other(format!("导入修改历史失败 ({}): {}", entry.card_id, e))
+        })?;
+    }
+    log::info!("📦 导入 {} 条修改历史", history.len());
+
     // 导入寄件人
     for sender in &data.tables.sf_senders {
         tx.execute(
@@ -345,9 +445,35 @@ pub fn import_from_export_data(
             ],
         )
         .map_err(|e| AppError::Other(format!("导入订单失败 ({}): {}", order.id, e)))?;
+
+        // 合并寄件关联（旧格式不含 card_ids 时取 card_id）
+        let card_ids = if order.card_ids.is_empty() {
+            order.card_id.iter().cloned().collect()
+        } else {
+            order.card_ids.clone()
+        };
+        link_order_cards_conn(&tx, &order.id, &card_ids, &order.created_at)
+            .map_err(|e| AppError::Other(format!("导入订单关联失败 ({}): {}", order.id, e)))?;
     }
     log::info!("📦 导入 {} 个订单", data.tables.sf_orders.len());
 
+    // 旧格式（1.3 之前）卡片的待处理运单号：与迁移回填一致，关联到同运单号订单或占位订单
+    let mut pending_count = 0;
+    for card in &data.tables.cards {
+        let pending = card
+            .metadata
+            .as_ref()
+            .and_then(|m| m.pending_waybill_no.as_deref())
+            .filter(|w| !w.is_empty());
+        if let Some(waybill_no) = pending {
+            link_pending_waybill_conn(&tx, &card.id, waybill_no, &card.updated_at)?;
+            pending_count += 1;
+        }
+    }
+    if pending_count > 0 {
+        log::info!("📦 关联 {} 个旧格式待处理运单号", pending_count);
+    }
+
     // 导入全局配置
     match app_settings_mode {
         AppSettingsClearMode::Conditional => {
@@ -395,31 +521,37 @@ pub fn import_from_export_data(
 
 /// 执行导入
 ///
-/// 清空现有数据并导入新数据（事务保证原子性）
-pub fn execute_import<P: AsRef<Path>>(file_path: P) -> Result<ExportStats, AppError> {
+/// 清空现有数据并导入新数据（事务保证原子性）。
+/// 加密备份在解密并通过完整性校验后才会清空本地数据。
+pub fn execute_import<P: AsRef<Path>>(
+    file_path: P,
+    passphrase: Option<&str>,
+) -> Result<ExportStats, AppError> {
+    execute_import_in(database()?, file_path, passphrase)
+}
+
+/// 同 [`execute_import`]，在指定的数据库上下文中执行
+pub fn execute_import_in<P: AsRef<Path>>(
+    db: &Database,
+    file_path: P,
+    passphrase: Option<&str>,
+) -> Result<ExportStats, AppError> {
     let file_path = file_path.as_ref();
 
-    // 读取文件内容
-    let content = fs::read_to_string(file_path).map_err(|e| {
-        AppError::Other(format!("无法读取文件: {}", e))
-    })?;
+    // 读取文件内容（加密备份以口令解密并校验完整性）
+    let content = read_backup_file(file_path, passphrase)?;
 
     // 解析 JSON（支持多版本）
     let data = parse_export_data(&content)?;
 
     // 验证版本
-    let conn = get_connection()?;
-    let local_db_version = get_db_version(&conn)?;
+    let mut conn = db.connection()?;
+    check_import_db_version(&conn, &data)?;
 
-    if data.db_version > local_db_version {
-        return Err(AppError::Other(format!(
-            "导入文件的数据库版本（{}）高于本地版本（{}），请升级应用后再导入",
-            data.db_version_display, format_version(local_db_version)
-        )));
-    }
+    // 覆盖本地数据前先备份
+    create_backup_conn(db, &conn, BackupReason::PreImport)?;
 
     // 复用共用导入内核（文件导入侧 app_settings 沿用条件清空语义）
-    let mut conn = get_connection()?;
     import_from_export_data(&mut conn, &data, AppSettingsClearMode::Conditional)?;
 
     // 恢复 client_id 到同步配置
@@ -463,12 +595,12 @@ pub fn execute_import<P: AsRef<Path>>(file_path: P) -> Result<ExportStats, AppEr
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::models::{AppSetting, CardStatus};
+    use crate::db::models::AppSetting;
     use rusqlite::Connection;
 
     #[test]
     fn test_import_preview_file_not_found() {
-        let result = preview_import("/nonexistent/file.qslhub");
+        let result = preview_import("/nonexistent/file.qslhub", None);
         assert!(result.is_err());
     }
 
@@ -488,13 +620,64 @@ mod tests {
                 project_id TEXT NOT NULL,
                 creator_id TEXT,
                 callsign TEXT NOT NULL,
+                via_callsign TEXT,
                 qty INTEGER NOT NULL,
                 serial INTEGER,
                 status TEXT NOT NULL DEFAULT 'pending',
-                metadata TEXT,
+                distributed_qty INTEGER NOT NULL DEFAULT 0,
+                storage_location_id TEXT,
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL
             );
+            CREATE TABLE storage_locations (
+                id TEXT PRIMARY KEY
+            );
+            CREATE TABLE card_distributions (
+                card_id TEXT PRIMARY KEY,
+                method TEXT NOT NULL,
+                address TEXT,
+                remarks TEXT,
+                proxy_callsign TEXT,
+                qty INTEGER,
+                distributed_at TEXT NOT NULL,
+                waybill_no TEXT
+            );
+            CREATE TABLE card_returns (
+                card_id TEXT PRIMARY KEY,
+                method TEXT NOT NULL,
+                remarks TEXT,
+                returned_at TEXT NOT NULL
+            );
+            CREATE TABLE card_addresses (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                card_id TEXT NOT NULL,
+                source TEXT NOT NULL,
+                chinese_address TEXT,
+                english_address TEXT,
+                name TEXT,
+                mail_method TEXT,
+                updated_at TEXT,
+                cached_at TEXT NOT NULL,
+                UNIQUE (card_id, source)
+            );
+            CREATE TABLE card_events (
+                id TEXT PRIMARY KEY,
+                card_id TEXT NOT NULL,
+                event_type TEXT NOT NULL,
+                payload TEXT,
+                created_at TEXT NOT NULL,
+                operator_id TEXT
+            );
+            CREATE TABLE card_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                card_id TEXT NOT NULL,
+                field TEXT NOT NULL,
+                old_value TEXT,
+                new_value TEXT,
+                changed_by TEXT,
+                changed_at TEXT NOT NULL,
+                operator_id TEXT
+            );
             CREATE TABLE sf_senders (
                 id TEXT PRIMARY KEY,
                 name TEXT NOT NULL,
@@ -521,6 +704,12 @@ mod tests {
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL
             );
+            CREATE TABLE sf_order_cards (
+                order_id TEXT NOT NULL,
+                card_id TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                PRIMARY KEY (order_id, card_id)
+            );
             CREATE TABLE app_settings (
                 key TEXT PRIMARY KEY,
                 value TEXT NOT NULL
@@ -536,7 +725,8 @@ mod tests {
         let mut parts = Vec::new();
         for (table, sql) in [
             ("projects", "SELECT group_concat(id||'|'||name||'|'||created_at||'|'||updated_at, ';') FROM (SELECT * FROM projects ORDER BY id)"),
-            ("cards", "SELECT group_concat(id||'|'||callsign||'|'||qty||'|'||IFNULL(serial,'')||'|'||status||'|'||IFNULL(metadata,''), ';') FROM (SELECT * FROM cards ORDER BY id)"),
+            ("cards", "SELECT group_concat(id||'|'||callsign||'|'||qty||'|'||IFNULL(serial,'')||'|'||status, ';') FROM (SELECT * FROM cards ORDER BY id)"),
+            ("card_returns", "SELECT group_concat(card_id||'|'||method||'|'||returned_at, ';') FROM (SELECT * FROM card_returns ORDER BY card_id)"),
             ("sf_senders", "SELECT group_concat(id||'|'||name||'|'||is_default||'|'||address, ';') FROM (SELECT * FROM sf_senders ORDER BY id)"),
             ("sf_orders", "SELECT group_concat(id||'|'||order_id||'|'||sender_info||'|'||recipient_info, ';') FROM (SELECT * FROM sf_orders ORDER BY id)"),
             ("app_settings", "SELECT group_concat(key||'|'||value, ';') FROM (SELECT * FROM app_settings ORDER BY key)"),
@@ -567,9 +757,12 @@ mod tests {
                     project_id: "p1".to_string(),
                     creator_id: None,
                     callsign: "BH2RO".to_string(),
+                    via_callsign: None,
                     qty: 1,
                     serial: Some(1),
                     status: CardStatus::Pending,
+                    distributed_qty: 0,
+                    storage_location_id: None,
                     metadata: None,
                     created_at: "2026-01-01T00:00:00+08:00".to_string(),
                     updated_at: "2026-01-01T00:00:00+08:00".to_string(),
@@ -577,6 +770,9 @@ mod tests {
                 sf_senders: vec![],
                 sf_orders: vec![],
                 app_settings,
+                card_events: None,
+                card_history: None,
+                serial_schemes: None,
             },
         }
     }
@@ -670,9 +866,12 @@ mod tests {
             project_id: "p1".to_string(),
             creator_id: None,
             callsign: "BG2ABC".to_string(),
+            via_callsign: None,
             qty: 5,
             serial: Some(42),
             status: CardStatus::Distributed,
+            distributed_qty: 0,
+            storage_location_id: None,
             metadata: None,
             created_at: "2026-01-02T00:00:00+08:00".to_string(),
             updated_at: "2026-01-02T00:00:00+08:00".to_string(),
@@ -680,15 +879,175 @@ mod tests {
 
         import_from_export_data(&mut conn, &data, AppSettingsClearMode::Unconditional).unwrap();
 
-        let (callsign, qty, serial): (String, i64, i64) = conn
+        let (callsign, qty, serial, distributed_qty): (String, i64, i64, i64) = conn
             .query_row(
-                "SELECT callsign, qty, serial FROM cards WHERE id = 'c2'",
+                "SELECT callsign, qty, serial, distributed_qty FROM cards WHERE id = 'c2'",
                 [],
-                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
             )
             .unwrap();
         assert_eq!(callsign, "BG2ABC");
         assert_eq!(qty, 5);
         assert_eq!(serial, 42);
+        // 旧格式不含已分发数量，已分发的卡片按全部分发导入
+        assert_eq!(distributed_qty, 5);
+    }
+
+    /// 旧格式（无事件日志）导入时按卡片元数据推导事件；带事件日志时原样导入
+    #[test]
+    fn test_import_card_events_legacy_and_current() {
+        use crate::db::models::{CardEvent, CardEventType, CardMetadata, ReturnInfo};
+
+        let mut conn = setup_test_db();
+        let mut data = make_export_data(None);
+        data.tables.cards[0].status = CardStatus::Returned;
+        data.tables.cards[0].metadata = Some(CardMetadata {
+            return_info: Some(ReturnInfo {
+                method: "REFUSED".to_string(),
+                remarks: None,
+                returned_at: "2026-01-02T00:00:00+08:00".to_string(),
+            }),
+            ..Default::default()
+        });
+
+        import_from_export_data(&mut conn, &data, AppSettingsClearMode::Conditional).unwrap();
+        let types: Vec<String> = conn
+            .prepare("SELECT event_type FROM card_events ORDER BY created_at, rowid")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(types, vec!["entered", "returned"]);
+
+        let mut event = CardEvent::new("c1", CardEventType::Entered);
+        event.id = "e1".to_string();
+        data.tables.card_events = Some(vec![event]);
+        import_from_export_data(&mut conn, &data, AppSettingsClearMode::Conditional).unwrap();
+        let ids: Vec<String> = conn
+            .prepare("SELECT id FROM card_events")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(ids, vec!["e1"]);
+    }
+
+    /// 修改历史随导出数据往返；旧格式不含修改历史时清空本地历史
+    #[test]
+    fn test_import_card_history() {
+        use crate::db::models::CardHistoryEntry;
+
+        let mut conn = setup_test_db();
+        let mut data = make_export_data(None);
+        data.tables.card_history = Some(vec![CardHistoryEntry {
+            id: 42,
+            card_id: "c1".to_string(),
+            field: "qty".to_string(),
+            old_value: Some("1".to_string()),
+            new_value: Some("2".to_string()),
+            changed_by: Some("张三".to_string()),
+            operator_id: None,
+            changed_at: "2026-01-02T00:00:00+08:00".to_string(),
+        }]);
+
+        let history = |conn: &Connection| -> Vec<(String, Option<String>, Option<String>)> {
+            conn.prepare("SELECT field, new_value, changed_by FROM card_history")
+                .unwrap()
+                .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+
+        import_from_export_data(&mut conn, &data, AppSettingsClearMode::Conditional).unwrap();
+        assert_eq!(
+            history(&conn),
+            vec![(
+                "qty".to_string(),
+                Some("2".to_string()),
+                Some("张三".to_string())
+            )]
+        );
+
+        data.tables.card_history = None;
+        import_from_export_data(&mut conn, &data, AppSettingsClearMode::Conditional).unwrap();
+        assert!(history(&conn).is_empty());
+    }
+
+    /// v1.0/v1.1 卡片内嵌的元数据 JSON 拆分写入关系表；旧订单的 card_id 转为寄件关联，
+    /// 无对应订单的待处理运单号关联到占位订单
+    #[test]
+    fn test_import_legacy_metadata_into_tables() {
+        let mut conn = setup_test_db();
+        let mut data = make_export_data(None);
+        data.tables.cards[0].status = CardStatus::Distributed;
+        data.tables.cards[0].metadata = Some(
+            serde_json::from_str(
+                r#"{
+                    "distribution": {"method": "代领", "proxy_callsign": "BG2XYZ", "distributed_at": "2026-01-02T00:00:00+08:00"},
+                    "address_history": [{"source": "qrz.cn", "chinese_address": "北京", "cached_at": "2026-01-01T00:00:00+08:00"}],
+                    "pending_waybill_no": "SF100"
+                }"#,
+            )
+            .unwrap(),
+        );
+        data.tables.sf_orders = vec![SFOrder {
+            id: "o1".to_string(),
+            order_id: "QSL1".to_string(),
+            waybill_no: Some("SF100".to_string()),
+            card_id: Some("c1".to_string()),
+            card_ids: vec![],
+            status: "pending".to_string(),
+            pay_method: None,
+            cargo_name: None,
+            sender_info: SenderInfo::default(),
+            recipient_info: RecipientInfo::default(),
+            created_at: "2026-01-02T00:00:00+08:00".to_string(),
+            updated_at: "2026-01-02T00:00:00+08:00".to_string(),
+        }];
+        let mut orphan = data.tables.cards[0].clone();
+        orphan.id = "c2".to_string();
+        orphan.metadata = Some(serde_json::from_str(r#"{"pending_waybill_no": "SF200"}"#).unwrap());
+        data.tables.cards.push(orphan);
+
+        import_from_export_data(&mut conn, &data, AppSettingsClearMode::Conditional).unwrap();
+
+        let (method, proxy): (String, Option<String>) = conn
+            .query_row(
+                "SELECT method, proxy_callsign FROM card_distributions WHERE card_id = 'c1'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(method, "代领");
+        assert_eq!(proxy.as_deref(), Some("BG2XYZ"));
+
+        let address: String = conn
+            .query_row(
+                "SELECT chinese_address FROM card_addresses WHERE card_id = 'c1' AND source = 'qrz.cn'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(address, "北京");
+
+        let linked: String = conn
+            .query_row("SELECT card_id FROM sf_order_cards WHERE order_id = 'o1'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(linked, "c1");
+
+        let (order_id, waybill_no, status): (String, String, String) = conn
+            .query_row(
+                "SELECT o.id, o.waybill_no, o.status FROM sf_order_cards oc
+                 JOIN sf_orders o ON o.id = oc.order_id WHERE oc.card_id = 'c2'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(order_id, "legacy-c2");
+        assert_eq!(waybill_no, "SF200");
+        assert_eq!(status, "confirmed");
     }
 }
@@ -0,0 +1,972 @@
+// 数据合并导入模块
+//
+// 将导出文件按 UUID 合并到本地数据库（不清空现有数据），用于合并多个电台的数据：
+// 本地没有的记录新增，两侧内容不同的记录按冲突策略决定保留本地或采用导入文件
+
+use crate::db::backups::{create_backup_conn, BackupReason};
+use crate::db::card_events::{events_from_card, insert_card_event_conn};
+use crate::db::card_metadata::save_card_metadata_conn;
+use crate::db::cards::{delete_card_conn, insert_card_history_conn};
+use crate::db::encrypted_backup::read_backup_file;
+use crate::db::export::{export_cards, export_orders, export_projects, export_senders, ExportData};
+use crate::db::import::{check_import_db_version, parse_export_data};
+use crate::db::models::{
+    format_datetime, now_china, Card, CardEvent, CardHistoryEntry, CardStatus, Project,
+};
+use crate::db::serial_schemes::save_serial_scheme_conn;
+use crate::db::shipments::link_order_cards_conn;
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use crate::sf_express::{SFOrder, SenderInfo};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// 合并导入的冲突策略（同一 UUID 的记录两侧内容不同时）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// 保留本地
+    KeepLocal,
+    /// 采用导入文件
+    TakeIncoming,
+    /// 更新时间较新的一方为准
+    Newest,
+    /// 逐条选择（未选择的保留本地）
+    Manual,
+}
+
+/// 合并导入选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct MergeOptions {
+    /// 冲突策略
+    pub strategy: MergeStrategy,
+    /// 逐条选择时采用导入文件的记录 ID（其余冲突保留本地）
+    #[serde(default)]
+    pub take_incoming: Vec<String>,
+}
+
+/// 单张表的合并统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct MergeTableStats {
+    /// 新增（本地没有的记录）
+    pub inserted: u32,
+    /// 以导入文件覆盖的记录（含因重复被导入卡片替换、移入回收站的本地卡片）
+    pub updated: u32,
+    /// 两侧内容不同或呼号 / 序列号重复的记录（含被覆盖与保留本地的）
+    pub conflicting: u32,
+    /// 未改动的本地记录（仅本地有、两侧相同或冲突时保留本地）
+    pub untouched: u32,
+}
+
+/// 合并冲突类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum MergeConflictKind {
+    /// 同一 UUID 的记录两侧内容不同
+    Modified,
+    /// 新增卡片与本地同项目卡片的呼号重复（大小写不敏感）
+    DuplicateCallsign,
+    /// 新增卡片与本地同项目卡片的序列号重复
+    DuplicateSerial,
+}
+
+/// 合并冲突（同一 UUID 的记录两侧内容不同，或新增卡片与本地卡片重复）
+///
+/// 重复冲突采用导入文件时，导入的卡片新增、与之重复的本地卡片移入回收站；
+/// 保留本地时不导入该卡片。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct MergeConflict {
+    /// 表名：projects | cards | sf_senders | sf_orders
+    pub table: String,
+    /// 冲突类型
+    pub kind: MergeConflictKind,
+    /// 导入记录 ID（逐条选择时以此标识）
+    pub id: String,
+    /// 本地记录 ID（重复冲突时为与之重复的本地卡片）
+    pub local_id: String,
+    /// 记录摘要（项目名称、呼号、寄件人姓名或订单号）
+    pub label: String,
+    /// 本地更新时间
+    pub local_updated_at: String,
+    /// 导入文件中的更新时间
+    pub incoming_updated_at: String,
+    /// 按当前策略是否采用导入文件
+    pub take_incoming: bool,
+}
+
+/// 合并导入结果（预览时为按当前选项合并的结果，不写入）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct MergeReport {
+    /// 是否为预览（未写入）
+    pub dry_run: bool,
+    /// 项目
+    pub projects: MergeTableStats,
+    /// 卡片
+    pub cards: MergeTableStats,
+    /// 寄件人
+    pub sf_senders: MergeTableStats,
+    /// 订单
+    pub sf_orders: MergeTableStats,
+    /// 冲突明细
+    pub conflicts: Vec<MergeConflict>,
+    /// 从回收站恢复的同名项目（导入项目的卡片并入其中）
+    pub restored_projects: Vec<String>,
+}
+
+/// 参与合并的记录
+trait MergeRecord: Serialize {
+    /// 表名
+    const TABLE: &'static str;
+    fn id(&self) -> &str;
+    fn updated_at(&self) -> &str;
+    /// 冲突明细中展示的摘要
+    fn label(&self) -> String;
+}
+
+impl MergeRecord for Project {
+    const TABLE: &'static str = "projects";
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl MergeRecord for Card {
+    const TABLE: &'static str = "cards";
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+    fn label(&self) -> String {
+        match self.serial {
+            Some(serial) => format!("{} #{}", self.callsign, serial),
+            None => self.callsign.clone(),
+        }
+    }
+}
+
+impl MergeRecord for SenderInfo {
+    const TABLE: &'static str = "sf_senders";
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+    fn label(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl MergeRecord for SFOrder {
+    const TABLE: &'static str = "sf_orders";
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+    fn label(&self) -> String {
+        self.waybill_no
+            .clone()
+            .unwrap_or_else(|| self.order_id.clone())
+    }
+}
+
+/// 对单条导入记录的处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MergeAction {
+    Insert,
+    Update,
+    Skip,
+}
+
+/// `incoming` 是否比 `local` 新（按 RFC 3339 解析，无法解析时按字符串比较）
+fn is_newer(incoming: &str, local: &str) -> bool {
+    match (
+        chrono::DateTime::parse_from_rfc3339(incoming),
+        chrono::DateTime::parse_from_rfc3339(local),
+    ) {
+        (Ok(incoming), Ok(local)) => incoming > local,
+        _ => incoming > local,
+    }
+}
+
+/// 合并过程的状态：冲突决策与统计
+struct Merger<'a> {
+    options: &'a MergeOptions,
+    manual: HashSet<&'a str>,
+    report: MergeReport,
+    /// 各表被导入文件覆盖或替换的本地记录 ID（同一本地记录只计一次）
+    touched: HashMap<&'static str, HashSet<String>>,
+}
+
+impl<'a> Merger<'a> {
+    fn new(options: &'a MergeOptions, dry_run: bool) -> Self {
+        Self {
+            options,
+            manual: options.take_incoming.iter().map(String::as_str).collect(),
+            report: MergeReport {
+                dry_run,
+                projects: MergeTableStats::default(),
+                cards: MergeTableStats::default(),
+                sf_senders: MergeTableStats::default(),
+                sf_orders: MergeTableStats::default(),
+                conflicts: Vec::new(),
+                restored_projects: Vec::new(),
+            },
+            touched: HashMap::new(),
+        }
+    }
+
+    /// 记录被改动的本地记录
+    fn touch(&mut self, table: &'static str, local_id: &str) {
+        self.touched
+            .entry(table)
+            .or_default()
+            .insert(local_id.to_string());
+    }
+
+    /// 未改动的本地记录数
+    fn untouched(&self, table: &str, local_count: usize) -> u32 {
+        let touched = self.touched.get(table).map_or(0, HashSet::len);
+        local_count.saturating_sub(touched) as u32
+    }
+
+    fn stats_mut(&mut self, table: &str) -> &mut MergeTableStats {
+        match table {
+            "projects" => &mut self.report.projects,
+            "cards" => &mut self.report.cards,
+            "sf_senders" => &mut self.report.sf_senders,
+            _ => &mut self.report.sf_orders,
+        }
+    }
+
+    /// 决定导入记录的处理方式并计入统计
+    ///
+    /// `local` 为本地未删除的同 ID 记录；`exists` 表示本地存在同 ID 记录（含回收站中的），
+    /// 本地已删除的记录保持删除状态，不以导入文件恢复。
+    fn decide<T: MergeRecord>(
+        &mut self,
+        local: Option<&T>,
+        exists: bool,
+        incoming: &T,
+    ) -> MergeAction {
+        let Some(local) = local else {
+            if exists {
+                return MergeAction::Skip;
+            }
+            self.stats_mut(T::TABLE).inserted += 1;
+            return MergeAction::Insert;
+        };
+        if serde_json::to_value(local).ok() == serde_json::to_value(incoming).ok() {
+            return MergeAction::Skip;
+        }
+
+        if self.resolve(local, incoming, MergeConflictKind::Modified) {
+            self.stats_mut(T::TABLE).updated += 1;
+            self.touch(T::TABLE, local.id());
+            MergeAction::Update
+        } else {
+            MergeAction::Skip
+        }
+    }
+
+    /// 新增卡片与本地卡片 `local` 重复时的处理：采用导入文件时新增并替换本地卡片，否则跳过
+    fn decide_duplicate(
+        &mut self,
+        local: &Card,
+        incoming: &Card,
+        kind: MergeConflictKind,
+    ) -> MergeAction {
+        if self.resolve(local, incoming, kind) {
+            self.report.cards.updated += 1;
+            self.touch(Card::TABLE, &local.id);
+            MergeAction::Insert
+        } else {
+            MergeAction::Skip
+        }
+    }
+
+    /// 按冲突策略决定是否采用导入文件，记入冲突明细
+    fn resolve<T: MergeRecord>(
+        &mut self,
+        local: &T,
+        incoming: &T,
+        kind: MergeConflictKind,
+    ) -> bool {
+        let take_incoming = match self.options.strategy {
+            MergeStrategy::KeepLocal => false,
+            MergeStrategy::TakeIncoming => true,
+            MergeStrategy::Newest => is_newer(incoming.updated_at(), local.updated_at()),
+            MergeStrategy::Manual => self.manual.contains(incoming.id()),
+        };
+        self.report.conflicts.push(MergeConflict {
+            table: T::TABLE.to_string(),
+            kind,
+            id: incoming.id().to_string(),
+            local_id: local.id().to_string(),
+            label: incoming.label(),
+            local_updated_at: local.updated_at().to_string(),
+            incoming_updated_at: incoming.updated_at().to_string(),
+            take_incoming,
+        });
+        self.stats_mut(T::TABLE).conflicting += 1;
+        take_incoming
+    }
+}
+
+/// 表中是否存在指定 ID 的记录（含回收站中的）
+fn row_exists(conn: &Connection, table: &str, id: &str) -> Result<bool, AppError> {
+    conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", table),
+        [id],
+        |row| row.get(0),
+    )
+    .map_err(|e| AppError::Other(format!("查询 {} 失败: {}", table, e)))
+}
+
+fn by_id<T: MergeRecord>(records: Vec<T>) -> HashMap<String, T> {
+    records
+        .into_iter()
+        .map(|r| (r.id().to_string(), r))
+        .collect()
+}
+
+/// 按 UUID 将导出数据合并到本地数据库
+///
+/// 在单个事务内依次合并项目、卡片（含元数据与事件日志）、寄件人与订单：
+/// - 本地没有的记录新增；导入项目与本地项目同名时视为同一项目，其卡片并入本地项目
+/// - 两侧内容相同的记录不变；不同的记录按 `options` 的冲突策略保留本地或以导入文件覆盖
+/// - 新增卡片与本地同项目卡片的呼号或序列号重复时同样按冲突策略处理
+/// - 编号方案随新增或覆盖的项目写入，其余项目仅在本地未配置方案时采用导入文件的方案
+/// - 本地回收站中的记录保持删除状态
+/// - 全局配置与同步身份不合并
+///
+/// `dry_run` 为真时合并后回滚，仅返回统计与冲突明细。
+pub fn merge_export_data(
+    conn: &mut Connection,
+    data: &ExportData,
+    options: &MergeOptions,
+    dry_run: bool,
+) -> Result<MergeReport, AppError> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    let mut merger = Merger::new(options, dry_run);
+
+    let local_projects = by_id(export_projects(&tx)?);
+    let local_senders = by_id(export_senders(&tx)?);
+    let local_orders = by_id(export_orders(&tx)?);
+
+    // 项目（同名项目映射到本地项目；同名项目在回收站中时先恢复，避免卡片并入不可见的项目）
+    let mut project_map: HashMap<&str, String> = HashMap::new();
+    // 以导入文件新增或覆盖的项目
+    let mut taken_projects: HashSet<&str> = HashSet::new();
+    for project in &data.tables.projects {
+        let exists = row_exists(&tx, "projects", &project.id)?;
+        if !exists {
+            let same_name: Option<(String, bool)> = tx
+                .query_row(
+                    "SELECT id, deleted_at IS NOT NULL FROM projects WHERE name = ?1",
+                    [&project.name],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .ok();
+            if let Some((local_id, deleted)) = same_name {
+                if deleted {
+                    tx.execute(
+                        "UPDATE projects SET deleted_at = NULL, updated_at = ?1 WHERE id = ?2",
+                        rusqlite::params![format_datetime(&now_china()), &local_id],
+                    )
+                    .map_err(|e| {
+                        AppError::Other(format!("恢复项目失败 ({}): {}", project.name, e))
+                    })?;
+                    merger.report.restored_projects.push(project.name.clone());
+                }
+                project_map.insert(&project.id, local_id);
+                continue;
+            }
+        }
+
+        let local = local_projects.get(&project.id);
+        if merger.decide(local, exists, project) == MergeAction::Skip {
+            continue;
+        }
+        taken_projects.insert(&project.id);
+        tx.execute(
+            "INSERT INTO projects (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, updated_at = excluded.updated_at",
+            rusqlite::params![
+                &project.id,
+                &project.name,
+                &project.created_at,
+                &project.updated_at
+            ],
+        )
+        .map_err(|e| AppError::Other(format!("合并项目失败 ({}): {}", project.name, e)))?;
+    }
+
+    // 编号方案：随以导入文件新增或覆盖的项目写入；其余项目仅在本地未配置方案时采用导入文件的方案
+    for incoming in data.tables.serial_schemes.iter().flatten() {
+        let taken = taken_projects.contains(incoming.project_id.as_str());
+        let mut scheme = incoming.clone();
+        if let Some(local_id) = project_map.get(scheme.project_id.as_str()) {
+            scheme.project_id = local_id.clone();
+        } else if !taken && !row_exists(&tx, "projects", &scheme.project_id)? {
+            continue;
+        }
+        let configured: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM serial_schemes WHERE project_id = ?1)",
+                [&scheme.project_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Other(format!("查询编号方案失败: {}", e)))?;
+        if taken || !configured {
+            save_serial_scheme_conn(&tx, &scheme)?;
+        }
+    }
+
+    // 卡片（在项目恢复之后读取本地卡片；本地各项目的呼号与序列号，用于检查新增卡片是否重复；
+    // 导入文件中有同 UUID 记录的本地卡片按 UUID 合并，不参与重复检查）
+    let local_cards = by_id(export_cards(&tx)?);
+    let matched: HashSet<&str> = data
+        .tables
+        .cards
+        .iter()
+        .map(|c| c.id.as_str())
+        .filter(|id| local_cards.contains_key(*id))
+        .collect();
+    let mut local_callsigns: HashMap<(&str, String), &Card> = HashMap::new();
+    let mut local_serials: HashMap<(&str, i32), &Card> = HashMap::new();
+    for card in local_cards.values() {
+        if matched.contains(card.id.as_str()) {
+            continue;
+        }
+        local_callsigns.insert((&card.project_id, card.callsign.to_uppercase()), card);
+        if let Some(serial) = card.serial {
+            local_serials.insert((&card.project_id, serial), card);
+        }
+    }
+    let mut events_by_card: HashMap<&str, Vec<&CardEvent>> = HashMap::new();
+    for event in data.tables.card_events.iter().flatten() {
+        events_by_card
+            .entry(&event.card_id)
+            .or_default()
+            .push(event);
+    }
+    let mut history_by_card: HashMap<&str, Vec<&CardHistoryEntry>> = HashMap::new();
+    for entry in data.tables.card_history.iter().flatten() {
+        history_by_card
+            .entry(&entry.card_id)
+            .or_default()
+            .push(entry);
+    }
+    for incoming in &data.tables.cards {
+        // 与本地导出形式一致后再比较：项目映射、旧格式的已分发数量、本地未登记的存放位置
+        let mut card = incoming.clone();
+        if let Some(local_id) = project_map.get(card.project_id.as_str()) {
+            card.project_id = local_id.clone();
+        }
+        if card.status == CardStatus::Distributed && card.distributed_qty == 0 {
+            card.distributed_qty = card.qty;
+        }
+        if let Some(location_id) = card.storage_location_id.take() {
+            card.storage_location_id =
+                row_exists(&tx, "storage_locations", &location_id)?.then_some(location_id);
+        }
+
+        let local = local_cards.get(&card.id);
+        let exists = local.is_some() || row_exists(&tx, "cards", &card.id)?;
+        let project_id = card.project_id.as_str();
+        let duplicate = if exists {
+            None
+        } else {
+            local_callsigns
+                .get(&(project_id, card.callsign.to_uppercase()))
+                .map(|local| (*local, MergeConflictKind::DuplicateCallsign))
+                .or_else(|| {
+                    let serial = card.serial?;
+                    let local = local_serials.get(&(project_id, serial))?;
+                    Some((*local, MergeConflictKind::DuplicateSerial))
+                })
+        };
+        let action = match duplicate {
+            Some((local, kind)) => {
+                let action = merger.decide_duplicate(local, &card, kind);
+                if action == MergeAction::Insert {
+                    delete_card_conn(&tx, &local.id, None)?;
+                    // 被替换的本地卡片不再参与后续卡片的重复检查
+                    local_callsigns.retain(|_, c| c.id != local.id);
+                    local_serials.retain(|_, c| c.id != local.id);
+                }
+                action
+            }
+            None => merger.decide(local, exists, &card),
+        };
+        if action == MergeAction::Skip {
+            continue;
+        }
+
+        tx.execute(
+            "INSERT INTO cards (id, project_id, creator_id, callsign, via_callsign, qty, serial, status, distributed_qty, created_at, updated_at, storage_location_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             ON CONFLICT(id) DO UPDATE SET project_id = excluded.project_id, creator_id = excluded.creator_id,
+                callsign = excluded.callsign, via_callsign = excluded.via_callsign, qty = excluded.qty,
+                serial = excluded.serial, status = excluded.status, distributed_qty = excluded.distributed_qty,
+                updated_at = excluded.updated_at, storage_location_id = excluded.storage_location_id",
+            rusqlite::params![
+                &card.id,
+                &card.project_id,
+                &card.creator_id,
+                &card.callsign,
+                &card.via_callsign,
+                card.qty,
+                card.serial,
+                card.status.as_str(),
+                card.distributed_qty,
+                &card.created_at,
+                &card.updated_at,
+                &card.storage_location_id,
+            ],
+        )
+        .map_err(|e| AppError::Other(format!("合并卡片失败 ({}): {}", card.callsign, e)))?;
+        save_card_metadata_conn(&tx, &card.id, card.metadata.as_ref())?;
+
+        // 事件日志以导入文件为准（旧格式不含事件日志时按卡片元数据推导）
+        if action == MergeAction::Update {
+            tx.execute("DELETE FROM card_events WHERE card_id = ?1", [&card.id])
+                .map_err(|e| AppError::Other(format!("清除卡片事件失败: {}", e)))?;
+        }
+        match data.tables.card_events {
+            Some(_) => {
+                for event in events_by_card.get(card.id.as_str()).into_iter().flatten() {
+                    insert_card_event_conn(&tx, event)?;
+                }
+            }
+            None => {
+                for event in events_from_card(&card) {
+                    insert_card_event_conn(&tx, &event)?;
+                }
+            }
+        }
+
+        // 修改历史同样以导入文件为准（旧格式不含修改历史时保留本地历史）
+        if data.tables.card_history.is_some() {
+            if action == MergeAction::Update {
+                tx.execute("DELETE FROM card_history WHERE card_id = ?1", [&card.id])
+                    .map_err(|e| AppError::Other(format!("清除修改历史失败: {}", e)))?;
+            }
+            for entry in history_by_card.get(card.id.as_str()).into_iter().flatten() {
+                insert_card_history_conn(&tx, entry)?;
+            }
+        }
+    }
+
+    // 寄件人（本地已有默认寄件人时保留本地默认）
+    let local_default = local_senders
+        .values()
+        .find(|s| s.is_default)
+        .map(|s| s.id.clone());
+    for incoming in &data.tables.sf_senders {
+        let mut sender = incoming.clone();
+        if local_default.as_ref().is_some_and(|id| *id != sender.id) {
+            sender.is_default = false;
+        }
+
+        let local = local_senders.get(&sender.id);
+        if merger.decide(local, local.is_some(), &sender) == MergeAction::Skip {
+            continue;
+        }
+        tx.execute(
+            "INSERT INTO sf_senders (id, name, phone, mobile, province, city, district, address, is_default, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, phone = excluded.phone, mobile = excluded.mobile,
+                province = excluded.province, city = excluded.city, district = excluded.district,
+                address = excluded.address, is_default = excluded.is_default, updated_at = excluded.updated_at",
+            rusqlite::params![
+                &sender.id,
+                &sender.name,
+                &sender.phone,
+                &sender.mobile,
+                &sender.province,
+                &sender.city,
+                &sender.district,
+                &sender.address,
+                sender.is_default as i32,
+                &sender.created_at,
+                &sender.updated_at,
+            ],
+        )
+        .map_err(|e| AppError::Other(format!("合并寄件人失败 ({}): {}", sender.name, e)))?;
+    }
+
+    // 订单（关联卡片仅保留本地存在的）
+    for incoming in &data.tables.sf_orders {
+        let mut order = incoming.clone();
+        if order.card_ids.is_empty() {
+            order.card_ids = order.card_id.iter().cloned().collect();
+        }
+        let mut card_ids = Vec::with_capacity(order.card_ids.len());
+        for card_id in &order.card_ids {
+            if local_cards.contains_key(card_id) || row_exists(&tx, "cards", card_id)? {
+                card_ids.push(card_id.clone());
+            }
+        }
+        order.card_id = order.card_id.filter(|id| card_ids.contains(id));
+        order.card_ids = card_ids;
+
+        let local = local_orders.get(&order.id);
+        let exists = local.is_some() || row_exists(&tx, "sf_orders", &order.id)?;
+        let action = merger.decide(local, exists, &order);
+        if action == MergeAction::Skip {
+            continue;
+        }
+
+        let sender_info_json = serde_json::to_string(&order.sender_info)
+            .map_err(|e| AppError::Other(format!("序列化寄件人信息失败: {}", e)))?;
+        let recipient_info_json = serde_json::to_string(&order.recipient_info)
+            .map_err(|e| AppError::Other(format!("序列化收件人信息失败: {}", e)))?;
+        tx.execute(
+            "INSERT INTO sf_orders (id, order_id, waybill_no, card_id, status, pay_method, cargo_name, sender_info, recipient_info, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+             ON CONFLICT(id) DO UPDATE SET order_id = excluded.order_id, waybill_no = excluded.waybill_no,
+                card_id = excluded.card_id, status = excluded.status, pay_method = excluded.pay_method,
+                cargo_name = excluded.cargo_name, sender_info = excluded.sender_info,
+                recipient_info = excluded.recipient_info, updated_at = excluded.updated_at",
+            rusqlite::params![
+                &order.id,
+                &order.order_id,
+                &order.waybill_no,
+                &order.card_id,
+                &order.status,
+                &order.pay_method,
+                &order.cargo_name,
+                &sender_info_json,
+                &recipient_info_json,
+                &order.created_at,
+                &order.updated_at,
+            ],
+        )
+        .map_err(|e| AppError::Other(format!("合并订单失败 ({}): {}", order.order_id, e)))?;
+
+        if action == MergeAction::Update {
+            tx.execute(
+                "DELETE FROM sf_order_cards WHERE order_id = ?1",
+                [&order.id],
+            )
+            .map_err(|e| AppError::Other(format!("清除寄件关联失败: {}", e)))?;
+        }
+        link_order_cards_conn(&tx, &order.id, &order.card_ids, &order.created_at)?;
+    }
+
+    // 未改动的本地记录
+    let untouched = [
+        merger.untouched(Project::TABLE, local_projects.len()),
+        merger.untouched(Card::TABLE, local_cards.len()),
+        merger.untouched(SenderInfo::TABLE, local_senders.len()),
+        merger.untouched(SFOrder::TABLE, local_orders.len()),
+    ];
+    let mut report = merger.report;
+    for (stats, untouched) in [
+        &mut report.projects,
+        &mut report.cards,
+        &mut report.sf_senders,
+        &mut report.sf_orders,
+    ]
+    .into_iter()
+    .zip(untouched)
+    {
+        stats.untouched = untouched;
+    }
+
+    // 预览不提交，事务在离开作用域时回滚
+    if !dry_run {
+        tx.commit()
+            .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+        log::info!(
+            "✅ 合并导入完成: 新增 {} 个项目、{} 张卡片、{} 个寄件人、{} 个订单，覆盖 {} 条，冲突 {} 条",
+            report.projects.inserted,
+            report.cards.inserted,
+            report.sf_senders.inserted,
+            report.sf_orders.inserted,
+            report.projects.updated
+                + report.cards.updated
+                + report.sf_senders.updated
+                + report.sf_orders.updated,
+            report.conflicts.len()
+        );
+    }
+    Ok(report)
+}
+
+/// 预览合并导入：按选项合并后回滚，返回各表统计与冲突明细
+pub fn preview_merge_import<P: AsRef<Path>>(
+    file_path: P,
+    options: &MergeOptions,
+    passphrase: Option<&str>,
+) -> Result<MergeReport, AppError> {
+    merge_import_file_in(database()?, file_path, options, passphrase, true)
+}
+
+/// 执行合并导入
+pub fn execute_merge_import<P: AsRef<Path>>(
+    file_path: P,
+    options: &MergeOptions,
+    passphrase: Option<&str>,
+) -> Result<MergeReport, AppError> {
+    merge_import_file_in(database()?, file_path, options, passphrase, false)
+}
+
+/// 同 [`preview_merge_import`]/[`execute_merge_import`]，在指定的数据库上下文中执行
+pub fn merge_import_file_in<P: AsRef<Path>>(
+    db: &Database,
+    file_path: P,
+    options: &MergeOptions,
+    passphrase: Option<&str>,
+    dry_run: bool,
+) -> Result<MergeReport, AppError> {
+    let content = read_backup_file(file_path.as_ref(), passphrase)?;
+    let data = parse_export_data(&content)?;
+
+    let mut conn = db.connection()?;
+    check_import_db_version(&conn, &data)?;
+    if !dry_run {
+        // 合并前先备份，覆盖的本地记录可从快照找回
+        create_backup_conn(db, &conn, BackupReason::PreImport)?;
+    }
+    merge_export_data(&mut conn, &data, options, dry_run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::cards::{create_card_in, get_card_history_in, get_card_in};
+    use crate::db::export::export_database_in;
+    use crate::db::projects::{create_project_in, delete_project_in};
+
+    /// 两侧各有独有卡片与同一张被修改的卡片：新增、冲突按策略处理，预览不写入
+    #[test]
+    fn test_merge_strategies() {
+        let local_db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&local_db, "项目".to_string()).unwrap();
+        let shared = create_card_in(
+            &local_db,
+            project.id.clone(),
+            "BH2RO".to_string(),
+            1,
+            Some(1),
+        )
+        .unwrap();
+        create_card_in(&local_db, project.id.clone(), "BA1AA".to_string(), 1, None).unwrap();
+
+        // 另一电台：同一份数据上修改共享卡片、新增卡片，并有同名但 ID 不同的项目
+        let mut data = export_database_in(&local_db).unwrap();
+        data.tables.cards.retain(|c| c.id == shared.id);
+        data.tables.cards[0].qty = 3;
+        data.tables.cards[0].updated_at = "2099-01-01T00:00:00+08:00".to_string();
+        let mut extra = Card::new(project.id.clone(), "BD7XYZ".to_string(), 2, None);
+        data.tables.projects[0].id = "other-project".to_string();
+        extra.project_id = "other-project".to_string();
+        data.tables.cards[0].project_id = "other-project".to_string();
+        data.tables.cards.push(extra.clone());
+        data.tables.card_events = None;
+        data.tables.card_history = Some(vec![CardHistoryEntry {
+            id: 1,
+            card_id: shared.id.clone(),
+            field: "qty".to_string(),
+            old_value: Some("1".to_string()),
+            new_value: Some("3".to_string()),
+            changed_by: None,
+            operator_id: None,
+            changed_at: "2099-01-01T00:00:00+08:00".to_string(),
+        }]);
+
+        let merge = |strategy, dry_run| {
+            let options = MergeOptions {
+                strategy,
+                take_incoming: Vec::new(),
+            };
+            merge_export_data(
+                &mut local_db.connection().unwrap(),
+                &data,
+                &options,
+                dry_run,
+            )
+            .unwrap()
+        };
+
+        let preview = merge(MergeStrategy::Newest, true);
+        assert!(preview.dry_run);
+        assert_eq!(preview.projects.inserted, 0);
+        assert_eq!(preview.projects.untouched, 1);
+        assert_eq!(preview.cards.inserted, 1);
+        assert_eq!(preview.cards.conflicting, 1);
+        assert_eq!(preview.cards.updated, 1);
+        assert_eq!(preview.cards.untouched, 1);
+        assert!(preview.conflicts[0].take_incoming);
+        assert!(get_card_in(&local_db, &extra.id).unwrap().is_none());
+
+        let kept = merge(MergeStrategy::KeepLocal, false);
+        assert_eq!(kept.cards.updated, 0);
+        assert_eq!(kept.cards.untouched, 2);
+        assert_eq!(get_card_in(&local_db, &shared.id).unwrap().unwrap().qty, 1);
+        assert!(get_card_history_in(&local_db, &shared.id).unwrap().is_empty());
+        let added = get_card_in(&local_db, &extra.id).unwrap().unwrap();
+        assert_eq!(added.project_id, project.id);
+
+        let taken = merge(MergeStrategy::TakeIncoming, false);
+        assert_eq!(taken.cards.inserted, 0);
+        assert_eq!(taken.cards.updated, 1);
+        assert_eq!(get_card_in(&local_db, &shared.id).unwrap().unwrap().qty, 3);
+        let history = get_card_history_in(&local_db, &shared.id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].new_value.as_deref(), Some("3"));
+
+        // 两侧一致后不再有冲突
+        let again = merge(MergeStrategy::Manual, true);
+        assert!(again.conflicts.is_empty());
+        assert_eq!(again.cards.untouched, 3);
+    }
+
+    /// 并入同名项目的新增卡片与本地卡片呼号或序列号重复时，作为冲突按策略处理
+    #[test]
+    fn test_merge_duplicate_cards() {
+        let local_db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&local_db, "项目".to_string()).unwrap();
+        let by_callsign =
+            create_card_in(&local_db, project.id.clone(), "BH2RO".to_string(), 1, Some(1))
+                .unwrap();
+        let by_serial =
+            create_card_in(&local_db, project.id.clone(), "BA1AA".to_string(), 1, Some(2))
+                .unwrap();
+
+        let mut data = export_database_in(&local_db).unwrap();
+        data.tables.projects[0].id = "other-project".to_string();
+        // 呼号大小写不同也视为重复
+        let mut same_callsign = Card::new(project.id.clone(), "BH2RO".to_string(), 1, None);
+        same_callsign.project_id = "other-project".to_string();
+        same_callsign.callsign = "bh2ro".to_string();
+        let mut same_serial = Card::new(project.id.clone(), "BD7XYZ".to_string(), 1, Some(2));
+        same_serial.project_id = "other-project".to_string();
+        data.tables.cards = vec![same_callsign.clone(), same_serial.clone()];
+        data.tables.card_events = None;
+
+        let merge = |strategy, dry_run| {
+            let options = MergeOptions {
+                strategy,
+                take_incoming: Vec::new(),
+            };
+            merge_export_data(
+                &mut local_db.connection().unwrap(),
+                &data,
+                &options,
+                dry_run,
+            )
+            .unwrap()
+        };
+
+        let preview = merge(MergeStrategy::KeepLocal, true);
+        let kinds: Vec<(MergeConflictKind, &str)> = preview
+            .conflicts
+            .iter()
+            .map(|c| (c.kind, c.local_id.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (MergeConflictKind::DuplicateCallsign, by_callsign.id.as_str()),
+                (MergeConflictKind::DuplicateSerial, by_serial.id.as_str()),
+            ]
+        );
+        assert_eq!(preview.cards.inserted, 0);
+        assert_eq!(preview.cards.conflicting, 2);
+
+        let taken = merge(MergeStrategy::TakeIncoming, false);
+        assert_eq!(taken.cards.updated, 2);
+        assert_eq!(taken.cards.untouched, 0);
+        assert!(get_card_in(&local_db, &by_callsign.id).unwrap().is_none());
+        assert!(get_card_in(&local_db, &by_serial.id).unwrap().is_none());
+        let added = get_card_in(&local_db, &same_serial.id).unwrap().unwrap();
+        assert_eq!(added.project_id, project.id);
+        assert!(get_card_in(&local_db, &same_callsign.id).unwrap().is_some());
+    }
+
+    /// 本地卡片按 UUID 合并（导入方改了呼号）时，不再作为新增卡片的重复对象被替换；
+    /// 回收站中的同名项目不参与项目映射
+    #[test]
+    fn test_merge_duplicate_matched_by_uuid() {
+        let local_db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&local_db, "项目".to_string()).unwrap();
+        let renamed =
+            create_card_in(&local_db, project.id.clone(), "BH2RO".to_string(), 1, Some(1))
+                .unwrap();
+
+        // 导入方把该卡片改为 BH2RP，又新增了一张 BH2RO
+        let mut data = export_database_in(&local_db).unwrap();
+        data.tables.cards[0].callsign = "BH2RP".to_string();
+        data.tables.cards[0].updated_at = "2099-01-01T00:00:00+08:00".to_string();
+        let added = Card::new(project.id.clone(), "BH2RO".to_string(), 1, None);
+        data.tables.cards.push(added.clone());
+        data.tables.card_events = None;
+
+        let options = MergeOptions {
+            strategy: MergeStrategy::TakeIncoming,
+            take_incoming: Vec::new(),
+        };
+        let report =
+            merge_export_data(&mut local_db.connection().unwrap(), &data, &options, false)
+                .unwrap();
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].kind, MergeConflictKind::Modified);
+        assert_eq!(report.cards.inserted, 1);
+        assert_eq!(report.cards.updated, 1);
+        assert_eq!(report.cards.untouched, 0);
+        let local = get_card_in(&local_db, &renamed.id).unwrap().unwrap();
+        assert_eq!(local.callsign, "BH2RP");
+        assert!(get_card_in(&local_db, &added.id).unwrap().is_some());
+
+        // 同名项目已在回收站：先恢复再并入，恢复后的本地卡片参与重复检查
+        let mut data = export_database_in(&local_db).unwrap();
+        delete_project_in(&local_db, &project.id).unwrap();
+        data.tables.projects[0].id = "other-project".to_string();
+        let mut extra = Card::new(project.id.clone(), "BA1AA".to_string(), 1, None);
+        extra.project_id = "other-project".to_string();
+        data.tables.cards = vec![extra.clone()];
+        data.tables.card_events = None;
+        let report =
+            merge_export_data(&mut local_db.connection().unwrap(), &data, &options, false)
+                .unwrap();
+        assert_eq!(report.restored_projects, vec!["项目".to_string()]);
+        assert_eq!(report.projects.inserted, 0);
+        assert_eq!(report.cards.inserted, 1);
+        assert_eq!(report.cards.untouched, 2);
+        let added = get_card_in(&local_db, &extra.id).unwrap().unwrap();
+        assert_eq!(added.project_id, project.id);
+    }
+}
@@ -3,19 +3,56 @@
 // 提供 SQLite 数据库访问和管理功能
 
 pub mod app_settings;
+pub mod backups;
+pub mod batch;
+pub mod callsign_aliases;
+pub mod callsign_dossier;
+pub mod card_events;
+pub mod card_import;
+pub mod card_lookup;
+pub mod card_metadata;
 pub mod cards;
+pub mod encrypted_backup;
 pub mod export;
+pub mod export_profiles;
+pub mod handling_methods;
 pub mod import;
+pub mod merge_import;
 pub mod models;
+pub mod operators;
 pub mod projects;
+pub mod qsl_managers;
+pub mod recycle_bin;
+pub mod serial_schemes;
 pub mod sf_express;
+pub mod shipments;
 pub mod sqlite;
+pub mod stocktake;
+pub mod storage_locations;
 
 pub use app_settings::*;
+pub use backups::*;
+pub use batch::*;
+pub use callsign_aliases::*;
+pub use callsign_dossier::*;
+pub use card_events::*;
+pub use card_import::*;
+pub use card_lookup::*;
 pub use cards::*;
+pub use encrypted_backup::*;
 pub use export::*;
+pub use export_profiles::*;
+pub use handling_methods::*;
 pub use import::*;
+pub use merge_import::*;
 pub use models::*;
+pub use operators::*;
 pub use projects::*;
+pub use qsl_managers::*;
+pub use recycle_bin::*;
+pub use serial_schemes::*;
 pub use sf_express::*;
+pub use shipments::*;
 pub use sqlite::*;
+pub use stocktake::*;
+pub use storage_locations::*;
@@ -2,6 +2,8 @@
 //
 // 定义项目和卡片的数据结构
 
+use crate::error::AppError;
+use crate::sf_express::SFOrderWithCard;
 use chrono::{DateTime, FixedOffset, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -74,12 +76,24 @@ pub struct ProjectWithStats {
     /// 待分发卡片数
     #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
     pub pending_cards: i64,
+    /// 部分分发卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub partially_distributed_cards: i64,
     /// 已分发卡片数
     #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
     pub distributed_cards: i64,
     /// 已退卡卡片数
     #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
     pub returned_cards: i64,
+    /// 待付邮资卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub awaiting_postage_cards: i64,
+    /// 留存待 QSL 管理员领取卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub held_cards: i64,
+    /// 已遗失卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub lost_cards: i64,
 }
 
 impl From<Project> for ProjectWithStats {
@@ -91,8 +105,12 @@ impl From<Project> for ProjectWithStats {
             updated_at: project.updated_at,
             total_cards: 0,
             pending_cards: 0,
+            partially_distributed_cards: 0,
             distributed_cards: 0,
             returned_cards: 0,
+            awaiting_postage_cards: 0,
+            held_cards: 0,
+            lost_cards: 0,
         }
     }
 }
@@ -105,26 +123,44 @@ impl From<Project> for ProjectWithStats {
 pub enum CardStatus {
     /// 已录入（待分发）
     Pending,
+    /// 部分分发（仍有剩余数量待分发）
+    #[serde(rename = "partially_distributed")]
+    PartiallyDistributed,
     /// 已分发
     Distributed,
     /// 已退卡
     Returned,
+    /// 待付邮资（会员缴纳邮资后再分发）
+    #[serde(rename = "awaiting_postage")]
+    AwaitingPostage,
+    /// 留存（留给 QSL 管理员统一领取）
+    Held,
+    /// 已遗失
+    Lost,
 }
 
 impl CardStatus {
     pub fn as_str(&self) -> &'static str {
         match self {
             CardStatus::Pending => "pending",
+            CardStatus::PartiallyDistributed => "partially_distributed",
             CardStatus::Distributed => "distributed",
             CardStatus::Returned => "returned",
+            CardStatus::AwaitingPostage => "awaiting_postage",
+            CardStatus::Held => "held",
+            CardStatus::Lost => "lost",
         }
     }
 
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "pending" => Some(CardStatus::Pending),
+            "partially_distributed" => Some(CardStatus::PartiallyDistributed),
             "distributed" => Some(CardStatus::Distributed),
             "returned" => Some(CardStatus::Returned),
+            "awaiting_postage" => Some(CardStatus::AwaitingPostage),
+            "held" => Some(CardStatus::Held),
+            "lost" => Some(CardStatus::Lost),
             _ => None,
         }
     }
@@ -132,8 +168,12 @@ impl CardStatus {
     pub fn display_name(&self) -> &'static str {
         match self {
             CardStatus::Pending => "已录入",
+            CardStatus::PartiallyDistributed => "部分分发",
             CardStatus::Distributed => "已分发",
             CardStatus::Returned => "已退卡",
+            CardStatus::AwaitingPostage => "待付邮资",
+            CardStatus::Held => "留存",
+            CardStatus::Lost => "已遗失",
         }
     }
 }
@@ -143,7 +183,7 @@ impl CardStatus {
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub struct DistributionInfo {
-    /// 处理方式：直接分发、邮寄、自取
+    /// 分发方式（登记表 card_handling_methods 中的取值）
     pub method: String,
     /// 分发地址
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -154,6 +194,12 @@ pub struct DistributionInfo {
     /// 代领人呼号（代领方式时使用）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub proxy_callsign: Option<String>,
+    /// 本次分发数量（为空表示分发全部剩余数量）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub qty: Option<i32>,
+    /// 运单号（随顺丰合并寄件确认分发时记录）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waybill_no: Option<String>,
     /// 分发时间
     pub distributed_at: String,
 }
@@ -163,7 +209,7 @@ pub struct DistributionInfo {
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub struct ReturnInfo {
-    /// 处理方式：NOT FOUND、CALLSIGN INVALID、REFUSED、OTHER
+    /// 退卡原因（登记表 card_handling_methods 中的取值，如 NOT FOUND、CALLSIGN INVALID）
     pub method: String,
     /// 备注
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -214,8 +260,9 @@ pub struct CardMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(alias = "address_history")]
     pub address_cache: Option<Vec<AddressEntry>>,
-    /// 待处理运单号（顺丰下单后暂存，确认分发后会移到 distribution.remarks）
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// 待处理运单号（1.3 之前的导出格式，仅导入时读取并关联到寄件订单，不再导出）
+    #[serde(default, skip_serializing)]
+    #[cfg_attr(feature = "ts-rs", ts(skip))]
     pub pending_waybill_no: Option<String>,
 }
 
@@ -228,11 +275,14 @@ pub struct Card {
     pub id: String,
     /// 所属项目 ID
     pub project_id: String,
-    /// 创建者 ID（预留字段）
+    /// 录入卡片的操作员 ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub creator_id: Option<String>,
     /// 呼号
     pub callsign: String,
+    /// 经由的 QSL 管理员呼号（录入时按 QSL 管理员登记自动标注）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub via_callsign: Option<String>,
     /// 数量
     pub qty: i32,
     /// 序列号（数字，前端显示时格式化为三位数如 "001"）
@@ -240,6 +290,12 @@ pub struct Card {
     pub serial: Option<i32>,
     /// 状态
     pub status: CardStatus,
+    /// 已分发数量（剩余数量为 qty - distributed_qty）
+    #[serde(default)]
+    pub distributed_qty: i32,
+    /// 存放位置 ID（录入时按分配规则自动分配）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_location_id: Option<String>,
     /// 元数据（分发/退卡信息）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<CardMetadata>,
@@ -258,15 +314,23 @@ impl Card {
             project_id,
             creator_id: None,
             callsign: callsign.to_uppercase(),
+            via_callsign: None,
             qty,
             serial,
             status: CardStatus::Pending,
+            distributed_qty: 0,
+            storage_location_id: None,
             metadata: None,
             created_at: now.clone(),
             updated_at: now,
         }
     }
 
+    /// 剩余待分发数量
+    pub fn remaining_qty(&self) -> i32 {
+        (self.qty - self.distributed_qty).max(0)
+    }
+
     /// 添加或更新地址缓存（智能去重，单版本）
     ///
     /// 规则：
@@ -386,13 +450,28 @@ pub struct CardWithProject {
     pub project_name: String,
     /// 呼号
     pub callsign: String,
+    /// 经由的 QSL 管理员呼号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub via_callsign: Option<String>,
     /// 数量
     pub qty: i32,
-    /// 序列号（数字，前端显示时格式化为三位数如 "001"）
+    /// 序列号（数字）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub serial: Option<i32>,
+    /// 按项目编号方案格式化的序列号（如 "001"、"BY-2026-0012"）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_display: Option<String>,
     /// 状态
     pub status: CardStatus,
+    /// 已分发数量
+    #[serde(default)]
+    pub distributed_qty: i32,
+    /// 存放位置 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_location_id: Option<String>,
+    /// 存放位置编号（如 `A-02-3`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_location: Option<String>,
     /// 元数据
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<CardMetadata>,
@@ -402,6 +481,169 @@ pub struct CardWithProject {
     pub updated_at: String,
 }
 
+impl CardWithProject {
+    /// 呼号及经由的 QSL 管理员（如 `X via Y`，无管理员时为呼号本身）
+    pub fn callsign_via(&self) -> String {
+        match self.via_callsign.as_deref() {
+            Some(via) => format!("{} via {}", self.callsign, via),
+            None => self.callsign.clone(),
+        }
+    }
+}
+
+/// 卡片可编辑字段（仅修改为 `Some` 的字段）
+#[derive(Debug, Clone, Default)]
+pub struct CardUpdate {
+    /// 呼号
+    pub callsign: Option<String>,
+    /// 数量
+    pub qty: Option<i32>,
+    /// 序列号（`Some(None)` 表示清空序列号）
+    pub serial: Option<Option<i32>>,
+    /// 所属项目 ID（移动到其他项目）
+    pub project_id: Option<String>,
+}
+
+impl CardUpdate {
+    /// 是否未指定任何修改
+    pub fn is_empty(&self) -> bool {
+        self.callsign.is_none()
+            && self.qty.is_none()
+            && self.serial.is_none()
+            && self.project_id.is_none()
+    }
+}
+
+/// 卡片修改历史记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct CardHistoryEntry {
+    /// 记录 ID（自增）
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub id: i64,
+    /// 卡片 ID
+    pub card_id: String,
+    /// 被修改的字段：callsign、qty、serial、project_id，
+    /// 以及恢复卡片时的 deleted_at、保存地址时的 address（新值为地址来源）
+    pub field: String,
+    /// 修改前的值
+    pub old_value: Option<String>,
+    /// 修改后的值
+    pub new_value: Option<String>,
+    /// 操作人
+    pub changed_by: Option<String>,
+    /// 操作员 ID
+    pub operator_id: Option<String>,
+    /// 修改时间
+    pub changed_at: String,
+}
+
+/// 卡片事件类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum CardEventType {
+    /// 录入
+    Entered,
+    /// 首次分发
+    Distributed,
+    /// 退卡
+    Returned,
+    /// 重新分发（已有分发记录后再次分发）
+    Redistributed,
+    /// 关联寄件（卡片加入顺丰订单时记录订单号；旧版为下单后暂存的运单号），不影响投影
+    WaybillAttached,
+    /// 标记为待付邮资
+    AwaitingPostage,
+    /// 标记为留存
+    Held,
+    /// 标记为遗失
+    Lost,
+    /// 解除待付邮资、留存或遗失，恢复为待分发
+    Released,
+}
+
+impl CardEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CardEventType::Entered => "entered",
+            CardEventType::Distributed => "distributed",
+            CardEventType::Returned => "returned",
+            CardEventType::Redistributed => "redistributed",
+            CardEventType::WaybillAttached => "waybill_attached",
+            CardEventType::AwaitingPostage => "awaiting_postage",
+            CardEventType::Held => "held",
+            CardEventType::Lost => "lost",
+            CardEventType::Released => "released",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "entered" => Some(CardEventType::Entered),
+            "distributed" => Some(CardEventType::Distributed),
+            "returned" => Some(CardEventType::Returned),
+            "redistributed" => Some(CardEventType::Redistributed),
+            "waybill_attached" => Some(CardEventType::WaybillAttached),
+            "awaiting_postage" => Some(CardEventType::AwaitingPostage),
+            "held" => Some(CardEventType::Held),
+            "lost" => Some(CardEventType::Lost),
+            "released" => Some(CardEventType::Released),
+            _ => None,
+        }
+    }
+}
+
+/// 卡片事件（只追加，卡片 metadata 为事件日志的投影）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct CardEvent {
+    /// 事件 ID
+    pub id: String,
+    /// 卡片 ID
+    pub card_id: String,
+    /// 事件类型
+    pub event_type: CardEventType,
+    /// 分发信息（分发、重新分发事件）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distribution: Option<DistributionInfo>,
+    /// 退卡信息（退卡事件）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "return")]
+    pub return_info: Option<ReturnInfo>,
+    /// 运单号（旧版关联寄件事件）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waybill_no: Option<String>,
+    /// 备注（待付邮资、留存、遗失、恢复事件；关联寄件事件为顺丰订单号）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remarks: Option<String>,
+    /// 操作员 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator_id: Option<String>,
+    /// 事件时间
+    pub created_at: String,
+}
+
+impl CardEvent {
+    /// 创建新事件（时间取当前东八区时间）
+    pub fn new(card_id: &str, event_type: CardEventType) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            card_id: card_id.to_string(),
+            event_type,
+            distribution: None,
+            return_info: None,
+            waybill_no: None,
+            remarks: None,
+            operator_id: None,
+            created_at: format_datetime(&now_china()),
+        }
+    }
+}
+
 /// 卡片查询过滤器
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CardFilter {
@@ -414,6 +656,123 @@ pub struct CardFilter {
     /// 状态筛选
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<CardStatus>,
+    /// 录入时间起（含，日期或完整时间，按前缀比较）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_from: Option<String>,
+    /// 录入时间止（含）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_to: Option<String>,
+    /// 分发时间起（含）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distributed_from: Option<String>,
+    /// 分发时间止（含）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distributed_to: Option<String>,
+    /// 退卡时间起（含）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub returned_from: Option<String>,
+    /// 退卡时间止（含）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub returned_to: Option<String>,
+    /// 分发方式（精确匹配）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distribution_method: Option<String>,
+    /// 退卡原因（精确匹配）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_method: Option<String>,
+    /// 序列号下限（含）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_min: Option<i32>,
+    /// 序列号上限（含）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_max: Option<i32>,
+    /// 数量下限（含）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qty_min: Option<i32>,
+    /// 数量上限（含）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qty_max: Option<i32>,
+    /// 是否有代领人
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_proxy: Option<bool>,
+    /// 是否有地址缓存
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_address: Option<bool>,
+    /// 是否已加入未确认的顺丰寄件
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_open_shipment: Option<bool>,
+    /// 经由的 QSL 管理员呼号（精确匹配）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via_callsign: Option<String>,
+    /// 存放位置 ID（精确匹配）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_location_id: Option<String>,
+    /// 排序字段（默认录入时间）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<CardSortField>,
+    /// 排序方向（默认降序）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<SortOrder>,
+}
+
+impl CardFilter {
+    /// 是否未设置任何筛选条件（排序不算筛选条件）
+    pub fn is_empty(&self) -> bool {
+        self.project_id.is_none()
+            && self.callsign.is_none()
+            && self.status.is_none()
+            && self.created_from.is_none()
+            && self.created_to.is_none()
+            && self.distributed_from.is_none()
+            && self.distributed_to.is_none()
+            && self.returned_from.is_none()
+            && self.returned_to.is_none()
+            && self.distribution_method.is_none()
+            && self.return_method.is_none()
+            && self.serial_min.is_none()
+            && self.serial_max.is_none()
+            && self.qty_min.is_none()
+            && self.qty_max.is_none()
+            && self.has_proxy.is_none()
+            && self.has_address.is_none()
+            && self.has_open_shipment.is_none()
+            && self.via_callsign.is_none()
+            && self.storage_location_id.is_none()
+    }
+}
+
+/// 卡片排序字段
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum CardSortField {
+    /// 录入时间
+    CreatedAt,
+    /// 更新时间
+    UpdatedAt,
+    /// 呼号
+    Callsign,
+    /// 序列号
+    Serial,
+    /// 数量
+    Qty,
+    /// 分发时间
+    DistributedAt,
+    /// 退卡时间
+    ReturnedAt,
+}
+
+/// 排序方向
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// 升序
+    Asc,
+    /// 降序
+    Desc,
 }
 
 /// 分页参数
@@ -452,6 +811,943 @@ pub struct PagedCards {
     pub total_pages: u32,
 }
 
+/// 批量操作目标
+#[derive(Debug, Clone)]
+pub enum BatchTarget {
+    /// 指定卡片 ID 列表
+    Ids(Vec<String>),
+    /// 符合筛选条件的全部卡片
+    Filter(Box<CardFilter>),
+}
+
+impl BatchTarget {
+    /// 由前端参数构造：ID 列表与筛选条件二选一
+    pub fn from_parts(
+        card_ids: Option<Vec<String>>,
+        filter: Option<CardFilter>,
+    ) -> Result<Self, AppError> {
+        match (card_ids, filter) {
+            (Some(ids), None) => Ok(BatchTarget::Ids(ids)),
+            (None, Some(filter)) => Ok(BatchTarget::Filter(Box::new(filter))),
+            _ => Err(AppError::InvalidParameter(
+                "批量操作需指定卡片 ID 列表或筛选条件（二选一）".to_string(),
+            )),
+        }
+    }
+}
+
+/// 批量操作类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum BatchOperation {
+    /// 批量分发
+    Distribute,
+    /// 批量退卡
+    Return,
+    /// 批量删除
+    Delete,
+    /// 批量移动到其他项目
+    Move,
+    /// 批量标记遗失
+    MarkLost,
+}
+
+impl BatchOperation {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            BatchOperation::Distribute => "分发",
+            BatchOperation::Return => "退卡",
+            BatchOperation::Delete => "删除",
+            BatchOperation::Move => "移动",
+            BatchOperation::MarkLost => "标记遗失",
+        }
+    }
+}
+
+/// 批量操作中单张卡片的处理结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct BatchItemResult {
+    /// 卡片 ID
+    pub card_id: String,
+    /// 呼号（卡片不存在时为空）
+    pub callsign: Option<String>,
+    /// 是否成功
+    pub success: bool,
+    /// 失败原因
+    pub error: Option<String>,
+}
+
+/// 批量操作报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct BatchReport {
+    /// 操作类型
+    pub operation: BatchOperation,
+    /// 目标卡片数
+    pub total: u32,
+    /// 成功数
+    pub succeeded: u32,
+    /// 失败数
+    pub failed: u32,
+    /// 逐卡结果
+    pub items: Vec<BatchItemResult>,
+}
+
+/// 批量操作进度（每处理一张卡片推送一次）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct BatchProgress {
+    /// 操作类型
+    pub operation: BatchOperation,
+    /// 已处理数
+    pub current: u32,
+    /// 目标卡片数
+    pub total: u32,
+    /// 当前卡片 ID
+    pub card_id: String,
+    /// 当前卡片是否成功
+    pub success: bool,
+}
+
+/// 回收站中的项目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct DeletedProject {
+    /// 项目 ID
+    pub id: String,
+    /// 项目名称
+    pub name: String,
+    /// 项目下的卡片数（随项目一并恢复或彻底删除）
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub card_count: i64,
+    /// 创建时间
+    pub created_at: String,
+    /// 删除时间
+    pub deleted_at: String,
+}
+
+/// 回收站中的卡片
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct DeletedCard {
+    /// 卡片 ID
+    pub id: String,
+    /// 所属项目 ID
+    pub project_id: String,
+    /// 项目名称
+    pub project_name: String,
+    /// 呼号
+    pub callsign: String,
+    /// 数量
+    pub qty: i32,
+    /// 序列号
+    pub serial: Option<i32>,
+    /// 删除前的状态
+    pub status: CardStatus,
+    /// 创建时间
+    pub created_at: String,
+    /// 删除时间
+    pub deleted_at: String,
+}
+
+/// 回收站内容（按删除时间倒序）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct RecycleBin {
+    /// 已删除的项目
+    pub projects: Vec<DeletedProject>,
+    /// 单独删除的卡片
+    pub cards: Vec<DeletedCard>,
+}
+
+/// 清空回收站的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct PurgeReport {
+    /// 彻底删除的项目数
+    pub projects: u32,
+    /// 彻底删除的卡片数（含随项目删除的卡片）
+    pub cards: u32,
+}
+
+/// 操作员
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct Operator {
+    /// 操作员 ID（UUID 格式）
+    pub id: String,
+    /// 姓名
+    pub name: String,
+    /// 呼号
+    pub callsign: Option<String>,
+    /// 是否设置了 PIN
+    pub has_pin: bool,
+    /// 创建时间
+    pub created_at: String,
+    /// 更新时间
+    pub updated_at: String,
+}
+
+/// 操作员工作量统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct OperatorStats {
+    /// 操作员 ID
+    pub operator_id: String,
+    /// 姓名
+    pub name: String,
+    /// 呼号
+    pub callsign: Option<String>,
+    /// 录入卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub entered: i64,
+    /// 分发次数（含重新分发）
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub distributed: i64,
+    /// 退卡次数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub returned: i64,
+    /// 修改字段次数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub edited: i64,
+}
+
+/// 呼号档案：某电台在所有项目中的卡片汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct CallsignDossier {
+    /// 呼号（统一大写；查询旧呼号时为解析后的现用呼号）
+    pub callsign: String,
+    /// 指向该呼号的别名（旧呼号）
+    pub aliases: Vec<CallsignAlias>,
+    /// 全部卡片（含旧呼号名下的卡片，按项目、序列号排序，不含回收站中的卡片）
+    pub cards: Vec<CardWithProject>,
+    /// 涉及项目数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub project_count: i64,
+    /// 卡片总数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub total_cards: i64,
+    /// 待分发卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub pending_cards: i64,
+    /// 部分分发卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub partially_distributed_cards: i64,
+    /// 已分发卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub distributed_cards: i64,
+    /// 已退卡卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub returned_cards: i64,
+    /// 待付邮资卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub awaiting_postage_cards: i64,
+    /// 留存待 QSL 管理员领取卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub held_cards: i64,
+    /// 已遗失卡片数
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub lost_cards: i64,
+    /// 可交付数量（待分发、部分分发、待付邮资、留存卡片的剩余数量之和）
+    pub deliverable_qty: i32,
+    /// 地址缓存（各卡片去重合并，按缓存时间倒序）
+    pub addresses: Vec<AddressEntry>,
+    /// 关联的顺丰订单（按创建时间倒序）
+    pub sf_orders: Vec<SFOrderWithCard>,
+    /// 最近一次当面领取
+    pub last_pickup: Option<CallsignPickup>,
+}
+
+/// 当面领取记录（自取或代领等无需地址的分发）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct CallsignPickup {
+    /// 卡片 ID
+    pub card_id: String,
+    /// 卡片呼号
+    pub callsign: String,
+    /// 项目名称
+    pub project_name: String,
+    /// 分发方式
+    pub method: String,
+    /// 代领人呼号
+    pub proxy_callsign: Option<String>,
+    /// 领取数量（为空表示领取全部剩余数量）
+    pub qty: Option<i32>,
+    /// 是否为档案呼号代他人领取
+    pub as_proxy: bool,
+    /// 领取时间
+    pub picked_up_at: String,
+}
+
+/// 处理方式类别
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum HandlingMethodKind {
+    /// 分发方式
+    Distribution,
+    /// 退卡原因
+    Return,
+}
+
+impl HandlingMethodKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HandlingMethodKind::Distribution => "distribution",
+            HandlingMethodKind::Return => "return",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            HandlingMethodKind::Distribution => "分发方式",
+            HandlingMethodKind::Return => "退卡原因",
+        }
+    }
+}
+
+/// 分发方式 / 退卡原因登记项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct HandlingMethod {
+    /// 类别
+    pub kind: HandlingMethodKind,
+    /// 取值（写入分发/退卡记录，登记后不可修改）
+    pub code: String,
+    /// 显示名称
+    pub display_name: String,
+    /// 是否须填写分发地址
+    pub requires_address: bool,
+    /// 是否须填写代领人呼号
+    pub requires_proxy_callsign: bool,
+    /// 是否须填写备注
+    pub requires_remarks: bool,
+    /// 是否启用（停用后不可用于新的分发/退卡）
+    pub is_active: bool,
+    /// 排序（升序）
+    pub sort_order: i32,
+    /// 创建时间
+    pub created_at: String,
+    /// 更新时间
+    pub updated_at: String,
+}
+
+/// 登记项可编辑字段（仅修改为 `Some` 的字段）
+#[derive(Debug, Clone, Default)]
+pub struct HandlingMethodUpdate {
+    /// 显示名称
+    pub display_name: Option<String>,
+    /// 是否须填写分发地址
+    pub requires_address: Option<bool>,
+    /// 是否须填写代领人呼号
+    pub requires_proxy_callsign: Option<bool>,
+    /// 是否须填写备注
+    pub requires_remarks: Option<bool>,
+    /// 是否启用
+    pub is_active: Option<bool>,
+    /// 排序
+    pub sort_order: Option<i32>,
+}
+
+impl HandlingMethodUpdate {
+    /// 是否未指定任何修改
+    pub fn is_empty(&self) -> bool {
+        self.display_name.is_none()
+            && self.requires_address.is_none()
+            && self.requires_proxy_callsign.is_none()
+            && self.requires_remarks.is_none()
+            && self.is_active.is_none()
+            && self.sort_order.is_none()
+    }
+}
+
+/// 呼号别名（旧呼号在生效期内解析为现用呼号）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct CallsignAlias {
+    /// ID
+    pub id: String,
+    /// 旧呼号（统一大写）
+    pub alias: String,
+    /// 现用呼号（统一大写）
+    pub callsign: String,
+    /// 生效日期（YYYY-MM-DD，含当日）
+    pub effective_from: String,
+    /// 失效日期（YYYY-MM-DD，含当日；为空表示长期有效）
+    pub effective_to: Option<String>,
+    /// 备注
+    pub remarks: Option<String>,
+    /// 创建时间
+    pub created_at: String,
+    /// 更新时间
+    pub updated_at: String,
+}
+
+/// QSL 管理员登记（电台在有效期内经由管理员转交卡片）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct QslManager {
+    /// ID
+    pub id: String,
+    /// 电台呼号（统一大写）
+    pub callsign: String,
+    /// QSL 管理员呼号（统一大写）
+    pub manager: String,
+    /// 生效日期（YYYY-MM-DD，含当日；为空表示不限起始）
+    pub effective_from: Option<String>,
+    /// 失效日期（YYYY-MM-DD，含当日；为空表示长期有效）
+    pub effective_to: Option<String>,
+    /// 备注
+    pub remarks: Option<String>,
+    /// 创建时间
+    pub created_at: String,
+    /// 更新时间
+    pub updated_at: String,
+}
+
+/// QSL 管理员 CSV 导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct QslManagerImportReport {
+    /// 新登记条数
+    pub imported: u32,
+    /// 与已有登记完全相同而跳过的条数
+    pub skipped: u32,
+    /// 出错的行（「第 N 行：原因」），出错行不导入
+    pub errors: Vec<String>,
+}
+
+/// 卡片表格导入的列映射（列下标从 0 开始）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct CardImportMapping {
+    /// 首行是否为表头（表头行不导入）
+    pub has_header: bool,
+    /// 呼号所在列
+    pub callsign: u32,
+    /// 数量所在列（未映射或单元格为空时数量为 1）
+    pub qty: Option<u32>,
+    /// 序列号所在列（可为按编号方案格式化的形式，取末尾的数字部分）
+    pub serial: Option<u32>,
+    /// 备注所在列（记入录入事件的备注）
+    pub remarks: Option<u32>,
+}
+
+/// 卡片表格导入预览：供前端选择列映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct CardImportPreview {
+    /// 首行内容（有表头时即各列标题）
+    pub headers: Vec<String>,
+    /// 数据行样例（不含表头，最多 10 行）
+    pub rows: Vec<Vec<String>>,
+    /// 数据行总数（不含表头与空行）
+    pub total_rows: u32,
+    /// 列数
+    pub column_count: u32,
+    /// 按表头推断的列映射
+    pub mapping: CardImportMapping,
+}
+
+/// 卡片表格导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct CardImportReport {
+    /// 是否为试导入（仅校验，不写入）
+    pub dry_run: bool,
+    /// 导入（试导入时为可导入）的卡片数
+    pub imported: u32,
+    /// 呼号重复而未导入的行数（与项目中已有卡片或文件中前面的行重复）
+    pub duplicates: u32,
+    /// 未导入的行及原因，含重复行
+    pub errors: Vec<CardImportRowError>,
+}
+
+/// 卡片表格导入的出错字段
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum CardImportField {
+    /// 呼号（无效或重复）
+    Callsign,
+    /// 数量
+    Qty,
+    /// 序列号（无法识别或超出编号方案范围）
+    Serial,
+    /// 备注
+    Remarks,
+}
+
+/// 卡片表格导入中未导入的行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct CardImportRowError {
+    /// 行号（即表格中的行号，从 1 开始）
+    pub row: u32,
+    /// 出错字段
+    pub field: CardImportField,
+    /// 原因
+    pub message: String,
+}
+
+/// 存放位置（柜/盒/格）及其自动分配规则
+///
+/// 规则字段均为空时不参与自动分配；设置多项时须同时满足。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct StorageLocation {
+    /// ID
+    pub id: String,
+    /// 柜
+    pub cabinet: String,
+    /// 盒（抽屉）
+    pub box_no: String,
+    /// 格（为空表示整盒）
+    pub slot: String,
+    /// 位置编号（柜-盒[-格]，如 `A-02-3`）
+    pub code: String,
+    /// 分配规则：呼号前缀（逗号分隔，如 `BH,BG`）
+    pub match_prefix: Option<String>,
+    /// 分配规则：分区数字（如 `2` 或 `1-3,5`）
+    pub match_district: Option<String>,
+    /// 分配规则：后缀首字母（如 `A-M`）
+    pub match_suffix: Option<String>,
+    /// 排列与规则匹配顺序（小者优先）
+    pub sort_order: i32,
+    /// 备注
+    pub remarks: Option<String>,
+    /// 存放中的卡片数（尚未分发完毕的卡片）
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub card_count: i64,
+    /// 创建时间
+    pub created_at: String,
+    /// 更新时间
+    pub updated_at: String,
+}
+
+/// 存放位置登记内容（新建与修改共用）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct StorageLocationInput {
+    /// 柜
+    pub cabinet: String,
+    /// 盒（抽屉）
+    pub box_no: String,
+    /// 格（为空表示整盒）
+    #[serde(default)]
+    pub slot: String,
+    /// 分配规则：呼号前缀
+    #[serde(default)]
+    pub match_prefix: Option<String>,
+    /// 分配规则：分区数字
+    #[serde(default)]
+    pub match_district: Option<String>,
+    /// 分配规则：后缀首字母
+    #[serde(default)]
+    pub match_suffix: Option<String>,
+    /// 排列与规则匹配顺序
+    #[serde(default)]
+    pub sort_order: i32,
+    /// 备注
+    #[serde(default)]
+    pub remarks: Option<String>,
+}
+
+/// 库存盘点会话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct StocktakeSession {
+    /// ID
+    pub id: String,
+    /// 名称
+    pub name: String,
+    /// 盘点范围的项目 ID（为空表示全部项目）
+    pub project_id: Option<String>,
+    /// 盘点范围的项目名称
+    pub project_name: Option<String>,
+    /// 发起盘点的操作员 ID
+    pub operator_id: Option<String>,
+    /// 备注
+    pub remarks: Option<String>,
+    /// 已扫描条数（含无法识别的录入）
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub scan_count: i64,
+    /// 开始时间
+    pub started_at: String,
+    /// 结束时间（为空表示进行中）
+    pub closed_at: Option<String>,
+}
+
+/// 盘点扫描记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct StocktakeScan {
+    /// 记录 ID
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub id: i64,
+    /// 盘点会话 ID
+    pub session_id: String,
+    /// 扫描或录入的原始内容
+    pub input: String,
+    /// 识别到的卡片 ID（为空表示无法识别）
+    pub card_id: Option<String>,
+    /// 扫描时间
+    pub scanned_at: String,
+}
+
+/// 单次扫描的核对结果
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum StocktakeScanOutcome {
+    /// 在待分发清单中
+    Found,
+    /// 卡片存在但不在待分发清单中（已分发、已退卡、已遗失，或不属于盘点项目）
+    NotPending,
+    /// 无法识别
+    Unknown,
+    /// 本次盘点已扫描过
+    Duplicate,
+}
+
+/// 扫描结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct StocktakeScanResult {
+    /// 核对结果
+    pub outcome: StocktakeScanOutcome,
+    /// 扫描记录（重复扫描时为此前的记录）
+    pub scan: StocktakeScan,
+    /// 识别到的卡片
+    pub card: Option<CardWithProject>,
+}
+
+/// 盘点核对报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct StocktakeReport {
+    /// 盘点会话
+    pub session: StocktakeSession,
+    /// 应在架上的卡片数（盘点范围内当前存放中的卡片）
+    pub expected_count: u32,
+    /// 已找到的应在架卡片数
+    pub found_count: u32,
+    /// 应在架但未扫描到的卡片
+    pub missing: Vec<CardWithProject>,
+    /// 扫描到但不在待分发清单中的卡片
+    pub not_pending: Vec<CardWithProject>,
+    /// 无法识别的扫描记录
+    pub unknown: Vec<StocktakeScan>,
+}
+
+/// 默认序列号补零位数（未配置编号方案的项目）
+pub const DEFAULT_SERIAL_WIDTH: u32 = 3;
+
+/// 项目序列号编号方案
+///
+/// 卡片仍以整数保存序列号，显示、打印与导出时按方案格式化。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SerialScheme {
+    /// 项目 ID
+    pub project_id: String,
+    /// 前缀（可为空）
+    pub prefix: String,
+    /// 年份（为空表示不含年份）
+    pub year: Option<i32>,
+    /// 数字部分补零位数
+    pub width: u32,
+    /// 起始序列号
+    pub start: u32,
+}
+
+impl SerialScheme {
+    /// 默认方案：无前缀、无年份、三位补零、从 1 开始
+    pub fn default_for(project_id: &str) -> Self {
+        Self {
+            project_id: project_id.to_string(),
+            prefix: String::new(),
+            year: None,
+            width: DEFAULT_SERIAL_WIDTH,
+            start: 1,
+        }
+    }
+
+    /// 数字部分可表示的最大序列号
+    pub fn max_serial(&self) -> u32 {
+        10u32.saturating_pow(self.width).saturating_sub(1)
+    }
+
+    /// 格式化序列号：前缀、年份与补零后的数字以 `-` 连接（空的部分省略）
+    ///
+    /// 如 `BY-2026-0012`；默认方案为 `012`。
+    pub fn format(&self, serial: i32) -> String {
+        let number = format!("{:0width$}", serial, width = self.width as usize);
+        let mut parts: Vec<String> = Vec::new();
+        if !self.prefix.is_empty() {
+            parts.push(self.prefix.clone());
+        }
+        if let Some(year) = self.year {
+            parts.push(year.to_string());
+        }
+        parts.push(number);
+        parts.join("-")
+    }
+}
+
+/// 序列号编号方案设置内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SerialSchemeInput {
+    /// 前缀（可为空）
+    #[serde(default)]
+    pub prefix: String,
+    /// 年份（为空表示不含年份）
+    #[serde(default)]
+    pub year: Option<i32>,
+    /// 数字部分补零位数（1-6）
+    pub width: u32,
+    /// 起始序列号
+    pub start: u32,
+}
+
+/// 序列号断号区间（含两端）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SerialGap {
+    /// 起始序列号
+    pub from: u32,
+    /// 结束序列号
+    pub to: u32,
+}
+
+/// 重复的序列号及使用该序列号的卡片
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SerialDuplicate {
+    /// 序列号
+    pub serial: i32,
+    /// 格式化后的序列号
+    pub serial_display: String,
+    /// 使用该序列号的卡片
+    pub cards: Vec<CardWithProject>,
+}
+
+/// 项目序列号检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SerialAudit {
+    /// 编号方案
+    pub scheme: SerialScheme,
+    /// 已编号的卡片数
+    pub numbered: u32,
+    /// 未编号的卡片数
+    pub unnumbered: u32,
+    /// 起始序列号至最大序列号之间的断号
+    pub gaps: Vec<SerialGap>,
+    /// 重复的序列号
+    pub duplicates: Vec<SerialDuplicate>,
+}
+
+/// Excel 导出列
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum ExportColumn {
+    /// 序号（按项目编号方案格式化）
+    Serial,
+    /// 呼号（经由 QSL 管理员时为「X via Y」）
+    Callsign,
+    /// 项目名称
+    Project,
+    /// 数量
+    Qty,
+    /// 状态
+    Status,
+    /// 存放位置
+    StorageLocation,
+    /// 分发方式
+    DistributionMethod,
+    /// 代领人呼号
+    ProxyCallsign,
+    /// 分发地址
+    DistributionAddress,
+    /// 运单号
+    WaybillNo,
+    /// 分发时间
+    DistributedAt,
+    /// 退卡原因
+    ReturnReason,
+    /// 退卡时间
+    ReturnedAt,
+    /// QRZ.cn 缓存中文地址
+    QrzCnChinese,
+    /// QRZ.cn 缓存英文地址
+    QrzCnEnglish,
+    /// QRZ.com 缓存地址
+    QrzComEnglish,
+    /// 录入时间
+    CreatedAt,
+}
+
+impl ExportColumn {
+    /// 未指定导出方案时的默认列
+    pub const DEFAULT: [ExportColumn; 7] = [
+        ExportColumn::Serial,
+        ExportColumn::Callsign,
+        ExportColumn::Qty,
+        ExportColumn::Status,
+        ExportColumn::QrzCnChinese,
+        ExportColumn::QrzCnEnglish,
+        ExportColumn::QrzComEnglish,
+    ];
+
+    /// 表头
+    pub fn header(&self) -> &'static str {
+        match self {
+            ExportColumn::Serial => "序号",
+            ExportColumn::Callsign => "呼号",
+            ExportColumn::Project => "项目",
+            ExportColumn::Qty => "数量",
+            ExportColumn::Status => "状态",
+            ExportColumn::StorageLocation => "存放位置",
+            ExportColumn::DistributionMethod => "分发方式",
+            ExportColumn::ProxyCallsign => "代领人",
+            ExportColumn::DistributionAddress => "分发地址",
+            ExportColumn::WaybillNo => "运单号",
+            ExportColumn::DistributedAt => "分发时间",
+            ExportColumn::ReturnReason => "退卡原因",
+            ExportColumn::ReturnedAt => "退卡时间",
+            ExportColumn::QrzCnChinese => "QRZ.cn(中文)",
+            ExportColumn::QrzCnEnglish => "QRZ.cn(English)",
+            ExportColumn::QrzComEnglish => "QRZ.com",
+            ExportColumn::CreatedAt => "录入时间",
+        }
+    }
+}
+
+/// Excel 导出的工作表分组方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum ExportGroupBy {
+    /// 全部卡片写入同一个工作表
+    #[default]
+    Single,
+    /// 每个项目一个工作表
+    Project,
+    /// 每种状态一个工作表
+    Status,
+}
+
+impl ExportGroupBy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportGroupBy::Single => "single",
+            ExportGroupBy::Project => "project",
+            ExportGroupBy::Status => "status",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "single" => Some(ExportGroupBy::Single),
+            "project" => Some(ExportGroupBy::Project),
+            "status" => Some(ExportGroupBy::Status),
+            _ => None,
+        }
+    }
+}
+
+/// Excel 导出方案设置内容（导出时也可直接使用未保存的设置）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct ExportProfileInput {
+    /// 方案名称
+    pub name: String,
+    /// 导出列（按导出顺序）
+    pub columns: Vec<ExportColumn>,
+    /// 工作表分组方式
+    #[serde(default)]
+    pub group_by: ExportGroupBy,
+    /// 是否附带汇总表
+    #[serde(default)]
+    pub include_summary: bool,
+}
+
+impl Default for ExportProfileInput {
+    fn default() -> Self {
+        Self {
+            name: "默认".to_string(),
+            columns: ExportColumn::DEFAULT.to_vec(),
+            group_by: ExportGroupBy::Single,
+            include_summary: false,
+        }
+    }
+}
+
+/// 已保存的 Excel 导出方案
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct ExportProfile {
+    /// 方案 ID（UUID 格式）
+    pub id: String,
+    /// 方案名称
+    pub name: String,
+    /// 导出列（按导出顺序）
+    pub columns: Vec<ExportColumn>,
+    /// 工作表分组方式
+    pub group_by: ExportGroupBy,
+    /// 是否附带汇总表
+    pub include_summary: bool,
+    /// 创建时间
+    pub created_at: String,
+    /// 更新时间
+    pub updated_at: String,
+}
+
 /// 全局配置项
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
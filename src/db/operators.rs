@@ -0,0 +1,571 @@
+// 操作员管理模块
+//
+// 本地操作员登记（姓名、呼号、可选 PIN）与当前操作员会话。
+// 卡片的录入、分发、退卡、修改、删除均归属到当前登录的操作员
+
+use crate::db::cards::validate_callsign;
+use crate::db::models::{format_datetime, now_china, parse_datetime, Operator, OperatorStats};
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use crate::security::encryption::{derive_key, PBKDF2_ITERATIONS};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{NaiveDate, TimeZone};
+use rusqlite::Connection;
+use subtle::ConstantTimeEq;
+
+/// PIN 验证（4-8 位数字）
+fn validate_pin(pin: &str) -> Result<(), AppError> {
+    if !(4..=8).contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(AppError::InvalidParameter(
+            "PIN 格式无效：必须为 4-8 位数字".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 计算 PIN 哈希（`盐$摘要`，均为 Base64）
+fn hash_pin(pin: &str) -> String {
+    let salt = *uuid::Uuid::new_v4().as_bytes();
+    let digest = derive_key(pin.as_bytes(), &salt, PBKDF2_ITERATIONS);
+    format!("{}${}", STANDARD.encode(salt), STANDARD.encode(digest))
+}
+
+/// 校验 PIN 与存储的哈希是否匹配（摘要按常量时间比较）
+fn verify_pin(pin: &str, pin_hash: &str) -> bool {
+    let Some((salt, digest)) = pin_hash.split_once('$') else {
+        return false;
+    };
+    match (STANDARD.decode(salt), STANDARD.decode(digest)) {
+        (Ok(salt), Ok(digest)) => derive_key(pin.as_bytes(), &salt, PBKDF2_ITERATIONS)
+            .ct_eq(&digest)
+            .into(),
+        _ => false,
+    }
+}
+
+/// 设置了 PIN 的操作员须提供正确的 PIN（`message` 为不匹配时的提示）
+fn check_pin(pin_hash: Option<&str>, pin: Option<&str>, message: &str) -> Result<(), AppError> {
+    if let Some(pin_hash) = pin_hash
+        && !pin.is_some_and(|pin| verify_pin(pin, pin_hash))
+    {
+        return Err(AppError::InvalidParameter(message.to_string()));
+    }
+    Ok(())
+}
+
+/// 规范化呼号（去空白、转大写，空串视为未填写）
+fn normalize_callsign(callsign: Option<String>) -> Result<Option<String>, AppError> {
+    match callsign
+        .map(|c| c.trim().to_uppercase())
+        .filter(|c| !c.is_empty())
+    {
+        Some(callsign) => {
+            validate_callsign(&callsign)?;
+            Ok(Some(callsign))
+        }
+        None => Ok(None),
+    }
+}
+
+/// 将查询行映射为 `Operator`（列序：id, name, callsign, pin_hash, created_at, updated_at）
+fn map_operator_row(row: &rusqlite::Row) -> rusqlite::Result<Operator> {
+    let pin_hash: Option<String> = row.get(3)?;
+    Ok(Operator {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        callsign: row.get(2)?,
+        has_pin: pin_hash.is_some(),
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+/// 查询操作员及其 PIN 哈希
+fn get_operator_with_pin_conn(
+    conn: &Connection,
+    id: &str,
+) -> Result<Option<(Operator, Option<String>)>, AppError> {
+    let result = conn.query_row(
+        "SELECT id, name, callsign, pin_hash, created_at, updated_at FROM operators WHERE id = ?1",
+        [id],
+        |row| Ok((map_operator_row(row)?, row.get::<_, Option<String>>(3)?)),
+    );
+
+    match result {
+        Ok(row) => Ok(Some(row)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询操作员失败: {}", e))),
+    }
+}
+
+/// 检查操作员姓名是否重复（`exclude_id` 为正在修改的操作员自身）
+fn check_operator_name_available(
+    conn: &Connection,
+    name: &str,
+    exclude_id: Option<&str>,
+) -> Result<(), AppError> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM operators WHERE name = ?1 AND id != COALESCE(?2, ''))",
+            rusqlite::params![name, exclude_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Other(format!("查询操作员失败: {}", e)))?;
+
+    if exists {
+        return Err(AppError::InvalidParameter(
+            "操作员姓名已存在，请使用其他名称".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 创建操作员
+pub fn create_operator(
+    name: String,
+    callsign: Option<String>,
+    pin: Option<String>,
+) -> Result<Operator, AppError> {
+    create_operator_in(database()?, name, callsign, pin)
+}
+
+/// 同 [`create_operator`]，在指定的数据库上下文中执行
+pub fn create_operator_in(
+    db: &Database,
+    name: String,
+    callsign: Option<String>,
+    pin: Option<String>,
+) -> Result<Operator, AppError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::InvalidParameter("操作员姓名不能为空".to_string()));
+    }
+    let callsign = normalize_callsign(callsign)?;
+    let pin = pin.filter(|p| !p.is_empty());
+    if let Some(ref pin) = pin {
+        validate_pin(pin)?;
+    }
+
+    let conn = db.connection()?;
+    check_operator_name_available(&conn, &name, None)?;
+
+    let now = format_datetime(&now_china());
+    let operator = Operator {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        callsign,
+        has_pin: pin.is_some(),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+
+    conn.execute(
+        r#"
+        INSERT INTO operators (id, name, callsign, pin_hash, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+        rusqlite::params![
+            &operator.id,
+            &operator.name,
+            &operator.callsign,
+            pin.as_deref().map(hash_pin),
+            &operator.created_at,
+            &operator.updated_at,
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("创建操作员失败: {}", e)))?;
+
+    log::info!("✅ 创建操作员成功: {} ({})", operator.name, operator.id);
+    Ok(operator)
+}
+
+/// 查询操作员列表（按姓名排序）
+pub fn list_operators() -> Result<Vec<Operator>, AppError> {
+    list_operators_in(database()?)
+}
+
+/// 同 [`list_operators`]，在指定的数据库上下文中执行
+pub fn list_operators_in(db: &Database) -> Result<Vec<Operator>, AppError> {
+    let conn = db.connection()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, callsign, pin_hash, created_at, updated_at FROM operators ORDER BY name ASC",
+        )
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let operators = stmt
+        .query_map([], map_operator_row)
+        .map_err(|e| AppError::Other(format!("查询操作员列表失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取操作员数据失败: {}", e)))?;
+
+    Ok(operators)
+}
+
+/// 修改操作员姓名与呼号（设置了 PIN 的操作员须提供正确的 PIN）
+pub fn update_operator(
+    id: &str,
+    name: String,
+    callsign: Option<String>,
+    pin: Option<String>,
+) -> Result<Operator, AppError> {
+    update_operator_in(database()?, id, name, callsign, pin)
+}
+
+/// 同 [`update_operator`]，在指定的数据库上下文中执行
+pub fn update_operator_in(
+    db: &Database,
+    id: &str,
+    name: String,
+    callsign: Option<String>,
+    pin: Option<String>,
+) -> Result<Operator, AppError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::InvalidParameter("操作员姓名不能为空".to_string()));
+    }
+    let callsign = normalize_callsign(callsign)?;
+
+    let conn = db.connection()?;
+    let (_, pin_hash) = get_operator_with_pin_conn(&conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("操作员不存在: {}", id)))?;
+    check_pin(pin_hash.as_deref(), pin.as_deref(), "PIN 不正确")?;
+    check_operator_name_available(&conn, &name, Some(id))?;
+
+    let now = format_datetime(&now_china());
+    conn.execute(
+        "UPDATE operators SET name = ?1, callsign = ?2, updated_at = ?3 WHERE id = ?4",
+        rusqlite::params![name, callsign, now, id],
+    )
+    .map_err(|e| AppError::Other(format!("更新操作员失败: {}", e)))?;
+
+    get_operator_with_pin_conn(&conn, id)?
+        .map(|(operator, _)| operator)
+        .ok_or_else(|| AppError::Other("更新后无法获取操作员".to_string()))
+}
+
+/// 设置或清除操作员 PIN
+///
+/// 已设置 PIN 时须提供正确的当前 PIN；`new_pin` 为空表示清除 PIN。
+pub fn set_operator_pin(
+    id: &str,
+    current_pin: Option<String>,
+    new_pin: Option<String>,
+) -> Result<Operator, AppError> {
+    set_operator_pin_in(database()?, id, current_pin, new_pin)
+}
+
+/// 同 [`set_operator_pin`]，在指定的数据库上下文中执行
+pub fn set_operator_pin_in(
+    db: &Database,
+    id: &str,
+    current_pin: Option<String>,
+    new_pin: Option<String>,
+) -> Result<Operator, AppError> {
+    let new_pin = new_pin.filter(|p| !p.is_empty());
+    if let Some(ref pin) = new_pin {
+        validate_pin(pin)?;
+    }
+
+    let conn = db.connection()?;
+    let (_, pin_hash) = get_operator_with_pin_conn(&conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("操作员不存在: {}", id)))?;
+
+    check_pin(pin_hash.as_deref(), current_pin.as_deref(), "当前 PIN 不正确")?;
+
+    let now = format_datetime(&now_china());
+    conn.execute(
+        "UPDATE operators SET pin_hash = ?1, updated_at = ?2 WHERE id = ?3",
+        rusqlite::params![new_pin.as_deref().map(hash_pin), now, id],
+    )
+    .map_err(|e| AppError::Other(format!("更新操作员 PIN 失败: {}", e)))?;
+
+    log::info!("✅ 更新操作员 PIN 成功: {}", id);
+    get_operator_with_pin_conn(&conn, id)?
+        .map(|(operator, _)| operator)
+        .ok_or_else(|| AppError::Other("更新后无法获取操作员".to_string()))
+}
+
+/// 删除操作员（设置了 PIN 的操作员须提供正确的 PIN）
+///
+/// 已归属到该操作员的卡片操作保留原操作员 ID；若其正处于登录状态则一并注销。
+pub fn delete_operator(id: &str, pin: Option<String>) -> Result<(), AppError> {
+    delete_operator_in(database()?, id, pin)
+}
+
+/// 同 [`delete_operator`]，在指定的数据库上下文中执行
+pub fn delete_operator_in(db: &Database, id: &str, pin: Option<String>) -> Result<(), AppError> {
+    let conn = db.connection()?;
+
+    let (_, pin_hash) = get_operator_with_pin_conn(&conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("操作员不存在: {}", id)))?;
+    check_pin(pin_hash.as_deref(), pin.as_deref(), "PIN 不正确")?;
+
+    conn.execute("DELETE FROM operators WHERE id = ?1", [id])
+        .map_err(|e| AppError::Other(format!("删除操作员失败: {}", e)))?;
+
+    if db.current_operator_id().as_deref() == Some(id) {
+        db.set_current_operator_id(None);
+    }
+
+    log::info!("✅ 删除操作员成功: {}", id);
+    Ok(())
+}
+
+/// 登录为当前操作员（设置了 PIN 的操作员须提供正确的 PIN）
+pub fn login_operator(id: &str, pin: Option<String>) -> Result<Operator, AppError> {
+    login_operator_in(database()?, id, pin)
+}
+
+/// 同 [`login_operator`]，在指定的数据库上下文中执行
+pub fn login_operator_in(
+    db: &Database,
+    id: &str,
+    pin: Option<String>,
+) -> Result<Operator, AppError> {
+    let conn = db.connection()?;
+    let (operator, pin_hash) = get_operator_with_pin_conn(&conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("操作员不存在: {}", id)))?;
+
+    check_pin(pin_hash.as_deref(), pin.as_deref(), "PIN 不正确")?;
+
+    db.set_current_operator_id(Some(operator.id.clone()));
+    log::info!("👤 当前操作员: {} ({})", operator.name, operator.id);
+    Ok(operator)
+}
+
+/// 注销当前操作员
+pub fn logout_operator() -> Result<(), AppError> {
+    logout_operator_in(database()?);
+    Ok(())
+}
+
+/// 同 [`logout_operator`]，在指定的数据库上下文中执行
+pub fn logout_operator_in(db: &Database) {
+    db.set_current_operator_id(None);
+    log::info!("👤 操作员已注销");
+}
+
+/// 获取当前登录的操作员
+pub fn get_current_operator() -> Result<Option<Operator>, AppError> {
+    get_current_operator_in(database()?)
+}
+
+/// 同 [`get_current_operator`]，在指定的数据库上下文中执行
+pub fn get_current_operator_in(db: &Database) -> Result<Option<Operator>, AppError> {
+    let Some(id) = db.current_operator_id() else {
+        return Ok(None);
+    };
+
+    let conn = db.connection()?;
+    Ok(get_operator_with_pin_conn(&conn, &id)?.map(|(operator, _)| operator))
+}
+
+/// 按操作员统计工作量
+///
+/// `from`/`to` 为可选的时间区间（含两端；`to` 仅传日期时包含当天）。
+pub fn get_operator_stats(
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<OperatorStats>, AppError> {
+    get_operator_stats_in(database()?, from, to)
+}
+
+/// 同 [`get_operator_stats`]，在指定的数据库上下文中执行
+pub fn get_operator_stats_in(
+    db: &Database,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<OperatorStats>, AppError> {
+    let conn = db.connection()?;
+    get_operator_stats_conn(&conn, from, to)
+}
+
+/// 统计区间终点转为不含的上界：仅传日期（`YYYY-MM-DD`）时为东八区次日零点，
+/// 传完整时间时为其后一秒
+fn exclusive_end(to: &str) -> Result<String, AppError> {
+    let invalid = || AppError::InvalidParameter(format!("统计截止时间无效: {}", to));
+    let end = match NaiveDate::parse_from_str(to, "%Y-%m-%d") {
+        Ok(date) => {
+            let midnight = date
+                .succ_opt()
+                .and_then(|next| next.and_hms_opt(0, 0, 0))
+                .ok_or_else(invalid)?;
+            now_china()
+                .offset()
+                .from_local_datetime(&midnight)
+                .single()
+                .ok_or_else(invalid)?
+        }
+        Err(_) => parse_datetime(to).map_err(|_| invalid())? + chrono::Duration::seconds(1),
+    };
+    Ok(format_datetime(&end))
+}
+
+/// 工作量统计主体（crate-private，接收连接以便测试）。
+fn get_operator_stats_conn(
+    conn: &Connection,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<OperatorStats>, AppError> {
+    let before = to.as_deref().map(exclusive_end).transpose()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+            WITH events AS (
+                SELECT operator_id, event_type FROM card_events
+                WHERE operator_id IS NOT NULL
+                  AND (?1 IS NULL OR created_at >= ?1)
+                  AND (?2 IS NULL OR created_at < ?2)
+            ),
+            edits AS (
+                SELECT operator_id FROM card_history
+                WHERE operator_id IS NOT NULL
+                  AND (?1 IS NULL OR changed_at >= ?1)
+                  AND (?2 IS NULL OR changed_at < ?2)
+            )
+            SELECT
+                o.id,
+                o.name,
+                o.callsign,
+                (SELECT COUNT(*) FROM events e WHERE e.operator_id = o.id AND e.event_type = 'entered'),
+                (SELECT COUNT(*) FROM events e WHERE e.operator_id = o.id AND e.event_type IN ('distributed', 'redistributed')),
+                (SELECT COUNT(*) FROM events e WHERE e.operator_id = o.id AND e.event_type = 'returned'),
+                (SELECT COUNT(*) FROM edits h WHERE h.operator_id = o.id)
+            FROM operators o
+            ORDER BY o.name ASC
+            "#,
+        )
+        .map_err(|e| AppError::Other(format!("准备统计语句失败: {}", e)))?;
+
+    let stats = stmt
+        .query_map(rusqlite::params![from, before], |row| {
+            Ok(OperatorStats {
+                operator_id: row.get(0)?,
+                name: row.get(1)?,
+                callsign: row.get(2)?,
+                entered: row.get(3)?,
+                distributed: row.get(4)?,
+                returned: row.get(5)?,
+                edited: row.get(6)?,
+            })
+        })
+        .map_err(|e| AppError::Other(format!("查询操作员统计失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取操作员统计失败: {}", e)))?;
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::cards::{
+        create_card_in, delete_card_in, distribute_card_in, get_card_history_in, get_card_in,
+        return_card_in, update_card_in,
+    };
+    use crate::db::models::CardUpdate;
+    use crate::db::projects::create_project_in;
+
+    /// PIN 登录：错误 PIN 拒绝，正确 PIN 建立会话，删除操作员后会话注销
+    #[test]
+    fn test_operator_login_with_pin() {
+        let db = Database::open_in_memory().unwrap();
+        let operator =
+            create_operator_in(&db, "张三".to_string(), Some("bh2ro".to_string()), Some("1234".to_string()))
+                .unwrap();
+        assert_eq!(operator.callsign.as_deref(), Some("BH2RO"));
+        assert!(operator.has_pin);
+        assert!(create_operator_in(&db, "张三".to_string(), None, None).is_err());
+        assert!(create_operator_in(&db, "李四".to_string(), None, Some("12a4".to_string())).is_err());
+
+        assert!(login_operator_in(&db, &operator.id, None).is_err());
+        assert!(login_operator_in(&db, &operator.id, Some("4321".to_string())).is_err());
+        assert!(get_current_operator_in(&db).unwrap().is_none());
+
+        login_operator_in(&db, &operator.id, Some("1234".to_string())).unwrap();
+        assert_eq!(db.current_operator_id().as_deref(), Some(operator.id.as_str()));
+
+        // 改名与删除同样须提供 PIN
+        assert!(update_operator_in(&db, &operator.id, "王五".to_string(), None, None).is_err());
+        let renamed = update_operator_in(
+            &db,
+            &operator.id,
+            "王五".to_string(),
+            None,
+            Some("1234".to_string()),
+        )
+        .unwrap();
+        assert_eq!(renamed.name, "王五");
+        assert!(delete_operator_in(&db, &operator.id, Some("4321".to_string())).is_err());
+
+        assert!(set_operator_pin_in(&db, &operator.id, None, None).is_err());
+        let cleared = set_operator_pin_in(&db, &operator.id, Some("1234".to_string()), None).unwrap();
+        assert!(!cleared.has_pin);
+
+        delete_operator_in(&db, &operator.id, None).unwrap();
+        assert!(db.current_operator_id().is_none());
+    }
+
+    /// 卡片操作归属到当前操作员，并计入工作量统计
+    #[test]
+    fn test_card_mutations_attributed_to_operator() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let alice = create_operator_in(&db, "Alice".to_string(), None, None).unwrap();
+        let bob = create_operator_in(&db, "Bob".to_string(), None, None).unwrap();
+
+        login_operator_in(&db, &alice.id, None).unwrap();
+        let card = create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, None).unwrap();
+        let other = create_card_in(&db, project.id.clone(), "BG2ABC".to_string(), 1, None).unwrap();
+        assert_eq!(card.creator_id.as_deref(), Some(alice.id.as_str()));
+
+        login_operator_in(&db, &bob.id, None).unwrap();
+        distribute_card_in(&db, &card.id, "自取".to_string(), None, None, None, None).unwrap();
+        return_card_in(&db, &other.id, "NOT FOUND".to_string(), None).unwrap();
+        let update = CardUpdate {
+            qty: Some(3),
+            ..Default::default()
+        };
+        update_card_in(&db, &card.id, update, None).unwrap();
+        let history = get_card_history_in(&db, &card.id).unwrap();
+        assert_eq!(history[0].operator_id.as_deref(), Some(bob.id.as_str()));
+        assert_eq!(history[0].changed_by.as_deref(), Some("Bob"));
+
+        delete_card_in(&db, &other.id).unwrap();
+        assert!(get_card_in(&db, &other.id).unwrap().is_none());
+        let deleted_by: Option<String> = db
+            .connection()
+            .unwrap()
+            .query_row("SELECT deleted_by FROM cards WHERE id = ?1", [&other.id], |r| r.get(0))
+            .unwrap();
+        assert_eq!(deleted_by.as_deref(), Some(bob.id.as_str()));
+
+        let stats = get_operator_stats_in(&db, None, None).unwrap();
+        let alice_stats = stats.iter().find(|s| s.operator_id == alice.id).unwrap();
+        let bob_stats = stats.iter().find(|s| s.operator_id == bob.id).unwrap();
+        assert_eq!((alice_stats.entered, alice_stats.distributed), (2, 0));
+        assert_eq!(
+            (bob_stats.entered, bob_stats.distributed, bob_stats.returned, bob_stats.edited),
+            (0, 1, 1, 1)
+        );
+
+        let none = get_operator_stats_in(&db, Some("2999-01-01".to_string()), None).unwrap();
+        assert!(none.iter().all(|s| s.entered == 0 && s.distributed == 0));
+
+        // 截止日期包含当天；截止时间包含该秒
+        let today = now_china().format("%Y-%m-%d").to_string();
+        let until_today = get_operator_stats_in(&db, None, Some(today)).unwrap();
+        let alice_today = until_today.iter().find(|s| s.operator_id == alice.id).unwrap();
+        assert_eq!(alice_today.entered, 2);
+        let until_2000 = get_operator_stats_in(&db, None, Some("2000-01-01".to_string())).unwrap();
+        assert!(until_2000.iter().all(|s| s.entered == 0));
+        assert_eq!(
+            exclusive_end("2026-10-18T23:59:59+08:00").unwrap(),
+            "2026-10-19T00:00:00+08:00"
+        );
+        assert_eq!(exclusive_end("2026-12-31").unwrap(), "2027-01-01T00:00:00+08:00");
+        assert!(get_operator_stats_in(&db, None, Some("2026-13-01".to_string())).is_err());
+    }
+}
@@ -3,33 +3,51 @@
 // 提供转卡项目的 CRUD 操作
 
 use crate::db::models::{format_datetime, now_china, Project, ProjectWithStats};
-use crate::db::sqlite::get_connection;
+use crate::db::sqlite::{database, Database};
 use crate::error::AppError;
+use rusqlite::Connection;
+
+/// 检查项目名称是否可用（`exclude_id` 为正在改名或恢复的项目自身）
+pub(crate) fn check_project_name_available(
+    conn: &Connection,
+    name: &str,
+    exclude_id: Option<&str>,
+) -> Result<(), AppError> {
+    let result = conn.query_row(
+        "SELECT deleted_at IS NOT NULL FROM projects WHERE name = ?1 AND id != COALESCE(?2, '')",
+        rusqlite::params![name, exclude_id],
+        |row| row.get::<_, bool>(0),
+    );
+
+    match result {
+        Ok(true) => Err(AppError::InvalidParameter(
+            "回收站中已有同名项目，请先恢复或彻底删除".to_string(),
+        )),
+        Ok(false) => Err(AppError::InvalidParameter(
+            "项目名称已存在，请使用其他名称".to_string(),
+        )),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(()),
+        Err(e) => Err(AppError::Other(format!("查询项目失败: {}", e))),
+    }
+}
 
 /// 创建新项目
 pub fn create_project(name: String) -> Result<Project, AppError> {
+    create_project_in(database()?, name)
+}
+
+/// 同 [`create_project`]，在指定的数据库上下文中执行
+pub fn create_project_in(db: &Database, name: String) -> Result<Project, AppError> {
     // 验证名称不为空
     let name = name.trim().to_string();
     if name.is_empty() {
         return Err(AppError::InvalidParameter("项目名称不能为空".to_string()));
     }
 
-    let conn = get_connection()?;
+    let conn = db.connection()?;
 
-    // 检查名称是否重复
-    let exists: bool = conn
-        .query_row(
-            "SELECT EXISTS(SELECT 1 FROM projects WHERE name = ?1)",
-            [&name],
-            |row| row.get(0),
-        )
-        .map_err(|e| AppError::Other(format!("查询项目失败: {}", e)))?;
-
-    if exists {
-        return Err(AppError::InvalidParameter(
-            "项目名称已存在，请使用其他名称".to_string(),
-        ));
-    }
+    // 检查名称是否重复（回收站中的项目同样占用名称）
+    check_project_name_available(&conn, &name, None)?;
 
     // 创建项目
     let project = Project::new(name);
@@ -49,9 +67,14 @@ pub fn create_project(name: String) -> Result<Project, AppError> {
     Ok(project)
 }
 
-/// 查询项目列表（带统计信息）
+/// 查询项目列表（带统计信息，不含回收站中的项目与卡片）
 pub fn list_projects() -> Result<Vec<ProjectWithStats>, AppError> {
-    let conn = get_connection()?;
+    list_projects_in(database()?)
+}
+
+/// 同 [`list_projects`]，在指定的数据库上下文中执行
+pub fn list_projects_in(db: &Database) -> Result<Vec<ProjectWithStats>, AppError> {
+    let conn = db.connection()?;
 
     let mut stmt = conn
         .prepare(
@@ -63,10 +86,15 @@ pub fn list_projects() -> Result<Vec<ProjectWithStats>, AppError> {
                 p.updated_at,
                 COALESCE(COUNT(c.id), 0) as total_cards,
                 COALESCE(SUM(CASE WHEN c.status = 'pending' THEN 1 ELSE 0 END), 0) as pending_cards,
+                COALESCE(SUM(CASE WHEN c.status = 'partially_distributed' THEN 1 ELSE 0 END), 0) as partially_distributed_cards,
                 COALESCE(SUM(CASE WHEN c.status = 'distributed' THEN 1 ELSE 0 END), 0) as distributed_cards,
-                COALESCE(SUM(CASE WHEN c.status = 'returned' THEN 1 ELSE 0 END), 0) as returned_cards
+                COALESCE(SUM(CASE WHEN c.status = 'returned' THEN 1 ELSE 0 END), 0) as returned_cards,
+                COALESCE(SUM(CASE WHEN c.status = 'awaiting_postage' THEN 1 ELSE 0 END), 0) as awaiting_postage_cards,
+                COALESCE(SUM(CASE WHEN c.status = 'held' THEN 1 ELSE 0 END), 0) as held_cards,
+                COALESCE(SUM(CASE WHEN c.status = 'lost' THEN 1 ELSE 0 END), 0) as lost_cards
             FROM projects p
-            LEFT JOIN cards c ON p.id = c.project_id
+            LEFT JOIN cards c ON p.id = c.project_id AND c.deleted_at IS NULL
+            WHERE p.deleted_at IS NULL
             GROUP BY p.id
             ORDER BY p.created_at DESC
             "#,
@@ -82,8 +110,12 @@ pub fn list_projects() -> Result<Vec<ProjectWithStats>, AppError> {
                 updated_at: row.get(3)?,
                 total_cards: row.get(4)?,
                 pending_cards: row.get(5)?,
-                distributed_cards: row.get(6)?,
-                returned_cards: row.get(7)?,
+                partially_distributed_cards: row.get(6)?,
+                distributed_cards: row.get(7)?,
+                returned_cards: row.get(8)?,
+                awaiting_postage_cards: row.get(9)?,
+                held_cards: row.get(10)?,
+                lost_cards: row.get(11)?,
             })
         })
         .map_err(|e| AppError::Other(format!("查询项目列表失败: {}", e)))?
@@ -95,10 +127,15 @@ pub fn list_projects() -> Result<Vec<ProjectWithStats>, AppError> {
 
 /// 获取单个项目
 pub fn get_project(id: &str) -> Result<Option<Project>, AppError> {
-    let conn = get_connection()?;
+    get_project_in(database()?, id)
+}
+
+/// 同 [`get_project`]，在指定的数据库上下文中执行
+pub fn get_project_in(db: &Database, id: &str) -> Result<Option<Project>, AppError> {
+    let conn = db.connection()?;
 
     let result = conn.query_row(
-        "SELECT id, name, created_at, updated_at FROM projects WHERE id = ?1",
+        "SELECT id, name, created_at, updated_at FROM projects WHERE id = ?1 AND deleted_at IS NULL",
         [id],
         |row| {
             Ok(Project {
@@ -119,18 +156,23 @@ pub fn get_project(id: &str) -> Result<Option<Project>, AppError> {
 
 /// 更新项目名称
 pub fn update_project(id: &str, name: String) -> Result<Project, AppError> {
+    update_project_in(database()?, id, name)
+}
+
+/// 同 [`update_project`]，在指定的数据库上下文中执行
+pub fn update_project_in(db: &Database, id: &str, name: String) -> Result<Project, AppError> {
     // 验证名称不为空
     let name = name.trim().to_string();
     if name.is_empty() {
         return Err(AppError::InvalidParameter("项目名称不能为空".to_string()));
     }
 
-    let conn = get_connection()?;
+    let conn = db.connection()?;
 
     // 检查项目是否存在
     let exists: bool = conn
         .query_row(
-            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1)",
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1 AND deleted_at IS NULL)",
             [id],
             |row| row.get(0),
         )
@@ -141,19 +183,7 @@ pub fn update_project(id: &str, name: String) -> Result<Project, AppError> {
     }
 
     // 检查新名称是否与其他项目重复
-    let name_exists: bool = conn
-        .query_row(
-            "SELECT EXISTS(SELECT 1 FROM projects WHERE name = ?1 AND id != ?2)",
-            [&name, id],
-            |row| row.get(0),
-        )
-        .map_err(|e| AppError::Other(format!("查询项目失败: {}", e)))?;
-
-    if name_exists {
-        return Err(AppError::InvalidParameter(
-            "项目名称已存在，请使用其他名称".to_string(),
-        ));
-    }
+    check_project_name_available(&conn, &name, Some(id))?;
 
     // 更新项目
     let updated_at = format_datetime(&now_china());
@@ -165,28 +195,67 @@ pub fn update_project(id: &str, name: String) -> Result<Project, AppError> {
     .map_err(|e| AppError::Other(format!("更新项目失败: {}", e)))?;
 
     // 返回更新后的项目
-    get_project(id)?.ok_or_else(|| AppError::Other("更新后无法获取项目".to_string()))
+    get_project_in(db, id)?.ok_or_else(|| AppError::Other("更新后无法获取项目".to_string()))
 }
 
-/// 删除项目
+/// 删除项目（连同其卡片移入回收站，可恢复或彻底删除）
 pub fn delete_project(id: &str) -> Result<(), AppError> {
-    let conn = get_connection()?;
+    delete_project_in(database()?, id)
+}
+
+/// 同 [`delete_project`]，在指定的数据库上下文中执行
+pub fn delete_project_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let conn = db.connection()?;
 
     // 检查项目是否存在
-    let project = get_project(id)?;
+    let project = get_project_in(db, id)?;
     if project.is_none() {
         return Err(AppError::ProfileNotFound(format!("项目不存在: {}", id)));
     }
 
-    // 删除项目（级联删除关联的卡片）
-    conn.execute("DELETE FROM projects WHERE id = ?1", [id])
-        .map_err(|e| AppError::Other(format!("删除项目失败: {}", e)))?;
+    // 仅标记项目，卡片保持原样，随项目一并隐藏
+    let now = format_datetime(&now_china());
+    conn.execute(
+        "UPDATE projects SET deleted_at = ?1, updated_at = ?1 WHERE id = ?2",
+        rusqlite::params![now, id],
+    )
+    .map_err(|e| AppError::Other(format!("删除项目失败: {}", e)))?;
 
-    log::info!("✅ 删除项目成功: {}", id);
+    log::info!("✅ 项目已移入回收站: {}", id);
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    // 测试需要独立的数据库实例，暂时跳过
+    use super::*;
+    use crate::db::cards::{create_card_in, distribute_card_in};
+
+    /// 在独立的内存数据库上完成项目的增删改查与统计
+    #[test]
+    fn test_project_crud_in_memory() {
+        let db = Database::open_in_memory().unwrap();
+
+        let project = create_project_in(&db, "  2026 活动  ".to_string()).unwrap();
+        assert_eq!(project.name, "2026 活动");
+        assert!(create_project_in(&db, "2026 活动".to_string()).is_err());
+
+        create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, None).unwrap();
+        let card = create_card_in(&db, project.id.clone(), "BG2ABC".to_string(), 2, None).unwrap();
+        distribute_card_in(&db, &card.id, "自取".to_string(), None, None, None, None).unwrap();
+        let card = create_card_in(&db, project.id.clone(), "BA1AA".to_string(), 3, None).unwrap();
+        distribute_card_in(&db, &card.id, "自取".to_string(), None, None, None, Some(1)).unwrap();
+
+        let projects = list_projects_in(&db).unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].total_cards, 3);
+        assert_eq!(projects[0].pending_cards, 1);
+        assert_eq!(projects[0].partially_distributed_cards, 1);
+        assert_eq!(projects[0].distributed_cards, 1);
+
+        let renamed = update_project_in(&db, &project.id, "改名".to_string()).unwrap();
+        assert_eq!(renamed.name, "改名");
+
+        delete_project_in(&db, &project.id).unwrap();
+        assert!(get_project_in(&db, &project.id).unwrap().is_none());
+    }
 }
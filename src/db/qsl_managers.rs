@@ -0,0 +1,565 @@
+// QSL 管理员模块
+//
+// 维护电台与其 QSL 管理员的对应关系（含有效期），支持从简单 CSV 列表导入；
+// 录入卡片时按有效期查出实际收卡人，标注为「X via Y」
+
+use crate::db::callsign_aliases::resolved_callsign_sql;
+use crate::db::cards::validate_callsign;
+use crate::db::models::{format_datetime, now_china, QslManager, QslManagerImportReport};
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use chrono::NaiveDate;
+use rusqlite::Connection;
+
+/// 查询列（与 `map_manager_row` 的列序一致）
+const MANAGER_COLUMNS: &str =
+    "id, callsign, manager, effective_from, effective_to, remarks, created_at, updated_at";
+
+/// 将查询行映射为 `QslManager`（列序见 `MANAGER_COLUMNS`）
+fn map_manager_row(row: &rusqlite::Row) -> rusqlite::Result<QslManager> {
+    Ok(QslManager {
+        id: row.get(0)?,
+        callsign: row.get(1)?,
+        manager: row.get(2)?,
+        effective_from: row.get(3)?,
+        effective_to: row.get(4)?,
+        remarks: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+/// 查询某呼号在指定日期经由的 QSL 管理员（crate-private，录入卡片时标注）
+///
+/// `date` 为 `YYYY-MM-DD…` 格式（仅比较前 10 位）。优先按呼号本身匹配，
+/// 未登记时按别名解析后的现用呼号匹配。
+pub(crate) fn effective_manager_conn(
+    conn: &Connection,
+    callsign: &str,
+    date: &str,
+) -> Result<Option<String>, AppError> {
+    let result = conn.query_row(
+        &format!(
+            r#"
+            SELECT manager FROM qsl_managers
+            WHERE callsign IN (UPPER(?1), {resolved})
+              AND COALESCE(effective_from, '') <= substr(?2, 1, 10)
+              AND (effective_to IS NULL OR effective_to >= substr(?2, 1, 10))
+            ORDER BY callsign = UPPER(?1) DESC
+            LIMIT 1
+            "#,
+            resolved = resolved_callsign_sql("?1", "?2"),
+        ),
+        [callsign, date],
+        |row| row.get(0),
+    );
+
+    match result {
+        Ok(manager) => Ok(Some(manager)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询 QSL 管理员失败: {}", e))),
+    }
+}
+
+fn get_manager_conn(conn: &Connection, id: &str) -> Result<Option<QslManager>, AppError> {
+    let result = conn.query_row(
+        &format!("SELECT {} FROM qsl_managers WHERE id = ?1", MANAGER_COLUMNS),
+        [id],
+        map_manager_row,
+    );
+
+    match result {
+        Ok(manager) => Ok(Some(manager)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询 QSL 管理员失败: {}", e))),
+    }
+}
+
+/// 校验并规范化可选日期（YYYY-MM-DD，空白视为未填写）
+fn normalize_date(value: Option<&str>, field: &str) -> Result<Option<String>, AppError> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .map(|d| Some(d.format("%Y-%m-%d").to_string()))
+            .map_err(|_| AppError::InvalidParameter(format!("{}格式无效，应为 YYYY-MM-DD", field))),
+        None => Ok(None),
+    }
+}
+
+/// 已校验的登记内容
+struct ManagerEntry {
+    callsign: String,
+    manager: String,
+    effective_from: Option<String>,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+}
+
+impl ManagerEntry {
+    /// 校验呼号与日期，统一大写
+    fn parse(
+        callsign: &str,
+        manager: &str,
+        effective_from: Option<&str>,
+        effective_to: Option<&str>,
+        remarks: Option<&str>,
+    ) -> Result<Self, AppError> {
+        let callsign = callsign.trim().to_uppercase();
+        let manager = manager.trim().to_uppercase();
+        validate_callsign(&callsign)?;
+        validate_callsign(&manager)?;
+        if callsign == manager {
+            return Err(AppError::InvalidParameter(
+                "QSL 管理员不能是电台本身".to_string(),
+            ));
+        }
+
+        let effective_from = normalize_date(effective_from, "生效日期")?;
+        let effective_to = normalize_date(effective_to, "失效日期")?;
+        if let (Some(from), Some(to)) = (&effective_from, &effective_to)
+            && to < from
+        {
+            return Err(AppError::InvalidParameter(
+                "失效日期不能早于生效日期".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            callsign,
+            manager,
+            effective_from,
+            effective_to,
+            remarks: remarks
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .map(str::to_string),
+        })
+    }
+}
+
+/// 查询同一电台有效期重叠的登记（排除 `id` 自身）
+fn find_overlapping_conn(
+    conn: &Connection,
+    id: Option<&str>,
+    entry: &ManagerEntry,
+) -> Result<Option<QslManager>, AppError> {
+    let result = conn.query_row(
+        &format!(
+            r#"
+            SELECT {} FROM qsl_managers
+            WHERE callsign = ?1 AND (?2 IS NULL OR id != ?2)
+              AND COALESCE(effective_from, '') <= COALESCE(?4, '9999-12-31')
+              AND COALESCE(effective_to, '9999-12-31') >= COALESCE(?3, '')
+            LIMIT 1
+            "#,
+            MANAGER_COLUMNS
+        ),
+        rusqlite::params![entry.callsign, id, entry.effective_from, entry.effective_to],
+        map_manager_row,
+    );
+
+    match result {
+        Ok(manager) => Ok(Some(manager)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询 QSL 管理员失败: {}", e))),
+    }
+}
+
+/// 有效期重叠的错误信息
+fn overlap_error(entry: &ManagerEntry, existing: &QslManager) -> AppError {
+    AppError::InvalidParameter(format!(
+        "{} 在该时段内已登记 QSL 管理员 {}",
+        entry.callsign, existing.manager
+    ))
+}
+
+/// 写入一条登记（crate-private，调用方已完成校验）
+fn insert_manager_conn(conn: &Connection, entry: ManagerEntry) -> Result<QslManager, AppError> {
+    let now = format_datetime(&now_china());
+    let manager = QslManager {
+        id: uuid::Uuid::new_v4().to_string(),
+        callsign: entry.callsign,
+        manager: entry.manager,
+        effective_from: entry.effective_from,
+        effective_to: entry.effective_to,
+        remarks: entry.remarks,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+
+    conn.execute(
+        r#"
+        INSERT INTO qsl_managers (id, callsign, manager, effective_from, effective_to, remarks, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        "#,
+        rusqlite::params![
+            &manager.id,
+            &manager.callsign,
+            &manager.manager,
+            &manager.effective_from,
+            &manager.effective_to,
+            &manager.remarks,
+            &manager.created_at,
+            &manager.updated_at,
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("登记 QSL 管理员失败: {}", e)))?;
+
+    Ok(manager)
+}
+
+/// 查询 QSL 管理员登记（按电台呼号、生效日期排序）
+///
+/// `callsign` 不为空时仅返回电台或管理员呼号与之相同的登记。
+pub fn list_qsl_managers(callsign: Option<&str>) -> Result<Vec<QslManager>, AppError> {
+    list_qsl_managers_in(database()?, callsign)
+}
+
+/// 同 [`list_qsl_managers`]，在指定的数据库上下文中执行
+pub fn list_qsl_managers_in(
+    db: &Database,
+    callsign: Option<&str>,
+) -> Result<Vec<QslManager>, AppError> {
+    let conn = db.connection()?;
+    let callsign = callsign.map(|c| c.trim().to_uppercase());
+
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+            SELECT {} FROM qsl_managers
+            WHERE ?1 IS NULL OR callsign = ?1 OR manager = ?1
+            ORDER BY callsign ASC, COALESCE(effective_from, '') ASC
+            "#,
+            MANAGER_COLUMNS
+        ))
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let managers = stmt
+        .query_map([callsign], map_manager_row)
+        .map_err(|e| AppError::Other(format!("查询 QSL 管理员失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取 QSL 管理员失败: {}", e)))?;
+
+    Ok(managers)
+}
+
+/// 登记 QSL 管理员
+///
+/// 同一电台的有效期不可重叠；已录入的卡片不受影响，仅之后录入的卡片按新登记标注。
+pub fn create_qsl_manager(
+    callsign: String,
+    manager: String,
+    effective_from: Option<String>,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<QslManager, AppError> {
+    create_qsl_manager_in(
+        database()?,
+        callsign,
+        manager,
+        effective_from,
+        effective_to,
+        remarks,
+    )
+}
+
+/// 同 [`create_qsl_manager`]，在指定的数据库上下文中执行
+pub fn create_qsl_manager_in(
+    db: &Database,
+    callsign: String,
+    manager: String,
+    effective_from: Option<String>,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<QslManager, AppError> {
+    let entry = ManagerEntry::parse(
+        &callsign,
+        &manager,
+        effective_from.as_deref(),
+        effective_to.as_deref(),
+        remarks.as_deref(),
+    )?;
+
+    let conn = db.connection()?;
+    if let Some(existing) = find_overlapping_conn(&conn, None, &entry)? {
+        return Err(overlap_error(&entry, &existing));
+    }
+
+    let manager = insert_manager_conn(&conn, entry)?;
+    log::info!(
+        "✅ 登记 QSL 管理员成功: {} via {}",
+        manager.callsign,
+        manager.manager
+    );
+    Ok(manager)
+}
+
+/// 修改 QSL 管理员登记（管理员呼号、有效期、备注）
+pub fn update_qsl_manager(
+    id: &str,
+    manager: String,
+    effective_from: Option<String>,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<QslManager, AppError> {
+    update_qsl_manager_in(
+        database()?,
+        id,
+        manager,
+        effective_from,
+        effective_to,
+        remarks,
+    )
+}
+
+/// 同 [`update_qsl_manager`]，在指定的数据库上下文中执行
+pub fn update_qsl_manager_in(
+    db: &Database,
+    id: &str,
+    manager: String,
+    effective_from: Option<String>,
+    effective_to: Option<String>,
+    remarks: Option<String>,
+) -> Result<QslManager, AppError> {
+    let conn = db.connection()?;
+    let current = get_manager_conn(&conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("QSL 管理员登记不存在: {}", id)))?;
+
+    let entry = ManagerEntry::parse(
+        &current.callsign,
+        &manager,
+        effective_from.as_deref(),
+        effective_to.as_deref(),
+        remarks.as_deref(),
+    )?;
+    if let Some(existing) = find_overlapping_conn(&conn, Some(id), &entry)? {
+        return Err(overlap_error(&entry, &existing));
+    }
+
+    let now = format_datetime(&now_china());
+    conn.execute(
+        r#"
+        UPDATE qsl_managers
+        SET manager = ?1, effective_from = ?2, effective_to = ?3, remarks = ?4, updated_at = ?5
+        WHERE id = ?6
+        "#,
+        rusqlite::params![
+            entry.manager,
+            entry.effective_from,
+            entry.effective_to,
+            entry.remarks,
+            now,
+            id
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("更新 QSL 管理员失败: {}", e)))?;
+
+    log::info!(
+        "✅ 更新 QSL 管理员成功: {} via {}",
+        entry.callsign,
+        entry.manager
+    );
+    get_manager_conn(&conn, id)?
+        .ok_or_else(|| AppError::Other("更新后无法获取 QSL 管理员登记".to_string()))
+}
+
+/// 删除 QSL 管理员登记（已录入卡片的标注保留）
+pub fn delete_qsl_manager(id: &str) -> Result<(), AppError> {
+    delete_qsl_manager_in(database()?, id)
+}
+
+/// 同 [`delete_qsl_manager`]，在指定的数据库上下文中执行
+pub fn delete_qsl_manager_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let conn = db.connection()?;
+
+    let affected = conn
+        .execute("DELETE FROM qsl_managers WHERE id = ?1", [id])
+        .map_err(|e| AppError::Other(format!("删除 QSL 管理员失败: {}", e)))?;
+
+    if affected == 0 {
+        return Err(AppError::ProfileNotFound(format!(
+            "QSL 管理员登记不存在: {}",
+            id
+        )));
+    }
+
+    log::info!("✅ 删除 QSL 管理员成功: {}", id);
+    Ok(())
+}
+
+/// 从 CSV 文本导入 QSL 管理员
+///
+/// 每行 `呼号,管理员[,生效日期[,失效日期[,备注]]]`，日期为 YYYY-MM-DD，可留空。
+/// 空行与 `#` 开头的行忽略；首行第一列不含数字时视为表头跳过。
+/// 与已有登记完全相同的行跳过，格式错误或有效期冲突的行记入错误、不导入，其余行在同一事务内写入。
+pub fn import_qsl_managers_csv(content: &str) -> Result<QslManagerImportReport, AppError> {
+    import_qsl_managers_csv_in(database()?, content)
+}
+
+/// 同 [`import_qsl_managers_csv`]，在指定的数据库上下文中执行
+pub fn import_qsl_managers_csv_in(
+    db: &Database,
+    content: &str,
+) -> Result<QslManagerImportReport, AppError> {
+    let mut report = QslManagerImportReport {
+        imported: 0,
+        skipped: 0,
+        errors: Vec::new(),
+    };
+
+    let mut conn = db.connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line
+            .split(',')
+            .map(|f| f.trim().trim_matches('"'))
+            .collect();
+        // 呼号必含数字，首行第一列不含数字时视为表头
+        if line_no == 1 && !fields[0].chars().any(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        if fields.len() < 2 {
+            report
+                .errors
+                .push(format!("第 {} 行：缺少 QSL 管理员呼号", line_no));
+            continue;
+        }
+
+        let parsed = ManagerEntry::parse(
+            fields[0],
+            fields[1],
+            fields.get(2).copied(),
+            fields.get(3).copied(),
+            fields.get(4).copied(),
+        );
+        let entry = match parsed {
+            Ok(entry) => entry,
+            Err(e) => {
+                report.errors.push(format!("第 {} 行：{}", line_no, e));
+                continue;
+            }
+        };
+
+        match find_overlapping_conn(&tx, None, &entry)? {
+            Some(existing)
+                if existing.manager == entry.manager
+                    && existing.effective_from == entry.effective_from
+                    && existing.effective_to == entry.effective_to =>
+            {
+                report.skipped += 1;
+            }
+            Some(existing) => {
+                report.errors.push(format!(
+                    "第 {} 行：{}",
+                    line_no,
+                    overlap_error(&entry, &existing)
+                ));
+            }
+            None => {
+                insert_manager_conn(&tx, entry)?;
+                report.imported += 1;
+            }
+        }
+    }
+
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!(
+        "✅ 导入 QSL 管理员: 新增 {} 条，跳过 {} 条，错误 {} 行",
+        report.imported,
+        report.skipped,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::cards::{create_card_in, list_cards_in, update_card_in};
+    use crate::db::models::{CardFilter, CardUpdate, Pagination};
+    use crate::db::projects::create_project_in;
+
+    /// CSV 导入：表头、注释与重复行跳过，错误行记录行号，有效期冲突不导入
+    #[test]
+    fn test_import_csv_reports_errors_per_line() {
+        let db = Database::open_in_memory().unwrap();
+        let csv = "callsign,manager,from,to\n\
+                   # DX 管理员\n\
+                   VK9XX,ve3abc\n\
+                   3Y0J,LA7GIA,2023-01-01,2023-12-31\n\
+                   3Y0J,DL1XYZ,2023-06-01\n\
+                   BAD!,BH2RO\n\
+                   JA1ZZ\n\
+                   VK9XX,VE3ABC\n";
+
+        let report = import_qsl_managers_csv_in(&db, csv).unwrap();
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.errors.len(), 3);
+        assert!(report.errors[0].starts_with("第 5 行"));
+        assert!(report.errors[2].starts_with("第 7 行"));
+
+        let managers = list_qsl_managers_in(&db, Some("ve3abc")).unwrap();
+        assert_eq!(managers.len(), 1);
+        assert_eq!(managers[0].callsign, "VK9XX");
+        assert!(managers[0].effective_from.is_none());
+    }
+
+    /// 录入卡片时按有效期标注经由的管理员，可按管理员筛选
+    #[test]
+    fn test_card_is_annotated_with_effective_manager() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        create_qsl_manager_in(
+            &db,
+            "VK9XX".to_string(),
+            "VE3ABC".to_string(),
+            Some("2020-01-01".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+        create_qsl_manager_in(
+            &db,
+            "3Y0J".to_string(),
+            "LA7GIA".to_string(),
+            None,
+            Some("2020-12-31".to_string()),
+            None,
+        )
+        .unwrap();
+
+        let card =
+            create_card_in(&db, project.id.clone(), "vk9xx".to_string(), 1, Some(1)).unwrap();
+        assert_eq!(card.via_callsign.as_deref(), Some("VE3ABC"));
+        let expired =
+            create_card_in(&db, project.id.clone(), "3Y0J".to_string(), 1, Some(2)).unwrap();
+        assert!(expired.via_callsign.is_none());
+
+        let filter = CardFilter {
+            via_callsign: Some("ve3abc".to_string()),
+            ..Default::default()
+        };
+        let paged = list_cards_in(&db, filter, Pagination::default()).unwrap();
+        assert_eq!(paged.total, 1);
+        assert_eq!(paged.items[0].callsign_via(), "VK9XX via VE3ABC");
+
+        // 修改呼号时按新呼号重新标注
+        let update = CardUpdate {
+            callsign: Some("BH2RO".to_string()),
+            ..Default::default()
+        };
+        let updated = update_card_in(&db, &card.id, update, None).unwrap();
+        assert!(updated.via_callsign.is_none());
+    }
+}
@@ -0,0 +1,490 @@
+// 回收站模块
+//
+// 卡片与项目删除后仅标记 deleted_at，在回收站中可恢复或彻底删除。
+// 项目删除时其卡片保持原样，随项目一并隐藏、恢复或彻底删除
+
+use crate::db::cards::insert_card_history_conn;
+use crate::db::models::{
+    format_datetime, now_china, CardHistoryEntry, CardStatus, DeletedCard, DeletedProject,
+    PurgeReport, RecycleBin,
+};
+use crate::db::operators::get_current_operator_in;
+use crate::db::projects::check_project_name_available;
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use rusqlite::Connection;
+
+/// 查询回收站内容
+pub fn list_recycle_bin() -> Result<RecycleBin, AppError> {
+    list_recycle_bin_in(database()?)
+}
+
+/// 同 [`list_recycle_bin`]，在指定的数据库上下文中执行
+pub fn list_recycle_bin_in(db: &Database) -> Result<RecycleBin, AppError> {
+    let conn = db.connection()?;
+    list_recycle_bin_conn(&conn)
+}
+
+/// 回收站查询主体（crate-private，接收连接以便测试）。
+fn list_recycle_bin_conn(conn: &Connection) -> Result<RecycleBin, AppError> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT p.id, p.name, COUNT(c.id), p.created_at, p.deleted_at
+            FROM projects p
+            LEFT JOIN cards c ON c.project_id = p.id
+            WHERE p.deleted_at IS NOT NULL
+            GROUP BY p.id
+            ORDER BY p.deleted_at DESC
+            "#,
+        )
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let projects = stmt
+        .query_map([], |row| {
+            Ok(DeletedProject {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                card_count: row.get(2)?,
+                created_at: row.get(3)?,
+                deleted_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| AppError::Other(format!("查询回收站项目失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取回收站项目失败: {}", e)))?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT c.id, c.project_id, p.name, c.callsign, c.qty, c.serial, c.status, c.created_at, c.deleted_at
+            FROM cards c
+            JOIN projects p ON p.id = c.project_id
+            WHERE c.deleted_at IS NOT NULL
+            ORDER BY c.deleted_at DESC
+            "#,
+        )
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let cards = stmt
+        .query_map([], |row| {
+            let status_str: String = row.get(6)?;
+
+            Ok(DeletedCard {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                project_name: row.get(2)?,
+                callsign: row.get(3)?,
+                qty: row.get(4)?,
+                serial: row.get(5)?,
+                status: CardStatus::from_str(&status_str).unwrap_or(CardStatus::Pending),
+                created_at: row.get(7)?,
+                deleted_at: row.get(8)?,
+            })
+        })
+        .map_err(|e| AppError::Other(format!("查询回收站卡片失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取回收站卡片失败: {}", e)))?;
+
+    Ok(RecycleBin { projects, cards })
+}
+
+/// 从回收站恢复卡片
+///
+/// 所属项目仍在回收站中、或项目中已录入同一呼号时拒绝恢复。
+/// 恢复操作以 `deleted_at` 字段写入修改历史，记录当前操作员。
+pub fn restore_card(id: &str) -> Result<(), AppError> {
+    restore_card_in(database()?, id)
+}
+
+/// 同 [`restore_card`]，在指定的数据库上下文中执行
+pub fn restore_card_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let operator = get_current_operator_in(db)?;
+    let mut conn = db.connection()?;
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    restore_card_conn(
+        &tx,
+        id,
+        operator.as_ref().map(|o| o.name.clone()),
+        operator.as_ref().map(|o| o.id.clone()),
+    )?;
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!("✅ 恢复卡片成功: {}", id);
+    Ok(())
+}
+
+/// 恢复卡片主体（crate-private，由调用方管理事务）。
+fn restore_card_conn(
+    conn: &Connection,
+    id: &str,
+    changed_by: Option<String>,
+    operator_id: Option<String>,
+) -> Result<(), AppError> {
+    let result = conn.query_row(
+        r#"
+        SELECT c.project_id, c.callsign, p.deleted_at IS NOT NULL, c.deleted_at
+        FROM cards c
+        JOIN projects p ON p.id = c.project_id
+        WHERE c.id = ?1 AND c.deleted_at IS NOT NULL
+        "#,
+        [id],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        },
+    );
+
+    let (project_id, callsign, project_deleted, deleted_at) = match result {
+        Ok(row) => row,
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+            return Err(AppError::ProfileNotFound(format!(
+                "回收站中不存在该卡片: {}",
+                id
+            )));
+        }
+        Err(e) => return Err(AppError::Other(format!("查询卡片失败: {}", e))),
+    };
+
+    if project_deleted {
+        return Err(AppError::InvalidParameter(
+            "所属项目在回收站中，请先恢复项目".to_string(),
+        ));
+    }
+
+    let callsign_exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM cards WHERE project_id = ?1 AND callsign = ?2 COLLATE NOCASE AND id != ?3 AND deleted_at IS NULL)",
+            [&project_id, &callsign, id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Other(format!("查询呼号失败: {}", e)))?;
+
+    if callsign_exists {
+        return Err(AppError::InvalidParameter(format!(
+            "项目中已录入呼号 {}，无法恢复",
+            callsign
+        )));
+    }
+
+    let now = format_datetime(&now_china());
+    conn.execute(
+        "UPDATE cards SET deleted_at = NULL, deleted_by = NULL, updated_at = ?1 WHERE id = ?2",
+        rusqlite::params![now, id],
+    )
+    .map_err(|e| AppError::Other(format!("恢复卡片失败: {}", e)))?;
+
+    insert_card_history_conn(
+        conn,
+        &CardHistoryEntry {
+            id: 0,
+            card_id: id.to_string(),
+            field: "deleted_at".to_string(),
+            old_value: Some(deleted_at),
+            new_value: None,
+            changed_by,
+            operator_id,
+            changed_at: now,
+        },
+    )?;
+
+    Ok(())
+}
+
+/// 从回收站恢复项目（其卡片随之恢复显示）
+///
+/// 已有同名项目时拒绝恢复。
+pub fn restore_project(id: &str) -> Result<(), AppError> {
+    restore_project_in(database()?, id)
+}
+
+/// 同 [`restore_project`]，在指定的数据库上下文中执行
+pub fn restore_project_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let mut conn = db.connection()?;
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+
+    let name = match tx.query_row(
+        "SELECT name FROM projects WHERE id = ?1 AND deleted_at IS NOT NULL",
+        [id],
+        |row| row.get::<_, String>(0),
+    ) {
+        Ok(name) => name,
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+            return Err(AppError::ProfileNotFound(format!(
+                "回收站中不存在该项目: {}",
+                id
+            )));
+        }
+        Err(e) => return Err(AppError::Other(format!("查询项目失败: {}", e))),
+    };
+    check_project_name_available(&tx, &name, Some(id))?;
+
+    let now = format_datetime(&now_china());
+    tx.execute(
+        "UPDATE projects SET deleted_at = NULL, updated_at = ?1 WHERE id = ?2",
+        rusqlite::params![now, id],
+    )
+    .map_err(|e| AppError::Other(format!("恢复项目失败: {}", e)))?;
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!(
+        "✅ 恢复项目成功: {} (操作员: {})",
+        id,
+        db.current_operator_id().as_deref().unwrap_or("-")
+    );
+    Ok(())
+}
+
+/// 彻底删除回收站中的卡片
+pub fn purge_card(id: &str) -> Result<(), AppError> {
+    purge_card_in(database()?, id)
+}
+
+/// 同 [`purge_card`]，在指定的数据库上下文中执行
+pub fn purge_card_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let conn = db.connection()?;
+
+    let affected = conn
+        .execute(
+            "DELETE FROM cards WHERE id = ?1 AND deleted_at IS NOT NULL",
+            [id],
+        )
+        .map_err(|e| AppError::Other(format!("删除卡片失败: {}", e)))?;
+
+    if affected == 0 {
+        return Err(AppError::ProfileNotFound(format!(
+            "回收站中不存在该卡片: {}",
+            id
+        )));
+    }
+
+    log::info!(
+        "✅ 彻底删除卡片成功: {} (操作员: {})",
+        id,
+        db.current_operator_id().as_deref().unwrap_or("-")
+    );
+    Ok(())
+}
+
+/// 彻底删除回收站中的项目（级联删除其全部卡片）
+pub fn purge_project(id: &str) -> Result<(), AppError> {
+    purge_project_in(database()?, id)
+}
+
+/// 同 [`purge_project`]，在指定的数据库上下文中执行
+pub fn purge_project_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let mut conn = db.connection()?;
+
+    // 删除项目与清理编号方案在同一事务中完成
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    let affected = tx
+        .execute(
+            "DELETE FROM projects WHERE id = ?1 AND deleted_at IS NOT NULL",
+            [id],
+        )
+        .map_err(|e| AppError::Other(format!("删除项目失败: {}", e)))?;
+
+    if affected == 0 {
+        return Err(AppError::ProfileNotFound(format!(
+            "回收站中不存在该项目: {}",
+            id
+        )));
+    }
+
+    // 编号方案不设外键，随项目一并清理
+    tx.execute("DELETE FROM serial_schemes WHERE project_id = ?1", [id])
+        .map_err(|e| AppError::Other(format!("删除编号方案失败: {}", e)))?;
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!(
+        "✅ 彻底删除项目成功: {} (操作员: {})",
+        id,
+        db.current_operator_id().as_deref().unwrap_or("-")
+    );
+    Ok(())
+}
+
+/// 清空回收站
+pub fn empty_recycle_bin() -> Result<PurgeReport, AppError> {
+    empty_recycle_bin_in(database()?)
+}
+
+/// 同 [`empty_recycle_bin`]，在指定的数据库上下文中执行
+pub fn empty_recycle_bin_in(db: &Database) -> Result<PurgeReport, AppError> {
+    let mut conn = db.connection()?;
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    let report = empty_recycle_bin_conn(&tx)?;
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!(
+        "✅ 清空回收站完成: {} 个项目, {} 张卡片 (操作员: {})",
+        report.projects,
+        report.cards,
+        db.current_operator_id().as_deref().unwrap_or("-")
+    );
+    Ok(report)
+}
+
+/// 清空回收站主体（crate-private，由调用方管理事务）。
+fn empty_recycle_bin_conn(conn: &Connection) -> Result<PurgeReport, AppError> {
+    // 先统计随项目级联删除的卡片，再删除项目与单独删除的卡片
+    let cascaded: i64 = conn
+        .query_row(
+            r#"
+            SELECT COUNT(*) FROM cards
+            WHERE deleted_at IS NULL
+              AND project_id IN (SELECT id FROM projects WHERE deleted_at IS NOT NULL)
+            "#,
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Other(format!("统计卡片失败: {}", e)))?;
+
+    let cards = conn
+        .execute("DELETE FROM cards WHERE deleted_at IS NOT NULL", [])
+        .map_err(|e| AppError::Other(format!("删除卡片失败: {}", e)))?;
+
+    conn.execute(
+        "DELETE FROM serial_schemes WHERE project_id IN (SELECT id FROM projects WHERE deleted_at IS NOT NULL)",
+        [],
+    )
+    .map_err(|e| AppError::Other(format!("删除编号方案失败: {}", e)))?;
+
+    let projects = conn
+        .execute("DELETE FROM projects WHERE deleted_at IS NOT NULL", [])
+        .map_err(|e| AppError::Other(format!("删除项目失败: {}", e)))?;
+
+    Ok(PurgeReport {
+        projects: projects as u32,
+        cards: (cards as i64 + cascaded) as u32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::cards::{create_card_in, delete_card_in, get_card_history_in, list_all_cards_in};
+    use crate::db::models::CardFilter;
+    use crate::db::models::SerialSchemeInput;
+    use crate::db::projects::{create_project_in, delete_project_in, list_projects_in};
+    use crate::db::serial_schemes::{get_serial_scheme_in, set_serial_scheme_in};
+
+    /// 删除卡片后从列表与统计中消失，恢复后重新出现
+    #[test]
+    fn test_soft_delete_and_restore_card() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let card = create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, None).unwrap();
+
+        delete_card_in(&db, &card.id).unwrap();
+        assert!(list_all_cards_in(&db, CardFilter::default()).unwrap().is_empty());
+        assert_eq!(list_projects_in(&db).unwrap()[0].total_cards, 0);
+
+        let bin = list_recycle_bin_in(&db).unwrap();
+        assert_eq!(bin.cards.len(), 1);
+        assert_eq!(bin.cards[0].callsign, "BH2RO");
+
+        // 回收站中的卡片不占用呼号，重新录入后恢复会冲突
+        let again = create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, None).unwrap();
+        assert!(matches!(
+            restore_card_in(&db, &card.id),
+            Err(AppError::InvalidParameter(_))
+        ));
+
+        delete_card_in(&db, &again.id).unwrap();
+        purge_card_in(&db, &again.id).unwrap();
+        restore_card_in(&db, &card.id).unwrap();
+        assert_eq!(list_all_cards_in(&db, CardFilter::default()).unwrap().len(), 1);
+        let history = get_card_history_in(&db, &card.id).unwrap();
+        assert_eq!(history.last().unwrap().field, "deleted_at");
+        assert!(history.last().unwrap().new_value.is_none());
+        assert!(list_recycle_bin_in(&db).unwrap().cards.is_empty());
+    }
+
+    /// 删除项目隐藏其卡片；卡片须待项目恢复后才能恢复；清空回收站级联删除卡片
+    #[test]
+    fn test_soft_delete_project_and_empty_bin() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let keep = create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, None).unwrap();
+        let removed = create_card_in(&db, project.id.clone(), "BG2ABC".to_string(), 1, None).unwrap();
+        delete_card_in(&db, &removed.id).unwrap();
+
+        delete_project_in(&db, &project.id).unwrap();
+        assert!(list_projects_in(&db).unwrap().is_empty());
+        assert!(list_all_cards_in(&db, CardFilter::default()).unwrap().is_empty());
+        assert!(create_project_in(&db, "项目".to_string()).is_err());
+        assert!(restore_card_in(&db, &removed.id).is_err());
+
+        let bin = list_recycle_bin_in(&db).unwrap();
+        assert_eq!(bin.projects.len(), 1);
+        assert_eq!(bin.projects[0].card_count, 2);
+
+        restore_project_in(&db, &project.id).unwrap();
+        let cards = list_all_cards_in(&db, CardFilter::default()).unwrap();
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].id, keep.id);
+
+        delete_project_in(&db, &project.id).unwrap();
+        let report = empty_recycle_bin_in(&db).unwrap();
+        assert_eq!(report.projects, 1);
+        assert_eq!(report.cards, 2);
+
+        let total: i64 = db
+            .connection()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM cards", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(total, 0);
+    }
+
+    /// 恢复项目保留其编号方案，彻底删除时才随项目清理
+    #[test]
+    fn test_restore_project_keeps_serial_scheme() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let scheme = set_serial_scheme_in(
+            &db,
+            &project.id,
+            SerialSchemeInput {
+                prefix: "CQ".to_string(),
+                year: Some(2026),
+                width: 4,
+                start: 100,
+            },
+        )
+        .unwrap();
+
+        delete_project_in(&db, &project.id).unwrap();
+        restore_project_in(&db, &project.id).unwrap();
+        assert_eq!(get_serial_scheme_in(&db, &project.id).unwrap(), scheme);
+
+        delete_project_in(&db, &project.id).unwrap();
+        purge_project_in(&db, &project.id).unwrap();
+        let remaining: i64 = db
+            .connection()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM serial_schemes", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(remaining, 0);
+    }
+}
@@ -0,0 +1,608 @@
+// 序列号编号方案模块
+//
+// 按项目配置序列号的前缀、年份、补零位数与起始值，检查断号与重复，
+// 并可按录入顺序在事务内重新编号
+
+use crate::db::cards::{list_cards_matching_conn, CARD_VISIBLE_CONDITION};
+use crate::db::models::{
+    format_datetime, now_china, SerialAudit, SerialDuplicate, SerialGap, SerialScheme,
+    SerialSchemeInput,
+};
+use crate::db::operators::get_current_operator_in;
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use rusqlite::Connection;
+
+/// 前缀最大长度
+const MAX_PREFIX_LEN: usize = 10;
+
+/// 补零位数上限
+const MAX_SERIAL_WIDTH: u32 = 6;
+
+/// 是否跳过该序列号（跳过含数字 4 的序列号，与录入时的「跳过 4」选项一致）
+fn is_skipped(serial: u32, skip_four: bool) -> bool {
+    skip_four && serial.to_string().contains('4')
+}
+
+/// 检查项目是否存在（不含回收站中的项目）
+fn check_project_conn(conn: &Connection, project_id: &str) -> Result<(), AppError> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1 AND deleted_at IS NULL)",
+            [project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Other(format!("查询项目失败: {}", e)))?;
+    if !exists {
+        return Err(AppError::ProfileNotFound(format!(
+            "项目不存在: {}",
+            project_id
+        )));
+    }
+    Ok(())
+}
+
+/// 查询项目的编号方案（未配置时返回默认方案）
+pub(crate) fn get_serial_scheme_conn(
+    conn: &Connection,
+    project_id: &str,
+) -> Result<SerialScheme, AppError> {
+    let result = conn.query_row(
+        "SELECT prefix, year, width, start_value FROM serial_schemes WHERE project_id = ?1",
+        [project_id],
+        |row| {
+            Ok(SerialScheme {
+                project_id: project_id.to_string(),
+                prefix: row.get(0)?,
+                year: row.get(1)?,
+                width: row.get(2)?,
+                start: row.get(3)?,
+            })
+        },
+    );
+
+    match result {
+        Ok(scheme) => Ok(scheme),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(SerialScheme::default_for(project_id)),
+        Err(e) => Err(AppError::Other(format!("查询编号方案失败: {}", e))),
+    }
+}
+
+/// 校验序列号符合项目的编号方案（crate-private，供录入、修改与表格导入调用）
+///
+/// 序列号须在方案的起始序列号与补零位数可表示的最大值之间。
+pub(crate) fn check_serial_conn(
+    conn: &Connection,
+    project_id: &str,
+    serial: i32,
+) -> Result<(), AppError> {
+    let scheme = get_serial_scheme_conn(conn, project_id)?;
+    if serial < scheme.start as i32 || serial as i64 > scheme.max_serial() as i64 {
+        return Err(AppError::InvalidParameter(format!(
+            "序列号 {} 超出编号方案范围（{} 至 {}）",
+            serial,
+            scheme.format(scheme.start as i32),
+            scheme.format(scheme.max_serial() as i32)
+        )));
+    }
+    Ok(())
+}
+
+/// 查询项目的编号方案
+pub fn get_serial_scheme(project_id: &str) -> Result<SerialScheme, AppError> {
+    get_serial_scheme_in(database()?, project_id)
+}
+
+/// 同 [`get_serial_scheme`]，在指定的数据库上下文中执行
+pub fn get_serial_scheme_in(db: &Database, project_id: &str) -> Result<SerialScheme, AppError> {
+    let conn = db.connection()?;
+    check_project_conn(&conn, project_id)?;
+    get_serial_scheme_conn(&conn, project_id)
+}
+
+/// 校验并规范化编号方案设置
+fn normalize_input(project_id: &str, input: SerialSchemeInput) -> Result<SerialScheme, AppError> {
+    let prefix = input.prefix.trim().to_string();
+    if prefix.chars().count() > MAX_PREFIX_LEN {
+        return Err(AppError::InvalidParameter(format!(
+            "前缀不能超过 {} 个字符",
+            MAX_PREFIX_LEN
+        )));
+    }
+    // 空白、`#` 与逗号是扫码录入「呼号 序列号」的分隔符
+    if prefix
+        .chars()
+        .any(|c| c.is_whitespace() || c == '#' || c == ',')
+    {
+        return Err(AppError::InvalidParameter(
+            "前缀不能包含空格、# 或逗号".to_string(),
+        ));
+    }
+    if let Some(year) = input.year
+        && !(2000..=2099).contains(&year)
+    {
+        return Err(AppError::InvalidParameter(format!("年份无效: {}", year)));
+    }
+    if !(1..=MAX_SERIAL_WIDTH).contains(&input.width) {
+        return Err(AppError::InvalidParameter(format!(
+            "补零位数须在 1-{} 之间",
+            MAX_SERIAL_WIDTH
+        )));
+    }
+
+    let scheme = SerialScheme {
+        project_id: project_id.to_string(),
+        prefix,
+        year: input.year,
+        width: input.width,
+        start: input.start,
+    };
+    if scheme.start < 1 || scheme.start > scheme.max_serial() {
+        return Err(AppError::InvalidParameter(format!(
+            "起始序列号须在 1-{} 之间",
+            scheme.max_serial()
+        )));
+    }
+    Ok(scheme)
+}
+
+/// 设置项目的编号方案（不改动已有卡片的序列号，仅影响显示、打印与导出格式）
+pub fn set_serial_scheme(
+    project_id: &str,
+    input: SerialSchemeInput,
+) -> Result<SerialScheme, AppError> {
+    set_serial_scheme_in(database()?, project_id, input)
+}
+
+/// 同 [`set_serial_scheme`]，在指定的数据库上下文中执行
+pub fn set_serial_scheme_in(
+    db: &Database,
+    project_id: &str,
+    input: SerialSchemeInput,
+) -> Result<SerialScheme, AppError> {
+    let conn = db.connection()?;
+    check_project_conn(&conn, project_id)?;
+    let scheme = normalize_input(project_id, input)?;
+    save_serial_scheme_conn(&conn, &scheme)?;
+
+    log::info!(
+        "✅ 设置编号方案: {} 示例 {}",
+        project_id,
+        scheme.format(scheme.start as i32)
+    );
+    Ok(scheme)
+}
+
+/// 写入项目的编号方案（已有则覆盖；crate-private，供设置与导入、合并调用）
+pub(crate) fn save_serial_scheme_conn(
+    conn: &Connection,
+    scheme: &SerialScheme,
+) -> Result<(), AppError> {
+    conn.execute(
+        r#"
+        INSERT INTO serial_schemes (project_id, prefix, year, width, start_value, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        ON CONFLICT(project_id) DO UPDATE SET
+            prefix = excluded.prefix,
+            year = excluded.year,
+            width = excluded.width,
+            start_value = excluded.start_value,
+            updated_at = excluded.updated_at
+        "#,
+        rusqlite::params![
+            scheme.project_id,
+            scheme.prefix,
+            scheme.year,
+            scheme.width,
+            scheme.start,
+            format_datetime(&now_china())
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("保存编号方案失败: {}", e)))?;
+    Ok(())
+}
+
+/// 查询已配置的编号方案（不含回收站中的项目；crate-private，供导出调用）
+pub(crate) fn list_serial_schemes_conn(conn: &Connection) -> Result<Vec<SerialScheme>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT s.project_id, s.prefix, s.year, s.width, s.start_value
+            FROM serial_schemes s
+            JOIN projects p ON p.id = s.project_id AND p.deleted_at IS NULL
+            ORDER BY p.created_at
+            "#,
+        )
+        .map_err(|e| AppError::Other(format!("准备编号方案查询失败: {}", e)))?;
+
+    let schemes = stmt
+        .query_map([], |row| {
+            Ok(SerialScheme {
+                project_id: row.get(0)?,
+                prefix: row.get(1)?,
+                year: row.get(2)?,
+                width: row.get(3)?,
+                start: row.get(4)?,
+            })
+        })
+        .map_err(|e| AppError::Other(format!("查询编号方案失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取编号方案失败: {}", e)))?;
+
+    Ok(schemes)
+}
+
+/// 按项目编号方案格式化卡片的序列号（卡片不存在或未编号时返回 `None`）
+pub fn format_card_serial(card_id: &str) -> Result<Option<String>, AppError> {
+    format_card_serial_in(database()?, card_id)
+}
+
+/// 同 [`format_card_serial`]，在指定的数据库上下文中执行
+pub fn format_card_serial_in(db: &Database, card_id: &str) -> Result<Option<String>, AppError> {
+    let conn = db.connection()?;
+    Ok(list_cards_matching_conn(&conn, "c.id = ?1", &[&card_id])?
+        .into_iter()
+        .next()
+        .and_then(|card| card.serial_display))
+}
+
+/// 检查项目的序列号：断号与重复
+///
+/// 断号为起始序列号至当前最大序列号之间未使用的序列号（`skip_four` 时不计含数字 4 的序列号）。
+pub fn audit_project_serials(project_id: &str, skip_four: bool) -> Result<SerialAudit, AppError> {
+    audit_project_serials_in(database()?, project_id, skip_four)
+}
+
+/// 同 [`audit_project_serials`]，在指定的数据库上下文中执行
+pub fn audit_project_serials_in(
+    db: &Database,
+    project_id: &str,
+    skip_four: bool,
+) -> Result<SerialAudit, AppError> {
+    let conn = db.connection()?;
+    check_project_conn(&conn, project_id)?;
+    let scheme = get_serial_scheme_conn(&conn, project_id)?;
+
+    let unnumbered: u32 = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*) FROM cards c WHERE {} AND c.project_id = ?1 AND c.serial IS NULL",
+                CARD_VISIBLE_CONDITION
+            ),
+            [project_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Other(format!("统计卡片失败: {}", e)))?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+            SELECT c.serial, COUNT(*) FROM cards c
+            WHERE {} AND c.project_id = ?1 AND c.serial IS NOT NULL
+            GROUP BY c.serial
+            ORDER BY c.serial ASC
+            "#,
+            CARD_VISIBLE_CONDITION
+        ))
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+    let counts = stmt
+        .query_map([project_id], |row| {
+            Ok((row.get::<_, i32>(0)?, row.get::<_, u32>(1)?))
+        })
+        .map_err(|e| AppError::Other(format!("查询序列号失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取序列号失败: {}", e)))?;
+
+    let mut gaps: Vec<SerialGap> = Vec::new();
+    let mut next = scheme.start;
+    for serial in counts.iter().map(|(serial, _)| *serial) {
+        let Ok(serial) = u32::try_from(serial) else {
+            continue;
+        };
+        if serial > next {
+            let mut gap: Option<SerialGap> = None;
+            for missing in next..serial {
+                if is_skipped(missing, skip_four) {
+                    continue;
+                }
+                match gap.as_mut() {
+                    Some(gap) => gap.to = missing,
+                    None => {
+                        gap = Some(SerialGap {
+                            from: missing,
+                            to: missing,
+                        })
+                    }
+                }
+            }
+            gaps.extend(gap);
+        }
+        next = next.max(serial + 1);
+    }
+
+    let mut duplicates = Vec::new();
+    for (serial, _) in counts.iter().filter(|(_, count)| *count > 1) {
+        duplicates.push(SerialDuplicate {
+            serial: *serial,
+            serial_display: scheme.format(*serial),
+            cards: list_cards_matching_conn(
+                &conn,
+                "c.project_id = ?1 AND c.serial = ?2",
+                &[&project_id, serial],
+            )?,
+        });
+    }
+
+    Ok(SerialAudit {
+        numbered: counts.iter().map(|(_, count)| count).sum(),
+        unnumbered,
+        gaps,
+        duplicates,
+        scheme,
+    })
+}
+
+/// 按录入顺序重新编号项目内的全部卡片（返回序列号有变化的卡片数）
+///
+/// 从编号方案的起始序列号开始连续编号（`skip_four` 时跳过含数字 4 的序列号），
+/// 在同一事务内改写序列号并记录修改历史。回收站中的卡片保持不变。
+pub fn renumber_project_serials(project_id: &str, skip_four: bool) -> Result<u32, AppError> {
+    renumber_project_serials_in(database()?, project_id, skip_four)
+}
+
+/// 同 [`renumber_project_serials`]，在指定的数据库上下文中执行
+pub fn renumber_project_serials_in(
+    db: &Database,
+    project_id: &str,
+    skip_four: bool,
+) -> Result<u32, AppError> {
+    let operator = get_current_operator_in(db)?;
+    let changed_by = operator.as_ref().map(|o| o.name.clone());
+    let operator_id = operator.map(|o| o.id);
+
+    let mut conn = db.connection()?;
+    check_project_conn(&conn, project_id)?;
+    let scheme = get_serial_scheme_conn(&conn, project_id)?;
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+
+    let cards: Vec<(String, Option<i32>)> = {
+        let mut stmt = tx
+            .prepare(&format!(
+                r#"
+                SELECT c.id, c.serial FROM cards c
+                WHERE {} AND c.project_id = ?1
+                ORDER BY c.created_at ASC, c.rowid ASC
+                "#,
+                CARD_VISIBLE_CONDITION
+            ))
+            .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+        stmt.query_map([project_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| AppError::Other(format!("查询卡片失败: {}", e)))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Other(format!("读取卡片失败: {}", e)))?
+    };
+
+    let now = format_datetime(&now_china());
+    let mut next = scheme.start;
+    let mut changed = 0u32;
+    for (id, old_serial) in &cards {
+        while is_skipped(next, skip_four) {
+            next += 1;
+        }
+        if next > scheme.max_serial() {
+            return Err(AppError::InvalidParameter(format!(
+                "卡片数量超出编号方案的 {} 位序列号，请先增加补零位数",
+                scheme.width
+            )));
+        }
+
+        let serial = next as i32;
+        next += 1;
+        if *old_serial == Some(serial) {
+            continue;
+        }
+
+        tx.execute(
+            "UPDATE cards SET serial = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![serial, now, id],
+        )
+        .map_err(|e| AppError::Other(format!("更新序列号失败: {}", e)))?;
+        tx.execute(
+            r#"
+            INSERT INTO card_history (card_id, field, old_value, new_value, changed_by, changed_at, operator_id)
+            VALUES (?1, 'serial', ?2, ?3, ?4, ?5, ?6)
+            "#,
+            rusqlite::params![
+                id,
+                old_serial.map(|s| s.to_string()),
+                serial.to_string(),
+                changed_by,
+                now,
+                operator_id
+            ],
+        )
+        .map_err(|e| AppError::Other(format!("写入修改历史失败: {}", e)))?;
+        changed += 1;
+    }
+
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!("✅ 重新编号完成: {} ({} 张卡片变化)", project_id, changed);
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::cards::{create_card_in, get_card_history_in, update_card_in};
+    use crate::db::export::export_database_in;
+    use crate::db::import::{import_from_export_data, parse_export_data, AppSettingsClearMode};
+    use crate::db::merge_import::{merge_export_data, MergeOptions, MergeStrategy};
+    use crate::db::models::CardUpdate;
+    use crate::db::projects::create_project_in;
+
+    /// 编号方案影响卡片的格式化序列号；断号与重复检查及按录入顺序重新编号
+    #[test]
+    fn test_scheme_audit_and_renumber() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        assert_eq!(
+            get_serial_scheme_in(&db, &project.id).unwrap(),
+            SerialScheme::default_for(&project.id)
+        );
+
+        let scheme = set_serial_scheme_in(
+            &db,
+            &project.id,
+            SerialSchemeInput {
+                prefix: " BY ".to_string(),
+                year: Some(2026),
+                width: 4,
+                start: 10,
+            },
+        )
+        .unwrap();
+        assert_eq!(scheme.format(12), "BY-2026-0012");
+
+        let first =
+            create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, Some(10)).unwrap();
+        create_card_in(&db, project.id.clone(), "BG2ABC".to_string(), 1, Some(13)).unwrap();
+        create_card_in(&db, project.id.clone(), "BA1AA".to_string(), 1, Some(13)).unwrap();
+        create_card_in(&db, project.id.clone(), "BD4XY".to_string(), 1, Some(16)).unwrap();
+        create_card_in(&db, project.id.clone(), "BY1PK".to_string(), 1, None).unwrap();
+
+        let conn = db.connection().unwrap();
+        let card = list_cards_matching_conn(&conn, "c.id = ?1", &[&first.id]).unwrap();
+        assert_eq!(card[0].serial_display.as_deref(), Some("BY-2026-0010"));
+        drop(conn);
+
+        let audit = audit_project_serials_in(&db, &project.id, true).unwrap();
+        assert_eq!(audit.numbered, 4);
+        assert_eq!(audit.unnumbered, 1);
+        assert_eq!(
+            audit.gaps,
+            vec![
+                SerialGap { from: 11, to: 12 },
+                SerialGap { from: 15, to: 15 }
+            ]
+        );
+        assert_eq!(audit.duplicates.len(), 1);
+        assert_eq!(audit.duplicates[0].serial_display, "BY-2026-0013");
+        assert_eq!(audit.duplicates[0].cards.len(), 2);
+
+        // 10 不变，其余依次为 11、12、13、15（跳过 14）
+        assert_eq!(
+            renumber_project_serials_in(&db, &project.id, true).unwrap(),
+            4
+        );
+        let audit = audit_project_serials_in(&db, &project.id, true).unwrap();
+        assert!(audit.gaps.is_empty());
+        assert!(audit.duplicates.is_empty());
+        assert_eq!(audit.unnumbered, 0);
+        assert!(get_card_history_in(&db, &first.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_scheme() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let input = |prefix: &str, width: u32, start: u32| SerialSchemeInput {
+            prefix: prefix.to_string(),
+            year: None,
+            width,
+            start,
+        };
+
+        assert!(set_serial_scheme_in(&db, &project.id, input("A B", 3, 1)).is_err());
+        assert!(set_serial_scheme_in(&db, &project.id, input("", 0, 1)).is_err());
+        assert!(set_serial_scheme_in(&db, &project.id, input("", 2, 100)).is_err());
+        assert!(set_serial_scheme_in(&db, "missing", input("", 3, 1)).is_err());
+    }
+
+    /// 录入与修改卡片时按编号方案校验序列号范围
+    #[test]
+    fn test_card_serial_checked_against_scheme() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        set_serial_scheme_in(
+            &db,
+            &project.id,
+            SerialSchemeInput {
+                prefix: String::new(),
+                year: None,
+                width: 3,
+                start: 100,
+            },
+        )
+        .unwrap();
+
+        assert!(create_card_in(&db, project.id.clone(), "BA1AA".to_string(), 1, Some(5)).is_err());
+        assert!(
+            create_card_in(&db, project.id.clone(), "BA1AA".to_string(), 1, Some(1000)).is_err()
+        );
+        let card =
+            create_card_in(&db, project.id.clone(), "BA1AA".to_string(), 1, Some(100)).unwrap();
+        create_card_in(&db, project.id.clone(), "BG2ABC".to_string(), 1, None).unwrap();
+
+        let update = |serial: i32| CardUpdate {
+            serial: Some(Some(serial)),
+            ..Default::default()
+        };
+        assert!(update_card_in(&db, &card.id, update(99), None).is_err());
+        assert_eq!(
+            update_card_in(&db, &card.id, update(999), None).unwrap().serial,
+            Some(999)
+        );
+    }
+
+    /// 编号方案随导出文件整库导入与合并导入（合并时本地已配置的方案保留）
+    #[test]
+    fn test_scheme_export_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let input = SerialSchemeInput {
+            prefix: "BY".to_string(),
+            year: Some(2026),
+            width: 4,
+            start: 10,
+        };
+        let scheme = set_serial_scheme_in(&db, &project.id, input.clone()).unwrap();
+
+        let json = serde_json::to_string(&export_database_in(&db).unwrap()).unwrap();
+        let data = parse_export_data(&json).unwrap();
+        assert_eq!(data.tables.serial_schemes, Some(vec![scheme.clone()]));
+
+        let restored = Database::open_in_memory().unwrap();
+        let mut conn = restored.connection().unwrap();
+        import_from_export_data(&mut conn, &data, AppSettingsClearMode::Conditional).unwrap();
+        drop(conn);
+        assert_eq!(get_serial_scheme_in(&restored, &project.id).unwrap(), scheme);
+
+        let options = MergeOptions {
+            strategy: MergeStrategy::TakeIncoming,
+            take_incoming: Vec::new(),
+        };
+        let merged = Database::open_in_memory().unwrap();
+        merge_export_data(&mut merged.connection().unwrap(), &data, &options, false).unwrap();
+        assert_eq!(get_serial_scheme_in(&merged, &project.id).unwrap(), scheme);
+
+        // 本地已有不同方案的同名项目：保留本地方案
+        let local = Database::open_in_memory().unwrap();
+        let same_name = create_project_in(&local, "项目".to_string()).unwrap();
+        let local_scheme = set_serial_scheme_in(
+            &local,
+            &same_name.id,
+            SerialSchemeInput {
+                prefix: String::new(),
+                ..input
+            },
+        )
+        .unwrap();
+        merge_export_data(&mut local.connection().unwrap(), &data, &options, false).unwrap();
+        assert_eq!(get_serial_scheme_in(&local, &same_name.id).unwrap(), local_scheme);
+    }
+}
@@ -2,8 +2,11 @@
 //
 // 提供寄件人和订单的 CRUD 操作
 
-use crate::db::models::{format_datetime, now_china};
-use crate::db::sqlite::get_connection;
+use crate::db::callsign_aliases::resolved_callsign_sql;
+use crate::db::card_events::insert_card_event_conn;
+use crate::db::models::{format_datetime, now_china, CardEvent, CardEventType};
+use crate::db::shipments::{check_shipment_cards_conn, link_order_cards_conn};
+use crate::db::sqlite::{database, Database};
 use crate::error::AppError;
 use crate::sf_express::{OrderStatus, RecipientInfo, SFOrder, SFOrderWithCard, SenderInfo};
 use uuid::Uuid;
@@ -21,7 +24,33 @@ pub fn create_sender(
     address: String,
     is_default: bool,
 ) -> Result<SenderInfo, AppError> {
-    let conn = get_connection()?;
+    create_sender_in(
+        database()?,
+        name,
+        phone,
+        mobile,
+        province,
+        city,
+        district,
+        address,
+        is_default,
+    )
+}
+
+/// 同 [`create_sender`]，在指定的数据库上下文中执行
+#[allow(clippy::too_many_arguments)]
+pub fn create_sender_in(
+    db: &Database,
+    name: String,
+    phone: String,
+    mobile: Option<String>,
+    province: String,
+    city: String,
+    district: String,
+    address: String,
+    is_default: bool,
+) -> Result<SenderInfo, AppError> {
+    let conn = db.connection()?;
 
     // 如果设为默认，先清除其他默认
     if is_default {
@@ -81,7 +110,35 @@ pub fn update_sender(
     address: String,
     is_default: bool,
 ) -> Result<SenderInfo, AppError> {
-    let conn = get_connection()?;
+    update_sender_in(
+        database()?,
+        id,
+        name,
+        phone,
+        mobile,
+        province,
+        city,
+        district,
+        address,
+        is_default,
+    )
+}
+
+/// 同 [`update_sender`]，在指定的数据库上下文中执行
+#[allow(clippy::too_many_arguments)]
+pub fn update_sender_in(
+    db: &Database,
+    id: &str,
+    name: String,
+    phone: String,
+    mobile: Option<String>,
+    province: String,
+    city: String,
+    district: String,
+    address: String,
+    is_default: bool,
+) -> Result<SenderInfo, AppError> {
+    let conn = db.connection()?;
 
     // 如果设为默认，先清除其他默认
     if is_default {
@@ -109,15 +166,20 @@ pub fn update_sender(
     .map_err(|e| AppError::Other(format!("更新寄件人失败: {}", e)))?;
 
     log::info!("✅ 更新寄件人成功: {}", id);
-    get_sender(id)?.ok_or_else(|| AppError::Other("更新后无法获取寄件人".to_string()))
+    get_sender_in(db, id)?.ok_or_else(|| AppError::Other("更新后无法获取寄件人".to_string()))
 }
 
 /// 删除寄件人
 pub fn delete_sender(id: &str) -> Result<(), AppError> {
-    let conn = get_connection()?;
+    delete_sender_in(database()?, id)
+}
+
+/// 同 [`delete_sender`]，在指定的数据库上下文中执行
+pub fn delete_sender_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let conn = db.connection()?;
 
     // 检查是否为默认寄件人
-    let sender = get_sender(id)?;
+    let sender = get_sender_in(db, id)?;
     let was_default = sender.map(|s| s.is_default).unwrap_or(false);
 
     conn.execute("DELETE FROM sf_senders WHERE id = ?1", [id])
@@ -138,7 +200,12 @@ pub fn delete_sender(id: &str) -> Result<(), AppError> {
 
 /// 获取单个寄件人
 pub fn get_sender(id: &str) -> Result<Option<SenderInfo>, AppError> {
-    let conn = get_connection()?;
+    get_sender_in(database()?, id)
+}
+
+/// 同 [`get_sender`]，在指定的数据库上下文中执行
+pub fn get_sender_in(db: &Database, id: &str) -> Result<Option<SenderInfo>, AppError> {
+    let conn = db.connection()?;
 
     let result = conn.query_row(
         r#"
@@ -172,7 +239,12 @@ pub fn get_sender(id: &str) -> Result<Option<SenderInfo>, AppError> {
 
 /// 获取寄件人列表
 pub fn list_senders() -> Result<Vec<SenderInfo>, AppError> {
-    let conn = get_connection()?;
+    list_senders_in(database()?)
+}
+
+/// 同 [`list_senders`]，在指定的数据库上下文中执行
+pub fn list_senders_in(db: &Database) -> Result<Vec<SenderInfo>, AppError> {
+    let conn = db.connection()?;
 
     let mut stmt = conn
         .prepare(
@@ -208,7 +280,12 @@ pub fn list_senders() -> Result<Vec<SenderInfo>, AppError> {
 
 /// 获取默认寄件人
 pub fn get_default_sender() -> Result<Option<SenderInfo>, AppError> {
-    let conn = get_connection()?;
+    get_default_sender_in(database()?)
+}
+
+/// 同 [`get_default_sender`]，在指定的数据库上下文中执行
+pub fn get_default_sender_in(db: &Database) -> Result<Option<SenderInfo>, AppError> {
+    let conn = db.connection()?;
 
     let result = conn.query_row(
         r#"
@@ -242,7 +319,12 @@ pub fn get_default_sender() -> Result<Option<SenderInfo>, AppError> {
 
 /// 设置默认寄件人
 pub fn set_default_sender(id: &str) -> Result<SenderInfo, AppError> {
-    let conn = get_connection()?;
+    set_default_sender_in(database()?, id)
+}
+
+/// 同 [`set_default_sender`]，在指定的数据库上下文中执行
+pub fn set_default_sender_in(db: &Database, id: &str) -> Result<SenderInfo, AppError> {
+    let conn = db.connection()?;
 
     // 清除所有默认
     conn.execute("UPDATE sf_senders SET is_default = 0", [])
@@ -257,21 +339,58 @@ pub fn set_default_sender(id: &str) -> Result<SenderInfo, AppError> {
     .map_err(|e| AppError::Other(format!("设置默认寄件人失败: {}", e)))?;
 
     log::info!("✅ 设置默认寄件人成功: {}", id);
-    get_sender(id)?.ok_or_else(|| AppError::Other("设置后无法获取寄件人".to_string()))
+    get_sender_in(db, id)?.ok_or_else(|| AppError::Other("设置后无法获取寄件人".to_string()))
 }
 
 // ==================== 订单操作 ====================
 
+/// 订单查询列（订单表别名须为 `o`），末列为按加入顺序排列的关联卡片 ID（JSON 数组）
+const ORDER_SELECT_COLUMNS: &str = r#"o.id, o.order_id, o.waybill_no, o.card_id, o.status, o.pay_method, o.cargo_name,
+        o.sender_info, o.recipient_info, o.created_at, o.updated_at,
+        (SELECT json_group_array(card_id) FROM (SELECT card_id FROM sf_order_cards WHERE order_id = o.id ORDER BY rowid))"#;
+
+/// 订单关联某卡片的条件（卡片 ID 为第 `index` 个参数）
+fn order_card_condition(index: usize) -> String {
+    format!(
+        "o.id IN (SELECT order_id FROM sf_order_cards WHERE card_id = ?{})",
+        index
+    )
+}
+
 /// 创建订单
+///
+/// `card_ids` 为合并寄件的卡片（同一呼号、均可分发、未加入其他待确认订单），可为空。
 pub fn create_order(
     order_id: String,
-    card_id: Option<String>,
+    card_ids: Vec<String>,
     pay_method: Option<i32>,
     cargo_name: Option<String>,
     sender_info: SenderInfo,
     recipient_info: RecipientInfo,
 ) -> Result<SFOrder, AppError> {
-    let conn = get_connection()?;
+    create_order_in(
+        database()?,
+        order_id,
+        card_ids,
+        pay_method,
+        cargo_name,
+        sender_info,
+        recipient_info,
+    )
+}
+
+/// 同 [`create_order`]，在指定的数据库上下文中执行
+pub fn create_order_in(
+    db: &Database,
+    order_id: String,
+    card_ids: Vec<String>,
+    pay_method: Option<i32>,
+    cargo_name: Option<String>,
+    sender_info: SenderInfo,
+    recipient_info: RecipientInfo,
+) -> Result<SFOrder, AppError> {
+    let operator_id = db.current_operator_id();
+    let mut conn = db.connection()?;
     let now = format_datetime(&now_china());
 
     // 序列化为 JSON 字符串存入数据库
@@ -284,7 +403,8 @@ pub fn create_order(
         id: Uuid::new_v4().to_string(),
         order_id,
         waybill_no: None,
-        card_id,
+        card_id: card_ids.first().cloned(),
+        card_ids,
         status: OrderStatus::Pending.to_string(),
         pay_method,
         cargo_name,
@@ -294,7 +414,13 @@ pub fn create_order(
         updated_at: now,
     };
 
-    conn.execute(
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+
+    check_shipment_cards_conn(&tx, &order.card_ids)?;
+
+    tx.execute(
         r#"
         INSERT INTO sf_orders (id, order_id, waybill_no, card_id, status, pay_method, cargo_name, sender_info, recipient_info, created_at, updated_at)
         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
@@ -315,7 +441,26 @@ pub fn create_order(
     )
     .map_err(|e| AppError::Other(format!("创建订单失败: {}", e)))?;
 
-    log::info!("✅ 创建订单成功: {} ({})", order.order_id, order.id);
+    link_order_cards_conn(&tx, &order.id, &order.card_ids, &order.created_at)?;
+
+    // 加入寄件记入各卡片的事件日志（不改变卡片状态）
+    for card_id in &order.card_ids {
+        let mut event = CardEvent::new(card_id, CardEventType::WaybillAttached);
+        event.remarks = Some(format!("加入顺丰订单 {}", order.order_id));
+        event.operator_id = operator_id.clone();
+        event.created_at = order.created_at.clone();
+        insert_card_event_conn(&tx, &event)?;
+    }
+
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!(
+        "✅ 创建订单成功: {} ({})，关联 {} 张卡片",
+        order.order_id,
+        order.id,
+        order.card_ids.len()
+    );
     Ok(order)
 }
 
@@ -325,7 +470,29 @@ pub fn update_order_status(
     status: OrderStatus,
     waybill_no: Option<String>,
 ) -> Result<SFOrder, AppError> {
-    let conn = get_connection()?;
+    update_order_status_in(database()?, order_id, status, waybill_no)
+}
+
+/// 同 [`update_order_status`]，在指定的数据库上下文中执行
+pub fn update_order_status_in(
+    db: &Database,
+    order_id: &str,
+    status: OrderStatus,
+    waybill_no: Option<String>,
+) -> Result<SFOrder, AppError> {
+    let conn = db.connection()?;
+    update_order_status_conn(&conn, order_id, status, waybill_no)?;
+    get_order_by_order_id_conn(&conn, order_id)?
+        .ok_or_else(|| AppError::Other("更新后无法获取订单".to_string()))
+}
+
+/// 更新订单状态主体（crate-private，确认订单事务内复用）。
+pub(crate) fn update_order_status_conn(
+    conn: &rusqlite::Connection,
+    order_id: &str,
+    status: OrderStatus,
+    waybill_no: Option<String>,
+) -> Result<(), AppError> {
     let updated_at = format_datetime(&now_china());
 
     conn.execute(
@@ -335,19 +502,23 @@ pub fn update_order_status(
     .map_err(|e| AppError::Other(format!("更新订单状态失败: {}", e)))?;
 
     log::info!("✅ 更新订单状态成功: {} -> {:?}", order_id, status);
-    get_order_by_order_id(order_id)?
-        .ok_or_else(|| AppError::Other("更新后无法获取订单".to_string()))
+    Ok(())
 }
 
 /// 获取订单（按内部 ID）
 pub fn get_order(id: &str) -> Result<Option<SFOrder>, AppError> {
-    let conn = get_connection()?;
+    get_order_in(database()?, id)
+}
+
+/// 同 [`get_order`]，在指定的数据库上下文中执行
+pub fn get_order_in(db: &Database, id: &str) -> Result<Option<SFOrder>, AppError> {
+    let conn = db.connection()?;
 
     let result = conn.query_row(
-        r#"
-        SELECT id, order_id, waybill_no, card_id, status, pay_method, cargo_name, sender_info, recipient_info, created_at, updated_at
-        FROM sf_orders WHERE id = ?1
-        "#,
+        &format!(
+            "SELECT {} FROM sf_orders o WHERE o.id = ?1",
+            ORDER_SELECT_COLUMNS
+        ),
         [id],
         row_to_order,
     );
@@ -361,13 +532,25 @@ pub fn get_order(id: &str) -> Result<Option<SFOrder>, AppError> {
 
 /// 获取订单（按顺丰订单号）
 pub fn get_order_by_order_id(order_id: &str) -> Result<Option<SFOrder>, AppError> {
-    let conn = get_connection()?;
+    get_order_by_order_id_in(database()?, order_id)
+}
 
+/// 同 [`get_order_by_order_id`]，在指定的数据库上下文中执行
+pub fn get_order_by_order_id_in(db: &Database, order_id: &str) -> Result<Option<SFOrder>, AppError> {
+    let conn = db.connection()?;
+    get_order_by_order_id_conn(&conn, order_id)
+}
+
+/// 按顺丰订单号查询主体（crate-private，确认订单事务内复用）。
+pub(crate) fn get_order_by_order_id_conn(
+    conn: &rusqlite::Connection,
+    order_id: &str,
+) -> Result<Option<SFOrder>, AppError> {
     let result = conn.query_row(
-        r#"
-        SELECT id, order_id, waybill_no, card_id, status, pay_method, cargo_name, sender_info, recipient_info, created_at, updated_at
-        FROM sf_orders WHERE order_id = ?1
-        "#,
+        &format!(
+            "SELECT {} FROM sf_orders o WHERE o.order_id = ?1",
+            ORDER_SELECT_COLUMNS
+        ),
         [order_id],
         row_to_order,
     );
@@ -381,13 +564,21 @@ pub fn get_order_by_order_id(order_id: &str) -> Result<Option<SFOrder>, AppError
 
 /// 获取订单（按运单号）
 pub fn get_order_by_waybill_no(waybill_no: &str) -> Result<Option<SFOrder>, AppError> {
-    let conn = get_connection()?;
+    get_order_by_waybill_no_in(database()?, waybill_no)
+}
+
+/// 同 [`get_order_by_waybill_no`]，在指定的数据库上下文中执行
+pub fn get_order_by_waybill_no_in(
+    db: &Database,
+    waybill_no: &str,
+) -> Result<Option<SFOrder>, AppError> {
+    let conn = db.connection()?;
 
     let result = conn.query_row(
-        r#"
-        SELECT id, order_id, waybill_no, card_id, status, pay_method, cargo_name, sender_info, recipient_info, created_at, updated_at
-        FROM sf_orders WHERE waybill_no = ?1
-        "#,
+        &format!(
+            "SELECT {} FROM sf_orders o WHERE o.waybill_no = ?1",
+            ORDER_SELECT_COLUMNS
+        ),
         [waybill_no],
         row_to_order,
     );
@@ -399,15 +590,24 @@ pub fn get_order_by_waybill_no(waybill_no: &str) -> Result<Option<SFOrder>, AppE
     }
 }
 
-/// 获取卡片关联的订单
+/// 获取卡片关联的订单（含合并寄件，取最近创建的一条）
 pub fn get_order_by_card_id(card_id: &str) -> Result<Option<SFOrder>, AppError> {
-    let conn = get_connection()?;
+    get_order_by_card_id_in(database()?, card_id)
+}
+
+/// 同 [`get_order_by_card_id`]，在指定的数据库上下文中执行
+pub fn get_order_by_card_id_in(db: &Database, card_id: &str) -> Result<Option<SFOrder>, AppError> {
+    let conn = db.connection()?;
 
     let result = conn.query_row(
-        r#"
-        SELECT id, order_id, waybill_no, card_id, status, pay_method, cargo_name, sender_info, recipient_info, created_at, updated_at
-        FROM sf_orders WHERE card_id = ?1 ORDER BY created_at DESC LIMIT 1
-        "#,
+        &format!(
+            r#"
+            SELECT {} FROM sf_orders o
+            WHERE o.id IN (SELECT order_id FROM sf_order_cards WHERE card_id = ?1)
+            ORDER BY o.created_at DESC LIMIT 1
+            "#,
+            ORDER_SELECT_COLUMNS
+        ),
         [card_id],
         row_to_order,
     );
@@ -419,6 +619,41 @@ pub fn get_order_by_card_id(card_id: &str) -> Result<Option<SFOrder>, AppError>
     }
 }
 
+/// 查询某呼号卡片关联的订单（crate-private，供呼号档案使用），按创建时间倒序
+///
+/// 合并寄件只要有一张关联卡片属于该呼号（含旧呼号名下的卡片）即列出。
+pub(crate) fn list_orders_by_callsign_conn(
+    conn: &rusqlite::Connection,
+    callsign: &str,
+) -> Result<Vec<SFOrderWithCard>, AppError> {
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+            SELECT {}, c.callsign, p.name as project_name, c.qty
+            FROM sf_orders o
+            LEFT JOIN cards c ON o.card_id = c.id
+            LEFT JOIN projects p ON c.project_id = p.id
+            WHERE o.id IN (
+                SELECT oc.order_id FROM sf_order_cards oc
+                JOIN cards lc ON lc.id = oc.card_id
+                WHERE {} = ?1 AND lc.deleted_at IS NULL
+            )
+            ORDER BY o.created_at DESC
+            "#,
+            ORDER_SELECT_COLUMNS,
+            resolved_callsign_sql("lc.callsign", "lc.created_at")
+        ))
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let orders = stmt
+        .query_map([callsign.to_uppercase()], row_to_order_with_card)
+        .map_err(|e| AppError::Other(format!("查询订单列表失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取订单数据失败: {}", e)))?;
+
+    Ok(orders)
+}
+
 /// 订单列表查询参数
 pub struct OrderFilter {
     pub status: Option<OrderStatus>,
@@ -442,19 +677,28 @@ pub struct PagedOrders {
 
 /// 查询订单列表
 pub fn list_orders(filter: OrderFilter, pagination: OrderPagination) -> Result<PagedOrders, AppError> {
-    let conn = get_connection()?;
+    list_orders_in(database()?, filter, pagination)
+}
+
+/// 同 [`list_orders`]，在指定的数据库上下文中执行
+pub fn list_orders_in(
+    db: &Database,
+    filter: OrderFilter,
+    pagination: OrderPagination,
+) -> Result<PagedOrders, AppError> {
+    let conn = db.connection()?;
 
     // 构建 WHERE 子句
     let mut conditions = Vec::new();
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
     if let Some(ref status) = filter.status {
-        conditions.push(format!("status = ?{}", params.len() + 1));
+        conditions.push(format!("o.status = ?{}", params.len() + 1));
         params.push(Box::new(status.to_string()));
     }
 
     if let Some(ref card_id) = filter.card_id {
-        conditions.push(format!("card_id = ?{}", params.len() + 1));
+        conditions.push(order_card_condition(params.len() + 1));
         params.push(Box::new(card_id.clone()));
     }
 
@@ -465,7 +709,7 @@ pub fn list_orders(filter: OrderFilter, pagination: OrderPagination) -> Result<P
     };
 
     // 查询总数
-    let count_sql = format!("SELECT COUNT(*) FROM sf_orders {}", where_clause);
+    let count_sql = format!("SELECT COUNT(*) FROM sf_orders o {}", where_clause);
     let total: u64 = {
         let mut stmt = conn
             .prepare(&count_sql)
@@ -485,9 +729,10 @@ pub fn list_orders(filter: OrderFilter, pagination: OrderPagination) -> Result<P
     // 查询数据
     let data_sql = format!(
         r#"
-        SELECT id, order_id, waybill_no, card_id, status, pay_method, cargo_name, sender_info, recipient_info, created_at, updated_at
-        FROM sf_orders {} ORDER BY created_at DESC LIMIT ?{} OFFSET ?{}
+        SELECT {}
+        FROM sf_orders o {} ORDER BY o.created_at DESC LIMIT ?{} OFFSET ?{}
         "#,
+        ORDER_SELECT_COLUMNS,
         where_clause,
         params.len() + 1,
         params.len() + 2
@@ -528,7 +773,16 @@ pub struct PagedOrdersWithCard {
 
 /// 查询订单列表（带卡片信息）
 pub fn list_orders_with_cards(filter: OrderFilter, pagination: OrderPagination) -> Result<PagedOrdersWithCard, AppError> {
-    let conn = get_connection()?;
+    list_orders_with_cards_in(database()?, filter, pagination)
+}
+
+/// 同 [`list_orders_with_cards`]，在指定的数据库上下文中执行
+pub fn list_orders_with_cards_in(
+    db: &Database,
+    filter: OrderFilter,
+    pagination: OrderPagination,
+) -> Result<PagedOrdersWithCard, AppError> {
+    let conn = db.connection()?;
 
     // 构建 WHERE 子句
     let mut conditions = Vec::new();
@@ -540,7 +794,7 @@ pub fn list_orders_with_cards(filter: OrderFilter, pagination: OrderPagination)
     }
 
     if let Some(ref card_id) = filter.card_id {
-        conditions.push(format!("o.card_id = ?{}", params.len() + 1));
+        conditions.push(order_card_condition(params.len() + 1));
         params.push(Box::new(card_id.clone()));
     }
 
@@ -568,17 +822,16 @@ pub fn list_orders_with_cards(filter: OrderFilter, pagination: OrderPagination)
     let offset = (page - 1) * page_size;
     let total_pages = ((total as f64) / (page_size as f64)).ceil() as u32;
 
-    // 查询数据（LEFT JOIN cards 和 projects 表获取卡片信息）
+    // 查询数据（LEFT JOIN 首张关联卡片和 projects 表获取卡片信息）
     let data_sql = format!(
         r#"
-        SELECT o.id, o.order_id, o.waybill_no, o.card_id, o.status, o.pay_method, o.cargo_name,
-               o.sender_info, o.recipient_info, o.created_at, o.updated_at,
-               c.callsign, p.name as project_name, c.qty
+        SELECT {}, c.callsign, p.name as project_name, c.qty
         FROM sf_orders o
         LEFT JOIN cards c ON o.card_id = c.id
         LEFT JOIN projects p ON c.project_id = p.id
         {} ORDER BY o.created_at DESC LIMIT ?{} OFFSET ?{}
         "#,
+        ORDER_SELECT_COLUMNS,
         where_clause,
         params.len() + 1,
         params.len() + 2
@@ -610,7 +863,12 @@ pub fn list_orders_with_cards(filter: OrderFilter, pagination: OrderPagination)
 
 /// 删除订单
 pub fn delete_order(id: &str) -> Result<(), AppError> {
-    let conn = get_connection()?;
+    delete_order_in(database()?, id)
+}
+
+/// 同 [`delete_order`]，在指定的数据库上下文中执行
+pub fn delete_order_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let conn = db.connection()?;
 
     conn.execute("DELETE FROM sf_orders WHERE id = ?1", [id])
         .map_err(|e| AppError::Other(format!("删除订单失败: {}", e)))?;
@@ -619,6 +877,14 @@ pub fn delete_order(id: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+// 辅助函数：解析关联卡片 ID 列（json_group_array 结果）
+fn parse_card_ids(row: &rusqlite::Row, idx: usize) -> rusqlite::Result<Vec<String>> {
+    let json: String = row.get(idx)?;
+    serde_json::from_str(&json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
 // 辅助函数：将数据库行转换为订单
 fn row_to_order(row: &rusqlite::Row) -> rusqlite::Result<SFOrder> {
     let sender_info_json: String = row.get(7)?;
@@ -649,6 +915,7 @@ fn row_to_order(row: &rusqlite::Row) -> rusqlite::Result<SFOrder> {
         recipient_info,
         created_at: row.get(9)?,
         updated_at: row.get(10)?,
+        card_ids: parse_card_ids(row, 11)?,
     })
 }
 
@@ -682,8 +949,9 @@ fn row_to_order_with_card(row: &rusqlite::Row) -> rusqlite::Result<SFOrderWithCa
         recipient_info,
         created_at: row.get(9)?,
         updated_at: row.get(10)?,
-        callsign: row.get(11)?,
-        project_name: row.get(12)?,
-        qty: row.get(13)?,
+        card_ids: parse_card_ids(row, 11)?,
+        callsign: row.get(12)?,
+        project_name: row.get(13)?,
+        qty: row.get(14)?,
     })
 }
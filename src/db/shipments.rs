@@ -0,0 +1,424 @@
+// 合并寄件模块
+//
+// 一个顺丰订单可关联同一呼号在多个项目中的多张待分发卡片（sf_order_cards 表），
+// 确认订单后以运单号一并分发全部关联卡片
+
+use crate::db::batch::run_batch_tx;
+use crate::db::callsign_aliases::{resolve_callsign_conn, resolve_callsign_on_conn};
+use crate::db::cards::{
+    distribute_card_conn, get_card_conn, list_callsign_cards_conn, validate_callsign,
+};
+use crate::db::models::{BatchOperation, BatchReport, BatchTarget, CardStatus, CardWithProject};
+use crate::db::sf_express::{get_order_by_order_id_conn, update_order_status_conn};
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use crate::sf_express::{OrderStatus, RecipientInfo, SFOrder};
+use rusqlite::{Connection, Transaction};
+use std::collections::HashSet;
+
+/// 合并寄件确认后使用的分发方式（内置登记项；确认时不要求仍为启用状态，
+/// 见 [`distribute_card_conn`]）
+const SHIPMENT_METHOD: &str = "快递";
+
+/// 卡片状态是否可加入合并寄件（尚有待交付数量的状态）
+fn is_shippable(status: &CardStatus) -> bool {
+    matches!(
+        status,
+        CardStatus::Pending
+            | CardStatus::PartiallyDistributed
+            | CardStatus::AwaitingPostage
+            | CardStatus::Held
+    )
+}
+
+/// 查询卡片所在的待确认订单（返回顺丰订单号）
+fn open_shipment_conn(conn: &Connection, card_id: &str) -> Result<Option<String>, AppError> {
+    let result = conn.query_row(
+        r#"
+        SELECT o.order_id FROM sf_order_cards oc
+        JOIN sf_orders o ON o.id = oc.order_id
+        WHERE oc.card_id = ?1 AND o.status = ?2
+        LIMIT 1
+        "#,
+        rusqlite::params![card_id, OrderStatus::Pending.to_string()],
+        |row| row.get(0),
+    );
+
+    match result {
+        Ok(order_id) => Ok(Some(order_id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询寄件关联失败: {}", e))),
+    }
+}
+
+/// 查询某呼号可合并寄件的卡片
+///
+/// 跨项目列出该呼号（含其旧呼号名下）尚有待交付数量、且未加入待确认订单的卡片。
+pub fn list_shipment_candidates(callsign: &str) -> Result<Vec<CardWithProject>, AppError> {
+    list_shipment_candidates_in(database()?, callsign)
+}
+
+/// 同 [`list_shipment_candidates`]，在指定的数据库上下文中执行
+pub fn list_shipment_candidates_in(
+    db: &Database,
+    callsign: &str,
+) -> Result<Vec<CardWithProject>, AppError> {
+    let conn = db.connection()?;
+    let callsign = callsign.trim().to_uppercase();
+    validate_callsign(&callsign)?;
+    let callsign = resolve_callsign_conn(&conn, &callsign)?;
+
+    let mut candidates = Vec::new();
+    for card in list_callsign_cards_conn(&conn, &callsign)? {
+        if is_shippable(&card.status)
+            && card.qty > card.distributed_qty
+            && open_shipment_conn(&conn, &card.id)?.is_none()
+        {
+            candidates.push(card);
+        }
+    }
+    Ok(candidates)
+}
+
+/// 校验合并寄件的卡片
+///
+/// 卡片须存在、不重复、属于同一呼号（按别名解析）、尚有待交付数量，且未加入其他待确认订单。
+/// 下单前调用，避免顺丰已受理而本地保存失败。
+pub fn check_shipment_cards(card_ids: &[String]) -> Result<(), AppError> {
+    check_shipment_cards_in(database()?, card_ids)
+}
+
+/// 同 [`check_shipment_cards`]，在指定的数据库上下文中执行
+pub fn check_shipment_cards_in(db: &Database, card_ids: &[String]) -> Result<(), AppError> {
+    let conn = db.connection()?;
+    check_shipment_cards_conn(&conn, card_ids)
+}
+
+/// 校验主体（crate-private，下单事务内复用）。
+pub(crate) fn check_shipment_cards_conn(
+    conn: &Connection,
+    card_ids: &[String],
+) -> Result<(), AppError> {
+    let mut seen = HashSet::new();
+    let mut callsign: Option<String> = None;
+
+    for id in card_ids {
+        if !seen.insert(id.as_str()) {
+            return Err(AppError::InvalidParameter(format!("卡片重复加入寄件: {}", id)));
+        }
+
+        let card = get_card_conn(conn, id)?
+            .ok_or_else(|| AppError::ProfileNotFound(format!("卡片不存在: {}", id)))?;
+        if !is_shippable(&card.status) || card.remaining_qty() == 0 {
+            return Err(AppError::InvalidParameter(format!(
+                "卡片 {} 当前状态为「{}」，无法加入寄件",
+                card.callsign,
+                card.status.display_name()
+            )));
+        }
+
+        let resolved = resolve_callsign_on_conn(conn, &card.callsign, &card.created_at)?;
+        match callsign {
+            Some(ref c) if *c != resolved => {
+                return Err(AppError::InvalidParameter(format!(
+                    "合并寄件的卡片须属于同一呼号：{} 与 {}",
+                    c, resolved
+                )));
+            }
+            Some(_) => {}
+            None => callsign = Some(resolved),
+        }
+
+        if let Some(order_id) = open_shipment_conn(conn, id)? {
+            return Err(AppError::InvalidParameter(format!(
+                "卡片 {} 已加入待确认订单 {}",
+                card.callsign, order_id
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// 写入订单与卡片的关联（按给定顺序）
+pub(crate) fn link_order_cards_conn(
+    conn: &Connection,
+    order_id: &str,
+    card_ids: &[String],
+    created_at: &str,
+) -> Result<(), AppError> {
+    for card_id in card_ids {
+        conn.execute(
+            "INSERT OR IGNORE INTO sf_order_cards (order_id, card_id, created_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![order_id, card_id, created_at],
+        )
+        .map_err(|e| AppError::Other(format!("写入寄件关联失败 ({}): {}", card_id, e)))?;
+    }
+    Ok(())
+}
+
+/// 关联旧格式卡片的待处理运单号（与迁移 2026.10.18.009 的回填一致）
+///
+/// 卡片加入运单号相同的既有订单；没有对应订单时生成已确认的占位订单 `legacy-<卡片 ID>` 并关联卡片，
+/// 分发时可照常带出运单号。
+pub(crate) fn link_pending_waybill_conn(
+    conn: &Connection,
+    card_id: &str,
+    waybill_no: &str,
+    linked_at: &str,
+) -> Result<(), AppError> {
+    let linked = conn
+        .execute(
+            "INSERT OR IGNORE INTO sf_order_cards (order_id, card_id, created_at)
+             SELECT id, ?1, ?2 FROM sf_orders WHERE waybill_no = ?3",
+            rusqlite::params![card_id, linked_at, waybill_no],
+        )
+        .map_err(|e| AppError::Other(format!("写入寄件关联失败 ({}): {}", card_id, e)))?;
+    if linked > 0 {
+        return Ok(());
+    }
+
+    let order_id = format!("legacy-{}", card_id);
+    conn.execute(
+        "INSERT OR IGNORE INTO sf_orders (id, order_id, waybill_no, card_id, status, sender_info, recipient_info, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, '{}', '{}', ?6, ?6)",
+        rusqlite::params![
+            &order_id,
+            format!("LEGACY-{}", card_id),
+            waybill_no,
+            card_id,
+            OrderStatus::Confirmed.to_string(),
+            linked_at,
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("创建占位订单失败 ({}): {}", card_id, e)))?;
+    link_order_cards_conn(conn, &order_id, &[card_id.to_string()], linked_at)
+}
+
+/// 收件地址（省市区 + 详细地址 + 收件人）
+fn format_recipient_address(recipient: &RecipientInfo) -> String {
+    let address = format!(
+        "{}{}{}{}",
+        recipient.province, recipient.city, recipient.district, recipient.address
+    );
+    match recipient.name.trim() {
+        "" => address,
+        name => format!("{} {}", address, name),
+    }
+}
+
+/// 确认合并寄件订单
+///
+/// 顺丰确认订单后调用：有运单号时以「快递」方式、订单收件地址与运单号逐张分发关联卡片的剩余全部数量，
+/// 并将订单标记为已确认（记录运单号）。分发与订单状态在同一事务内写入，失败时整体回滚，可重新确认；
+/// 订单已确认时不再重复分发。单张卡片失败（如期间已标记遗失）不影响其余卡片，结果见返回的批量报告。
+///
+/// `order_id` 为顺丰订单号。
+pub fn confirm_shipment(
+    order_id: &str,
+    waybill_no: Option<String>,
+) -> Result<(SFOrder, Option<BatchReport>), AppError> {
+    confirm_shipment_in(database()?, order_id, waybill_no)
+}
+
+/// 同 [`confirm_shipment`]，在指定的数据库上下文中执行
+pub fn confirm_shipment_in(
+    db: &Database,
+    order_id: &str,
+    waybill_no: Option<String>,
+) -> Result<(SFOrder, Option<BatchReport>), AppError> {
+    let operator_id = db.current_operator_id();
+    let mut conn = db.connection()?;
+    let mut tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+
+    let order = get_order_by_order_id_conn(&tx, order_id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("订单不存在: {}", order_id)))?;
+
+    let distribution = match waybill_no {
+        Some(ref waybill)
+            if !order.card_ids.is_empty()
+                && order.status != OrderStatus::Confirmed.to_string() =>
+        {
+            log::info!(
+                "分发寄件关联卡片: {} 张, waybill={}",
+                order.card_ids.len(),
+                waybill
+            );
+            let address = format_recipient_address(&order.recipient_info);
+            Some(distribute_shipment_tx(
+                &mut tx,
+                order.card_ids.clone(),
+                &address,
+                waybill,
+                operator_id.as_deref(),
+            )?)
+        }
+        _ => None,
+    };
+
+    update_order_status_conn(&tx, order_id, OrderStatus::Confirmed, waybill_no)?;
+    let order = get_order_by_order_id_conn(&tx, order_id)?
+        .ok_or_else(|| AppError::Other("更新后无法获取订单".to_string()))?;
+
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    Ok((order, distribution))
+}
+
+/// 在确认订单的事务内逐张分发关联卡片
+fn distribute_shipment_tx(
+    tx: &mut Transaction,
+    card_ids: Vec<String>,
+    address: &str,
+    waybill_no: &str,
+    operator_id: Option<&str>,
+) -> Result<BatchReport, AppError> {
+    run_batch_tx(
+        tx,
+        BatchOperation::Distribute,
+        BatchTarget::Ids(card_ids),
+        &mut |_| {},
+        |conn, id| {
+            distribute_card_conn(
+                conn,
+                id,
+                SHIPMENT_METHOD.to_string(),
+                Some(address.to_string()),
+                None,
+                None,
+                None,
+                Some(waybill_no.to_string()),
+                operator_id,
+            )
+            .map(|_| ())
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::card_events::list_card_events_in;
+    use crate::db::cards::{create_card_in, get_card_in, set_card_status_in};
+    use crate::db::handling_methods::update_handling_method_in;
+    use crate::db::models::{CardEventType, HandlingMethodKind, HandlingMethodUpdate};
+    use crate::db::projects::create_project_in;
+    use crate::db::sf_express::{create_order_in, get_order_by_card_id_in};
+    use crate::sf_express::SenderInfo;
+
+    fn recipient() -> RecipientInfo {
+        RecipientInfo {
+            name: "张三".to_string(),
+            phone: "13800000000".to_string(),
+            province: "北京市".to_string(),
+            city: "北京市".to_string(),
+            district: "海淀区".to_string(),
+            address: "某街道1号".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 合并寄件：跨项目同一呼号的卡片加入同一订单，确认后逐张分发并记录运单号
+    #[test]
+    fn test_shipment_links_and_distributes_cards() {
+        let db = Database::open_in_memory().unwrap();
+        let first = create_project_in(&db, "项目一".to_string()).unwrap();
+        let second = create_project_in(&db, "项目二".to_string()).unwrap();
+        let a = create_card_in(&db, first.id.clone(), "BH2RO".to_string(), 2, Some(1)).unwrap();
+        let b = create_card_in(&db, second.id.clone(), "BH2RO".to_string(), 1, Some(1)).unwrap();
+        let c = create_card_in(&db, second.id.clone(), "BG2ABC".to_string(), 1, Some(2)).unwrap();
+
+        let candidates = list_shipment_candidates_in(&db, "bh2ro").unwrap();
+        assert_eq!(candidates.len(), 2);
+
+        // 不同呼号不可合并
+        let err = create_order_in(
+            &db,
+            "QSL1".to_string(),
+            vec![a.id.clone(), c.id.clone()],
+            None,
+            None,
+            SenderInfo::default(),
+            recipient(),
+        );
+        assert!(err.is_err());
+
+        let order = create_order_in(
+            &db,
+            "QSL2".to_string(),
+            vec![a.id.clone(), b.id.clone()],
+            None,
+            None,
+            SenderInfo::default(),
+            recipient(),
+        )
+        .unwrap();
+        assert_eq!(order.card_id.as_deref(), Some(a.id.as_str()));
+        let loaded = get_order_by_card_id_in(&db, &b.id).unwrap().unwrap();
+        assert_eq!(loaded.card_ids, vec![a.id.clone(), b.id.clone()]);
+        assert!(list_shipment_candidates_in(&db, "BH2RO").unwrap().is_empty());
+
+        // 已加入待确认订单的卡片不可重复加入
+        assert!(create_order_in(
+            &db,
+            "QSL3".to_string(),
+            vec![b.id.clone()],
+            None,
+            None,
+            SenderInfo::default(),
+            recipient(),
+        )
+        .is_err());
+
+        // 确认前其中一张已遗失：该卡分发失败，其余卡片照常分发；
+        // 「快递」已停用不影响已下单寄件的分发
+        set_card_status_in(&db, &b.id, CardStatus::Lost, None).unwrap();
+        update_handling_method_in(
+            &db,
+            HandlingMethodKind::Distribution,
+            SHIPMENT_METHOD,
+            HandlingMethodUpdate {
+                is_active: Some(false),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let (confirmed, report) =
+            confirm_shipment_in(&db, "QSL2", Some("SF1001".to_string())).unwrap();
+        assert_eq!(confirmed.status, OrderStatus::Confirmed.to_string());
+        assert_eq!(confirmed.waybill_no.as_deref(), Some("SF1001"));
+        let report = report.unwrap();
+        assert_eq!(report.total, 2);
+        assert_eq!(report.succeeded, 1);
+        assert_eq!(report.items[1].card_id, b.id);
+        assert!(!report.items[1].success);
+
+        let card = get_card_in(&db, &a.id).unwrap().unwrap();
+        assert_eq!(card.status, CardStatus::Distributed);
+        let distribution = card.metadata.unwrap().distribution.unwrap();
+        assert_eq!(distribution.method, "快递");
+        assert_eq!(distribution.waybill_no.as_deref(), Some("SF1001"));
+        assert_eq!(distribution.address.as_deref(), Some("北京市北京市海淀区某街道1号 张三"));
+
+        // 加入寄件与分发均记入事件日志；重复确认不再分发
+        let events: Vec<CardEventType> = list_card_events_in(&db, &a.id)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event_type)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                CardEventType::Entered,
+                CardEventType::WaybillAttached,
+                CardEventType::Distributed
+            ]
+        );
+        let (_, again) = confirm_shipment_in(&db, "QSL2", Some("SF1001".to_string())).unwrap();
+        assert!(again.is_none());
+        assert_eq!(list_card_events_in(&db, &a.id).unwrap().len(), 3);
+    }
+}
@@ -2,14 +2,43 @@
 //
 // 提供数据库连接、初始化和迁移功能
 
+use crate::db::backups::{backup_dir_for, snapshot_connection, BackupReason};
 use crate::error::AppError;
 use include_dir::{include_dir, Dir};
 use once_cell::sync::OnceCell;
 use rusqlite::Connection;
-use std::path::PathBuf;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
 
-/// 数据库路径缓存
-static DB_PATH: OnceCell<PathBuf> = OnceCell::new();
+/// 应用全局数据库（`init_database` 后可用）
+static DATABASE: OnceCell<Database> = OnceCell::new();
+
+/// 命令行指定的数据目录（优先于环境变量）
+static DATA_DIR_OVERRIDE: OnceCell<PathBuf> = OnceCell::new();
+
+/// 覆盖数据目录的环境变量（便携安装时使用）
+///
+/// 数据目录仅存放数据库文件及其快照（`backups/`）；配置文件、模板与钥匙串凭据
+/// 仍使用各自的默认位置，不随数据目录移动。
+pub const DATA_DIR_ENV: &str = "QSL_CARDHUB_DATA_DIR";
+
+/// 覆盖数据目录的命令行参数（`--data-dir <目录>` 或 `--data-dir=<目录>`）
+pub const DATA_DIR_ARG: &str = "--data-dir";
+
+/// 内存数据库的路径标识
+pub const MEMORY_DB_PATH: &str = ":memory:";
+
+/// 连接池保留的空闲连接上限（超出的连接归还时直接关闭）
+const MAX_IDLE_CONNECTIONS: usize = 4;
+
+/// 数据库被其他连接锁定时的等待时间
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 每个连接缓存的预编译语句数量
+const STATEMENT_CACHE_CAPACITY: usize = 64;
 
 /// 编译时嵌入的迁移文件目录
 static MIGRATIONS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/migrations");
@@ -21,16 +50,51 @@ struct Migration {
     sql: String,
 }
 
-/// 获取数据库文件路径
-pub fn get_db_path() -> Result<PathBuf, AppError> {
+/// 从命令行参数中解析数据目录（`--data-dir <目录>` 或 `--data-dir=<目录>`）
+pub fn data_dir_from_args<I: IntoIterator<Item = String>>(args: I) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == DATA_DIR_ARG {
+            return args.next().filter(|v| !v.is_empty()).map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix(DATA_DIR_ARG).and_then(|v| v.strip_prefix('=')) {
+            return (!value.is_empty()).then(|| PathBuf::from(value));
+        }
+    }
+    None
+}
+
+/// 设置数据目录覆盖（须在 `init_database` 之前调用，仅首次生效）
+pub fn set_data_dir_override(dir: PathBuf) {
+    let _ = DATA_DIR_OVERRIDE.set(dir);
+}
+
+/// 获取数据目录（数据库文件与快照所在目录）
+///
+/// 优先级：命令行 `--data-dir` > 环境变量 `QSL_CARDHUB_DATA_DIR` > 默认目录。
+/// 覆盖只影响数据库，配置目录不变。
+pub fn get_data_dir() -> Result<PathBuf, AppError> {
+    let data_dir = match DATA_DIR_OVERRIDE.get() {
+        Some(dir) => dir.clone(),
+        None => match std::env::var_os(DATA_DIR_ENV).filter(|v| !v.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => default_data_dir()?,
+        },
+    };
+
+    std::fs::create_dir_all(&data_dir).map_err(|e| {
+        AppError::DirectoryCreationFailed(format!("无法创建数据目录: {}", e))
+    })?;
+
+    Ok(data_dir)
+}
+
+/// 默认数据目录
+fn default_data_dir() -> Result<PathBuf, AppError> {
     // 开发模式：使用项目根目录的 data/
     #[cfg(debug_assertions)]
     {
-        let data_dir = PathBuf::from("data");
-        std::fs::create_dir_all(&data_dir).map_err(|e| {
-            AppError::DirectoryCreationFailed(format!("无法创建数据目录: {}", e))
-        })?;
-        return Ok(data_dir.join("cards.db"));
+        return Ok(PathBuf::from("data"));
     }
 
     // 生产模式：使用系统配置目录
@@ -54,14 +118,15 @@ pub fn get_db_path() -> Result<PathBuf, AppError> {
             home_dir.join(".config").join("qsl-cardhub")
         };
 
-        std::fs::create_dir_all(&config_dir).map_err(|e| {
-            AppError::DirectoryCreationFailed(format!("无法创建配置目录: {}", e))
-        })?;
-
-        Ok(config_dir.join("cards.db"))
+        Ok(config_dir)
     }
 }
 
+/// 获取数据库文件路径
+pub fn get_db_path() -> Result<PathBuf, AppError> {
+    Ok(get_data_dir()?.join("cards.db"))
+}
+
 /// 初始化数据库
 ///
 /// 如果数据库文件不存在，创建新数据库并执行初始化脚本。
@@ -71,44 +136,275 @@ pub fn init_database() -> Result<(), AppError> {
 
     log::info!("📁 数据库路径: {}", db_path.display());
 
-    // 创建或打开数据库连接
-    let conn = Connection::open(&db_path).map_err(|e| {
-        AppError::Other(format!("无法打开数据库: {}", e))
-    })?;
+    let database = Database::open(&db_path)?;
+    let _ = DATABASE.set(database);
 
-    // 启用外键支持
-    conn.execute("PRAGMA foreign_keys = ON;", []).map_err(|e| {
-        AppError::Other(format!("无法启用外键支持: {}", e))
-    })?;
+    log::info!("✅ 数据库初始化完成");
+    Ok(())
+}
 
-    // 执行自动化迁移
-    run_migrations(&conn)?;
+/// 数据库上下文
+///
+/// 持有指向某个数据库文件（或内存数据库）的连接池。`db::*` 函数的 `_in` 变体
+/// 接收该上下文，便于库调用方与测试使用独立的数据库；不带后缀的函数使用应用全局数据库。
+///
+/// 上下文同时持有当前操作员会话，卡片的各项修改归属到该操作员。
+pub struct Database {
+    pool: ConnectionPool,
+    /// 内存数据库的常驻连接：共享缓存的内存库在最后一个连接关闭时销毁
+    _memory_keeper: Option<Mutex<Connection>>,
+    /// 当前登录的操作员 ID
+    current_operator: RwLock<Option<String>>,
+}
 
-    // 缓存数据库路径
-    let _ = DB_PATH.set(db_path);
+impl Database {
+    /// 打开指定路径的数据库并执行迁移；路径为 `:memory:` 时打开内存数据库
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, AppError> {
+        let path = path.as_ref();
+        if path == Path::new(MEMORY_DB_PATH) {
+            return Self::open_in_memory();
+        }
 
-    log::info!("✅ 数据库初始化完成");
-    Ok(())
+        // 创建或打开数据库连接（启用 WAL 与外键支持）
+        let conn = open_configured_connection(path)?;
+
+        // 有待执行的迁移时先备份现有数据库（备份失败不阻止启动）
+        if has_pending_migrations(&conn)? {
+            let backup_dir = backup_dir_for(path);
+            if let Err(e) = snapshot_connection(&conn, &backup_dir, BackupReason::PreMigration) {
+                log::error!("迁移前备份失败: {}", e);
+            }
+        }
+
+        // 执行自动化迁移
+        run_migrations(&conn)?;
+
+        // 迁移用的连接作为首个空闲连接
+        let pool = ConnectionPool::new(path.to_path_buf());
+        pool.release(conn, pool.generation());
+
+        Ok(Self {
+            pool,
+            _memory_keeper: None,
+            current_operator: RwLock::new(None),
+        })
+    }
+
+    /// 打开一个独立的内存数据库并执行迁移（各实例互不可见）
+    pub fn open_in_memory() -> Result<Self, AppError> {
+        let uri = format!(
+            "file:qsl-cardhub-{}?mode=memory&cache=shared",
+            uuid::Uuid::new_v4().simple()
+        );
+        let keeper = open_configured_connection(Path::new(&uri))?;
+        run_migrations(&keeper)?;
+
+        Ok(Self {
+            pool: ConnectionPool::new(PathBuf::from(uri)),
+            _memory_keeper: Some(Mutex::new(keeper)),
+            current_operator: RwLock::new(None),
+        })
+    }
+
+    /// 从连接池取出一个连接，离开作用域时自动归还
+    pub fn connection(&self) -> Result<PooledConnection<'_>, AppError> {
+        self.pool.get()
+    }
+
+    /// 数据库文件路径（内存数据库为 None）
+    pub fn file_path(&self) -> Option<&Path> {
+        match self._memory_keeper {
+            Some(_) => None,
+            None => Some(&self.pool.path),
+        }
+    }
+
+    /// 作废全部连接：空闲连接立即关闭，正在使用的连接归还时关闭而不再复用
+    ///
+    /// 删除数据库文件或整库替换数据库内容（如从快照恢复）前后调用，之后取出的均为新打开的连接。
+    pub fn reset_connections(&self) {
+        self.pool.reset();
+    }
+
+    /// 当前登录的操作员 ID（未登录时为 None）
+    pub fn current_operator_id(&self) -> Option<String> {
+        self.current_operator
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 设置当前操作员会话（None 表示注销）
+    pub(crate) fn set_current_operator_id(&self, operator_id: Option<String>) {
+        *self
+            .current_operator
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = operator_id;
+    }
 }
 
-/// 获取数据库连接（每次创建新连接）
-pub fn get_connection() -> Result<Connection, AppError> {
-    let db_path = DB_PATH
+/// 获取应用全局数据库
+pub fn database() -> Result<&'static Database, AppError> {
+    DATABASE
         .get()
-        .ok_or_else(|| AppError::Other("数据库未初始化".to_string()))?;
+        .ok_or_else(|| AppError::Other("数据库未初始化".to_string()))
+}
 
-    let conn = Connection::open(db_path).map_err(|e| {
+/// 打开数据库文件并设置连接参数
+///
+/// - WAL 日志模式：读写互不阻塞，`synchronous = NORMAL` 在 WAL 下仍保证一致性
+/// - 忙等待超时：避免并发写入时立即返回 `database is locked`
+/// - 外键约束与预编译语句缓存
+fn open_configured_connection(path: &Path) -> Result<Connection, AppError> {
+    let conn = Connection::open(path).map_err(|e| {
         AppError::Other(format!("无法打开数据库: {}", e))
     })?;
 
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::Other(format!("无法启用 WAL 模式: {}", e)))?;
+    conn.pragma_update(None, "synchronous", "NORMAL")
+        .map_err(|e| AppError::Other(format!("无法设置同步模式: {}", e)))?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| AppError::Other(format!("无法设置忙等待超时: {}", e)))?;
+
     // 启用外键支持
     conn.execute("PRAGMA foreign_keys = ON;", []).map_err(|e| {
         AppError::Other(format!("无法启用外键支持: {}", e))
     })?;
 
+    conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+
     Ok(conn)
 }
 
+/// 数据库连接池
+///
+/// 复用已配置好的连接（含各自的预编译语句缓存），空闲连接不足时按需新建。
+/// 每次作废全部连接时代数加一，取出时代数较旧的连接归还时直接关闭。
+struct ConnectionPool {
+    path: PathBuf,
+    idle: Mutex<Vec<Connection>>,
+    generation: AtomicU64,
+}
+
+impl ConnectionPool {
+    /// 创建指向 `path` 的空连接池
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            idle: Mutex::new(Vec::new()),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// 当前代数
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// 取出一个连接（优先复用空闲连接）
+    fn get(&self) -> Result<PooledConnection<'_>, AppError> {
+        let generation = self.generation();
+        let idle = self.lock_idle().pop();
+        let conn = match idle {
+            Some(conn) => conn,
+            None => open_configured_connection(&self.path)?,
+        };
+
+        Ok(PooledConnection {
+            conn: Some(conn),
+            pool: self,
+            generation,
+        })
+    }
+
+    /// 归还连接：仍处于事务中、取出后连接已被作废或空闲连接已满时直接关闭
+    fn release(&self, conn: Connection, generation: u64) {
+        if !conn.is_autocommit() {
+            log::warn!("连接归还时仍处于事务中，已关闭");
+            return;
+        }
+
+        let mut idle = self.lock_idle();
+        if generation == self.generation() && idle.len() < MAX_IDLE_CONNECTIONS {
+            idle.push(conn);
+        }
+    }
+
+    /// 作废全部连接（持有空闲连接锁时加代数，避免旧连接在清空后被归还）
+    fn reset(&self) {
+        let mut idle = self.lock_idle();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        idle.clear();
+    }
+
+    /// 空闲连接数
+    #[cfg(test)]
+    fn idle_count(&self) -> usize {
+        self.lock_idle().len()
+    }
+
+    fn lock_idle(&self) -> std::sync::MutexGuard<'_, Vec<Connection>> {
+        // 持锁期间不会 panic，中毒时直接取回内部数据
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 从连接池取出的连接，离开作用域时自动归还
+pub struct PooledConnection<'a> {
+    conn: Option<Connection>,
+    pool: &'a ConnectionPool,
+    /// 取出时连接池的代数
+    generation: u64,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("连接已归还")
+    }
+}
+
+impl DerefMut for PooledConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn.as_mut().expect("连接已归还")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.release(conn, self.generation);
+        }
+    }
+}
+
+/// 获取数据库连接（从应用全局数据库的连接池复用）
+pub fn get_connection() -> Result<PooledConnection<'static>, AppError> {
+    database()?.connection()
+}
+
+/// 关闭应用全局数据库的全部连接
+///
+/// 空闲连接立即关闭，正在使用的连接归还时关闭而不再复用。
+/// 删除数据库文件（恢复出厂设置）前调用，避免文件被占用。
+pub fn close_all_connections() {
+    if let Some(database) = DATABASE.get() {
+        database.reset_connections();
+    }
+}
+
+/// 打开内存数据库并执行全部迁移（仅测试使用，schema 与生产一致）
+#[cfg(test)]
+pub(crate) fn open_test_connection() -> Connection {
+    let conn = Connection::open_in_memory().expect("无法打开内存数据库");
+    conn.execute("PRAGMA foreign_keys = ON;", [])
+        .expect("无法启用外键支持");
+    run_migrations(&conn).expect("执行迁移失败");
+    conn
+}
+
 /// 获取数据库版本
 pub fn get_db_version(conn: &Connection) -> Result<i32, AppError> {
     let version: i32 = conn
@@ -268,8 +564,19 @@ fn migrate_version_number(old_version: i32) -> i32 {
     old_version
 }
 
+/// 是否有待执行的迁移（新建的空数据库返回 false）
+pub(crate) fn has_pending_migrations(conn: &Connection) -> Result<bool, AppError> {
+    let current_version = get_db_version(conn)?;
+    if current_version == 0 {
+        return Ok(false);
+    }
+    let current_version = migrate_version_number(current_version);
+
+    Ok(parse_migrations()?.iter().any(|m| m.version > current_version))
+}
+
 /// 执行数据库迁移
-fn run_migrations(conn: &Connection) -> Result<(), AppError> {
+pub(crate) fn run_migrations(conn: &Connection) -> Result<(), AppError> {
     let migrations = parse_migrations()?;
     let mut current_version = get_db_version(conn)?;
 
@@ -309,7 +616,23 @@ fn run_migrations(conn: &Connection) -> Result<(), AppError> {
 }
 
 /// 执行迁移 SQL，对 ALTER TABLE ADD COLUMN 语句忽略"duplicate column name"错误
+///
+/// 重建表的迁移在文件中以 `BEGIN IMMEDIATE` … `COMMIT` 包裹重建过程，
+/// `PRAGMA foreign_keys` 须写在事务之外（事务内设置无效）。
+/// 迁移失败时回滚未提交的事务并恢复外键约束。
 fn execute_migration_sql(conn: &Connection, sql: &str, migration_name: &str) -> Result<(), AppError> {
+    let result = execute_migration_statements(conn, sql, migration_name);
+    if result.is_err() {
+        if !conn.is_autocommit() {
+            let _ = conn.execute_batch("ROLLBACK");
+        }
+        let _ = conn.execute_batch("PRAGMA foreign_keys = ON");
+    }
+    result
+}
+
+/// 逐条执行迁移语句（`PRAGMA foreign_key_check` 发现违反外键的行时返回错误）
+fn execute_migration_statements(conn: &Connection, sql: &str, migration_name: &str) -> Result<(), AppError> {
     // 将 SQL 拆分为单独的语句
     let statements: Vec<&str> = sql
         .split(';')
@@ -335,6 +658,12 @@ fn execute_migration_sql(conn: &Connection, sql: &str, migration_name: &str) ->
             continue;
         }
 
+        // 重建表后、提交前校验外键
+        if actual_sql.trim().eq_ignore_ascii_case("PRAGMA foreign_key_check") {
+            check_foreign_keys(conn, migration_name)?;
+            continue;
+        }
+
         // 执行语句
         let result = conn.execute(&actual_sql, []);
 
@@ -358,6 +687,28 @@ fn execute_migration_sql(conn: &Connection, sql: &str, migration_name: &str) ->
     Ok(())
 }
 
+/// 执行 `PRAGMA foreign_key_check`，存在违反外键的行时返回错误
+fn check_foreign_keys(conn: &Connection, migration_name: &str) -> Result<(), AppError> {
+    let mut stmt = conn
+        .prepare("PRAGMA foreign_key_check")
+        .map_err(|e| AppError::Other(format!("迁移 {} 执行失败: {}", migration_name, e)))?;
+    let violations = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::Other(format!("迁移 {} 执行失败: {}", migration_name, e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("迁移 {} 执行失败: {}", migration_name, e)))?;
+
+    if let Some(table) = violations.first() {
+        return Err(AppError::Other(format!(
+            "迁移 {} 执行失败: 表 {} 有 {} 行违反外键约束",
+            migration_name,
+            table,
+            violations.len()
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -463,4 +814,221 @@ mod tests {
             assert!(migrations[i].version > migrations[i - 1].version);
         }
     }
+
+    /// 按文件名顺序执行迁移，直到（不含）指定迁移
+    fn migrate_before(conn: &Connection, name: &str) {
+        conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+        for migration in parse_migrations().unwrap() {
+            if migration.name.as_str() >= name {
+                break;
+            }
+            execute_migration_sql(conn, &migration.sql, &migration.name).unwrap();
+        }
+    }
+
+    /// 迁移 006 在事务中重建 cards：保留事件与元数据，回填已分发数量，完成后恢复外键
+    #[test]
+    fn test_partial_distribution_migration_rebuilds_cards() {
+        let name = "2026.10.18.006_add_partial_distribution.sql";
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_before(&conn, name);
+        conn.execute_batch(
+            r#"
+            INSERT INTO projects (id, name, created_at, updated_at) VALUES ('p1', '项目', '2026-01-01', '2026-01-01');
+            INSERT INTO cards (id, project_id, callsign, qty, status, created_at, updated_at)
+            VALUES ('c1', 'p1', 'BH2RO', 3, 'distributed', '2026-01-01', '2026-01-01');
+            INSERT INTO card_events (id, card_id, event_type, created_at) VALUES ('e1', 'c1', 'entered', '2026-01-01');
+            INSERT INTO card_distributions (card_id, method, distributed_at) VALUES ('c1', '自取', '2026-01-02');
+            "#,
+        )
+        .unwrap();
+
+        let migration = parse_migrations()
+            .unwrap()
+            .into_iter()
+            .find(|m| m.name == name)
+            .unwrap();
+        execute_migration_sql(&conn, &migration.sql, &migration.name).unwrap();
+
+        assert!(conn.is_autocommit());
+        let fk: i32 = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+        assert_eq!(fk, 1);
+        let distributed_qty: i32 = conn
+            .query_row("SELECT distributed_qty FROM cards WHERE id = 'c1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(distributed_qty, 3);
+        let kept: i64 = conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM card_events) + (SELECT COUNT(*) FROM card_distributions)",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(kept, 2);
+    }
+
+    /// 迁移 007 在事务中重建 cards 与 card_events：事件按写入顺序保留，修改历史不被级联删除
+    #[test]
+    fn test_hold_states_migration_rebuilds_events() {
+        let name = "2026.10.18.007_add_card_hold_states.sql";
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_before(&conn, name);
+        conn.execute_batch(
+            r#"
+            INSERT INTO projects (id, name, created_at, updated_at) VALUES ('p1', '项目', '2026-01-01', '2026-01-01');
+            INSERT INTO cards (id, project_id, callsign, qty, status, created_at, updated_at)
+            VALUES ('c1', 'p1', 'BH2RO', 1, 'pending', '2026-01-01', '2026-01-01');
+            INSERT INTO card_events (id, card_id, event_type, created_at) VALUES ('e2', 'c1', 'entered', '2026-01-01');
+            INSERT INTO card_events (id, card_id, event_type, created_at) VALUES ('e1', 'c1', 'distributed', '2026-01-01');
+            INSERT INTO card_history (card_id, field, old_value, new_value, changed_at)
+            VALUES ('c1', 'qty', '2', '1', '2026-01-01');
+            "#,
+        )
+        .unwrap();
+
+        let migration = parse_migrations()
+            .unwrap()
+            .into_iter()
+            .find(|m| m.name == name)
+            .unwrap();
+        execute_migration_sql(&conn, &migration.sql, &migration.name).unwrap();
+
+        assert!(conn.is_autocommit());
+        let fk: i32 = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+        assert_eq!(fk, 1);
+        let ids: Vec<String> = conn
+            .prepare("SELECT id FROM card_events ORDER BY created_at, rowid")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(ids, ["e2", "e1"]);
+        let history: i64 = conn
+            .query_row("SELECT COUNT(*) FROM card_history", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(history, 1);
+        conn.execute(
+            "INSERT INTO card_events (id, card_id, event_type, created_at) VALUES ('e3', 'c1', 'held', '2026-01-02')",
+            [],
+        )
+        .unwrap();
+    }
+
+    /// 重建迁移提交前校验外键：存在违反外键的行时整体回滚并恢复外键
+    #[test]
+    fn test_rebuild_migration_rolls_back_on_foreign_key_violation() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+        let sql = r#"
+            PRAGMA foreign_keys = OFF;
+            BEGIN IMMEDIATE;
+            CREATE TABLE parents (id TEXT PRIMARY KEY);
+            CREATE TABLE children (parent_id TEXT REFERENCES parents(id));
+            INSERT INTO children (parent_id) VALUES ('missing');
+            PRAGMA foreign_key_check;
+            COMMIT;
+            PRAGMA foreign_keys = ON;
+        "#;
+
+        assert!(execute_migration_sql(&conn, sql, "test.sql").is_err());
+        assert!(conn.is_autocommit());
+        let fk: i32 = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+        assert_eq!(fk, 1);
+        let tables: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name IN ('parents', 'children')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tables, 0);
+    }
+
+    /// 连接池：连接使用 WAL 模式，归还后被复用，事务中与已作废的连接不归还
+    #[test]
+    fn test_connection_pool_reuse() {
+        let path = std::env::temp_dir().join(format!("qsl-pool-{}.db", uuid::Uuid::new_v4()));
+        let pool = ConnectionPool::new(path.clone());
+
+        {
+            let conn = pool.get().unwrap();
+            let mode: String = conn
+                .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(mode, "wal");
+            let fk: i32 = conn
+                .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(fk, 1);
+        }
+        assert_eq!(pool.idle_count(), 1);
+
+        {
+            let a = pool.get().unwrap();
+            let _b = pool.get().unwrap();
+            assert_eq!(pool.idle_count(), 0);
+            a.execute_batch("BEGIN").unwrap();
+        }
+        // a 仍处于事务中被关闭，只有 b 归还
+        assert_eq!(pool.idle_count(), 1);
+
+        pool.reset();
+        assert_eq!(pool.idle_count(), 0);
+
+        // 作废后，作废前取出的连接归还时关闭
+        let stale = pool.get().unwrap();
+        pool.reset();
+        drop(stale);
+        assert_eq!(pool.idle_count(), 0);
+        drop(pool.get().unwrap());
+        assert_eq!(pool.idle_count(), 1);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    /// 数据目录参数：支持空格与等号两种写法，空值忽略
+    #[test]
+    fn test_data_dir_from_args() {
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            data_dir_from_args(args(&["app", "--data-dir", "/portable/data"])),
+            Some(PathBuf::from("/portable/data"))
+        );
+        assert_eq!(
+            data_dir_from_args(args(&["app", "--data-dir=D:\\qsl"])),
+            Some(PathBuf::from("D:\\qsl"))
+        );
+        assert_eq!(data_dir_from_args(args(&["app", "--data-dir="])), None);
+        assert_eq!(data_dir_from_args(args(&["app", "--data-dir"])), None);
+        assert_eq!(data_dir_from_args(args(&["app"])), None);
+    }
+
+    /// 内存数据库：同一上下文的多个连接共享数据，不同上下文互相隔离
+    #[test]
+    fn test_in_memory_database_isolation() {
+        let a = Database::open(MEMORY_DB_PATH).unwrap();
+        let b = Database::open_in_memory().unwrap();
+
+        a.connection()
+            .unwrap()
+            .execute(
+                "INSERT INTO projects (id, name, created_at, updated_at) VALUES ('p1', 'A', 't', 't')",
+                [],
+            )
+            .unwrap();
+
+        let count = |db: &Database| -> i64 {
+            db.connection()
+                .unwrap()
+                .query_row("SELECT COUNT(*) FROM projects", [], |r| r.get(0))
+                .unwrap()
+        };
+        // 取两个连接，确保第二个连接也能看到数据
+        let _held = a.connection().unwrap();
+        assert_eq!(count(&a), 1);
+        assert_eq!(count(&b), 0);
+    }
 }
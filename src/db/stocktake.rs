@@ -0,0 +1,585 @@
+// 库存盘点模块
+//
+// 盘点会话中逐张扫描（标签条码）或录入（呼号 + 序列号）架上卡片，
+// 与盘点范围内存放中的卡片核对，生成缺失、非待分发与无法识别三类清单，可将缺失卡片批量标记遗失
+
+use crate::db::batch::run_batch;
+use crate::db::card_lookup::resolve_card_input_conn;
+use crate::db::cards::{
+    list_cards_matching_conn, set_card_status_conn, CARD_STOCKED_CONDITION, CARD_VISIBLE_CONDITION,
+};
+use crate::db::models::{
+    format_datetime, now_china, BatchOperation, BatchReport, BatchTarget, CardStatus,
+    CardWithProject, StocktakeReport, StocktakeScan, StocktakeScanOutcome, StocktakeScanResult,
+    StocktakeSession,
+};
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use rusqlite::Connection;
+use std::collections::HashSet;
+
+/// 会话查询列（与 `map_session_row` 的列序一致），会话表别名 `s`、项目表别名 `p`
+const SESSION_SELECT: &str = r#"
+    SELECT s.id, s.name, s.project_id, p.name, s.operator_id, s.remarks,
+           (SELECT COUNT(*) FROM stocktake_scans sc WHERE sc.session_id = s.id),
+           s.started_at, s.closed_at
+    FROM stocktake_sessions s
+    LEFT JOIN projects p ON p.id = s.project_id
+"#;
+
+/// 盘点范围条件（`?1` 为项目 ID，为空表示全部项目）
+const SCOPE_CONDITION: &str = "(?1 IS NULL OR c.project_id = ?1)";
+
+/// 已扫描条件（`?2` 为会话 ID）
+const SCANNED_CONDITION: &str =
+    "c.id IN (SELECT card_id FROM stocktake_scans WHERE session_id = ?2 AND card_id IS NOT NULL)";
+
+fn map_session_row(row: &rusqlite::Row) -> rusqlite::Result<StocktakeSession> {
+    Ok(StocktakeSession {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        project_id: row.get(2)?,
+        project_name: row.get(3)?,
+        operator_id: row.get(4)?,
+        remarks: row.get(5)?,
+        scan_count: row.get(6)?,
+        started_at: row.get(7)?,
+        closed_at: row.get(8)?,
+    })
+}
+
+fn map_scan_row(row: &rusqlite::Row) -> rusqlite::Result<StocktakeScan> {
+    Ok(StocktakeScan {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        input: row.get(2)?,
+        card_id: row.get(3)?,
+        scanned_at: row.get(4)?,
+    })
+}
+
+fn get_session_conn(conn: &Connection, id: &str) -> Result<Option<StocktakeSession>, AppError> {
+    let result = conn.query_row(
+        &format!("{}WHERE s.id = ?1", SESSION_SELECT),
+        [id],
+        map_session_row,
+    );
+
+    match result {
+        Ok(session) => Ok(Some(session)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询盘点失败: {}", e))),
+    }
+}
+
+/// 查询进行中的盘点（不存在或已结束时报错）
+fn open_session_conn(conn: &Connection, id: &str) -> Result<StocktakeSession, AppError> {
+    let session = get_session_conn(conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("盘点不存在: {}", id)))?;
+    if session.closed_at.is_some() {
+        return Err(AppError::InvalidParameter(format!(
+            "盘点「{}」已结束",
+            session.name
+        )));
+    }
+    Ok(session)
+}
+
+/// 卡片是否在盘点范围的存放清单中（与 `CARD_STOCKED_CONDITION` 一致）
+fn is_expected(card: &CardWithProject, session: &StocktakeSession) -> bool {
+    let stocked = matches!(
+        card.status,
+        CardStatus::Pending
+            | CardStatus::PartiallyDistributed
+            | CardStatus::AwaitingPostage
+            | CardStatus::Held
+    );
+    stocked
+        && session
+            .project_id
+            .as_ref()
+            .is_none_or(|project_id| *project_id == card.project_id)
+}
+
+/// 开始盘点
+///
+/// `project_id` 为空时盘点全部项目；`name` 为空时以当天日期命名。
+pub fn start_stocktake(
+    name: Option<String>,
+    project_id: Option<String>,
+    remarks: Option<String>,
+) -> Result<StocktakeSession, AppError> {
+    start_stocktake_in(database()?, name, project_id, remarks)
+}
+
+/// 同 [`start_stocktake`]，在指定的数据库上下文中执行
+pub fn start_stocktake_in(
+    db: &Database,
+    name: Option<String>,
+    project_id: Option<String>,
+    remarks: Option<String>,
+) -> Result<StocktakeSession, AppError> {
+    let conn = db.connection()?;
+
+    if let Some(ref project_id) = project_id {
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM projects WHERE id = ?1 AND deleted_at IS NULL)",
+                [project_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Other(format!("查询项目失败: {}", e)))?;
+        if !exists {
+            return Err(AppError::ProfileNotFound(format!(
+                "项目不存在: {}",
+                project_id
+            )));
+        }
+    }
+
+    let now = now_china();
+    let name = name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| format!("盘点 {}", now.format("%Y-%m-%d")));
+    let remarks = remarks
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    let id = uuid::Uuid::new_v4().to_string();
+
+    conn.execute(
+        r#"
+        INSERT INTO stocktake_sessions (id, name, project_id, operator_id, remarks, started_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+        rusqlite::params![
+            id,
+            name,
+            project_id,
+            db.current_operator_id(),
+            remarks,
+            format_datetime(&now)
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("创建盘点失败: {}", e)))?;
+
+    log::info!("✅ 开始盘点: {} ({})", name, id);
+    get_session_conn(&conn, &id)?.ok_or_else(|| AppError::Other("创建后无法获取盘点".to_string()))
+}
+
+/// 查询全部盘点（按开始时间倒序）
+pub fn list_stocktake_sessions() -> Result<Vec<StocktakeSession>, AppError> {
+    list_stocktake_sessions_in(database()?)
+}
+
+/// 同 [`list_stocktake_sessions`]，在指定的数据库上下文中执行
+pub fn list_stocktake_sessions_in(db: &Database) -> Result<Vec<StocktakeSession>, AppError> {
+    let conn = db.connection()?;
+    let mut stmt = conn
+        .prepare(&format!("{}ORDER BY s.started_at DESC", SESSION_SELECT))
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let sessions = stmt
+        .query_map([], map_session_row)
+        .map_err(|e| AppError::Other(format!("查询盘点失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取盘点失败: {}", e)))?;
+
+    Ok(sessions)
+}
+
+/// 记录一次扫描或录入
+///
+/// 同一张卡片（或同一无法识别的内容）在本次盘点中只记录一次，重复扫描返回此前的记录。
+pub fn record_stocktake_scan(
+    session_id: &str,
+    input: &str,
+) -> Result<StocktakeScanResult, AppError> {
+    record_stocktake_scan_in(database()?, session_id, input)
+}
+
+/// 同 [`record_stocktake_scan`]，在指定的数据库上下文中执行
+pub fn record_stocktake_scan_in(
+    db: &Database,
+    session_id: &str,
+    input: &str,
+) -> Result<StocktakeScanResult, AppError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(AppError::InvalidParameter("扫描内容不能为空".to_string()));
+    }
+
+    let conn = db.connection()?;
+    let session = open_session_conn(&conn, session_id)?;
+    let card_id = resolve_card_input_conn(&conn, session.project_id.as_deref(), input)?;
+
+    // 已识别的卡片按卡片去重，无法识别的内容按原文去重
+    let existing = conn.query_row(
+        r#"
+        SELECT id, session_id, input, card_id, scanned_at FROM stocktake_scans
+        WHERE session_id = ?1 AND (card_id = ?2 OR (?2 IS NULL AND card_id IS NULL AND input = ?3))
+        LIMIT 1
+        "#,
+        rusqlite::params![session_id, card_id, input],
+        map_scan_row,
+    );
+    let (scan, duplicate) = match existing {
+        Ok(scan) => (scan, true),
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+            let scanned_at = format_datetime(&now_china());
+            conn.execute(
+                r#"
+                INSERT INTO stocktake_scans (session_id, input, card_id, operator_id, scanned_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+                rusqlite::params![
+                    session_id,
+                    input,
+                    card_id,
+                    db.current_operator_id(),
+                    scanned_at
+                ],
+            )
+            .map_err(|e| AppError::Other(format!("记录扫描失败: {}", e)))?;
+            let scan = StocktakeScan {
+                id: conn.last_insert_rowid(),
+                session_id: session_id.to_string(),
+                input: input.to_string(),
+                card_id: card_id.clone(),
+                scanned_at,
+            };
+            (scan, false)
+        }
+        Err(e) => return Err(AppError::Other(format!("查询扫描记录失败: {}", e))),
+    };
+
+    let card = match card_id {
+        Some(ref id) => list_cards_matching_conn(&conn, "c.id = ?1", &[id])?
+            .into_iter()
+            .next(),
+        None => None,
+    };
+    let outcome = match &card {
+        _ if duplicate => StocktakeScanOutcome::Duplicate,
+        None => StocktakeScanOutcome::Unknown,
+        Some(card) if is_expected(card, &session) => StocktakeScanOutcome::Found,
+        Some(_) => StocktakeScanOutcome::NotPending,
+    };
+
+    Ok(StocktakeScanResult {
+        outcome,
+        scan,
+        card,
+    })
+}
+
+/// 撤销一条扫描记录（仅限进行中的盘点）
+pub fn delete_stocktake_scan(scan_id: i64) -> Result<(), AppError> {
+    delete_stocktake_scan_in(database()?, scan_id)
+}
+
+/// 同 [`delete_stocktake_scan`]，在指定的数据库上下文中执行
+pub fn delete_stocktake_scan_in(db: &Database, scan_id: i64) -> Result<(), AppError> {
+    let conn = db.connection()?;
+    let session_id: String = conn
+        .query_row(
+            "SELECT session_id FROM stocktake_scans WHERE id = ?1",
+            [scan_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => {
+                AppError::ProfileNotFound(format!("扫描记录不存在: {}", scan_id))
+            }
+            e => AppError::Other(format!("查询扫描记录失败: {}", e)),
+        })?;
+    open_session_conn(&conn, &session_id)?;
+
+    conn.execute("DELETE FROM stocktake_scans WHERE id = ?1", [scan_id])
+        .map_err(|e| AppError::Other(format!("删除扫描记录失败: {}", e)))?;
+    Ok(())
+}
+
+/// 盘点范围内应在架但未扫描到的卡片
+fn list_missing_conn(
+    conn: &Connection,
+    session: &StocktakeSession,
+) -> Result<Vec<CardWithProject>, AppError> {
+    list_cards_matching_conn(
+        conn,
+        &format!(
+            "{} AND {} AND NOT {}",
+            SCOPE_CONDITION, CARD_STOCKED_CONDITION, SCANNED_CONDITION
+        ),
+        &[&session.project_id, &session.id],
+    )
+}
+
+/// 生成盘点核对报告
+///
+/// 按当前卡片状态核对：盘点期间已分发的卡片若被扫描，列入非待分发清单。
+pub fn get_stocktake_report(session_id: &str) -> Result<StocktakeReport, AppError> {
+    get_stocktake_report_in(database()?, session_id)
+}
+
+/// 同 [`get_stocktake_report`]，在指定的数据库上下文中执行
+pub fn get_stocktake_report_in(
+    db: &Database,
+    session_id: &str,
+) -> Result<StocktakeReport, AppError> {
+    let conn = db.connection()?;
+    let session = get_session_conn(&conn, session_id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("盘点不存在: {}", session_id)))?;
+
+    let found_count: u32 = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*) FROM cards c WHERE {} AND {} AND {} AND {}",
+                CARD_VISIBLE_CONDITION, SCOPE_CONDITION, CARD_STOCKED_CONDITION, SCANNED_CONDITION
+            ),
+            rusqlite::params![session.project_id, session.id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Other(format!("统计盘点结果失败: {}", e)))?;
+
+    let missing = list_missing_conn(&conn, &session)?;
+    let not_pending = list_cards_matching_conn(
+        &conn,
+        &format!(
+            "{} AND NOT ({} AND {})",
+            SCANNED_CONDITION, SCOPE_CONDITION, CARD_STOCKED_CONDITION
+        ),
+        &[&session.project_id, &session.id],
+    )?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id, session_id, input, card_id, scanned_at FROM stocktake_scans
+            WHERE session_id = ?1 AND card_id IS NULL
+            ORDER BY id ASC
+            "#,
+        )
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+    let unknown = stmt
+        .query_map([&session.id], map_scan_row)
+        .map_err(|e| AppError::Other(format!("查询扫描记录失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取扫描记录失败: {}", e)))?;
+
+    Ok(StocktakeReport {
+        expected_count: found_count + missing.len() as u32,
+        found_count,
+        missing,
+        not_pending,
+        unknown,
+        session,
+    })
+}
+
+/// 结束盘点（结束后不可再扫描，仍可查看报告与批量标记遗失）
+pub fn close_stocktake(session_id: &str) -> Result<StocktakeSession, AppError> {
+    close_stocktake_in(database()?, session_id)
+}
+
+/// 同 [`close_stocktake`]，在指定的数据库上下文中执行
+pub fn close_stocktake_in(db: &Database, session_id: &str) -> Result<StocktakeSession, AppError> {
+    let conn = db.connection()?;
+    let session = open_session_conn(&conn, session_id)?;
+
+    conn.execute(
+        "UPDATE stocktake_sessions SET closed_at = ?1 WHERE id = ?2",
+        rusqlite::params![format_datetime(&now_china()), session_id],
+    )
+    .map_err(|e| AppError::Other(format!("结束盘点失败: {}", e)))?;
+
+    log::info!("✅ 结束盘点: {}", session.name);
+    get_session_conn(&conn, session_id)?
+        .ok_or_else(|| AppError::Other("结束后无法获取盘点".to_string()))
+}
+
+/// 删除盘点及其扫描记录（不影响卡片）
+pub fn delete_stocktake(session_id: &str) -> Result<(), AppError> {
+    delete_stocktake_in(database()?, session_id)
+}
+
+/// 同 [`delete_stocktake`]，在指定的数据库上下文中执行
+pub fn delete_stocktake_in(db: &Database, session_id: &str) -> Result<(), AppError> {
+    let mut conn = db.connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+
+    tx.execute(
+        "DELETE FROM stocktake_scans WHERE session_id = ?1",
+        [session_id],
+    )
+    .map_err(|e| AppError::Other(format!("删除扫描记录失败: {}", e)))?;
+    let affected = tx
+        .execute("DELETE FROM stocktake_sessions WHERE id = ?1", [session_id])
+        .map_err(|e| AppError::Other(format!("删除盘点失败: {}", e)))?;
+    if affected == 0 {
+        return Err(AppError::ProfileNotFound(format!(
+            "盘点不存在: {}",
+            session_id
+        )));
+    }
+
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+    log::info!("✅ 删除盘点: {}", session_id);
+    Ok(())
+}
+
+/// 将盘点缺失的卡片批量标记为遗失
+///
+/// 仅处理当前仍在缺失清单中的卡片（其余卡片在报告中记为失败），遗失备注注明盘点名称。
+pub fn mark_stocktake_missing_lost(
+    session_id: &str,
+    card_ids: Vec<String>,
+) -> Result<BatchReport, AppError> {
+    mark_stocktake_missing_lost_in(database()?, session_id, card_ids)
+}
+
+/// 同 [`mark_stocktake_missing_lost`]，在指定的数据库上下文中执行
+pub fn mark_stocktake_missing_lost_in(
+    db: &Database,
+    session_id: &str,
+    card_ids: Vec<String>,
+) -> Result<BatchReport, AppError> {
+    let operator_id = db.current_operator_id();
+    let mut conn = db.connection()?;
+    let session = get_session_conn(&conn, session_id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("盘点不存在: {}", session_id)))?;
+    let missing: HashSet<String> = list_missing_conn(&conn, &session)?
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    let remarks = format!("盘点「{}」未找到", session.name);
+
+    run_batch(
+        &mut conn,
+        BatchOperation::MarkLost,
+        BatchTarget::Ids(card_ids),
+        &mut |_| {},
+        |conn, id| {
+            if !missing.contains(id) {
+                return Err(AppError::InvalidParameter(
+                    "卡片不在本次盘点的缺失清单中".to_string(),
+                ));
+            }
+            set_card_status_conn(
+                conn,
+                id,
+                CardStatus::Lost,
+                Some(remarks.clone()),
+                operator_id.as_deref(),
+            )
+            .map(|_| ())
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::cards::{create_card_in, distribute_card_in, get_card_in};
+    use crate::db::projects::create_project_in;
+
+    /// 核对报告：缺失、非待分发（已分发或其他项目）与无法识别三类清单，缺失卡片批量标记遗失
+    #[test]
+    fn test_report_and_mark_missing_lost() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let other = create_project_in(&db, "其他".to_string()).unwrap();
+        let found =
+            create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, Some(1)).unwrap();
+        let missing =
+            create_card_in(&db, project.id.clone(), "BG2ABC".to_string(), 1, Some(2)).unwrap();
+        let distributed =
+            create_card_in(&db, project.id.clone(), "BA1AA".to_string(), 1, Some(3)).unwrap();
+        distribute_card_in(
+            &db,
+            &distributed.id,
+            "自取".to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let foreign =
+            create_card_in(&db, other.id.clone(), "BD4XY".to_string(), 1, Some(1)).unwrap();
+
+        let session = start_stocktake_in(&db, None, Some(project.id.clone()), None).unwrap();
+        assert!(session.name.starts_with("盘点 "));
+
+        let result = record_stocktake_scan_in(&db, &session.id, &found.id).unwrap();
+        assert_eq!(result.outcome, StocktakeScanOutcome::Found);
+        let result = record_stocktake_scan_in(&db, &session.id, "bh2ro 1").unwrap();
+        assert_eq!(result.outcome, StocktakeScanOutcome::Duplicate);
+        let result = record_stocktake_scan_in(&db, &session.id, "BA1AA#003").unwrap();
+        assert_eq!(result.outcome, StocktakeScanOutcome::NotPending);
+        let result = record_stocktake_scan_in(&db, &session.id, "BD4XY").unwrap();
+        assert_eq!(result.outcome, StocktakeScanOutcome::NotPending);
+        assert_eq!(result.card.unwrap().id, foreign.id);
+        let result = record_stocktake_scan_in(&db, &session.id, "JA1ZZZ").unwrap();
+        assert_eq!(result.outcome, StocktakeScanOutcome::Unknown);
+
+        let report = get_stocktake_report_in(&db, &session.id).unwrap();
+        assert_eq!(report.session.scan_count, 4);
+        assert_eq!(report.expected_count, 2);
+        assert_eq!(report.found_count, 1);
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].id, missing.id);
+        assert_eq!(report.not_pending.len(), 2);
+        assert_eq!(report.unknown[0].input, "JA1ZZZ");
+
+        close_stocktake_in(&db, &session.id).unwrap();
+        assert!(record_stocktake_scan_in(&db, &session.id, "BG2ABC").is_err());
+
+        let batch = mark_stocktake_missing_lost_in(
+            &db,
+            &session.id,
+            vec![missing.id.clone(), found.id.clone()],
+        )
+        .unwrap();
+        assert_eq!(batch.succeeded, 1);
+        assert!(!batch.items[1].success);
+        let lost = get_card_in(&db, &missing.id).unwrap().unwrap();
+        assert_eq!(lost.status, CardStatus::Lost);
+        assert!(
+            get_stocktake_report_in(&db, &session.id)
+                .unwrap()
+                .missing
+                .is_empty()
+        );
+    }
+
+    /// 全部项目盘点时同一呼号对应多张存放中的卡片，须补充序列号
+    #[test]
+    fn test_ambiguous_callsign_requires_serial() {
+        let db = Database::open_in_memory().unwrap();
+        let first = create_project_in(&db, "项目一".to_string()).unwrap();
+        let second = create_project_in(&db, "项目二".to_string()).unwrap();
+        create_card_in(&db, first.id.clone(), "BH2RO".to_string(), 1, Some(1)).unwrap();
+        let card = create_card_in(&db, second.id.clone(), "BH2RO".to_string(), 1, Some(7)).unwrap();
+
+        let session = start_stocktake_in(&db, Some("年度盘点".to_string()), None, None).unwrap();
+        assert!(record_stocktake_scan_in(&db, &session.id, "BH2RO").is_err());
+        let result = record_stocktake_scan_in(&db, &session.id, "BH2RO 7").unwrap();
+        assert_eq!(result.outcome, StocktakeScanOutcome::Found);
+        assert_eq!(result.scan.card_id.as_deref(), Some(card.id.as_str()));
+
+        delete_stocktake_scan_in(&db, result.scan.id).unwrap();
+        assert_eq!(
+            get_stocktake_report_in(&db, &session.id)
+                .unwrap()
+                .missing
+                .len(),
+            2
+        );
+        delete_stocktake_in(&db, &session.id).unwrap();
+        assert!(list_stocktake_sessions_in(&db).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,653 @@
+// 存放位置模块
+//
+// 维护待分发卡片的存放位置（柜/盒/格）及其自动分配规则：
+// 录入卡片时按呼号前缀、分区数字或后缀首字母匹配第一个符合规则的格位
+
+use crate::db::cards::{get_card_conn, CARD_STOCKED_CONDITION, CARD_VISIBLE_CONDITION};
+use crate::db::models::{format_datetime, now_china, StorageLocation, StorageLocationInput};
+use crate::db::sqlite::{database, Database};
+use crate::error::AppError;
+use rusqlite::Connection;
+
+/// 位置编号（柜-盒[-格]，卡片查询中的 SQL 表达式须与之一致）
+fn location_code(cabinet: &str, box_no: &str, slot: &str) -> String {
+    if slot.is_empty() {
+        format!("{}-{}", cabinet, box_no)
+    } else {
+        format!("{}-{}-{}", cabinet, box_no, slot)
+    }
+}
+
+/// 查询 SQL（列序与 `map_location_row` 一致）
+fn location_query(condition: &str) -> String {
+    format!(
+        r#"
+        SELECT sl.id, sl.cabinet, sl.box_no, sl.slot, sl.match_prefix, sl.match_district, sl.match_suffix,
+               sl.sort_order, sl.remarks, sl.created_at, sl.updated_at,
+               (SELECT COUNT(*) FROM cards c WHERE c.storage_location_id = sl.id AND {} AND {})
+        FROM storage_locations sl
+        {}
+        ORDER BY sl.sort_order ASC, sl.cabinet ASC, sl.box_no ASC, sl.slot ASC
+        "#,
+        CARD_VISIBLE_CONDITION, CARD_STOCKED_CONDITION, condition
+    )
+}
+
+/// 将查询行映射为 `StorageLocation`（列序见 `location_query`）
+fn map_location_row(row: &rusqlite::Row) -> rusqlite::Result<StorageLocation> {
+    let cabinet: String = row.get(1)?;
+    let box_no: String = row.get(2)?;
+    let slot: String = row.get(3)?;
+
+    Ok(StorageLocation {
+        id: row.get(0)?,
+        code: location_code(&cabinet, &box_no, &slot),
+        cabinet,
+        box_no,
+        slot,
+        match_prefix: row.get(4)?,
+        match_district: row.get(5)?,
+        match_suffix: row.get(6)?,
+        sort_order: row.get(7)?,
+        remarks: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+        card_count: row.get(11)?,
+    })
+}
+
+fn get_location_conn(conn: &Connection, id: &str) -> Result<Option<StorageLocation>, AppError> {
+    let result = conn.query_row(&location_query("WHERE sl.id = ?1"), [id], map_location_row);
+
+    match result {
+        Ok(location) => Ok(Some(location)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("查询存放位置失败: {}", e))),
+    }
+}
+
+/// 基本呼号（`/` 分隔时取最长的一段，如 `VK9/BH2RO/P` 取 `BH2RO`）
+fn base_callsign(callsign: &str) -> &str {
+    callsign.split('/').fold(
+        "",
+        |best, part| if part.len() > best.len() { part } else { best },
+    )
+}
+
+/// 呼号的分区数字与后缀（基本呼号中最后一个数字及其后的部分，如 `BH2RO` 为 `2`、`RO`）
+fn district_and_suffix(base: &str) -> Option<(char, &str)> {
+    let index = base.rfind(|c: char| c.is_ascii_digit())?;
+    let district = base[index..].chars().next()?;
+    Some((district, &base[index + 1..]))
+}
+
+/// 字符集合是否包含 `c`（集合形如 `1-3,5`、`A-M`，已规范化）
+fn char_set_contains(set: &str, c: char) -> bool {
+    set.split(',').any(|item| {
+        let chars: Vec<char> = item.chars().collect();
+        match chars.as_slice() {
+            [single] => *single == c,
+            [from, '-', to] => (*from..=*to).contains(&c),
+            _ => false,
+        }
+    })
+}
+
+/// 位置的分配规则是否匹配呼号（规则均为空时不匹配）
+fn rules_match(location: &StorageLocation, callsign: &str) -> bool {
+    if location.match_prefix.is_none()
+        && location.match_district.is_none()
+        && location.match_suffix.is_none()
+    {
+        return false;
+    }
+
+    let base = base_callsign(callsign);
+    let parts = district_and_suffix(base);
+
+    if let Some(prefixes) = &location.match_prefix
+        && !prefixes.split(',').any(|p| base.starts_with(p))
+    {
+        return false;
+    }
+    if let Some(districts) = &location.match_district
+        && !parts.is_some_and(|(district, _)| char_set_contains(districts, district))
+    {
+        return false;
+    }
+    if let Some(letters) = &location.match_suffix
+        && !parts
+            .and_then(|(_, suffix)| suffix.chars().next())
+            .is_some_and(|first| char_set_contains(letters, first))
+    {
+        return false;
+    }
+    true
+}
+
+/// 按分配规则查找呼号的存放位置（crate-private，录入卡片时分配）
+///
+/// 按排列顺序返回第一个规则匹配的位置 ID，无匹配时返回 `None`。
+pub(crate) fn assign_storage_location_conn(
+    conn: &Connection,
+    callsign: &str,
+) -> Result<Option<String>, AppError> {
+    let callsign = callsign.trim().to_uppercase();
+    let location = list_storage_locations_conn(conn)?
+        .into_iter()
+        .find(|location| rules_match(location, &callsign));
+    Ok(location.map(|l| l.id))
+}
+
+fn list_storage_locations_conn(conn: &Connection) -> Result<Vec<StorageLocation>, AppError> {
+    let mut stmt = conn
+        .prepare(&location_query(""))
+        .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+
+    let locations = stmt
+        .query_map([], map_location_row)
+        .map_err(|e| AppError::Other(format!("查询存放位置失败: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Other(format!("读取存放位置失败: {}", e)))?;
+
+    Ok(locations)
+}
+
+/// 规范化字符集合规则（逗号分隔的单个字符或 `X-Y` 区间，空白视为未设置）
+fn normalize_char_set(
+    value: Option<&str>,
+    field: &str,
+    is_valid: fn(char) -> bool,
+) -> Result<Option<String>, AppError> {
+    let value = match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => v.to_uppercase().replace(char::is_whitespace, ""),
+        None => return Ok(None),
+    };
+
+    let mut items = Vec::new();
+    for item in value.split(',').filter(|i| !i.is_empty()) {
+        let chars: Vec<char> = item.chars().collect();
+        let valid = match chars.as_slice() {
+            [single] => is_valid(*single),
+            [from, '-', to] => is_valid(*from) && is_valid(*to) && from <= to,
+            _ => false,
+        };
+        if !valid {
+            return Err(AppError::InvalidParameter(format!(
+                "{}格式无效：{}",
+                field, item
+            )));
+        }
+        items.push(item.to_string());
+    }
+
+    Ok(if items.is_empty() {
+        None
+    } else {
+        Some(items.join(","))
+    })
+}
+
+/// 规范化呼号前缀规则（逗号分隔，仅字母与数字）
+fn normalize_prefixes(value: Option<&str>) -> Result<Option<String>, AppError> {
+    let value = match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => v.to_uppercase().replace(char::is_whitespace, ""),
+        None => return Ok(None),
+    };
+
+    let prefixes: Vec<&str> = value.split(',').filter(|p| !p.is_empty()).collect();
+    if let Some(invalid) = prefixes
+        .iter()
+        .find(|p| !p.chars().all(|c| c.is_ascii_alphanumeric()))
+    {
+        return Err(AppError::InvalidParameter(format!(
+            "呼号前缀格式无效：{}",
+            invalid
+        )));
+    }
+
+    Ok(if prefixes.is_empty() {
+        None
+    } else {
+        Some(prefixes.join(","))
+    })
+}
+
+/// 校验并规范化登记内容（柜、盒必填，统一大写）
+fn normalize_input(input: StorageLocationInput) -> Result<StorageLocationInput, AppError> {
+    let cabinet = input.cabinet.trim().to_uppercase();
+    let box_no = input.box_no.trim().to_uppercase();
+    let slot = input.slot.trim().to_uppercase();
+    if cabinet.is_empty() || box_no.is_empty() {
+        return Err(AppError::InvalidParameter(
+            "存放位置须填写柜与盒".to_string(),
+        ));
+    }
+    if [&cabinet, &box_no, &slot]
+        .iter()
+        .any(|part| part.contains('-'))
+    {
+        return Err(AppError::InvalidParameter(
+            "柜、盒、格名称不能包含「-」".to_string(),
+        ));
+    }
+
+    Ok(StorageLocationInput {
+        cabinet,
+        box_no,
+        slot,
+        match_prefix: normalize_prefixes(input.match_prefix.as_deref())?,
+        match_district: normalize_char_set(input.match_district.as_deref(), "分区数字", |c| {
+            c.is_ascii_digit()
+        })?,
+        match_suffix: normalize_char_set(input.match_suffix.as_deref(), "后缀首字母", |c| {
+            c.is_ascii_uppercase()
+        })?,
+        sort_order: input.sort_order,
+        remarks: input
+            .remarks
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty()),
+    })
+}
+
+/// 检查编号是否已被其他位置使用（排除 `id` 自身）
+fn check_code_unique(
+    conn: &Connection,
+    id: Option<&str>,
+    input: &StorageLocationInput,
+) -> Result<(), AppError> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM storage_locations WHERE cabinet = ?1 AND box_no = ?2 AND slot = ?3 AND (?4 IS NULL OR id != ?4))",
+            rusqlite::params![input.cabinet, input.box_no, input.slot, id],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Other(format!("查询存放位置失败: {}", e)))?;
+
+    if exists {
+        return Err(AppError::InvalidParameter(format!(
+            "存放位置已存在: {}",
+            location_code(&input.cabinet, &input.box_no, &input.slot)
+        )));
+    }
+    Ok(())
+}
+
+/// 查询全部存放位置（按排列顺序，含存放中的卡片数）
+pub fn list_storage_locations() -> Result<Vec<StorageLocation>, AppError> {
+    list_storage_locations_in(database()?)
+}
+
+/// 同 [`list_storage_locations`]，在指定的数据库上下文中执行
+pub fn list_storage_locations_in(db: &Database) -> Result<Vec<StorageLocation>, AppError> {
+    let conn = db.connection()?;
+    list_storage_locations_conn(&conn)
+}
+
+/// 查询单个存放位置
+pub fn get_storage_location(id: &str) -> Result<Option<StorageLocation>, AppError> {
+    get_storage_location_in(database()?, id)
+}
+
+/// 同 [`get_storage_location`]，在指定的数据库上下文中执行
+pub fn get_storage_location_in(
+    db: &Database,
+    id: &str,
+) -> Result<Option<StorageLocation>, AppError> {
+    let conn = db.connection()?;
+    get_location_conn(&conn, id)
+}
+
+/// 新建存放位置
+///
+/// 已录入的卡片不受影响，可通过 [`assign_storage_locations`] 为尚未分配位置的卡片补充分配。
+pub fn create_storage_location(input: StorageLocationInput) -> Result<StorageLocation, AppError> {
+    create_storage_location_in(database()?, input)
+}
+
+/// 同 [`create_storage_location`]，在指定的数据库上下文中执行
+pub fn create_storage_location_in(
+    db: &Database,
+    input: StorageLocationInput,
+) -> Result<StorageLocation, AppError> {
+    let input = normalize_input(input)?;
+    let conn = db.connection()?;
+    check_code_unique(&conn, None, &input)?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = format_datetime(&now_china());
+    conn.execute(
+        r#"
+        INSERT INTO storage_locations (id, cabinet, box_no, slot, match_prefix, match_district, match_suffix, sort_order, remarks, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10)
+        "#,
+        rusqlite::params![
+            id,
+            input.cabinet,
+            input.box_no,
+            input.slot,
+            input.match_prefix,
+            input.match_district,
+            input.match_suffix,
+            input.sort_order,
+            input.remarks,
+            now,
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("新建存放位置失败: {}", e)))?;
+
+    log::info!(
+        "✅ 新建存放位置成功: {}",
+        location_code(&input.cabinet, &input.box_no, &input.slot)
+    );
+    get_location_conn(&conn, &id)?
+        .ok_or_else(|| AppError::Other("新建后无法获取存放位置".to_string()))
+}
+
+/// 修改存放位置（编号与分配规则，已存放的卡片保持不变）
+pub fn update_storage_location(
+    id: &str,
+    input: StorageLocationInput,
+) -> Result<StorageLocation, AppError> {
+    update_storage_location_in(database()?, id, input)
+}
+
+/// 同 [`update_storage_location`]，在指定的数据库上下文中执行
+pub fn update_storage_location_in(
+    db: &Database,
+    id: &str,
+    input: StorageLocationInput,
+) -> Result<StorageLocation, AppError> {
+    let input = normalize_input(input)?;
+    let conn = db.connection()?;
+    if get_location_conn(&conn, id)?.is_none() {
+        return Err(AppError::ProfileNotFound(format!("存放位置不存在: {}", id)));
+    }
+    check_code_unique(&conn, Some(id), &input)?;
+
+    let now = format_datetime(&now_china());
+    conn.execute(
+        r#"
+        UPDATE storage_locations
+        SET cabinet = ?1, box_no = ?2, slot = ?3, match_prefix = ?4, match_district = ?5,
+            match_suffix = ?6, sort_order = ?7, remarks = ?8, updated_at = ?9
+        WHERE id = ?10
+        "#,
+        rusqlite::params![
+            input.cabinet,
+            input.box_no,
+            input.slot,
+            input.match_prefix,
+            input.match_district,
+            input.match_suffix,
+            input.sort_order,
+            input.remarks,
+            now,
+            id
+        ],
+    )
+    .map_err(|e| AppError::Other(format!("更新存放位置失败: {}", e)))?;
+
+    log::info!("✅ 更新存放位置成功: {}", id);
+    get_location_conn(&conn, id)?
+        .ok_or_else(|| AppError::Other("更新后无法获取存放位置".to_string()))
+}
+
+/// 删除存放位置（仍有卡片存放时拒绝删除，已分发卡片的位置记录一并清除）
+pub fn delete_storage_location(id: &str) -> Result<(), AppError> {
+    delete_storage_location_in(database()?, id)
+}
+
+/// 同 [`delete_storage_location`]，在指定的数据库上下文中执行
+pub fn delete_storage_location_in(db: &Database, id: &str) -> Result<(), AppError> {
+    let mut conn = db.connection()?;
+    let location = get_location_conn(&conn, id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("存放位置不存在: {}", id)))?;
+    if location.card_count > 0 {
+        return Err(AppError::InvalidParameter(format!(
+            "存放位置 {} 中仍有 {} 张卡片，请先移出",
+            location.code, location.card_count
+        )));
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+    tx.execute(
+        "UPDATE cards SET storage_location_id = NULL WHERE storage_location_id = ?1",
+        [id],
+    )
+    .map_err(|e| AppError::Other(format!("清除卡片存放位置失败: {}", e)))?;
+    tx.execute("DELETE FROM storage_locations WHERE id = ?1", [id])
+        .map_err(|e| AppError::Other(format!("删除存放位置失败: {}", e)))?;
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!("✅ 删除存放位置成功: {}", location.code);
+    Ok(())
+}
+
+/// 修改卡片的存放位置（`location_id` 为 `None` 时清除）
+pub fn set_card_storage_location(
+    card_id: &str,
+    location_id: Option<String>,
+) -> Result<(), AppError> {
+    set_card_storage_location_in(database()?, card_id, location_id)
+}
+
+/// 同 [`set_card_storage_location`]，在指定的数据库上下文中执行
+pub fn set_card_storage_location_in(
+    db: &Database,
+    card_id: &str,
+    location_id: Option<String>,
+) -> Result<(), AppError> {
+    let conn = db.connection()?;
+    let card = get_card_conn(&conn, card_id)?
+        .ok_or_else(|| AppError::ProfileNotFound(format!("卡片不存在: {}", card_id)))?;
+    let new_code = match &location_id {
+        Some(id) => Some(
+            get_location_conn(&conn, id)?
+                .ok_or_else(|| AppError::ProfileNotFound(format!("存放位置不存在: {}", id)))?
+                .code,
+        ),
+        None => None,
+    };
+
+    let now = format_datetime(&now_china());
+    conn.execute(
+        "UPDATE cards SET storage_location_id = ?1, updated_at = ?2 WHERE id = ?3",
+        rusqlite::params![location_id, now, card_id],
+    )
+    .map_err(|e| AppError::Other(format!("更新存放位置失败: {}", e)))?;
+
+    log::info!(
+        "✅ 卡片 {} 存放位置: {}",
+        card.callsign,
+        new_code.as_deref().unwrap_or("无")
+    );
+    Ok(())
+}
+
+/// 为尚未分配位置的存放中卡片按规则补充分配（`project_id` 为空时处理全部项目）
+///
+/// 返回新分配位置的卡片数；规则不匹配的卡片保持未分配。
+pub fn assign_storage_locations(project_id: Option<&str>) -> Result<u32, AppError> {
+    assign_storage_locations_in(database()?, project_id)
+}
+
+/// 同 [`assign_storage_locations`]，在指定的数据库上下文中执行
+pub fn assign_storage_locations_in(
+    db: &Database,
+    project_id: Option<&str>,
+) -> Result<u32, AppError> {
+    let mut conn = db.connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| AppError::Other(format!("无法开始事务: {}", e)))?;
+
+    let locations = list_storage_locations_conn(&tx)?;
+    let cards: Vec<(String, String)> = {
+        let mut stmt = tx
+            .prepare(&format!(
+                r#"
+                SELECT c.id, c.callsign FROM cards c
+                WHERE c.storage_location_id IS NULL AND (?1 IS NULL OR c.project_id = ?1) AND {} AND {}
+                "#,
+                CARD_VISIBLE_CONDITION, CARD_STOCKED_CONDITION
+            ))
+            .map_err(|e| AppError::Other(format!("准备查询语句失败: {}", e)))?;
+        stmt.query_map([project_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| AppError::Other(format!("查询卡片失败: {}", e)))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Other(format!("读取卡片失败: {}", e)))?
+    };
+
+    let mut assigned = 0;
+    for (card_id, callsign) in cards {
+        if let Some(location) = locations.iter().find(|l| rules_match(l, &callsign)) {
+            tx.execute(
+                "UPDATE cards SET storage_location_id = ?1 WHERE id = ?2",
+                [&location.id, &card_id],
+            )
+            .map_err(|e| AppError::Other(format!("更新存放位置失败: {}", e)))?;
+            assigned += 1;
+        }
+    }
+
+    tx.commit()
+        .map_err(|e| AppError::Other(format!("提交事务失败: {}", e)))?;
+
+    log::info!("✅ 按规则分配存放位置: {} 张卡片", assigned);
+    Ok(assigned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::cards::{create_card_in, get_card_in, list_cards_in};
+    use crate::db::models::{CardFilter, Pagination};
+    use crate::db::projects::create_project_in;
+
+    fn input(cabinet: &str, box_no: &str, slot: &str) -> StorageLocationInput {
+        StorageLocationInput {
+            cabinet: cabinet.to_string(),
+            box_no: box_no.to_string(),
+            slot: slot.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 分配规则：按分区数字与后缀首字母匹配，排列靠前者优先，规则格式校验
+    #[test]
+    fn test_rules_assign_location_on_card_entry() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+
+        let first_half = create_storage_location_in(
+            &db,
+            StorageLocationInput {
+                match_district: Some("2".to_string()),
+                match_suffix: Some("a - m".to_string()),
+                sort_order: 1,
+                ..input("a", "02", "1")
+            },
+        )
+        .unwrap();
+        assert_eq!(first_half.code, "A-02-1");
+        assert_eq!(first_half.match_suffix.as_deref(), Some("A-M"));
+        let district = create_storage_location_in(
+            &db,
+            StorageLocationInput {
+                match_district: Some("1-3,5".to_string()),
+                sort_order: 2,
+                ..input("A", "02", "")
+            },
+        )
+        .unwrap();
+        let dx = create_storage_location_in(
+            &db,
+            StorageLocationInput {
+                match_prefix: Some("VK, JA".to_string()),
+                ..input("B", "01", "")
+            },
+        )
+        .unwrap();
+
+        assert!(create_storage_location_in(&db, input("A", "02", "1")).is_err());
+        let bad = StorageLocationInput {
+            match_suffix: Some("M-A".to_string()),
+            ..input("C", "01", "")
+        };
+        assert!(create_storage_location_in(&db, bad).is_err());
+
+        let card =
+            create_card_in(&db, project.id.clone(), "BH2ABC".to_string(), 1, Some(1)).unwrap();
+        assert_eq!(
+            card.storage_location_id.as_deref(),
+            Some(first_half.id.as_str())
+        );
+        let card =
+            create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, Some(2)).unwrap();
+        assert_eq!(
+            card.storage_location_id.as_deref(),
+            Some(district.id.as_str())
+        );
+        let card =
+            create_card_in(&db, project.id.clone(), "VK9/BA5XY".to_string(), 1, Some(3)).unwrap();
+        assert_eq!(
+            card.storage_location_id.as_deref(),
+            Some(district.id.as_str())
+        );
+        let card =
+            create_card_in(&db, project.id.clone(), "VK2XX".to_string(), 1, Some(4)).unwrap();
+        assert_eq!(card.storage_location_id.as_deref(), Some(dx.id.as_str()));
+        let card =
+            create_card_in(&db, project.id.clone(), "BG7AA".to_string(), 1, Some(5)).unwrap();
+        assert!(card.storage_location_id.is_none());
+
+        let filter = CardFilter {
+            storage_location_id: Some(district.id.clone()),
+            ..Default::default()
+        };
+        let paged = list_cards_in(&db, filter, Pagination::default()).unwrap();
+        assert_eq!(paged.total, 2);
+        assert_eq!(paged.items[0].storage_location.as_deref(), Some("A-02"));
+    }
+
+    /// 手动调整、补充分配与删除保护
+    #[test]
+    fn test_manual_assignment_and_delete_guard() {
+        let db = Database::open_in_memory().unwrap();
+        let project = create_project_in(&db, "项目".to_string()).unwrap();
+        let card =
+            create_card_in(&db, project.id.clone(), "BH2RO".to_string(), 1, Some(1)).unwrap();
+        assert!(card.storage_location_id.is_none());
+
+        let location = create_storage_location_in(
+            &db,
+            StorageLocationInput {
+                match_district: Some("2".to_string()),
+                ..input("A", "01", "")
+            },
+        )
+        .unwrap();
+        assert_eq!(assign_storage_locations_in(&db, None).unwrap(), 1);
+        assert_eq!(assign_storage_locations_in(&db, None).unwrap(), 0);
+        let loaded = get_storage_location_in(&db, &location.id).unwrap().unwrap();
+        assert_eq!(loaded.card_count, 1);
+
+        assert!(delete_storage_location_in(&db, &location.id).is_err());
+        set_card_storage_location_in(&db, &card.id, None).unwrap();
+        assert!(
+            get_card_in(&db, &card.id)
+                .unwrap()
+                .unwrap()
+                .storage_location_id
+                .is_none()
+        );
+        assert!(set_card_storage_location_in(&db, &card.id, Some("missing".to_string())).is_err());
+        delete_storage_location_in(&db, &location.id).unwrap();
+        assert!(list_storage_locations_in(&db).unwrap().is_empty());
+    }
+}
@@ -18,17 +18,43 @@ mod utils;
 
 use commands::{
     app_settings::{get_all_app_settings_cmd, get_app_setting_cmd, set_app_setting_cmd},
+    backups::{
+        create_backup_cmd, delete_backup_cmd, get_backup_settings_cmd, list_backups_cmd,
+        restore_backup_cmd, save_backup_settings_cmd,
+    },
+    callsign_aliases::{
+        check_callsign_alias_cmd, create_callsign_alias_cmd, delete_callsign_alias_cmd,
+        list_callsign_aliases_cmd, update_callsign_alias_cmd,
+    },
+    card_import::{import_cards_file_cmd, preview_card_import_cmd},
     cards::{
-        create_card_cmd, delete_card_cmd, distribute_card_cmd, get_card_cmd, get_max_serial_cmd,
-        get_project_callsigns_cmd, list_cards_cmd, return_card_cmd, save_card_address_cmd,
-        save_pending_waybill_cmd,
+        batch_delete_cards_cmd, batch_distribute_cards_cmd, batch_move_cards_cmd,
+        batch_return_cards_cmd, create_card_cmd, delete_card_cmd, distribute_card_cmd,
+        get_callsign_dossier_cmd, get_card_cmd, get_card_events_cmd, get_card_history_cmd,
+        get_max_serial_cmd, get_project_callsigns_cmd, get_card_transitions_cmd, list_cards_cmd,
+        lookup_card_cmd, return_card_cmd, save_card_address_cmd, set_card_status_cmd,
+        update_card_cmd,
+    },
+    data_transfer::{
+        export_data, import_data, is_backup_encrypted, merge_import_data, preview_import_data,
+        preview_merge_import_data,
+    },
+    export::{
+        delete_export_profile_cmd, export_cards_to_excel, list_export_profiles_cmd,
+        save_export_profile_cmd,
     },
-    data_transfer::{export_data, import_data, preview_import_data},
-    export::export_cards_to_excel,
     factory_reset::factory_reset,
+    handling_methods::{
+        create_handling_method_cmd, list_handling_methods_cmd, update_handling_method_cmd,
+    },
     logger::{clear_logs, export_logs, get_log_file_path, get_logs, log_from_frontend},
+    operators::{
+        create_operator_cmd, delete_operator_cmd, get_current_operator_cmd,
+        get_operator_stats_cmd, list_operators_cmd, login_operator_cmd, logout_operator_cmd,
+        set_operator_pin_cmd, update_operator_cmd,
+    },
     platform::get_platform_info,
-    printer::{PrinterState, generate_tspl, get_address_template_config, get_printers, get_template_config, load_template, preview_address, preview_qsl, print_address, print_qsl, save_address_template_config, save_template, save_template_config},
+    printer::{PrinterState, generate_tspl, get_address_template_config, get_printers, get_template_config, load_template, preview_address, preview_qsl, preview_storage_label, print_address, print_qsl, print_storage_label, save_address_template_config, save_template, save_template_config},
     profile::{
         ProfileState, create_profile, delete_profile, export_profile, get_default_profile_id,
         get_default_template_name, get_printer_config, get_profile, get_profiles, import_profile,
@@ -47,9 +73,21 @@ use commands::{
         qrz_com_query_callsign, qrz_com_save_and_login, qrz_com_test_connection,
     },
     qrz_herbertgao::qrz_herbertgao_query_callsign,
+    qsl_managers::{
+        create_qsl_manager_cmd, delete_qsl_manager_cmd, import_qsl_managers_csv_cmd,
+        list_qsl_managers_cmd, update_qsl_manager_cmd,
+    },
+    recycle_bin::{
+        empty_recycle_bin_cmd, list_recycle_bin_cmd, purge_card_cmd, purge_project_cmd,
+        restore_card_cmd, restore_project_cmd,
+    },
     security::{
         check_keyring_available, clear_credentials, load_credentials, save_credentials,
     },
+    serial_schemes::{
+        audit_project_serials_cmd, get_serial_scheme_cmd, renumber_project_serials_cmd,
+        set_serial_scheme_cmd,
+    },
     sf_express::{
         sf_clear_config, sf_fetch_waybill, sf_load_config, sf_print_waybill, sf_save_config,
         sf_get_default_api_config, sf_apply_default_api_config,
@@ -58,10 +96,20 @@ use commands::{
         sf_get_default_sender, sf_set_default_sender,
         // 下单管理
         sf_create_order, sf_confirm_order, sf_cancel_order, sf_search_order,
+        sf_list_shipment_candidates,
         // 订单列表
         sf_list_orders, sf_get_order, sf_get_order_by_order_id, sf_get_order_by_card_id,
         sf_delete_order, sf_mark_order_printed,
     },
+    stocktake::{
+        close_stocktake_cmd, delete_stocktake_cmd, delete_stocktake_scan_cmd,
+        get_stocktake_report_cmd, list_stocktake_sessions_cmd, mark_stocktake_missing_lost_cmd,
+        record_stocktake_scan_cmd, start_stocktake_cmd,
+    },
+    storage_locations::{
+        assign_storage_locations_cmd, create_storage_location_cmd, delete_storage_location_cmd,
+        list_storage_locations_cmd, set_card_storage_location_cmd, update_storage_location_cmd,
+    },
     sync::{
         clear_sync_config_cmd, execute_sync_cmd, export_sync_config_string_cmd,
         import_sync_config_string_cmd, load_sync_config_cmd, restore_from_cloud,
@@ -75,6 +123,11 @@ use std::sync::{Arc, Mutex};
 use tauri::Manager;
 
 fn main() {
+    // 命令行 --data-dir 覆盖数据目录（优先于环境变量 QSL_CARDHUB_DATA_DIR），仅影响数据库与快照，配置目录不变
+    if let Some(dir) = db::sqlite::data_dir_from_args(std::env::args()) {
+        db::sqlite::set_data_dir_override(dir);
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_process::init())
@@ -102,6 +155,9 @@ fn main() {
             // 初始化数据库
             db::init_database().map_err(|e| format!("无法初始化数据库: {}", e))?;
 
+            // 启动定时备份
+            db::start_backup_scheduler();
+
             // 初始化 ProfileManager
             let profile_manager = ProfileManager::new(config_dir)
                 .map_err(|e| format!("无法初始化配置管理器: {}", e))?;
@@ -151,6 +207,8 @@ fn main() {
             save_template_config,
             get_address_template_config,
             save_address_template_config,
+            preview_storage_label,
+            print_storage_label,
             // 日志管理
             get_logs,
             clear_logs,
@@ -169,11 +227,78 @@ fn main() {
             get_card_cmd,
             get_max_serial_cmd,
             get_project_callsigns_cmd,
+            get_callsign_dossier_cmd,
+            lookup_card_cmd,
+            update_card_cmd,
+            get_card_history_cmd,
+            get_card_events_cmd,
+            batch_distribute_cards_cmd,
+            batch_return_cards_cmd,
+            batch_delete_cards_cmd,
+            batch_move_cards_cmd,
             distribute_card_cmd,
             return_card_cmd,
+            set_card_status_cmd,
+            get_card_transitions_cmd,
             delete_card_cmd,
             save_card_address_cmd,
-            save_pending_waybill_cmd,
+            // 卡片表格导入
+            preview_card_import_cmd,
+            import_cards_file_cmd,
+            // 呼号别名
+            list_callsign_aliases_cmd,
+            check_callsign_alias_cmd,
+            create_callsign_alias_cmd,
+            update_callsign_alias_cmd,
+            delete_callsign_alias_cmd,
+            // QSL 管理员
+            list_qsl_managers_cmd,
+            create_qsl_manager_cmd,
+            update_qsl_manager_cmd,
+            delete_qsl_manager_cmd,
+            import_qsl_managers_csv_cmd,
+            // 序列号编号方案
+            get_serial_scheme_cmd,
+            set_serial_scheme_cmd,
+            audit_project_serials_cmd,
+            renumber_project_serials_cmd,
+            // 存放位置
+            list_storage_locations_cmd,
+            create_storage_location_cmd,
+            update_storage_location_cmd,
+            delete_storage_location_cmd,
+            set_card_storage_location_cmd,
+            assign_storage_locations_cmd,
+            // 库存盘点
+            start_stocktake_cmd,
+            list_stocktake_sessions_cmd,
+            record_stocktake_scan_cmd,
+            delete_stocktake_scan_cmd,
+            get_stocktake_report_cmd,
+            close_stocktake_cmd,
+            delete_stocktake_cmd,
+            mark_stocktake_missing_lost_cmd,
+            // 分发方式与退卡原因
+            list_handling_methods_cmd,
+            create_handling_method_cmd,
+            update_handling_method_cmd,
+            // 回收站
+            list_recycle_bin_cmd,
+            restore_card_cmd,
+            restore_project_cmd,
+            purge_card_cmd,
+            purge_project_cmd,
+            empty_recycle_bin_cmd,
+            // 操作员
+            create_operator_cmd,
+            list_operators_cmd,
+            update_operator_cmd,
+            set_operator_pin_cmd,
+            delete_operator_cmd,
+            login_operator_cmd,
+            logout_operator_cmd,
+            get_current_operator_cmd,
+            get_operator_stats_cmd,
             // 安全凭据管理
             save_credentials,
             load_credentials,
@@ -215,6 +340,7 @@ fn main() {
             sf_confirm_order,
             sf_cancel_order,
             sf_search_order,
+            sf_list_shipment_candidates,
             // 顺丰订单列表
             sf_list_orders,
             sf_get_order,
@@ -226,12 +352,25 @@ fn main() {
             get_app_setting_cmd,
             set_app_setting_cmd,
             get_all_app_settings_cmd,
+            // 数据库快照
+            list_backups_cmd,
+            create_backup_cmd,
+            restore_backup_cmd,
+            delete_backup_cmd,
+            get_backup_settings_cmd,
+            save_backup_settings_cmd,
             // 数据导出导入
             export_data,
+            is_backup_encrypted,
             preview_import_data,
             import_data,
+            preview_merge_import_data,
+            merge_import_data,
             // 卡片导出 Excel
             export_cards_to_excel,
+            list_export_profiles_cmd,
+            save_export_profile_cmd,
+            delete_export_profile_cmd,
             // 云端同步
             save_sync_config_cmd,
             load_sync_config_cmd,
@@ -410,6 +549,20 @@ threshold = 160
         }
     }
 
+    // 复制存放位置标签模板文件（如果不存在）
+    let storage_template_src = resource_path.join("config/templates/storage_slot.toml");
+    let storage_template_dst = templates_dir.join("storage_slot.toml");
+
+    if !storage_template_dst.exists() {
+        if storage_template_src.exists() {
+            fs::copy(&storage_template_src, &storage_template_dst)
+                .map_err(|e| format!("无法复制存放位置标签模板: {}", e))?;
+            println!("✅ 已复制存放位置标签模板到: {}", storage_template_dst.display());
+        } else {
+            println!("⚠️  资源目录中未找到存放位置标签模板，跳过");
+        }
+    }
+
     // 复制顺丰默认配置文件（如果不存在）
     let sf_config_src = resource_path.join("config/sf_express_default.toml");
     let sf_config_dst = config_dir.join("sf_express_default.toml");
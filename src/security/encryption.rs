@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use aes_gcm::{
-    aead::{rand_core::RngCore, Aead, KeyInit, OsRng},
+    aead::{rand_core::RngCore, Aead, KeyInit, OsRng, Payload},
     Aes256Gcm, Nonce,
 };
 use pbkdf2::pbkdf2_hmac;
@@ -12,6 +12,69 @@ use std::path::PathBuf;
 
 use super::credentials::CredentialStorage;
 
+/// PBKDF2 密钥派生的迭代次数
+pub const PBKDF2_ITERATIONS: u32 = 100_000;
+
+/// 以 PBKDF2-HMAC-SHA256 从密钥材料（主机名或用户口令）派生 AES-256 密钥
+pub fn derive_key(secret: &[u8], salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(secret, salt, iterations, &mut key);
+    key
+}
+
+/// AES-GCM 加密结果
+pub struct SealedData {
+    /// 随机盐值（用于密钥派生）
+    pub salt: Vec<u8>,
+    /// 随机 nonce
+    pub nonce: Vec<u8>,
+    /// 密文（含认证标签）
+    pub ciphertext: Vec<u8>,
+}
+
+/// 以密钥材料加密数据（随机盐值与 nonce），`aad` 为参与认证但不加密的附加数据
+pub fn seal(secret: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<SealedData> {
+    let mut salt = vec![0u8; 32];
+    let mut nonce_bytes = vec![0u8; 12];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce_bytes);
+
+    let key = derive_key(secret, &salt, PBKDF2_ITERATIONS);
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| anyhow::anyhow!("无法创建加密器: {}", e))?;
+
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), Payload { msg: plaintext, aad })
+        .map_err(|e| anyhow::anyhow!("加密失败: {}", e))?;
+
+    Ok(SealedData {
+        salt,
+        nonce: nonce_bytes,
+        ciphertext,
+    })
+}
+
+/// 解密并校验 [`seal`] 的结果（密钥材料错误或数据被篡改时认证失败）
+pub fn open(secret: &[u8], sealed: &SealedData, aad: &[u8], iterations: u32) -> Result<Vec<u8>> {
+    if sealed.nonce.len() != 12 {
+        anyhow::bail!("nonce 长度无效");
+    }
+
+    let key = derive_key(secret, &sealed.salt, iterations);
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| anyhow::anyhow!("无法创建解密器: {}", e))?;
+
+    cipher
+        .decrypt(
+            Nonce::from_slice(&sealed.nonce),
+            Payload {
+                msg: &sealed.ciphertext,
+                aad,
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("解密失败: {}", e))
+}
+
 /// 加密存储条目
 #[derive(Serialize, Deserialize)]
 struct EncryptedEntry {
@@ -72,18 +135,6 @@ impl EncryptedFileStorage {
         Ok(hostname)
     }
 
-    /// 派生加密密钥
-    fn derive_key(&self, salt: &[u8]) -> [u8; 32] {
-        let mut key = [0u8; 32];
-        pbkdf2_hmac::<Sha256>(
-            self.machine_id.as_bytes(),
-            salt,
-            100_000,
-            &mut key,
-        );
-        key
-    }
-
     /// 读取存储文件
     fn read_store(&self) -> Result<EncryptedStore> {
         if !self.store_path.exists() {
@@ -121,48 +172,23 @@ impl EncryptedFileStorage {
 
     /// 加密值
     fn encrypt(&self, value: &str) -> Result<EncryptedEntry> {
-        // 生成随机盐值和 nonce
-        let mut salt = vec![0u8; 32];
-        let mut nonce_bytes = vec![0u8; 12];
-        OsRng.fill_bytes(&mut salt);
-        OsRng.fill_bytes(&mut nonce_bytes);
-
-        // 派生加密密钥
-        let key = self.derive_key(&salt);
-
-        // 创建加密器
-        let cipher = Aes256Gcm::new_from_slice(&key)
-            .map_err(|e| anyhow::anyhow!("无法创建加密器: {}", e))?;
-
-        let nonce = Nonce::from_slice(&nonce_bytes);
-
-        // 加密数据
-        let ciphertext = cipher
-            .encrypt(nonce, value.as_bytes())
-            .map_err(|e| anyhow::anyhow!("加密失败: {}", e))?;
+        let sealed = seal(self.machine_id.as_bytes(), value.as_bytes(), &[])?;
 
         Ok(EncryptedEntry {
-            salt,
-            nonce: nonce_bytes,
-            ciphertext,
+            salt: sealed.salt,
+            nonce: sealed.nonce,
+            ciphertext: sealed.ciphertext,
         })
     }
 
     /// 解密值
     fn decrypt(&self, entry: &EncryptedEntry) -> Result<String> {
-        // 派生解密密钥
-        let key = self.derive_key(&entry.salt);
-
-        // 创建解密器
-        let cipher = Aes256Gcm::new_from_slice(&key)
-            .map_err(|e| anyhow::anyhow!("无法创建解密器: {}", e))?;
-
-        let nonce = Nonce::from_slice(&entry.nonce);
-
-        // 解密数据
-        let plaintext = cipher
-            .decrypt(nonce, entry.ciphertext.as_ref())
-            .map_err(|e| anyhow::anyhow!("解密失败: {}", e))?;
+        let sealed = SealedData {
+            salt: entry.salt.clone(),
+            nonce: entry.nonce.clone(),
+            ciphertext: entry.ciphertext.clone(),
+        };
+        let plaintext = open(self.machine_id.as_bytes(), &sealed, &[], PBKDF2_ITERATIONS)?;
 
         String::from_utf8(plaintext)
             .context("解密后的数据不是有效的 UTF-8")
@@ -490,8 +490,11 @@ pub struct SFOrder {
     pub order_id: String,
     /// 运单号（确认后获取）
     pub waybill_no: Option<String>,
-    /// 关联的卡片 ID
+    /// 关联的卡片 ID（合并寄件时为首张卡片）
     pub card_id: Option<String>,
+    /// 合并寄件关联的全部卡片 ID（按加入顺序，旧数据缺省时取 card_id）
+    #[serde(default)]
+    pub card_ids: Vec<String>,
     /// 订单状态
     pub status: String,
     /// 付款方式（1=寄方付, 2=收方付, 3=第三方付）
@@ -519,8 +522,11 @@ pub struct SFOrderWithCard {
     pub order_id: String,
     /// 运单号（确认后获取）
     pub waybill_no: Option<String>,
-    /// 关联的卡片 ID
+    /// 关联的卡片 ID（合并寄件时为首张卡片）
     pub card_id: Option<String>,
+    /// 合并寄件关联的全部卡片 ID（按加入顺序，旧数据缺省时取 card_id）
+    #[serde(default)]
+    pub card_ids: Vec<String>,
     /// 订单状态
     pub status: String,
     /// 付款方式（1=寄方付, 2=收方付, 3=第三方付）
@@ -537,9 +543,9 @@ pub struct SFOrderWithCard {
     pub updated_at: String,
     /// 关联卡片的呼号
     pub callsign: Option<String>,
-    /// 关联卡片的项目名称
+    /// 首张关联卡片的项目名称
     pub project_name: Option<String>,
-    /// 关联卡片的数量
+    /// 首张关联卡片的数量
     pub qty: Option<i32>,
 }
 
@@ -79,6 +79,21 @@ pub struct SyncData {
     pub sf_orders: Vec<crate::sf_express::SFOrder>,
     /// 全局配置项列表
     pub app_settings: Vec<crate::db::models::AppSetting>,
+    /// 卡片事件日志（含待付邮资、留存、遗失等状态流转）
+    ///
+    /// 旧服务端不回传时为空，恢复时按卡片状态与元数据推导。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card_events: Option<Vec<crate::db::models::CardEvent>>,
+    /// 卡片修改历史
+    ///
+    /// 旧服务端不回传时为空，恢复后无修改历史。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card_history: Option<Vec<crate::db::models::CardHistoryEntry>>,
+    /// 项目编号方案
+    ///
+    /// 旧服务端不回传时为空，恢复时保留本地同 ID 项目的方案。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_schemes: Option<Vec<crate::db::models::SerialScheme>>,
 }
 
 /// 同步响应
@@ -357,8 +372,10 @@ pub async fn sync_data(
 
     log::info!("🔄 开始同步数据到: {}", sync_url);
 
-    // 导出数据
-    let export_data = export_database()
+    // 导出数据（在阻塞线程池中读库，不占用异步运行时）
+    let export_data = tokio::task::spawn_blocking(export_database)
+        .await
+        .map_err(|e| e.to_string())?
         .map_err(|e| format!("导出数据失败: {}", e))?;
 
     let stats = ExportStats {
@@ -380,6 +397,9 @@ pub async fn sync_data(
             sf_senders: export_data.tables.sf_senders,
             sf_orders: export_data.tables.sf_orders,
             app_settings: export_data.tables.app_settings.unwrap_or_default(),
+            card_events: export_data.tables.card_events,
+            card_history: export_data.tables.card_history,
+            serial_schemes: export_data.tables.serial_schemes,
         },
     };
 
@@ -552,6 +572,9 @@ mod tests {
             sf_senders: vec![],
             sf_orders: vec![],
             app_settings: vec![],
+            card_events: None,
+            card_history: None,
+            serial_schemes: None,
         }
     }
 
@@ -674,6 +697,37 @@ mod tests {
         assert_eq!(resp.data.app_settings.len(), 1);
     }
 
+    #[test]
+    fn test_pull_response_carries_hold_states_and_events() {
+        // 新状态与其事件随快照往返；旧服务端不回传 card_events 时为 None
+        let json = r#"{
+            "success": true,
+            "data": {
+                "projects": [],
+                "cards": [
+                    {"id":"c1","project_id":"p1","callsign":"BH2RO","qty":1,"status":"lost",
+                     "created_at":"2026-01-01T00:00:00+08:00","updated_at":"2026-01-02T00:00:00+08:00"}
+                ],
+                "sf_senders": [],
+                "sf_orders": [],
+                "app_settings": [],
+                "card_events": [
+                    {"id":"e1","card_id":"c1","event_type":"lost","remarks":"柜中未找到",
+                     "created_at":"2026-01-02T00:00:00+08:00"}
+                ]
+            }
+        }"#;
+
+        let resp: PullResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.data.cards[0].status, crate::db::models::CardStatus::Lost);
+        let events = resp.data.card_events.unwrap();
+        assert_eq!(events[0].event_type, crate::db::models::CardEventType::Lost);
+        assert_eq!(events[0].remarks.as_deref(), Some("柜中未找到"));
+
+        let json = serde_json::to_string(&empty_sync_data()).unwrap();
+        assert!(!json.contains("card_events"), "card_events 不应出现: {}", json);
+    }
+
     #[test]
     fn test_pull_response_tolerates_empty_pii() {
         // RC-B：worker 兜底把缺/空 sender_info/recipient_info 存为 '{}'，/pull 读回 {}；
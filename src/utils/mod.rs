@@ -1,3 +1,4 @@
 // 工具模块
 
 pub mod platform;
+pub mod spreadsheet;
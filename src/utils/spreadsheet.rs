@@ -0,0 +1,174 @@
+// 表格文件读取工具
+//
+// 读取 .xlsx（第一个工作表）与 .csv 文件为按行排列的文本单元格，供卡片导入等功能使用
+
+use crate::error::AppError;
+use calamine::{open_workbook_auto, Data, Reader};
+use chrono::NaiveTime;
+use std::path::Path;
+
+/// 读取表格文件的全部行（按扩展名识别 .xlsx 与 .csv/.txt）
+///
+/// 返回值的下标即表格中的行号减一：.xlsx 中跳过的空行以空行补齐，
+/// 便于导入报告按表格行号定位。
+pub fn read_sheet_rows(path: &Path) -> Result<Vec<Vec<String>>, AppError> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "xlsx" => read_xlsx_rows(path),
+        "csv" | "txt" => {
+            let bytes = std::fs::read(path)
+                .map_err(|e| AppError::FileReadFailed(format!("读取文件失败: {}", e)))?;
+            Ok(parse_csv(&decode_text(&bytes)))
+        }
+        "xls" => Err(AppError::DataFormatError(
+            "不支持旧版 .xls 文件，请在 Excel 中另存为 .xlsx 或 .csv 后导入".to_string(),
+        )),
+        _ => Err(AppError::DataFormatError(format!(
+            "不支持的文件类型: {}（仅支持 .xlsx 与 .csv）",
+            path.display()
+        ))),
+    }
+}
+
+/// 文本解码：UTF-8（去除 BOM），无法按 UTF-8 解码时按 GB18030 解码（兼容中文 Excel 另存的 CSV）
+fn decode_text(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => encoding_rs::GB18030.decode(bytes).0.into_owned(),
+    }
+}
+
+/// 解析 CSV 文本（支持双引号包裹的字段、字段内的逗号与换行、`""` 转义）
+pub(crate) fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// 读取 .xlsx 文件第一个工作表
+fn read_xlsx_rows(path: &Path) -> Result<Vec<Vec<String>>, AppError> {
+    let mut workbook = open_workbook_auto(path)
+        .map_err(|e| AppError::DataFormatError(format!("无法解析 .xlsx 文件: {}", e)))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| AppError::DataFormatError("工作簿中没有工作表".to_string()))?
+        .map_err(|e| AppError::DataFormatError(format!("无法读取工作表: {}", e)))?;
+
+    // 区域不一定从 A1 开始：按起始行列补齐，使下标与表格行号、列号对应
+    let Some((start_row, start_column)) = range.start() else {
+        return Ok(Vec::new());
+    };
+    let mut rows = vec![Vec::new(); start_row as usize];
+    for cells in range.rows() {
+        let mut row = vec![String::new(); start_column as usize];
+        row.extend(cells.iter().map(cell_text));
+        // 去除行尾空单元格，全空的行为空行
+        while row.last().is_some_and(|cell| cell.is_empty()) {
+            row.pop();
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// 单元格的显示文本
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+        Data::Int(n) => n.to_string(),
+        Data::Float(n) => format_number(*n),
+        Data::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        Data::DateTime(dt) => match dt.as_datetime() {
+            Some(dt) if dt.time() == NaiveTime::MIN => dt.format("%Y-%m-%d").to_string(),
+            Some(dt) => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+            None => format_number(dt.as_f64()),
+        },
+        Data::Error(e) => e.to_string(),
+    }
+}
+
+/// 数值单元格的显示文本：整数去掉小数部分（Excel 以浮点数保存数字）
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// CSV：引号字段、字段内逗号与换行、`""` 转义与 GBK 编码
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv("呼号,备注\r\nBH2RO,\"a, \"\"b\"\"\nc\"\n\nBA1AA,");
+        assert_eq!(rows[0], vec!["呼号", "备注"]);
+        assert_eq!(rows[1], vec!["BH2RO", "a, \"b\"\nc"]);
+        assert_eq!(rows[2], vec![""]);
+        assert_eq!(rows[3], vec!["BA1AA", ""]);
+
+        let (gbk, _, _) = encoding_rs::GB18030.encode("呼号,数量\n");
+        assert_eq!(parse_csv(&decode_text(&gbk))[0], vec!["呼号", "数量"]);
+    }
+
+    /// .xlsx：共享字符串、数字与空行按行号补齐
+    #[test]
+    fn test_read_xlsx_rows() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cards.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_string(0, 0, "呼号").unwrap();
+        sheet.write_string(0, 2, "A & B").unwrap();
+        sheet.write_string(2, 0, "BH2RO").unwrap();
+        sheet.write_number(2, 1, 3.0).unwrap();
+        sheet.write_number(2, 2, 1.5).unwrap();
+        workbook.save(&path).unwrap();
+
+        let rows = read_sheet_rows(&path).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], vec!["呼号", "", "A & B"]);
+        assert!(rows[1].is_empty());
+        assert_eq!(rows[2], vec!["BH2RO", "3", "1.5"]);
+    }
+}
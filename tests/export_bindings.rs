@@ -19,8 +19,9 @@ mod export {
     use qsl_cardhub::config::models::{Platform, PrinterConfig, Profile, Template};
     use qsl_cardhub::db::export::ExportStats;
     use qsl_cardhub::db::models::{
-        AddressEntry, Card, CardMetadata, CardStatus, CardWithProject, DistributionInfo,
-        PagedCards, Project, ProjectWithStats, ReturnInfo,
+        AddressEntry, BatchItemResult, BatchOperation, BatchProgress, BatchReport, Card, CardEvent,
+        CardEventType, CardHistoryEntry, CardMetadata, CardSortField, CardStatus, CardWithProject,
+        DistributionInfo, PagedCards, Project, ProjectWithStats, ReturnInfo, SortOrder,
     };
     use qsl_cardhub::sf_express::models::{OrderStatus, SFOrder, SFOrderWithCard, SenderInfo};
     use qsl_cardhub::sync::client::{
@@ -55,6 +56,15 @@ mod export {
         Card::export_all(&config).expect("Failed to export Card");
         CardWithProject::export_all(&config).expect("Failed to export CardWithProject");
         CardMetadata::export_all(&config).expect("Failed to export CardMetadata");
+        CardHistoryEntry::export_all(&config).expect("Failed to export CardHistoryEntry");
+        CardEventType::export_all(&config).expect("Failed to export CardEventType");
+        CardEvent::export_all(&config).expect("Failed to export CardEvent");
+        BatchOperation::export_all(&config).expect("Failed to export BatchOperation");
+        BatchItemResult::export_all(&config).expect("Failed to export BatchItemResult");
+        BatchReport::export_all(&config).expect("Failed to export BatchReport");
+        BatchProgress::export_all(&config).expect("Failed to export BatchProgress");
+        CardSortField::export_all(&config).expect("Failed to export CardSortField");
+        SortOrder::export_all(&config).expect("Failed to export SortOrder");
         DistributionInfo::export_all(&config).expect("Failed to export DistributionInfo");
         ReturnInfo::export_all(&config).expect("Failed to export ReturnInfo");
         AddressEntry::export_all(&config).expect("Failed to export AddressEntry");
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 快照原因
+ */
+export type BackupReason = "scheduled" | "manual" | "pre_migration" | "pre_import" | "pre_cloud_restore" | "pre_factory_reset" | "pre_restore";
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 备份设置
+ */
+export type BackupSettings = { 
+/**
+ * 是否启用定时备份
+ */
+enabled: boolean, 
+/**
+ * 定时备份间隔（小时，1-168）
+ */
+interval_hours: number, 
+/**
+ * 按日保留的定时快照天数（每天保留最新一份）
+ */
+keep_daily: number, 
+/**
+ * 按周保留的定时快照周数（每周保留最新一份）
+ */
+keep_weekly: number, 
+/**
+ * 操作前快照保留份数
+ */
+keep_pre_operation: number, };
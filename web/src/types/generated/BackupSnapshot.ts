@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackupReason } from "./BackupReason";
+
+/**
+ * 数据库快照
+ */
+export type BackupSnapshot = { 
+/**
+ * 文件名（恢复 / 删除时作为标识）
+ */
+file_name: string, 
+/**
+ * 快照原因
+ */
+reason: BackupReason, 
+/**
+ * 创建时间
+ */
+created_at: string, 
+/**
+ * 文件大小（字节）
+ */
+size_bytes: number, 
+/**
+ * 快照的数据库版本号（无法读取时为 0）
+ */
+db_version: number, 
+/**
+ * 快照的可读版本号
+ */
+db_version_display: string, };
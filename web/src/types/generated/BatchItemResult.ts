@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 批量操作中单张卡片的处理结果
+ */
+export type BatchItemResult = { 
+/**
+ * 卡片 ID
+ */
+card_id: string, 
+/**
+ * 呼号（卡片不存在时为空）
+ */
+callsign: string | null, 
+/**
+ * 是否成功
+ */
+success: boolean, 
+/**
+ * 失败原因
+ */
+error: string | null, };
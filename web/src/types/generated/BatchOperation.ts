@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 批量操作类型
+ */
+export type BatchOperation = "distribute" | "return" | "delete" | "move" | "mark_lost";
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BatchOperation } from "./BatchOperation";
+
+/**
+ * 批量操作进度（每处理一张卡片推送一次）
+ */
+export type BatchProgress = { 
+/**
+ * 操作类型
+ */
+operation: BatchOperation, 
+/**
+ * 已处理数
+ */
+current: number, 
+/**
+ * 目标卡片数
+ */
+total: number, 
+/**
+ * 当前卡片 ID
+ */
+card_id: string, 
+/**
+ * 当前卡片是否成功
+ */
+success: boolean, };
@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BatchItemResult } from "./BatchItemResult";
+import type { BatchOperation } from "./BatchOperation";
+
+/**
+ * 批量操作报告
+ */
+export type BatchReport = { 
+/**
+ * 操作类型
+ */
+operation: BatchOperation, 
+/**
+ * 目标卡片数
+ */
+total: number, 
+/**
+ * 成功数
+ */
+succeeded: number, 
+/**
+ * 失败数
+ */
+failed: number, 
+/**
+ * 逐卡结果
+ */
+items: Array<BatchItemResult>, };
@@ -0,0 +1,38 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 呼号别名（旧呼号在生效期内解析为现用呼号）
+ */
+export type CallsignAlias = { 
+/**
+ * ID
+ */
+id: string, 
+/**
+ * 旧呼号（统一大写）
+ */
+alias: string, 
+/**
+ * 现用呼号（统一大写）
+ */
+callsign: string, 
+/**
+ * 生效日期（YYYY-MM-DD，含当日）
+ */
+effective_from: string, 
+/**
+ * 失效日期（YYYY-MM-DD，含当日；为空表示长期有效）
+ */
+effective_to: string | null, 
+/**
+ * 备注
+ */
+remarks: string | null, 
+/**
+ * 创建时间
+ */
+created_at: string, 
+/**
+ * 更新时间
+ */
+updated_at: string, };
@@ -0,0 +1,75 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AddressEntry } from "./AddressEntry";
+import type { CallsignAlias } from "./CallsignAlias";
+import type { CallsignPickup } from "./CallsignPickup";
+import type { CardWithProject } from "./CardWithProject";
+import type { SFOrderWithCard } from "./SFOrderWithCard";
+
+/**
+ * 呼号档案：某电台在所有项目中的卡片汇总
+ */
+export type CallsignDossier = { 
+/**
+ * 呼号（统一大写；查询旧呼号时为解析后的现用呼号）
+ */
+callsign: string, 
+/**
+ * 指向该呼号的别名（旧呼号）
+ */
+aliases: Array<CallsignAlias>, 
+/**
+ * 全部卡片（含旧呼号名下的卡片，按项目、序列号排序，不含回收站中的卡片）
+ */
+cards: Array<CardWithProject>, 
+/**
+ * 涉及项目数
+ */
+project_count: number, 
+/**
+ * 卡片总数
+ */
+total_cards: number, 
+/**
+ * 待分发卡片数
+ */
+pending_cards: number, 
+/**
+ * 部分分发卡片数
+ */
+partially_distributed_cards: number, 
+/**
+ * 已分发卡片数
+ */
+distributed_cards: number, 
+/**
+ * 已退卡卡片数
+ */
+returned_cards: number, 
+/**
+ * 待付邮资卡片数
+ */
+awaiting_postage_cards: number, 
+/**
+ * 留存待 QSL 管理员领取卡片数
+ */
+held_cards: number, 
+/**
+ * 已遗失卡片数
+ */
+lost_cards: number, 
+/**
+ * 可交付数量（待分发、部分分发、待付邮资、留存卡片的剩余数量之和）
+ */
+deliverable_qty: number, 
+/**
+ * 地址缓存（各卡片去重合并，按缓存时间倒序）
+ */
+addresses: Array<AddressEntry>, 
+/**
+ * 关联的顺丰订单（按创建时间倒序）
+ */
+sf_orders: Array<SFOrderWithCard>, 
+/**
+ * 最近一次当面领取
+ */
+last_pickup: CallsignPickup | null, };
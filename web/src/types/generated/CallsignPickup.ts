@@ -0,0 +1,38 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 当面领取记录（自取或代领等无需地址的分发）
+ */
+export type CallsignPickup = { 
+/**
+ * 卡片 ID
+ */
+card_id: string, 
+/**
+ * 卡片呼号
+ */
+callsign: string, 
+/**
+ * 项目名称
+ */
+project_name: string, 
+/**
+ * 分发方式
+ */
+method: string, 
+/**
+ * 代领人呼号
+ */
+proxy_callsign: string | null, 
+/**
+ * 领取数量（为空表示领取全部剩余数量）
+ */
+qty: number | null, 
+/**
+ * 是否为档案呼号代他人领取
+ */
+as_proxy: boolean, 
+/**
+ * 领取时间
+ */
+picked_up_at: string, };
@@ -22,6 +22,10 @@ creator_id: string | null,
  * 呼号
  */
 callsign: string, 
+/**
+ * 经由的 QSL 管理员呼号（录入时按 QSL 管理员登记自动标注）
+ */
+via_callsign: string | null, 
 /**
  * 数量
  */
@@ -34,6 +38,14 @@ serial: number | null,
  * 状态
  */
 status: CardStatus, 
+/**
+ * 已分发数量（剩余数量为 qty - distributed_qty）
+ */
+distributed_qty: number, 
+/**
+ * 存放位置 ID（录入时按分配规则自动分配）
+ */
+storage_location_id: string | null, 
 /**
  * 元数据（分发/退卡信息）
  */
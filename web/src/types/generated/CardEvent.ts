@@ -0,0 +1,45 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardEventType } from "./CardEventType";
+import type { DistributionInfo } from "./DistributionInfo";
+import type { ReturnInfo } from "./ReturnInfo";
+
+/**
+ * 卡片事件（只追加，卡片 metadata 为事件日志的投影）
+ */
+export type CardEvent = { 
+/**
+ * 事件 ID
+ */
+id: string, 
+/**
+ * 卡片 ID
+ */
+card_id: string, 
+/**
+ * 事件类型
+ */
+event_type: CardEventType, 
+/**
+ * 分发信息（分发、重新分发事件）
+ */
+distribution: DistributionInfo | null, 
+/**
+ * 退卡信息（退卡事件）
+ */
+return: ReturnInfo | null, 
+/**
+ * 运单号（旧版关联寄件事件）
+ */
+waybill_no: string | null, 
+/**
+ * 备注（待付邮资、留存、遗失、恢复事件；关联寄件事件为顺丰订单号）
+ */
+remarks: string | null, 
+/**
+ * 操作员 ID
+ */
+operator_id: string | null, 
+/**
+ * 事件时间
+ */
+created_at: string, };
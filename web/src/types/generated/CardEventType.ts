@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 卡片事件类型
+ */
+export type CardEventType = "entered" | "distributed" | "returned" | "redistributed" | "waybill_attached" | "awaiting_postage" | "held" | "lost" | "released";
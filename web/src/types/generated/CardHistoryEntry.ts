@@ -0,0 +1,39 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 卡片修改历史记录
+ */
+export type CardHistoryEntry = { 
+/**
+ * 记录 ID（自增）
+ */
+id: number, 
+/**
+ * 卡片 ID
+ */
+card_id: string, 
+/**
+ * 被修改的字段：callsign、qty、serial、project_id，
+ * 以及恢复卡片时的 deleted_at、保存地址时的 address（新值为地址来源）
+ */
+field: string, 
+/**
+ * 修改前的值
+ */
+old_value: string | null, 
+/**
+ * 修改后的值
+ */
+new_value: string | null, 
+/**
+ * 操作人
+ */
+changed_by: string | null, 
+/**
+ * 操作员 ID
+ */
+operator_id: string | null, 
+/**
+ * 修改时间
+ */
+changed_at: string, };
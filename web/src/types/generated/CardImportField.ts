@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 卡片表格导入的出错字段
+ */
+export type CardImportField = "callsign" | "qty" | "serial" | "remarks";
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 卡片表格导入的列映射（列下标从 0 开始）
+ */
+export type CardImportMapping = { 
+/**
+ * 首行是否为表头（表头行不导入）
+ */
+has_header: boolean, 
+/**
+ * 呼号所在列
+ */
+callsign: number, 
+/**
+ * 数量所在列（未映射或单元格为空时数量为 1）
+ */
+qty: number | null, 
+/**
+ * 序列号所在列（可为按编号方案格式化的形式，取末尾的数字部分）
+ */
+serial: number | null, 
+/**
+ * 备注所在列（记入录入事件的备注）
+ */
+remarks: number | null, };
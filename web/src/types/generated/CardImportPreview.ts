@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardImportMapping } from "./CardImportMapping";
+
+/**
+ * 卡片表格导入预览：供前端选择列映射
+ */
+export type CardImportPreview = { 
+/**
+ * 首行内容（有表头时即各列标题）
+ */
+headers: Array<string>, 
+/**
+ * 数据行样例（不含表头，最多 10 行）
+ */
+rows: Array<Array<string>>, 
+/**
+ * 数据行总数（不含表头与空行）
+ */
+total_rows: number, 
+/**
+ * 列数
+ */
+column_count: number, 
+/**
+ * 按表头推断的列映射
+ */
+mapping: CardImportMapping, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardImportRowError } from "./CardImportRowError";
+
+/**
+ * 卡片表格导入结果
+ */
+export type CardImportReport = { 
+/**
+ * 是否为试导入（仅校验，不写入）
+ */
+dry_run: boolean, 
+/**
+ * 导入（试导入时为可导入）的卡片数
+ */
+imported: number, 
+/**
+ * 呼号重复而未导入的行数（与项目中已有卡片或文件中前面的行重复）
+ */
+duplicates: number, 
+/**
+ * 未导入的行及原因，含重复行
+ */
+errors: Array<CardImportRowError>, };
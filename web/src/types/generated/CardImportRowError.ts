@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardImportField } from "./CardImportField";
+
+/**
+ * 卡片表格导入中未导入的行
+ */
+export type CardImportRowError = { 
+/**
+ * 行号（即表格中的行号，从 1 开始）
+ */
+row: number, 
+/**
+ * 出错字段
+ */
+field: CardImportField, 
+/**
+ * 原因
+ */
+message: string, };
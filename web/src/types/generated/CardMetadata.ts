@@ -18,8 +18,4 @@ return: ReturnInfo | null,
 /**
  * 地址缓存（每个来源只保留1条最新记录）
  */
-address_cache: Array<AddressEntry> | null, 
-/**
- * 待处理运单号（顺丰下单后暂存，确认分发后会移到 distribution.remarks）
- */
-pending_waybill_no: string | null, };
+address_cache: Array<AddressEntry> | null, };
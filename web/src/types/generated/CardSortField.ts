@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 卡片排序字段
+ */
+export type CardSortField = "created_at" | "updated_at" | "callsign" | "serial" | "qty" | "distributed_at" | "returned_at";
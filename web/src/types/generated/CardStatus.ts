@@ -3,4 +3,4 @@
 /**
  * 卡片状态
  */
-export type CardStatus = "pending" | "distributed" | "returned";
+export type CardStatus = "pending" | "partially_distributed" | "distributed" | "returned" | "awaiting_postage" | "held" | "lost";
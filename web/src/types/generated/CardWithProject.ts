@@ -22,18 +22,38 @@ project_name: string,
  * 呼号
  */
 callsign: string, 
+/**
+ * 经由的 QSL 管理员呼号
+ */
+via_callsign: string | null, 
 /**
  * 数量
  */
 qty: number, 
 /**
- * 序列号（数字，前端显示时格式化为三位数如 "001"）
+ * 序列号（数字）
  */
 serial: number | null, 
+/**
+ * 按项目编号方案格式化的序列号（如 "001"、"BY-2026-0012"）
+ */
+serial_display: string | null, 
 /**
  * 状态
  */
 status: CardStatus, 
+/**
+ * 已分发数量
+ */
+distributed_qty: number, 
+/**
+ * 存放位置 ID
+ */
+storage_location_id: string | null, 
+/**
+ * 存放位置编号（如 `A-02-3`）
+ */
+storage_location: string | null, 
 /**
  * 元数据
  */
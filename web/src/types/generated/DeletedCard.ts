@@ -0,0 +1,43 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardStatus } from "./CardStatus";
+
+/**
+ * 回收站中的卡片
+ */
+export type DeletedCard = { 
+/**
+ * 卡片 ID
+ */
+id: string, 
+/**
+ * 所属项目 ID
+ */
+project_id: string, 
+/**
+ * 项目名称
+ */
+project_name: string, 
+/**
+ * 呼号
+ */
+callsign: string, 
+/**
+ * 数量
+ */
+qty: number, 
+/**
+ * 序列号
+ */
+serial: number | null, 
+/**
+ * 删除前的状态
+ */
+status: CardStatus, 
+/**
+ * 创建时间
+ */
+created_at: string, 
+/**
+ * 删除时间
+ */
+deleted_at: string, };
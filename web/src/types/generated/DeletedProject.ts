@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 回收站中的项目
+ */
+export type DeletedProject = { 
+/**
+ * 项目 ID
+ */
+id: string, 
+/**
+ * 项目名称
+ */
+name: string, 
+/**
+ * 项目下的卡片数（随项目一并恢复或彻底删除）
+ */
+card_count: number, 
+/**
+ * 创建时间
+ */
+created_at: string, 
+/**
+ * 删除时间
+ */
+deleted_at: string, };
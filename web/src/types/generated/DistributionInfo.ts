@@ -5,7 +5,7 @@
  */
 export type DistributionInfo = { 
 /**
- * 处理方式：直接分发、邮寄、自取
+ * 分发方式（登记表 card_handling_methods 中的取值）
  */
 method: string, 
 /**
@@ -20,6 +20,14 @@ remarks: string | null,
  * 代领人呼号（代领方式时使用）
  */
 proxy_callsign: string | null, 
+/**
+ * 本次分发数量（为空表示分发全部剩余数量）
+ */
+qty: number | null, 
+/**
+ * 运单号（随顺丰合并寄件确认分发时记录）
+ */
+waybill_no: string | null, 
 /**
  * 分发时间
  */
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Excel 导出列
+ */
+export type ExportColumn = "serial" | "callsign" | "project" | "qty" | "status" | "storage_location" | "distribution_method" | "proxy_callsign" | "distribution_address" | "waybill_no" | "distributed_at" | "return_reason" | "returned_at" | "qrz_cn_chinese" | "qrz_cn_english" | "qrz_com_english" | "created_at";
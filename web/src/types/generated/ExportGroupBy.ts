@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Excel 导出的工作表分组方式
+ */
+export type ExportGroupBy = "single" | "project" | "status";
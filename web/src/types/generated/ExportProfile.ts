@@ -0,0 +1,36 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportColumn } from "./ExportColumn";
+import type { ExportGroupBy } from "./ExportGroupBy";
+
+/**
+ * 已保存的 Excel 导出方案
+ */
+export type ExportProfile = { 
+/**
+ * 方案 ID（UUID 格式）
+ */
+id: string, 
+/**
+ * 方案名称
+ */
+name: string, 
+/**
+ * 导出列（按导出顺序）
+ */
+columns: Array<ExportColumn>, 
+/**
+ * 工作表分组方式
+ */
+group_by: ExportGroupBy, 
+/**
+ * 是否附带汇总表
+ */
+include_summary: boolean, 
+/**
+ * 创建时间
+ */
+created_at: string, 
+/**
+ * 更新时间
+ */
+updated_at: string, };
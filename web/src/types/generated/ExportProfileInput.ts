@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportColumn } from "./ExportColumn";
+import type { ExportGroupBy } from "./ExportGroupBy";
+
+/**
+ * Excel 导出方案设置内容（导出时也可直接使用未保存的设置）
+ */
+export type ExportProfileInput = { 
+/**
+ * 方案名称
+ */
+name: string, 
+/**
+ * 导出列（按导出顺序）
+ */
+columns: Array<ExportColumn>, 
+/**
+ * 工作表分组方式
+ */
+group_by: ExportGroupBy, 
+/**
+ * 是否附带汇总表
+ */
+include_summary: boolean, };
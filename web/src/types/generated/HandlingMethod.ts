@@ -0,0 +1,47 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HandlingMethodKind } from "./HandlingMethodKind";
+
+/**
+ * 分发方式 / 退卡原因登记项
+ */
+export type HandlingMethod = { 
+/**
+ * 类别
+ */
+kind: HandlingMethodKind, 
+/**
+ * 取值（写入分发/退卡记录，登记后不可修改）
+ */
+code: string, 
+/**
+ * 显示名称
+ */
+display_name: string, 
+/**
+ * 是否须填写分发地址
+ */
+requires_address: boolean, 
+/**
+ * 是否须填写代领人呼号
+ */
+requires_proxy_callsign: boolean, 
+/**
+ * 是否须填写备注
+ */
+requires_remarks: boolean, 
+/**
+ * 是否启用（停用后不可用于新的分发/退卡）
+ */
+is_active: boolean, 
+/**
+ * 排序（升序）
+ */
+sort_order: number, 
+/**
+ * 创建时间
+ */
+created_at: string, 
+/**
+ * 更新时间
+ */
+updated_at: string, };
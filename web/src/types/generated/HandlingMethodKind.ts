@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 处理方式类别
+ */
+export type HandlingMethodKind = "distribution" | "return";
@@ -0,0 +1,42 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MergeConflictKind } from "./MergeConflictKind";
+
+/**
+ * 合并冲突（同一 UUID 的记录两侧内容不同，或新增卡片与本地卡片重复）
+ *
+ * 重复冲突采用导入文件时，导入的卡片新增、与之重复的本地卡片移入回收站；
+ * 保留本地时不导入该卡片。
+ */
+export type MergeConflict = { 
+/**
+ * 表名：projects | cards | sf_senders | sf_orders
+ */
+table: string, 
+/**
+ * 冲突类型
+ */
+kind: MergeConflictKind, 
+/**
+ * 导入记录 ID（逐条选择时以此标识）
+ */
+id: string, 
+/**
+ * 本地记录 ID（重复冲突时为与之重复的本地卡片）
+ */
+local_id: string, 
+/**
+ * 记录摘要（项目名称、呼号、寄件人姓名或订单号）
+ */
+label: string, 
+/**
+ * 本地更新时间
+ */
+local_updated_at: string, 
+/**
+ * 导入文件中的更新时间
+ */
+incoming_updated_at: string, 
+/**
+ * 按当前策略是否采用导入文件
+ */
+take_incoming: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 合并冲突类型
+ */
+export type MergeConflictKind = "modified" | "duplicate_callsign" | "duplicate_serial";
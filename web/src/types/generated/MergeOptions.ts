@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MergeStrategy } from "./MergeStrategy";
+
+/**
+ * 合并导入选项
+ */
+export type MergeOptions = { 
+/**
+ * 冲突策略
+ */
+strategy: MergeStrategy, 
+/**
+ * 逐条选择时采用导入文件的记录 ID（其余冲突保留本地）
+ */
+take_incoming: Array<string>, };
@@ -0,0 +1,36 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MergeConflict } from "./MergeConflict";
+import type { MergeTableStats } from "./MergeTableStats";
+
+/**
+ * 合并导入结果（预览时为按当前选项合并的结果，不写入）
+ */
+export type MergeReport = { 
+/**
+ * 是否为预览（未写入）
+ */
+dry_run: boolean, 
+/**
+ * 项目
+ */
+projects: MergeTableStats, 
+/**
+ * 卡片
+ */
+cards: MergeTableStats, 
+/**
+ * 寄件人
+ */
+sf_senders: MergeTableStats, 
+/**
+ * 订单
+ */
+sf_orders: MergeTableStats, 
+/**
+ * 冲突明细
+ */
+conflicts: Array<MergeConflict>, 
+/**
+ * 从回收站恢复的同名项目（导入项目的卡片并入其中）
+ */
+restored_projects: Array<string>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 合并导入的冲突策略（同一 UUID 的记录两侧内容不同时）
+ */
+export type MergeStrategy = "keep_local" | "take_incoming" | "newest" | "manual";
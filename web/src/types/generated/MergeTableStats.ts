@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 单张表的合并统计
+ */
+export type MergeTableStats = { 
+/**
+ * 新增（本地没有的记录）
+ */
+inserted: number, 
+/**
+ * 以导入文件覆盖的记录（含因重复被导入卡片替换、移入回收站的本地卡片）
+ */
+updated: number, 
+/**
+ * 两侧内容不同或呼号 / 序列号重复的记录（含被覆盖与保留本地的）
+ */
+conflicting: number, 
+/**
+ * 未改动的本地记录（仅本地有、两侧相同或冲突时保留本地）
+ */
+untouched: number, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 操作员
+ */
+export type Operator = { 
+/**
+ * 操作员 ID（UUID 格式）
+ */
+id: string, 
+/**
+ * 姓名
+ */
+name: string, 
+/**
+ * 呼号
+ */
+callsign: string | null, 
+/**
+ * 是否设置了 PIN
+ */
+has_pin: boolean, 
+/**
+ * 创建时间
+ */
+created_at: string, 
+/**
+ * 更新时间
+ */
+updated_at: string, };
@@ -0,0 +1,34 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 操作员工作量统计
+ */
+export type OperatorStats = { 
+/**
+ * 操作员 ID
+ */
+operator_id: string, 
+/**
+ * 姓名
+ */
+name: string, 
+/**
+ * 呼号
+ */
+callsign: string | null, 
+/**
+ * 录入卡片数
+ */
+entered: number, 
+/**
+ * 分发次数（含重新分发）
+ */
+distributed: number, 
+/**
+ * 退卡次数
+ */
+returned: number, 
+/**
+ * 修改字段次数
+ */
+edited: number, };
@@ -28,6 +28,10 @@ total_cards: number,
  * 待分发卡片数
  */
 pending_cards: number, 
+/**
+ * 部分分发卡片数
+ */
+partially_distributed_cards: number, 
 /**
  * 已分发卡片数
  */
@@ -35,4 +39,16 @@ distributed_cards: number,
 /**
  * 已退卡卡片数
  */
-returned_cards: number, };
+returned_cards: number, 
+/**
+ * 待付邮资卡片数
+ */
+awaiting_postage_cards: number, 
+/**
+ * 留存待 QSL 管理员领取卡片数
+ */
+held_cards: number, 
+/**
+ * 已遗失卡片数
+ */
+lost_cards: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 清空回收站的结果
+ */
+export type PurgeReport = { 
+/**
+ * 彻底删除的项目数
+ */
+projects: number, 
+/**
+ * 彻底删除的卡片数（含随项目删除的卡片）
+ */
+cards: number, };
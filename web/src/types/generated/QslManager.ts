@@ -0,0 +1,38 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * QSL 管理员登记（电台在有效期内经由管理员转交卡片）
+ */
+export type QslManager = { 
+/**
+ * ID
+ */
+id: string, 
+/**
+ * 电台呼号（统一大写）
+ */
+callsign: string, 
+/**
+ * QSL 管理员呼号（统一大写）
+ */
+manager: string, 
+/**
+ * 生效日期（YYYY-MM-DD，含当日；为空表示不限起始）
+ */
+effective_from: string | null, 
+/**
+ * 失效日期（YYYY-MM-DD，含当日；为空表示长期有效）
+ */
+effective_to: string | null, 
+/**
+ * 备注
+ */
+remarks: string | null, 
+/**
+ * 创建时间
+ */
+created_at: string, 
+/**
+ * 更新时间
+ */
+updated_at: string, };
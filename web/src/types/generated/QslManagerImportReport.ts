@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * QSL 管理员 CSV 导入结果
+ */
+export type QslManagerImportReport = { 
+/**
+ * 新登记条数
+ */
+imported: number, 
+/**
+ * 与已有登记完全相同而跳过的条数
+ */
+skipped: number, 
+/**
+ * 出错的行（「第 N 行：原因」），出错行不导入
+ */
+errors: Array<string>, };
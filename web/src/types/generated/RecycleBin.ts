@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DeletedCard } from "./DeletedCard";
+import type { DeletedProject } from "./DeletedProject";
+
+/**
+ * 回收站内容（按删除时间倒序）
+ */
+export type RecycleBin = { 
+/**
+ * 已删除的项目
+ */
+projects: Array<DeletedProject>, 
+/**
+ * 单独删除的卡片
+ */
+cards: Array<DeletedCard>, };
@@ -5,7 +5,7 @@
  */
 export type ReturnInfo = { 
 /**
- * 处理方式：NOT FOUND、CALLSIGN INVALID、REFUSED、OTHER
+ * 退卡原因（登记表 card_handling_methods 中的取值，如 NOT FOUND、CALLSIGN INVALID）
  */
 method: string, 
 /**
@@ -19,9 +19,13 @@ order_id: string,
  */
 waybill_no: string | null, 
 /**
- * 关联的卡片 ID
+ * 关联的卡片 ID（合并寄件时为首张卡片）
  */
 card_id: string | null, 
+/**
+ * 合并寄件关联的全部卡片 ID（按加入顺序，旧数据缺省时取 card_id）
+ */
+card_ids: Array<string>, 
 /**
  * 订单状态
  */
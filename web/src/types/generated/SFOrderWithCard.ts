@@ -19,9 +19,13 @@ order_id: string,
  */
 waybill_no: string | null, 
 /**
- * 关联的卡片 ID
+ * 关联的卡片 ID（合并寄件时为首张卡片）
  */
 card_id: string | null, 
+/**
+ * 合并寄件关联的全部卡片 ID（按加入顺序，旧数据缺省时取 card_id）
+ */
+card_ids: Array<string>, 
 /**
  * 订单状态
  */
@@ -55,10 +59,10 @@ updated_at: string,
  */
 callsign: string | null, 
 /**
- * 关联卡片的项目名称
+ * 首张关联卡片的项目名称
  */
 project_name: string | null, 
 /**
- * 关联卡片的数量
+ * 首张关联卡片的数量
  */
 qty: number | null, };
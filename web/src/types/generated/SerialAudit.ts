@@ -0,0 +1,29 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SerialDuplicate } from "./SerialDuplicate";
+import type { SerialGap } from "./SerialGap";
+import type { SerialScheme } from "./SerialScheme";
+
+/**
+ * 项目序列号检查结果
+ */
+export type SerialAudit = { 
+/**
+ * 编号方案
+ */
+scheme: SerialScheme, 
+/**
+ * 已编号的卡片数
+ */
+numbered: number, 
+/**
+ * 未编号的卡片数
+ */
+unnumbered: number, 
+/**
+ * 起始序列号至最大序列号之间的断号
+ */
+gaps: Array<SerialGap>, 
+/**
+ * 重复的序列号
+ */
+duplicates: Array<SerialDuplicate>, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardWithProject } from "./CardWithProject";
+
+/**
+ * 重复的序列号及使用该序列号的卡片
+ */
+export type SerialDuplicate = { 
+/**
+ * 序列号
+ */
+serial: number, 
+/**
+ * 格式化后的序列号
+ */
+serial_display: string, 
+/**
+ * 使用该序列号的卡片
+ */
+cards: Array<CardWithProject>, };
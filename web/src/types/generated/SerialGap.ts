@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 序列号断号区间（含两端）
+ */
+export type SerialGap = { 
+/**
+ * 起始序列号
+ */
+from: number, 
+/**
+ * 结束序列号
+ */
+to: number, };
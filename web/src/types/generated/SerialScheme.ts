@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 项目序列号编号方案
+ *
+ * 卡片仍以整数保存序列号，显示、打印与导出时按方案格式化。
+ */
+export type SerialScheme = { 
+/**
+ * 项目 ID
+ */
+project_id: string, 
+/**
+ * 前缀（可为空）
+ */
+prefix: string, 
+/**
+ * 年份（为空表示不含年份）
+ */
+year: number | null, 
+/**
+ * 数字部分补零位数
+ */
+width: number, 
+/**
+ * 起始序列号
+ */
+start: number, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 序列号编号方案设置内容
+ */
+export type SerialSchemeInput = { 
+/**
+ * 前缀（可为空）
+ */
+prefix: string, 
+/**
+ * 年份（为空表示不含年份）
+ */
+year: number | null, 
+/**
+ * 数字部分补零位数（1-6）
+ */
+width: number, 
+/**
+ * 起始序列号
+ */
+start: number, };
@@ -0,0 +1,33 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardWithProject } from "./CardWithProject";
+import type { StocktakeScan } from "./StocktakeScan";
+import type { StocktakeSession } from "./StocktakeSession";
+
+/**
+ * 盘点核对报告
+ */
+export type StocktakeReport = { 
+/**
+ * 盘点会话
+ */
+session: StocktakeSession, 
+/**
+ * 应在架上的卡片数（盘点范围内当前存放中的卡片）
+ */
+expected_count: number, 
+/**
+ * 已找到的应在架卡片数
+ */
+found_count: number, 
+/**
+ * 应在架但未扫描到的卡片
+ */
+missing: Array<CardWithProject>, 
+/**
+ * 扫描到但不在待分发清单中的卡片
+ */
+not_pending: Array<CardWithProject>, 
+/**
+ * 无法识别的扫描记录
+ */
+unknown: Array<StocktakeScan>, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 盘点扫描记录
+ */
+export type StocktakeScan = { 
+/**
+ * 记录 ID
+ */
+id: number, 
+/**
+ * 盘点会话 ID
+ */
+session_id: string, 
+/**
+ * 扫描或录入的原始内容
+ */
+input: string, 
+/**
+ * 识别到的卡片 ID（为空表示无法识别）
+ */
+card_id: string | null, 
+/**
+ * 扫描时间
+ */
+scanned_at: string, };
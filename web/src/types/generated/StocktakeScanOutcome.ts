@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 单次扫描的核对结果
+ */
+export type StocktakeScanOutcome = "found" | "not_pending" | "unknown" | "duplicate";
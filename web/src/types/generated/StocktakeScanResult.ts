@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CardWithProject } from "./CardWithProject";
+import type { StocktakeScan } from "./StocktakeScan";
+import type { StocktakeScanOutcome } from "./StocktakeScanOutcome";
+
+/**
+ * 扫描结果
+ */
+export type StocktakeScanResult = { 
+/**
+ * 核对结果
+ */
+outcome: StocktakeScanOutcome, 
+/**
+ * 扫描记录（重复扫描时为此前的记录）
+ */
+scan: StocktakeScan, 
+/**
+ * 识别到的卡片
+ */
+card: CardWithProject | null, };
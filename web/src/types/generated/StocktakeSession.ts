@@ -0,0 +1,42 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 库存盘点会话
+ */
+export type StocktakeSession = { 
+/**
+ * ID
+ */
+id: string, 
+/**
+ * 名称
+ */
+name: string, 
+/**
+ * 盘点范围的项目 ID（为空表示全部项目）
+ */
+project_id: string | null, 
+/**
+ * 盘点范围的项目名称
+ */
+project_name: string | null, 
+/**
+ * 发起盘点的操作员 ID
+ */
+operator_id: string | null, 
+/**
+ * 备注
+ */
+remarks: string | null, 
+/**
+ * 已扫描条数（含无法识别的录入）
+ */
+scan_count: number, 
+/**
+ * 开始时间
+ */
+started_at: string, 
+/**
+ * 结束时间（为空表示进行中）
+ */
+closed_at: string | null, };
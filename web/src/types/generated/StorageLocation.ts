@@ -0,0 +1,60 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 存放位置（柜/盒/格）及其自动分配规则
+ *
+ * 规则字段均为空时不参与自动分配；设置多项时须同时满足。
+ */
+export type StorageLocation = { 
+/**
+ * ID
+ */
+id: string, 
+/**
+ * 柜
+ */
+cabinet: string, 
+/**
+ * 盒（抽屉）
+ */
+box_no: string, 
+/**
+ * 格（为空表示整盒）
+ */
+slot: string, 
+/**
+ * 位置编号（柜-盒[-格]，如 `A-02-3`）
+ */
+code: string, 
+/**
+ * 分配规则：呼号前缀（逗号分隔，如 `BH,BG`）
+ */
+match_prefix: string | null, 
+/**
+ * 分配规则：分区数字（如 `2` 或 `1-3,5`）
+ */
+match_district: string | null, 
+/**
+ * 分配规则：后缀首字母（如 `A-M`）
+ */
+match_suffix: string | null, 
+/**
+ * 排列与规则匹配顺序（小者优先）
+ */
+sort_order: number, 
+/**
+ * 备注
+ */
+remarks: string | null, 
+/**
+ * 存放中的卡片数（尚未分发完毕的卡片）
+ */
+card_count: number, 
+/**
+ * 创建时间
+ */
+created_at: string, 
+/**
+ * 更新时间
+ */
+updated_at: string, };
@@ -0,0 +1,38 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * 存放位置登记内容（新建与修改共用）
+ */
+export type StorageLocationInput = { 
+/**
+ * 柜
+ */
+cabinet: string, 
+/**
+ * 盒（抽屉）
+ */
+box_no: string, 
+/**
+ * 格（为空表示整盒）
+ */
+slot: string, 
+/**
+ * 分配规则：呼号前缀
+ */
+match_prefix: string | null, 
+/**
+ * 分配规则：分区数字
+ */
+match_district: string | null, 
+/**
+ * 分配规则：后缀首字母
+ */
+match_suffix: string | null, 
+/**
+ * 排列与规则匹配顺序
+ */
+sort_order: number, 
+/**
+ * 备注
+ */
+remarks: string | null, };
@@ -13,6 +13,45 @@ export type { AddressEntry } from './generated/AddressEntry'
 export type { Project } from './generated/Project'
 export type { ProjectWithStats } from './generated/ProjectWithStats'
 export type { PagedCards } from './generated/PagedCards'
+export type { CardHistoryEntry } from './generated/CardHistoryEntry'
+export type { CardEvent } from './generated/CardEvent'
+export type { CardEventType } from './generated/CardEventType'
+export type { BatchOperation } from './generated/BatchOperation'
+export type { BatchItemResult } from './generated/BatchItemResult'
+export type { BatchReport } from './generated/BatchReport'
+export type { BatchProgress } from './generated/BatchProgress'
+export type { CardSortField } from './generated/CardSortField'
+export type { SortOrder } from './generated/SortOrder'
+export type { DeletedProject } from './generated/DeletedProject'
+export type { DeletedCard } from './generated/DeletedCard'
+export type { RecycleBin } from './generated/RecycleBin'
+export type { PurgeReport } from './generated/PurgeReport'
+export type { Operator } from './generated/Operator'
+export type { OperatorStats } from './generated/OperatorStats'
+export type { CallsignDossier } from './generated/CallsignDossier'
+export type { CallsignAlias } from './generated/CallsignAlias'
+export type { CardImportField } from './generated/CardImportField'
+export type { CardImportMapping } from './generated/CardImportMapping'
+export type { CardImportPreview } from './generated/CardImportPreview'
+export type { CardImportReport } from './generated/CardImportReport'
+export type { CardImportRowError } from './generated/CardImportRowError'
+export type { QslManager } from './generated/QslManager'
+export type { QslManagerImportReport } from './generated/QslManagerImportReport'
+export type { StorageLocation } from './generated/StorageLocation'
+export type { StorageLocationInput } from './generated/StorageLocationInput'
+export type { SerialScheme } from './generated/SerialScheme'
+export type { SerialSchemeInput } from './generated/SerialSchemeInput'
+export type { SerialGap } from './generated/SerialGap'
+export type { SerialDuplicate } from './generated/SerialDuplicate'
+export type { SerialAudit } from './generated/SerialAudit'
+export type { StocktakeSession } from './generated/StocktakeSession'
+export type { StocktakeScan } from './generated/StocktakeScan'
+export type { StocktakeScanOutcome } from './generated/StocktakeScanOutcome'
+export type { StocktakeScanResult } from './generated/StocktakeScanResult'
+export type { StocktakeReport } from './generated/StocktakeReport'
+export type { CallsignPickup } from './generated/CallsignPickup'
+export type { HandlingMethod } from './generated/HandlingMethod'
+export type { HandlingMethodKind } from './generated/HandlingMethodKind'
 
 // 顺丰模型
 export type { SenderInfo } from './generated/SenderInfo'
@@ -31,6 +70,25 @@ export type { Template } from './generated/Template'
 export type { TsplPrintConfig } from './generated/TsplPrintConfig'
 export type { SinglePrinterConfig } from './generated/SinglePrinterConfig'
 
+// 合并导入类型
+export type { MergeStrategy } from './generated/MergeStrategy'
+export type { MergeOptions } from './generated/MergeOptions'
+export type { MergeTableStats } from './generated/MergeTableStats'
+export type { MergeConflictKind } from './generated/MergeConflictKind'
+export type { MergeConflict } from './generated/MergeConflict'
+export type { MergeReport } from './generated/MergeReport'
+
+// 数据库快照类型
+export type { BackupReason } from './generated/BackupReason'
+export type { BackupSettings } from './generated/BackupSettings'
+export type { BackupSnapshot } from './generated/BackupSnapshot'
+
+// Excel 导出方案类型
+export type { ExportColumn } from './generated/ExportColumn'
+export type { ExportGroupBy } from './generated/ExportGroupBy'
+export type { ExportProfileInput } from './generated/ExportProfileInput'
+export type { ExportProfile } from './generated/ExportProfile'
+
 // 云端同步类型（4-C2）
 export type { ExportStats } from './generated/ExportStats'
 export type { SyncStats } from './generated/SyncStats'
@@ -51,6 +109,25 @@ export interface CardFilter {
   project_id?: string | null
   callsign?: string | null
   status?: string | null
+  created_from?: string | null
+  created_to?: string | null
+  distributed_from?: string | null
+  distributed_to?: string | null
+  returned_from?: string | null
+  returned_to?: string | null
+  distribution_method?: string | null
+  return_method?: string | null
+  serial_min?: number | null
+  serial_max?: number | null
+  qty_min?: number | null
+  qty_max?: number | null
+  has_proxy?: boolean | null
+  has_address?: boolean | null
+  has_open_shipment?: boolean | null
+  via_callsign?: string | null
+  storage_location_id?: string | null
+  sort_by?: import('./generated/CardSortField').CardSortField | null
+  sort_order?: import('./generated/SortOrder').SortOrder | null
   page?: number
   page_size?: number
 }
@@ -148,6 +225,7 @@ export interface CreateOrderParams {
   cargo_name?: string | null
   pay_method?: number | null
   card_id?: string | null
+  card_ids?: string[] | null
 }
 
 // 联系人展示信息
@@ -178,6 +256,7 @@ export interface ConfirmOrderResponse {
   waybill_no_list: string[]
   res_status?: number | null
   local_order: import('./generated/SFOrder').SFOrder
+  distribution?: import('./generated/BatchReport').BatchReport | null
 }
 
 // 查询订单响应
@@ -1,10 +1,37 @@
 import type {
+  BackupSettings,
+  BackupSnapshot,
   Card,
   CardFilter,
+  CardStatus,
   CardWithProject,
+  ExportProfile,
+  ExportProfileInput,
+  CardImportMapping,
+  CardImportPreview,
+  CardImportReport,
+  CallsignDossier,
+  CallsignAlias,
+  QslManager,
+  QslManagerImportReport,
+  StorageLocation,
+  StorageLocationInput,
+  SerialScheme,
+  SerialSchemeInput,
+  SerialAudit,
+  StocktakeSession,
+  StocktakeScanResult,
+  StocktakeReport,
+  BatchReport,
   PagedCards,
   Project,
   ProjectWithStats,
+  PurgeReport,
+  RecycleBin,
+  Operator,
+  OperatorStats,
+  HandlingMethod,
+  HandlingMethodKind,
   Profile,
   TemplateConfig,
   LogEntry,
@@ -35,7 +62,10 @@ export interface CreateCardParams {
   serial?: number | null
 }
 
-export interface ListCardsParams extends CardFilter {}
+export interface ListCardsParams extends CardFilter {
+  // 高级筛选与排序条件（后端以嵌套对象接收）
+  filter?: CardFilter | null
+}
 
 export interface UpdateCardParams {
   id: string
@@ -54,6 +84,7 @@ export interface DistributeCardParams {
   address?: string | null
   remarks?: string | null
   proxy_callsign?: string | null
+  qty?: number | null
 }
 
 export interface ReturnCardParams {
@@ -62,6 +93,48 @@ export interface ReturnCardParams {
   remarks?: string | null
 }
 
+export interface CreateCallsignAliasParams {
+  alias: string
+  callsign: string
+  effectiveFrom: string
+  effectiveTo?: string | null
+  remarks?: string | null
+}
+
+export interface ImportCardsFileParams {
+  projectId: string
+  filePath: string
+  mapping: CardImportMapping
+  dryRun: boolean
+}
+
+export interface CreateQslManagerParams {
+  callsign: string
+  manager: string
+  effectiveFrom?: string | null
+  effectiveTo?: string | null
+  remarks?: string | null
+}
+
+export interface UpdateQslManagerParams {
+  id: string
+  manager: string
+  effectiveFrom?: string | null
+  effectiveTo?: string | null
+  remarks?: string | null
+}
+
+export interface UpdateHandlingMethodParams {
+  kind: HandlingMethodKind
+  code: string
+  displayName?: string | null
+  requiresAddress?: boolean | null
+  requiresProxyCallsign?: boolean | null
+  requiresRemarks?: boolean | null
+  isActive?: boolean | null
+  sortOrder?: number | null
+}
+
 // ========== Profile 配置管理命令 ==========
 
 export interface CreateProfileParams {
@@ -148,10 +221,91 @@ export interface TauriCommands {
   list_cards_cmd: (params: ListCardsParams) => Promise<PagedCards>
   get_card_cmd: (params: { id: string }) => Promise<CardWithProject>
   get_max_serial_cmd: (params: { project_id: string }) => Promise<number | null>
+  get_callsign_dossier_cmd: (params: { callsign: string }) => Promise<CallsignDossier>
+  lookup_card_cmd: (params: { input: string }) => Promise<CardWithProject | null>
+  preview_card_import_cmd: (params: { filePath: string }) => Promise<CardImportPreview>
+  import_cards_file_cmd: (params: ImportCardsFileParams) => Promise<CardImportReport>
   update_card_cmd: (params: UpdateCardParams) => Promise<Card>
   delete_card_cmd: (params: DeleteCardParams) => Promise<void>
   distribute_card_cmd: (params: DistributeCardParams) => Promise<Card>
   return_card_cmd: (params: ReturnCardParams) => Promise<Card>
+  set_card_status_cmd: (params: { id: string; status: CardStatus; remarks?: string | null }) => Promise<Card>
+  get_card_transitions_cmd: (params: { status: CardStatus }) => Promise<CardStatus[]>
+
+  // 呼号别名
+  list_callsign_aliases_cmd: (params: { callsign?: string | null }) => Promise<CallsignAlias[]>
+  check_callsign_alias_cmd: (params: { callsign: string }) => Promise<CallsignAlias | null>
+  create_callsign_alias_cmd: (params: CreateCallsignAliasParams) => Promise<CallsignAlias>
+  update_callsign_alias_cmd: (params: { id: string; effectiveFrom: string; effectiveTo?: string | null; remarks?: string | null }) => Promise<CallsignAlias>
+  delete_callsign_alias_cmd: (params: { id: string }) => Promise<void>
+
+  // QSL 管理员
+  list_qsl_managers_cmd: (params: { callsign?: string | null }) => Promise<QslManager[]>
+  create_qsl_manager_cmd: (params: CreateQslManagerParams) => Promise<QslManager>
+  update_qsl_manager_cmd: (params: UpdateQslManagerParams) => Promise<QslManager>
+  delete_qsl_manager_cmd: (params: { id: string }) => Promise<void>
+  import_qsl_managers_csv_cmd: (params: { filePath: string }) => Promise<QslManagerImportReport>
+
+  // 序列号编号方案
+  get_serial_scheme_cmd: (params: { projectId: string }) => Promise<SerialScheme>
+  set_serial_scheme_cmd: (params: { projectId: string; scheme: SerialSchemeInput }) => Promise<SerialScheme>
+  audit_project_serials_cmd: (params: { projectId: string; skipFour: boolean }) => Promise<SerialAudit>
+  renumber_project_serials_cmd: (params: { projectId: string; skipFour: boolean }) => Promise<number>
+
+  // 存放位置
+  list_storage_locations_cmd: () => Promise<StorageLocation[]>
+  create_storage_location_cmd: (params: { location: StorageLocationInput }) => Promise<StorageLocation>
+  update_storage_location_cmd: (params: { id: string; location: StorageLocationInput }) => Promise<StorageLocation>
+  delete_storage_location_cmd: (params: { id: string }) => Promise<void>
+  set_card_storage_location_cmd: (params: { cardId: string; locationId?: string | null }) => Promise<void>
+  assign_storage_locations_cmd: (params: { projectId?: string | null }) => Promise<number>
+
+  // 库存盘点
+  start_stocktake_cmd: (params: { name?: string | null; projectId?: string | null; remarks?: string | null }) => Promise<StocktakeSession>
+  list_stocktake_sessions_cmd: () => Promise<StocktakeSession[]>
+  record_stocktake_scan_cmd: (params: { sessionId: string; input: string }) => Promise<StocktakeScanResult>
+  delete_stocktake_scan_cmd: (params: { scanId: number }) => Promise<void>
+  get_stocktake_report_cmd: (params: { sessionId: string }) => Promise<StocktakeReport>
+  close_stocktake_cmd: (params: { sessionId: string }) => Promise<StocktakeSession>
+  delete_stocktake_cmd: (params: { sessionId: string }) => Promise<void>
+  mark_stocktake_missing_lost_cmd: (params: { sessionId: string; cardIds: string[] }) => Promise<BatchReport>
+
+  // 分发方式与退卡原因
+  list_handling_methods_cmd: (params: { kind?: HandlingMethodKind | null; includeInactive?: boolean | null }) => Promise<HandlingMethod[]>
+  create_handling_method_cmd: (params: { kind: HandlingMethodKind; code: string; displayName?: string | null }) => Promise<HandlingMethod>
+  update_handling_method_cmd: (params: UpdateHandlingMethodParams) => Promise<HandlingMethod>
+
+  // 回收站
+  list_recycle_bin_cmd: () => Promise<RecycleBin>
+  restore_card_cmd: (params: { id: string }) => Promise<void>
+  restore_project_cmd: (params: { id: string }) => Promise<void>
+  purge_card_cmd: (params: { id: string }) => Promise<void>
+  purge_project_cmd: (params: { id: string }) => Promise<void>
+  empty_recycle_bin_cmd: () => Promise<PurgeReport>
+
+  // 操作员
+  create_operator_cmd: (params: { name: string; callsign?: string | null; pin?: string | null }) => Promise<Operator>
+  list_operators_cmd: () => Promise<Operator[]>
+  update_operator_cmd: (params: { id: string; name: string; callsign?: string | null; pin?: string | null }) => Promise<Operator>
+  set_operator_pin_cmd: (params: { id: string; currentPin?: string | null; newPin?: string | null }) => Promise<Operator>
+  delete_operator_cmd: (params: { id: string; pin?: string | null }) => Promise<void>
+  login_operator_cmd: (params: { id: string; pin?: string | null }) => Promise<Operator>
+  logout_operator_cmd: () => Promise<void>
+  get_current_operator_cmd: () => Promise<Operator | null>
+  get_operator_stats_cmd: (params: { from?: string | null; to?: string | null }) => Promise<OperatorStats[]>
+
+  // 数据库快照
+  list_backups_cmd: () => Promise<BackupSnapshot[]>
+  create_backup_cmd: () => Promise<BackupSnapshot | null>
+  restore_backup_cmd: (params: { fileName: string }) => Promise<BackupSnapshot>
+  delete_backup_cmd: (params: { fileName: string }) => Promise<void>
+  get_backup_settings_cmd: () => Promise<BackupSettings>
+  save_backup_settings_cmd: (params: { settings: BackupSettings }) => Promise<void>
+
+  // Excel 导出方案
+  list_export_profiles_cmd: () => Promise<ExportProfile[]>
+  save_export_profile_cmd: (params: { id?: string | null; input: ExportProfileInput }) => Promise<ExportProfile>
+  delete_export_profile_cmd: (params: { id: string }) => Promise<void>
 
   // Profile 管理
   get_profiles: () => Promise<Profile[]>
@@ -163,6 +317,7 @@ export interface TauriCommands {
   // 打印
   print_qsl: (params: PrintQSLParams) => Promise<void>
   preview_qsl: (params: PreviewQSLParams) => Promise<string>
+  print_storage_label: (params: { printerName: string; locationId: string }) => Promise<void>
 
   // QRZ.cn
   qrz_save_and_login: (params: QRZSaveAndLoginParams) => Promise<string>
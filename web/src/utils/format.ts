@@ -1,13 +1,67 @@
 // 格式化工具函数
 
+import type { SerialScheme } from '@/types/models'
+
 /**
- * 格式化序列号显示（3位数，前导零）
+ * 格式化序列号显示（与后端 SerialScheme::format 一致）
+ *
+ * 未指定编号方案时为 3 位数前导零；指定时前缀、年份与补零后的数字以 `-` 连接（空的部分省略）
  * @param serial 序列号（数字或字符串）
- * @returns 格式化后的序列号字符串，如 "001"、"012"、"123"
+ * @param scheme 项目编号方案
+ * @returns 格式化后的序列号字符串，如 "001"、"012"、"BY-2026-0012"
  */
-export function formatSerial(serial: number | string | null | undefined): string {
+export function formatSerial(
+  serial: number | string | null | undefined,
+  scheme?: SerialScheme | null
+): string {
   if (serial === null || serial === undefined || serial === '') {
     return '-'
   }
-  return String(serial).padStart(3, '0')
+  const number = String(serial).padStart(scheme?.width ?? 3, '0')
+  if (!scheme) {
+    return number
+  }
+  const parts: string[] = []
+  if (scheme.prefix) parts.push(scheme.prefix)
+  if (scheme.year !== null) parts.push(String(scheme.year))
+  parts.push(number)
+  return parts.join('-')
+}
+
+/**
+ * 格式化呼号及经由的 QSL 管理员
+ * @param callsign 呼号
+ * @param via QSL 管理员呼号
+ * @returns 如 "VK9XX via VE3ABC"，无管理员时为呼号本身
+ */
+export function formatCallsignVia(callsign: string, via: string | null | undefined): string {
+  return via ? `${callsign} via ${via}` : callsign
+}
+
+/**
+ * 构建 QSL 标签的运行时数据
+ *
+ * 模板可使用 {callsign}、{via}（QSL 管理员，可能为空）与 {callsign_via}（"X via Y"）；
+ * 传入卡片 ID 时后端补充 {card_code}（卡片编号，供条码扫码识别单张卡片）
+ */
+export function buildQslLabelData(
+  projectName: string,
+  callsign: string,
+  via: string | null | undefined,
+  serial: string,
+  qty: string,
+  cardId?: string
+): Record<string, string> {
+  const data: Record<string, string> = {
+    project_name: projectName,
+    callsign,
+    via: via || '',
+    callsign_via: formatCallsignVia(callsign, via),
+    sn: serial,
+    qty
+  }
+  if (cardId) {
+    data.card_id = cardId
+  }
+  return data
 }